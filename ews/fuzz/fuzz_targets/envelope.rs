@@ -0,0 +1,28 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Deserializes arbitrary documents as responses to the most common
+//! operations, which must fail with an error rather than panic whatever the
+//! server sends.
+
+#![no_main]
+
+use ews::{
+    find_item::FindItemResponse, get_attachment::GetAttachmentResponse,
+    get_folder::GetFolderResponse, get_item::GetItemResponse, resolve_names::ResolveNamesResponse,
+    soap::Envelope, sync_folder_items::SyncFolderItemsResponse,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|document: &[u8]| {
+    let _ = Envelope::<GetItemResponse>::from_xml_document(document, None);
+    let _ = Envelope::<FindItemResponse>::from_xml_document(document, None);
+    let _ = Envelope::<GetFolderResponse>::from_xml_document(document, None);
+    let _ = Envelope::<SyncFolderItemsResponse>::from_xml_document(document, None);
+    let _ = Envelope::<GetAttachmentResponse>::from_xml_document(document, None);
+    let _ = Envelope::<ResolveNamesResponse>::from_xml_document(document, None);
+
+    // Streaming deserialization takes a different path through the document.
+    let _ = Envelope::<GetItemResponse>::from_reader(document, None);
+});
@@ -0,0 +1,33 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Serializes arbitrary items and folders and deserializes them again, which
+//! must not panic.
+//!
+//! Not every value survives the round trip, e.g. empty recipient lists or
+//! extended properties with neither a value nor values, so the results are
+//! only checked for panics.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use ews::{test_utils::roundtrip_variant, Folder, RealItem};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Input {
+    Item(Box<RealItem>),
+    Folder(Folder),
+}
+
+fuzz_target!(|input: Input| {
+    match input {
+        Input::Item(item) => {
+            let _ = roundtrip_variant(item.as_ref());
+        }
+        Input::Folder(folder) => {
+            let _ = roundtrip_variant(&folder);
+        }
+    }
+});
@@ -0,0 +1,1494 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Clients for sending EWS operations over HTTP.
+//!
+//! This module takes care of wrapping an [`Operation`] in a SOAP envelope,
+//! sending it to an EWS endpoint with the appropriate headers, and parsing the
+//! response into the operation's [`OperationResponse`] type.
+//!
+//! An asynchronous client built on `reqwest` is available with the `client`
+//! feature, and a synchronous client built on `ureq` is available in
+//! [`blocking`] with the `blocking` feature. Neither is enabled by default, so
+//! that library clients which bring their own HTTP stack do not need to depend
+//! on one.
+
+use std::{fmt, sync::Arc};
+#[cfg(feature = "client")]
+use std::{future::Future, pin::Pin};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+#[cfg(feature = "client")]
+use crate::{
+    delete_item::{DeleteItem, DeleteItemResponseMessage},
+    find_item::FindItem,
+    get_user_configuration::GetUserConfiguration,
+    move_item::MoveItem,
+    registry::{self, OperationEntry},
+    sync_folder_items::{ChangeBatch, ItemSync, SyncFolderItems},
+    update_item::{ChangeKeyRefresh, UpdateItem, UpdateItemResponseMessage},
+    AttachmentId, BaseFolderId, BatchOperation, CategoryList, ContactPhoto, FolderHierarchy,
+    FolderTree, ItemResponseMessage, PagedOperation, Pager, ResponseClass, SyncState,
+};
+use crate::{
+    raw::{RawOperation, RawResponse},
+    server_version::ExchangeServerVersion,
+    soap::{CustomHeader, Envelope},
+    BaseItemId, ContentFormat, DateTimeFormat, Error, ItemLike, Operation, OperationResponse,
+    RealItem, TimeZoneContext, MESSAGES_NS_URI,
+};
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+mod affinity;
+pub use self::affinity::AffinityCookies;
+use self::affinity::{COOKIE, SET_COOKIE, X_ANCHOR_MAILBOX};
+
+mod challenge;
+use self::challenge::{authorization_header, parse_challenge, MAX_HANDSHAKE_ROUNDS};
+pub use self::challenge::{ChallengeAuthenticator, Handshake};
+
+mod retry;
+use self::retry::server_busy_back_off;
+#[cfg(feature = "client")]
+use self::retry::BusyResponse;
+pub use self::retry::RetryPolicy;
+
+mod throttling;
+use self::throttling::RETRY_AFTER;
+pub use self::throttling::{ThrottlingLimits, ThrottlingState};
+
+mod trace;
+pub use self::trace::{TraceEvent, TraceHook, Tracer};
+
+mod version;
+use self::version::Negotiation;
+pub use self::version::ServerVersions;
+
+/// The name of the header carrying request credentials.
+const AUTHORIZATION: &str = "Authorization";
+
+/// The name of the header carrying authentication challenges in responses.
+const WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+
+/// The name of the header indicating the media type of a request.
+const CONTENT_TYPE: &str = "Content-Type";
+
+/// The value of the `Content-Type` header for EWS requests.
+const SOAP_CONTENT_TYPE: &str = "text/xml; charset=utf-8";
+
+/// The name of the header indicating the operation in a SOAP request.
+const SOAP_ACTION: &str = "SOAPAction";
+
+/// The credentials used to authenticate requests to EWS.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum Credentials {
+    /// HTTP Basic authentication with a username and password.
+    Basic { username: String, password: String },
+
+    /// A bearer token, e.g. an OAuth2 access token for Exchange Online.
+    Bearer(String),
+
+    /// Bearer tokens fetched from a [`TokenProvider`] before each request.
+    ///
+    /// These credentials are only supported by the asynchronous client.
+    #[cfg(feature = "client")]
+    TokenProvider(Arc<dyn TokenProvider>),
+
+    /// A challenge-response scheme such as NTLM or Negotiate, performed by a
+    /// [`ChallengeAuthenticator`] for each request.
+    Challenge(Arc<dyn ChallengeAuthenticator>),
+}
+
+// Implemented manually so that secrets don't end up in logs.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"<redacted>")
+                .finish(),
+            Self::Bearer(_) => f.debug_tuple("Bearer").field(&"<redacted>").finish(),
+            #[cfg(feature = "client")]
+            Self::TokenProvider(_) => f.debug_tuple("TokenProvider").finish_non_exhaustive(),
+            Self::Challenge(authenticator) => f
+                .debug_tuple("Challenge")
+                .field(&authenticator.scheme())
+                .finish(),
+        }
+    }
+}
+
+impl Credentials {
+    /// Gets the value of the `Authorization` header for these credentials, if
+    /// it can be determined without fetching a token.
+    pub(crate) fn static_authorization_header(&self) -> Option<String> {
+        match self {
+            Self::Basic { username, password } => Some(format!(
+                "Basic {}",
+                STANDARD.encode(format!("{username}:{password}"))
+            )),
+            Self::Bearer(token) => Some(format!("Bearer {token}")),
+            #[cfg(feature = "client")]
+            Self::TokenProvider(_) => None,
+            Self::Challenge(_) => None,
+        }
+    }
+}
+
+/// The error type returned by a [`TokenProvider`] or [`ChallengeAuthenticator`].
+pub type TokenError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The future returned by [`TokenProvider::token`].
+#[cfg(feature = "client")]
+pub type TokenFuture<'a> = Pin<Box<dyn Future<Output = Result<String, TokenError>> + Send + 'a>>;
+
+/// A source of bearer tokens for authenticating requests, e.g. OAuth2 access
+/// tokens for Exchange Online.
+///
+/// [`EwsClient`] requests a token before sending each request, so
+/// implementations are expected to cache tokens and transparently refresh them
+/// when they expire. If the server rejects a token with a `401 Unauthorized`
+/// status, the client calls [`invalidate`] and retries the request once with a
+/// freshly fetched token.
+///
+/// [`invalidate`]: TokenProvider::invalidate
+#[cfg(feature = "client")]
+pub trait TokenProvider: Send + Sync {
+    /// Gets a token with which to authenticate the next request.
+    fn token(&self) -> TokenFuture<'_>;
+
+    /// Notifies the provider that the server rejected the most recently
+    /// provided token, such that the next call to [`token`] should fetch a new
+    /// one rather than returning a cached token.
+    ///
+    /// [`token`]: TokenProvider::token
+    fn invalidate(&self) {}
+}
+
+/// An asynchronous client for an EWS endpoint.
+///
+/// This client is only available with the `client` feature enabled.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn run() -> Result<(), ews::Error> {
+/// use ews::client::{Credentials, EwsClient};
+/// use ews::get_folder::GetFolder;
+/// use ews::{BaseFolderId, BaseShape, FolderShape};
+///
+/// let client = EwsClient::new(
+///     "https://outlook.office365.com/EWS/Exchange.asmx",
+///     Credentials::Bearer("token".to_string()),
+/// );
+///
+/// let response = client
+///     .send(GetFolder {
+///         folder_shape: FolderShape {
+///             base_shape: BaseShape::Default,
+///             additional_properties: None,
+///         },
+///         folder_ids: vec![BaseFolderId::DistinguishedFolderId {
+///             id: "inbox".to_string(),
+///             change_key: None,
+///         }],
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "client")]
+#[derive(Clone, Debug)]
+pub struct EwsClient {
+    http: reqwest::Client,
+    endpoint: String,
+    credentials: Credentials,
+    server_version: ExchangeServerVersion,
+    date_time_format: DateTimeFormat,
+    content_format: ContentFormat,
+    time_zone_context: Option<TimeZoneContext>,
+    custom_headers: Vec<CustomHeader>,
+    retry_policy: RetryPolicy,
+    max_batch_size: Option<usize>,
+    tracer: Option<Tracer>,
+    anchor_mailbox: Option<String>,
+    affinity_cookies: AffinityCookies,
+    throttling: ThrottlingState,
+    server_versions: ServerVersions,
+}
+
+#[cfg(feature = "client")]
+impl EwsClient {
+    /// Creates a new client for the EWS endpoint at the given URL.
+    pub fn new(endpoint: impl Into<String>, credentials: Credentials) -> Self {
+        Self::with_http_client(reqwest::Client::new(), endpoint, credentials)
+    }
+
+    /// Creates a new client for the EWS endpoint at the given URL, sending
+    /// requests through an existing [`reqwest::Client`].
+    pub fn with_http_client(
+        http: reqwest::Client,
+        endpoint: impl Into<String>,
+        credentials: Credentials,
+    ) -> Self {
+        Self {
+            http,
+            endpoint: endpoint.into(),
+            credentials,
+            server_version: ExchangeServerVersion::default(),
+            date_time_format: DateTimeFormat::default(),
+            content_format: ContentFormat::default(),
+            time_zone_context: None,
+            custom_headers: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            max_batch_size: None,
+            tracer: None,
+            anchor_mailbox: None,
+            affinity_cookies: AffinityCookies::new(),
+            throttling: ThrottlingState::default(),
+            server_versions: ServerVersions::new(),
+        }
+    }
+
+    /// Sets the schema version targeted by requests from this client.
+    ///
+    /// Requests which the server rejects for targeting a version it doesn't
+    /// support are retried targeting older versions, and later requests
+    /// target the version negotiated with the endpoint, as recorded in the
+    /// client's [`ServerVersions`].
+    pub fn with_server_version(mut self, server_version: ExchangeServerVersion) -> Self {
+        self.server_version = server_version;
+        self
+    }
+
+    /// Sets the format in which times are written in requests from this
+    /// client, for servers which reject the default format.
+    pub fn with_date_time_format(mut self, date_time_format: DateTimeFormat) -> Self {
+        self.date_time_format = date_time_format;
+        self
+    }
+
+    /// Sets the format in which the content of bodies and MIME content is
+    /// written in requests from this client.
+    pub fn with_content_format(mut self, content_format: ContentFormat) -> Self {
+        self.content_format = content_format;
+        self
+    }
+
+    /// Sets the time zone in which the server interprets times without an
+    /// offset from UTC in requests from this client, and in which such times
+    /// in its responses are deserialized.
+    pub fn with_time_zone_context(mut self, time_zone_context: TimeZoneContext) -> Self {
+        self.time_zone_context = Some(time_zone_context);
+        self
+    }
+
+    /// Adds a header to send in the SOAP envelope of each request from this
+    /// client, after the known EWS headers.
+    pub fn with_custom_header(mut self, header: CustomHeader) -> Self {
+        self.custom_headers.push(header);
+        self
+    }
+
+    /// Sets the policy for retrying requests which the server was too busy to
+    /// process.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the largest number of inputs sent in a single request by
+    /// [`send_batch`], in place of the [`MAX_BATCH_SIZE`] of each operation,
+    /// for servers whose throttling policy accepts more or fewer inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_batch_size` is zero.
+    ///
+    /// [`send_batch`]: EwsClient::send_batch
+    /// [`MAX_BATCH_SIZE`]: BatchOperation::MAX_BATCH_SIZE
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        assert!(max_batch_size > 0, "batch size must not be zero");
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    /// Sets a tracer to receive the raw contents of requests and responses.
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Sets the mailbox sent in the `X-AnchorMailbox` header of each request,
+    /// so that Exchange Online routes requests to the server hosting it.
+    ///
+    /// This is usually the primary SMTP address of the mailbox being
+    /// accessed, and is required for streaming subscriptions.
+    pub fn with_anchor_mailbox(mut self, anchor_mailbox: impl Into<String>) -> Self {
+        self.anchor_mailbox = Some(anchor_mailbox.into());
+        self
+    }
+
+    /// Sets the store of affinity cookies used by this client.
+    ///
+    /// Each client otherwise has its own store, which is shared with its
+    /// clones. Cookies set by responses are recorded in the store and sent
+    /// with each subsequent request.
+    pub fn with_affinity_cookies(mut self, affinity_cookies: AffinityCookies) -> Self {
+        self.affinity_cookies = affinity_cookies;
+        self
+    }
+
+    /// Gets the store of affinity cookies used by this client.
+    pub fn affinity_cookies(&self) -> &AffinityCookies {
+        &self.affinity_cookies
+    }
+
+    /// Sets the state in which this client tracks its usage of the server's
+    /// throttling budgets.
+    ///
+    /// Each client otherwise has its own state, tracked against the limits of
+    /// the default Exchange Online policy and shared with its clones.
+    pub fn with_throttling_state(mut self, throttling: ThrottlingState) -> Self {
+        self.throttling = throttling;
+        self
+    }
+
+    /// Gets the state in which this client tracks its usage of the server's
+    /// throttling budgets.
+    pub fn throttling_state(&self) -> &ThrottlingState {
+        &self.throttling
+    }
+
+    /// Sets the store of schema versions negotiated with EWS endpoints used
+    /// by this client.
+    ///
+    /// Each client otherwise has its own store, which is shared with its
+    /// clones.
+    pub fn with_server_versions(mut self, server_versions: ServerVersions) -> Self {
+        self.server_versions = server_versions;
+        self
+    }
+
+    /// Gets the store of schema versions negotiated with EWS endpoints used
+    /// by this client.
+    pub fn server_versions(&self) -> &ServerVersions {
+        &self.server_versions
+    }
+
+    /// Sends an operation to the EWS endpoint and returns its response.
+    ///
+    /// Requests rejected because the server is too busy are retried according
+    /// to the client's [`RetryPolicy`], and those rejected because the server
+    /// doesn't support the targeted schema version are retried targeting
+    /// older versions.
+    pub async fn send<O>(&self, operation: O) -> Result<O::Response, Error>
+    where
+        O: Operation,
+    {
+        let action = soap_action(&operation);
+        let build_body = |server_version| {
+            build_request_body(
+                &operation,
+                server_version,
+                self.date_time_format,
+                self.content_format,
+                self.time_zone_context.as_ref(),
+                &self.custom_headers,
+            )
+        };
+
+        self.send_negotiating_version(
+            Some(operation.name()),
+            &action,
+            build_body,
+            |status, body| parse_response(status, body, self.time_zone_context.as_ref()),
+        )
+        .await
+    }
+
+    /// Sends an operation which this crate doesn't model yet, and returns the
+    /// XML of its response.
+    ///
+    /// Requests are retried like those of [`send`], except that the messages
+    /// of the response aren't inspected for `ErrorServerBusy` codes.
+    ///
+    /// [`send`]: EwsClient::send
+    pub async fn send_raw(&self, operation: &RawOperation) -> Result<RawResponse, Error> {
+        let action = raw_soap_action(operation);
+        let build_body = |server_version| {
+            build_raw_request_body(
+                operation,
+                server_version,
+                self.time_zone_context.as_ref(),
+                &self.custom_headers,
+            )
+        };
+
+        let name = registry::find_operation(operation.name()).map(OperationEntry::operation_name);
+        self.send_negotiating_version(name, &action, build_body, |status, body| {
+            parse_raw_response(status, body, operation)
+        })
+        .await
+    }
+
+    /// Sends a request built for the schema version negotiated with the
+    /// endpoint, retrying it targeting older versions while the server
+    /// rejects the targeted version, and parses its response with the given
+    /// function.
+    async fn send_negotiating_version<R, B, F>(
+        &self,
+        name: Option<&'static str>,
+        action: &str,
+        build_body: B,
+        parse: F,
+    ) -> Result<R, Error>
+    where
+        R: BusyResponse,
+        B: Fn(ExchangeServerVersion) -> Result<Vec<u8>, Error>,
+        F: Fn(u16, &[u8]) -> Result<R, Error>,
+    {
+        let mut negotiation =
+            Negotiation::start(&self.server_versions, &self.endpoint, self.server_version);
+        let mut body = build_body(negotiation.version)?;
+
+        loop {
+            let result = self.send_with_retries(name, action, body, &parse).await;
+
+            match negotiation.retry(&result, &build_body) {
+                Some(older_body) => body = older_body,
+                None => {
+                    negotiation.finish(&result, &self.server_versions, &self.endpoint);
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Sends a serialized request, retrying it according to the client's
+    /// [`RetryPolicy`], and parses its response with the given function.
+    ///
+    /// Only requests for the named operation, if any, are tracked against
+    /// the client's throttling budgets.
+    async fn send_with_retries<R, F>(
+        &self,
+        name: Option<&'static str>,
+        action: &str,
+        body: Vec<u8>,
+        parse: F,
+    ) -> Result<R, Error>
+    where
+        R: BusyResponse,
+        F: Fn(u16, &[u8]) -> Result<R, Error>,
+    {
+        let mut retries = 0;
+        loop {
+            let result = {
+                let _in_flight = name.map(|name| self.throttling.begin_request(name));
+                self.send_request(action, body.clone(), &parse).await
+            };
+
+            if let Some(back_off) = server_busy_back_off(&result) {
+                self.throttling.record_throttled(back_off);
+            }
+
+            match self.retry_policy.retry_delay(&result, retries) {
+                Some(delay) => {
+                    log::debug!("server busy, retrying {action} in {delay:?}");
+                    tokio::time::sleep(delay).await;
+                    retries += 1;
+                }
+                None => return result,
+            }
+        }
+    }
+
+    /// Sends a batch operation to the EWS endpoint, split into as many
+    /// requests as needed to stay within the batch size set with
+    /// [`with_max_batch_size`] or the operation's [`MAX_BATCH_SIZE`], and
+    /// returns the response messages for the whole batch in the order of its
+    /// inputs.
+    ///
+    /// Requests are sent one after the other. If any of them fails, the
+    /// messages for the requests already sent are discarded, so callers should
+    /// be prepared for part of the batch to have taken effect.
+    ///
+    /// [`with_max_batch_size`]: EwsClient::with_max_batch_size
+    /// [`MAX_BATCH_SIZE`]: BatchOperation::MAX_BATCH_SIZE
+    pub async fn send_batch<O>(
+        &self,
+        operation: O,
+    ) -> Result<Vec<ResponseClass<<O::Response as OperationResponse>::Message>>, Error>
+    where
+        O: BatchOperation + Clone,
+    {
+        let max_batch_size = self.max_batch_size.unwrap_or(O::MAX_BATCH_SIZE);
+        let mut responses = Vec::new();
+        for chunk in operation.split_batch(max_batch_size) {
+            responses.push(self.send(chunk).await?);
+        }
+
+        Ok(O::merge_response_messages(responses))
+    }
+
+    /// Iterates over the pages of results of a paged operation, such as
+    /// [`FindItem`], sending a request for each page as it is needed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run(client: ews::client::EwsClient) -> Result<(), ews::Error> {
+    /// use ews::{find_item::FindItem, BaseFolderId};
+    ///
+    /// let mut pages = client.pages(FindItem::in_folder(BaseFolderId::inbox()).page(100).build());
+    /// while let Some(items) = pages.next().await {
+    ///     for item in items? {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`FindItem`]: crate::find_item::FindItem
+    pub fn pages<O>(&self, operation: O) -> Pages<'_, O>
+    where
+        O: PagedOperation,
+    {
+        Pages {
+            client: self,
+            pager: Pager::new(operation),
+        }
+    }
+
+    /// Retrieves the changes to a folder since the synchronization state of
+    /// the operation, sending requests until the server reports that all
+    /// changes have been returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn run(client: ews::client::EwsClient, state: Option<ews::SyncState>) -> Result<(), ews::Error> {
+    /// use ews::{sync_folder_items::SyncFolderItems, BaseFolderId, ItemShape};
+    ///
+    /// let mut operation = SyncFolderItems::new(ItemShape::sync_default(), BaseFolderId::inbox(), 100);
+    /// operation.sync_state = state;
+    ///
+    /// let mut sync = client.sync_folder_items(operation);
+    /// while let Some(batch) = sync.next().await {
+    ///     let batch = batch?;
+    ///     for change in batch.changes {
+    ///         // ...
+    ///     }
+    ///     // Persist `batch.sync_state` to resume from here later.
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sync_folder_items(&self, operation: SyncFolderItems) -> ItemChanges<'_> {
+        ItemChanges {
+            client: self,
+            sync: ItemSync::new(operation),
+        }
+    }
+
+    /// Sends an [`UpdateItem`] operation, retrying once with the current
+    /// change keys any changes which were rejected because of a missing or
+    /// outdated change key.
+    ///
+    /// See [`ChangeKeyRefresh`] for details.
+    pub async fn update_item_refreshing_change_keys(
+        &self,
+        operation: UpdateItem,
+    ) -> Result<Vec<ResponseClass<UpdateItemResponseMessage>>, Error> {
+        let mut messages = self.send(operation.clone()).await?.into_response_messages();
+        let Some(mut refresh) = ChangeKeyRefresh::new(&operation, &messages) else {
+            return Ok(messages);
+        };
+
+        let current = self.send(refresh.get_item()).await?;
+        if let Some(retry) = refresh.retry_operation(current)? {
+            let response = self.send(retry).await?;
+            refresh.merge(&mut messages, response)?;
+        }
+
+        Ok(messages)
+    }
+
+    /// Gets the photo of a contact, returning `None` if it has none.
+    ///
+    /// See [`ContactPhoto`] for details.
+    pub async fn get_contact_photo(
+        &self,
+        contact_id: BaseItemId,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let photo = ContactPhoto::new(contact_id);
+        let response = self.send(photo.get_item()).await?;
+        let Some(attachment_id) = photo.attachment_id(response)? else {
+            return Ok(None);
+        };
+
+        let response = self.send(photo.get_attachment(attachment_id)).await?;
+        photo
+            .content(response)?
+            .map(|content| decode_attachment_content(&content))
+            .transpose()
+    }
+
+    /// Sets the photo of a contact, replacing any existing one, and returns
+    /// the identifier of the new photo attachment.
+    ///
+    /// See [`ContactPhoto`] for details.
+    pub async fn set_contact_photo(
+        &self,
+        contact_id: BaseItemId,
+        content: &[u8],
+        content_type: impl Into<String>,
+    ) -> Result<AttachmentId, Error> {
+        let mut photo = ContactPhoto::new(contact_id);
+        let response = self.send(photo.get_item()).await?;
+        if let Some(attachment_id) = photo.attachment_id(response)? {
+            let response = self.send(photo.delete_attachment(attachment_id)).await?;
+            photo.process_deletion(response)?;
+        }
+
+        let operation = photo.create_attachment(STANDARD.encode(content), content_type);
+        let response = self.send(operation).await?;
+        photo.created_attachment_id(response)
+    }
+
+    /// Gets the master category list of the mailbox.
+    ///
+    /// See [`CategoryList`] for details.
+    pub async fn get_category_list(&self) -> Result<CategoryList, Error> {
+        let response = self.send(GetUserConfiguration::category_list()).await?;
+        let category_list = response.single()?.user_configuration.category_list()?;
+
+        Ok(category_list.unwrap_or_default())
+    }
+
+    /// Gets the hierarchy of folders below the given folder, identifying the
+    /// common distinguished folders among them.
+    ///
+    /// See [`FolderHierarchy`] for details, e.g. to identify other
+    /// distinguished folders.
+    pub async fn get_folder_tree(&self, root: BaseFolderId) -> Result<FolderTree, Error> {
+        let hierarchy = FolderHierarchy::new(root);
+        let folders = self.pages(hierarchy.find_folder()).collect_all().await?;
+
+        let distinguished_folders = match hierarchy.get_distinguished_folders() {
+            Some(operation) => Some(self.send(operation).await?),
+            None => None,
+        };
+
+        Ok(hierarchy.build(folders, distinguished_folders))
+    }
+
+    /// Recovers the items found by a search, usually of the Recoverable Items
+    /// Deletions folder as created by [`FindItem::recoverable_items`], by
+    /// moving them to the given folder.
+    ///
+    /// Every page of results is fetched before the items are moved. The
+    /// response messages carry the new identifiers of the recovered items,
+    /// in the order the items were found.
+    pub async fn recover_items(
+        &self,
+        search: FindItem,
+        to_folder_id: BaseFolderId,
+    ) -> Result<Vec<ResponseClass<ItemResponseMessage>>, Error> {
+        let item_ids = found_item_ids(self.pages(search).collect_all().await?);
+        if item_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.send_batch(MoveItem::recover(item_ids, to_folder_id))
+            .await
+    }
+
+    /// Purges the items found by a search, usually of the Recoverable Items
+    /// Deletions folder as created by [`FindItem::recoverable_items`], so
+    /// that they can no longer be recovered.
+    ///
+    /// See [`DeleteItem::purge`] for details.
+    pub async fn purge_items(
+        &self,
+        search: FindItem,
+    ) -> Result<Vec<ResponseClass<DeleteItemResponseMessage>>, Error> {
+        let item_ids = found_item_ids(self.pages(search).collect_all().await?);
+        if item_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.send_batch(DeleteItem::purge(item_ids)).await
+    }
+
+    /// Sends a serialized request to the EWS endpoint and parses its response
+    /// with the given function.
+    async fn send_request<R>(
+        &self,
+        action: &str,
+        body: Vec<u8>,
+        parse: impl Fn(u16, &[u8]) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        if let Some(tracer) = &self.tracer {
+            tracer.request(action, &body);
+        }
+
+        let response = match &self.credentials {
+            Credentials::Challenge(authenticator) => {
+                self.post_with_handshake(authenticator.as_ref(), action, body)
+                    .await?
+            }
+            _ => {
+                let authorization = self.authorization().await?;
+                let mut response = self.post(action, body.clone(), authorization).await?;
+
+                // A rejected token may simply have expired or been revoked, so
+                // give the token provider a chance to fetch a new one before
+                // giving up.
+                if let Credentials::TokenProvider(provider) = &self.credentials {
+                    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+                        provider.invalidate();
+                        let authorization = self.authorization().await?;
+                        response = self.post(action, body, authorization).await?;
+                    }
+                }
+
+                response
+            }
+        };
+
+        let status = response.status().as_u16();
+        let body = response.bytes().await?;
+
+        if let Some(tracer) = &self.tracer {
+            tracer.response(status, &body);
+        }
+
+        parse(status, &body)
+    }
+
+    /// Gets the value of the `Authorization` header for the next request,
+    /// fetching a token if necessary.
+    async fn authorization(&self) -> Result<String, Error> {
+        match &self.credentials {
+            Credentials::TokenProvider(provider) => {
+                let token = provider.token().await.map_err(Error::TokenProvider)?;
+                Ok(format!("Bearer {token}"))
+            }
+            credentials => credentials
+                .static_authorization_header()
+                .ok_or(Error::UnsupportedCredentials),
+        }
+    }
+
+    /// Sends a serialized request to the EWS endpoint, performing a
+    /// challenge-response authentication handshake along the way.
+    async fn post_with_handshake(
+        &self,
+        authenticator: &dyn ChallengeAuthenticator,
+        action: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, Error> {
+        let scheme = authenticator.scheme();
+        let mut handshake = authenticator.start().map_err(Error::Handshake)?;
+        let mut challenge: Option<Vec<u8>> = None;
+        let mut rounds = 0;
+
+        loop {
+            let token = handshake
+                .step(challenge.as_deref())
+                .map_err(Error::Handshake)?;
+            let response = self
+                .post(action, body.clone(), authorization_header(scheme, &token))
+                .await?;
+            rounds += 1;
+
+            if response.status() != reqwest::StatusCode::UNAUTHORIZED
+                || rounds == MAX_HANDSHAKE_ROUNDS
+            {
+                return Ok(response);
+            }
+
+            challenge = parse_challenge(
+                scheme,
+                response
+                    .headers()
+                    .get_all(WWW_AUTHENTICATE)
+                    .iter()
+                    .filter_map(|value| value.to_str().ok()),
+            );
+            if challenge.is_none() {
+                // The server rejected the handshake outright.
+                return Ok(response);
+            }
+
+            // Read the body of the challenge response so that the connection
+            // can be reused for the next leg of the handshake.
+            response.bytes().await?;
+        }
+    }
+
+    /// Sends a serialized request to the EWS endpoint.
+    async fn post(
+        &self,
+        action: &str,
+        body: Vec<u8>,
+        authorization: String,
+    ) -> Result<reqwest::Response, Error> {
+        let mut request = self
+            .http
+            .post(&self.endpoint)
+            .header(CONTENT_TYPE, SOAP_CONTENT_TYPE)
+            .header(SOAP_ACTION, action)
+            .header(AUTHORIZATION, authorization);
+
+        if let Some(anchor_mailbox) = &self.anchor_mailbox {
+            request = request.header(X_ANCHOR_MAILBOX, anchor_mailbox);
+        }
+        if let Some(cookies) = self.affinity_cookies.header() {
+            request = request.header(COOKIE, cookies);
+        }
+
+        let response = request.body(body).send().await?;
+
+        if let Some(retry_after) = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+        {
+            self.throttling.record_retry_after(retry_after);
+        }
+
+        self.affinity_cookies.record(
+            response
+                .headers()
+                .get_all(SET_COOKIE)
+                .iter()
+                .filter_map(|value| value.to_str().ok()),
+        );
+
+        Ok(response)
+    }
+}
+
+/// The pages of results of a [`PagedOperation`], as returned by
+/// [`EwsClient::pages`].
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub struct Pages<'a, O> {
+    client: &'a EwsClient,
+    pager: Pager<O>,
+}
+
+#[cfg(feature = "client")]
+impl<O> Pages<'_, O>
+where
+    O: PagedOperation,
+{
+    /// Sends the request for the next page and returns its results, or
+    /// `None` once all pages have been retrieved.
+    ///
+    /// No further pages are requested after an error.
+    pub async fn next(&mut self) -> Option<Result<Vec<O::Output>, Error>> {
+        let operation = self.pager.next_operation()?;
+
+        Some(match self.client.send(operation).await {
+            Ok(response) => self.pager.process(response),
+            Err(err) => {
+                self.pager.stop();
+                Err(err)
+            }
+        })
+    }
+
+    /// Retrieves the results of all remaining pages.
+    pub async fn collect_all(mut self) -> Result<Vec<O::Output>, Error> {
+        let mut results = Vec::new();
+        while let Some(page) = self.next().await {
+            results.extend(page?);
+        }
+
+        Ok(results)
+    }
+}
+
+/// The batches of changes to a folder, as returned by
+/// [`EwsClient::sync_folder_items`].
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub struct ItemChanges<'a> {
+    client: &'a EwsClient,
+    sync: ItemSync,
+}
+
+#[cfg(feature = "client")]
+impl ItemChanges<'_> {
+    /// Sends the request for the next batch of changes and returns it, or
+    /// `None` once the folder has been fully synchronized.
+    ///
+    /// No further batches are requested after an error.
+    pub async fn next(&mut self) -> Option<Result<ChangeBatch, Error>> {
+        let operation = self.sync.next_operation()?;
+
+        Some(match self.client.send(operation).await {
+            Ok(response) => self.sync.process(response),
+            Err(err) => {
+                self.sync.stop();
+                Err(err)
+            }
+        })
+    }
+
+    /// Gets the synchronization state following the last batch of changes.
+    pub fn sync_state(&self) -> Option<&SyncState> {
+        self.sync.sync_state()
+    }
+
+    /// Retrieves all remaining changes, along with the final synchronization
+    /// state.
+    pub async fn collect_all(mut self) -> Result<ChangeBatch, Error> {
+        let mut collected = ChangeBatch {
+            changes: Vec::new(),
+            sync_state: self.sync_state().cloned().unwrap_or_default(),
+        };
+        while let Some(batch) = self.next().await {
+            let batch = batch?;
+            collected.changes.extend(batch.changes);
+            collected.sync_state = batch.sync_state;
+        }
+
+        Ok(collected)
+    }
+}
+
+/// Gets the value of the `SOAPAction` header for the given operation.
+pub(crate) fn soap_action<O: Operation>(operation: &O) -> String {
+    format!("{MESSAGES_NS_URI}/{}", operation.name())
+}
+
+/// Gets the value of the `SOAPAction` header for the given raw operation.
+pub(crate) fn raw_soap_action(operation: &RawOperation) -> String {
+    format!("{MESSAGES_NS_URI}/{}", operation.name())
+}
+
+/// Serializes an operation into a complete SOAP request document.
+///
+/// Returns an [`Error::UnsupportedOperation`] if the operation is newer than
+/// the targeted server version, and any error found by
+/// [`Operation::validate`], as the server would reject the request. The
+/// operation is adapted to the server version with
+/// [`Operation::for_server_version`].
+pub(crate) fn build_request_body<O: Operation>(
+    operation: &O,
+    server_version: ExchangeServerVersion,
+    date_time_format: DateTimeFormat,
+    content_format: ContentFormat,
+    time_zone_context: Option<&TimeZoneContext>,
+    custom_headers: &[CustomHeader],
+) -> Result<Vec<u8>, Error> {
+    if !O::is_supported_by(server_version) {
+        return Err(Error::UnsupportedOperation {
+            operation: operation.name(),
+            min_version: O::MIN_VERSION,
+            server_version,
+        });
+    }
+    operation.validate()?;
+
+    let adapted = operation.for_server_version(server_version)?;
+    let operation = adapted.as_ref().unwrap_or(operation);
+
+    let mut envelope = Envelope::new_with_version(operation, server_version);
+    if let Some(time_zone_context) = time_zone_context {
+        envelope.headers.push(time_zone_context.header());
+    }
+    envelope.custom_headers.extend_from_slice(custom_headers);
+    envelope.content_format = content_format;
+    envelope.date_time_format = date_time_format;
+
+    envelope.write_document(|operation, writer| {
+        operation.serialize_as_element(writer, operation.name())?;
+
+        Ok(())
+    })
+}
+
+/// Serializes a raw operation into a complete SOAP request document.
+pub(crate) fn build_raw_request_body(
+    operation: &RawOperation,
+    server_version: ExchangeServerVersion,
+    time_zone_context: Option<&TimeZoneContext>,
+    custom_headers: &[CustomHeader],
+) -> Result<Vec<u8>, Error> {
+    let mut envelope = Envelope::new_with_version(operation.clone(), server_version);
+    if let Some(time_zone_context) = time_zone_context {
+        envelope.headers.push(time_zone_context.header());
+    }
+    envelope.custom_headers.extend_from_slice(custom_headers);
+
+    envelope.as_raw_xml_document()
+}
+
+/// Gets the identifiers of the items found by a search, skipping any
+/// returned without one.
+pub(crate) fn found_item_ids(items: Vec<RealItem>) -> Vec<BaseItemId> {
+    items
+        .iter()
+        .filter_map(|item| item.item_id().cloned().map(BaseItemId::from))
+        .collect()
+}
+
+/// Decodes the base64-encoded content of an attachment.
+pub(crate) fn decode_attachment_content(content: &str) -> Result<Vec<u8>, Error> {
+    // Servers may wrap the encoded content across lines.
+    let encoded: Vec<u8> = content
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+
+    STANDARD.decode(encoded).map_err(Error::AttachmentDecode)
+}
+
+/// Parses the body of an HTTP response from EWS.
+///
+/// EWS reports SOAP faults with a `500 Internal Server Error` status, so the
+/// body of such responses is parsed in order to surface the fault. Any other
+/// unsuccessful status is reported as [`Error::HttpStatus`].
+///
+/// Times without an offset are deserialized in the given time zone, or in UTC
+/// without one.
+pub(crate) fn parse_response<R: OperationResponse>(
+    status: u16,
+    body: &[u8],
+    time_zone_context: Option<&TimeZoneContext>,
+) -> Result<R, Error> {
+    parse_response_with(status, body, |body| {
+        Envelope::<R>::from_xml_document(body, time_zone_context).map(|envelope| envelope.body)
+    })
+}
+
+/// Parses the body of an HTTP response from EWS to a raw operation, as for
+/// [`parse_response`].
+pub(crate) fn parse_raw_response(
+    status: u16,
+    body: &[u8],
+    operation: &RawOperation,
+) -> Result<RawResponse, Error> {
+    parse_response_with(status, body, |body| {
+        operation.parse_response(body).map(|envelope| envelope.body)
+    })
+}
+
+/// Parses the body of an HTTP response from EWS with the given function,
+/// handling unsuccessful statuses as described for [`parse_response`].
+fn parse_response_with<T>(
+    status: u16,
+    body: &[u8],
+    parse: impl FnOnce(&[u8]) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let is_success = (200..300).contains(&status);
+    if !is_success && status != 500 {
+        return Err(Error::HttpStatus {
+            status,
+            body: body.to_vec(),
+        });
+    }
+
+    match parse(body) {
+        Ok(response) => Ok(response),
+        Err(err @ Error::RequestFault(_)) => Err(err),
+        Err(_) if !is_success => Err(Error::HttpStatus {
+            status,
+            body: body.to_vec(),
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        get_folder::{GetFolder, GetFolderResponse},
+        BaseFolderId, BaseShape, Error, FolderShape,
+    };
+
+    use super::{build_request_body, parse_response, soap_action, Credentials};
+
+    #[test]
+    fn credentials_authorization_header() {
+        let basic = Credentials::Basic {
+            username: "user@example.com".to_string(),
+            password: "hunter2".to_string(),
+        };
+        assert_eq!(
+            basic.static_authorization_header().as_deref(),
+            Some("Basic dXNlckBleGFtcGxlLmNvbTpodW50ZXIy")
+        );
+        assert!(!format!("{basic:?}").contains("hunter2"));
+
+        let bearer = Credentials::Bearer("token".to_string());
+        assert_eq!(
+            bearer.static_authorization_header().as_deref(),
+            Some("Bearer token")
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn token_provider_credentials_have_no_static_header() {
+        use std::sync::Arc;
+
+        use super::{TokenFuture, TokenProvider};
+
+        struct StaticTokenProvider;
+
+        impl TokenProvider for StaticTokenProvider {
+            fn token(&self) -> TokenFuture<'_> {
+                Box::pin(async { Ok("token".to_string()) })
+            }
+        }
+
+        let credentials = Credentials::TokenProvider(Arc::new(StaticTokenProvider));
+        assert_eq!(credentials.static_authorization_header(), None);
+        assert_eq!(format!("{credentials:?}"), "TokenProvider(..)");
+    }
+
+    #[test]
+    fn request_headers_and_body() {
+        let operation = GetFolder {
+            folder_shape: FolderShape {
+                base_shape: BaseShape::IdOnly,
+                additional_properties: None,
+            },
+            folder_ids: vec![BaseFolderId::DistinguishedFolderId {
+                id: "inbox".to_string(),
+                change_key: None,
+            }],
+        };
+
+        assert_eq!(
+            soap_action(&operation),
+            "http://schemas.microsoft.com/exchange/services/2006/messages/GetFolder"
+        );
+
+        let body = build_request_body(
+            &operation,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            &[],
+        )
+        .expect("serialization should succeed");
+        let body = String::from_utf8(body).expect("request body should be UTF-8");
+
+        assert!(body.starts_with(r#"<?xml version="1.0" encoding="utf-8"?><soap:Envelope"#));
+        assert!(body.contains(r#"<t:RequestServerVersion Version="Exchange2013_SP1"/>"#));
+        assert!(body.contains("<GetFolder "));
+    }
+
+    #[test]
+    fn request_body_includes_custom_headers() {
+        use xml_struct::XmlSerialize;
+
+        use crate::soap::CustomHeader;
+
+        #[derive(XmlSerialize)]
+        #[xml_struct(default_ns = "urn:example:gateway")]
+        struct RoutingHint {
+            #[xml_struct(attribute)]
+            region: &'static str,
+            tenant: &'static str,
+        }
+
+        let header = CustomHeader::new(
+            "RoutingHint",
+            &RoutingHint {
+                region: "emea",
+                tenant: "contoso & co",
+            },
+        )
+        .expect("header should serialize");
+
+        let operation = GetFolder::new(FolderShape::default(), vec![BaseFolderId::inbox()]);
+        let body = build_request_body(
+            &operation,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            &[header],
+        )
+        .expect("serialization should succeed");
+        let body = String::from_utf8(body).expect("request body should be UTF-8");
+
+        assert!(body.contains(concat!(
+            r#"<soap:Header><t:RequestServerVersion Version="Exchange2013_SP1"/>"#,
+            r#"<RoutingHint xmlns="urn:example:gateway" Region="emea"><Tenant>contoso &amp; co</Tenant></RoutingHint>"#,
+            "</soap:Header>",
+        )));
+    }
+
+    #[test]
+    fn request_body_rejects_unsupported_operation() {
+        use crate::{
+            get_server_time_zones::GetServerTimeZones, server_version::ExchangeServerVersion,
+            Operation,
+        };
+
+        assert!(GetFolder::is_supported_by(
+            ExchangeServerVersion::Exchange2007
+        ));
+        assert!(!GetServerTimeZones::is_supported_by(
+            ExchangeServerVersion::Exchange2007_SP1
+        ));
+
+        let result = build_request_body(
+            &GetServerTimeZones::default(),
+            ExchangeServerVersion::Exchange2007_SP1,
+            Default::default(),
+            Default::default(),
+            None,
+            &[],
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedOperation {
+                operation: "GetServerTimeZones",
+                min_version: ExchangeServerVersion::Exchange2010,
+                server_version: ExchangeServerVersion::Exchange2007_SP1,
+            })
+        ));
+
+        build_request_body(
+            &GetServerTimeZones::default(),
+            ExchangeServerVersion::Exchange2010,
+            Default::default(),
+            Default::default(),
+            None,
+            &[],
+        )
+        .expect("operation should be supported");
+    }
+
+    #[test]
+    fn request_body_rejects_invalid_operation() {
+        use crate::{get_item::GetItem, server_version::ExchangeServerVersion, ItemShape};
+
+        let result = build_request_body(
+            &GetItem::new(ItemShape::id_only(), Vec::new()),
+            ExchangeServerVersion::Exchange2013_SP1,
+            Default::default(),
+            Default::default(),
+            None,
+            &[],
+        );
+
+        match result {
+            Err(err @ Error::InvalidRequest { .. }) => assert_eq!(
+                err.to_string(),
+                "invalid GetItem request: `item_ids` must not be empty"
+            ),
+            other => panic!("expected an invalid request, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn request_body_uses_date_time_format() {
+        use crate::{create_item::CreateItem, DateTime, DateTimeFormat, RealItem, Task};
+
+        let due = time::OffsetDateTime::from_unix_timestamp(1_717_200_000)
+            .unwrap()
+            .to_offset(time::UtcOffset::from_hms(2, 0, 0).unwrap());
+        let operation = CreateItem::builder(vec![RealItem::Task(Task {
+            due_date: Some(DateTime(due)),
+            ..Task::new("Water the plants")
+        })])
+        .build();
+
+        let body = build_request_body(
+            &operation,
+            Default::default(),
+            DateTimeFormat::new().without_subseconds().utc(),
+            Default::default(),
+            None,
+            &[],
+        )
+        .expect("serialization should succeed");
+        let body = String::from_utf8(body).expect("request body should be UTF-8");
+
+        assert!(body.contains("<t:DueDate>2024-06-01T00:00:00Z</t:DueDate>"));
+    }
+
+    #[test]
+    fn request_body_targets_server_version() {
+        use crate::{
+            delete_item::DeleteItem, get_user_availability::SerializableTimeZone,
+            server_version::ExchangeServerVersion, BaseItemId, DeleteType, TimeZoneContext,
+            TimeZoneReference,
+        };
+
+        let context = TimeZoneContext::new(
+            TimeZoneReference::new("Pacific Standard Time"),
+            SerializableTimeZone::from_fixed_offset(time::UtcOffset::from_hms(-7, 0, 0).unwrap()),
+        );
+        let body = |version| {
+            let operation = DeleteItem {
+                delete_type: DeleteType::MoveToDeletedItems,
+                send_meeting_cancellations: None,
+                affected_task_occurrences: None,
+                suppress_read_receipts: Some(true),
+                item_ids: vec![BaseItemId::ItemId {
+                    id: "AAMkAD".to_string(),
+                    change_key: None,
+                }],
+            };
+            let body = build_request_body(
+                &operation,
+                version,
+                Default::default(),
+                Default::default(),
+                Some(&context),
+                &[],
+            )
+            .expect("serialization should succeed");
+
+            String::from_utf8(body).expect("request body should be UTF-8")
+        };
+
+        let body_2013 = body(ExchangeServerVersion::Exchange2013_SP1);
+        assert!(body_2013.contains(r#"SuppressReadReceipts="true""#));
+        assert!(body_2013.contains("<t:TimeZoneContext>"));
+
+        let body_2010 = body(ExchangeServerVersion::Exchange2010);
+        assert!(!body_2010.contains("SuppressReadReceipts"));
+        assert!(body_2010.contains("<t:TimeZoneContext>"));
+
+        let body_2007 = body(ExchangeServerVersion::Exchange2007_SP1);
+        assert!(!body_2007.contains("SuppressReadReceipts"));
+        assert!(!body_2007.contains("TimeZoneContext"));
+    }
+
+    #[test]
+    fn parse_response_status_handling() {
+        let err = parse_response::<GetFolderResponse>(401, b"", None)
+            .expect_err("unauthorized response should be an error");
+        assert!(matches!(err, Error::HttpStatus { status: 401, .. }));
+
+        let fault = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault><faultcode>a:ErrorSchemaValidation</faultcode><faultstring>The request failed schema validation.</faultstring></s:Fault></s:Body></s:Envelope>"#;
+        let err = parse_response::<GetFolderResponse>(500, fault.as_bytes(), None)
+            .expect_err("fault response should be an error");
+        assert!(matches!(err, Error::RequestFault(_)));
+
+        let err = parse_response::<GetFolderResponse>(500, b"Internal Server Error", None)
+            .expect_err("server error should be an error");
+        assert!(matches!(err, Error::HttpStatus { status: 500, .. }));
+    }
+
+    #[test]
+    fn time_zone_context_handling() {
+        use crate::{
+            get_item::GetItemResponse,
+            get_user_availability::{DayOfWeek, SerializableTimeZone, SerializableTimeZoneTime},
+            DateTime, RealItem, TimeZoneContext, TimeZoneReference,
+        };
+
+        let context = TimeZoneContext::new(
+            TimeZoneReference::new("Pacific Standard Time"),
+            SerializableTimeZone {
+                bias: 480,
+                standard_time: Some(SerializableTimeZoneTime {
+                    bias: 0,
+                    time: "02:00:00".to_string(),
+                    day_of_week: DayOfWeek::Sunday,
+                    month: 11,
+                    day_order: 1,
+                }),
+                daylight_time: Some(SerializableTimeZoneTime {
+                    bias: -60,
+                    time: "02:00:00".to_string(),
+                    day_of_week: DayOfWeek::Sunday,
+                    month: 3,
+                    day_order: 2,
+                }),
+            },
+        );
+
+        let operation = GetFolder {
+            folder_shape: FolderShape {
+                base_shape: BaseShape::Default,
+                additional_properties: None,
+            },
+            folder_ids: vec![BaseFolderId::DistinguishedFolderId {
+                id: "inbox".to_string(),
+                change_key: None,
+            }],
+        };
+        let body = build_request_body(
+            &operation,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Some(&context),
+            &[],
+        )
+        .expect("serialization should succeed");
+        let body = String::from_utf8(body).expect("request body should be UTF-8");
+        assert!(body.contains(
+            r#"<t:TimeZoneContext><t:TimeZoneDefinition Id="Pacific Standard Time"/></t:TimeZoneContext>"#
+        ));
+
+        let response = concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header/><s:Body>"#,
+            r#"<m:GetItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+            r#"<m:GetItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Items>"#,
+            r#"<t:CalendarItem><t:Start>2024-03-09T09:00:00</t:Start><t:End>2024-03-11T09:00:00</t:End></t:CalendarItem>"#,
+            r#"</m:Items></m:GetItemResponseMessage></m:ResponseMessages></m:GetItemResponse></s:Body></s:Envelope>"#,
+        );
+
+        let start_and_end = |time_zone_context| {
+            let response: GetItemResponse =
+                parse_response(200, response.as_bytes(), time_zone_context)
+                    .expect("response should parse");
+            let message = response
+                .response_messages
+                .response_messages
+                .into_iter()
+                .next()
+                .expect("response should have a message")
+                .into_result()
+                .expect("message should be successful");
+            match message.items.inner.into_iter().next() {
+                Some(RealItem::CalendarItem(item)) => (
+                    item.start.expect("item should have a start"),
+                    item.end.expect("item should have an end"),
+                ),
+                other => panic!("unexpected item {other:?}"),
+            }
+        };
+
+        // The item spans the start of daylight saving time, so its start is
+        // in standard time and its end in daylight saving time.
+        let start = time::OffsetDateTime::from_unix_timestamp(1_710_003_600).unwrap();
+        let end = time::OffsetDateTime::from_unix_timestamp(1_710_172_800).unwrap();
+        assert_eq!(
+            start_and_end(Some(&context)),
+            (DateTime(start), DateTime(end))
+        );
+        assert_eq!(
+            start_and_end(None),
+            (
+                DateTime(start - time::Duration::hours(8)),
+                DateTime(end - time::Duration::hours(7))
+            )
+        );
+    }
+}
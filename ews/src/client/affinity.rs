@@ -0,0 +1,169 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Support for keeping requests routed to the same Exchange backend server.
+//!
+//! Exchange Online load balances requests across many backend servers. Some
+//! state, notably streaming subscriptions, only exists on the server which
+//! created it, so requests touching that state must be routed to the same
+//! server. Exchange does this using the `X-AnchorMailbox` header, which names
+//! the mailbox whose server should handle the request, and affinity cookies
+//! such as `X-BackEndOverrideCookie`, which the server sets on responses and
+//! expects to be sent back on subsequent requests.
+//!
+//! See <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/how-to-maintain-affinity-between-group-of-subscriptions-and-mailbox-server>
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// The name of the header naming the mailbox whose server should handle a
+/// request.
+pub(crate) const X_ANCHOR_MAILBOX: &str = "X-AnchorMailbox";
+
+/// The name of the header carrying cookies in requests.
+pub(crate) const COOKIE: &str = "Cookie";
+
+/// The name of the header setting cookies in responses.
+pub(crate) const SET_COOKIE: &str = "Set-Cookie";
+
+/// A store of the cookies set by EWS responses, sent back on subsequent
+/// requests to maintain affinity with a backend server.
+///
+/// Clones of a store share the same cookies, so a store can be shared between
+/// clients, e.g. so that a client dedicated to a streaming subscription
+/// connects to the server which created the subscription.
+///
+/// The store is intended for a single EWS endpoint and does not track the
+/// domain, path or expiry of cookies beyond their removal by the server.
+#[derive(Clone, Default)]
+pub struct AffinityCookies {
+    cookies: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl AffinityCookies {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the value of the cookie with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.lock().get(name).cloned()
+    }
+
+    /// Removes all cookies from the store, e.g. when the subscriptions
+    /// requiring affinity have ended.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Records the cookies set by the values of the `Set-Cookie` headers in a
+    /// response.
+    pub(crate) fn record<'a>(&self, header_values: impl IntoIterator<Item = &'a str>) {
+        let mut cookies = self.lock();
+
+        for value in header_values {
+            let mut parts = value.split(';');
+            let Some((name, cookie_value)) = parts.next().and_then(|pair| pair.split_once('='))
+            else {
+                continue;
+            };
+
+            let name = name.trim();
+            let cookie_value = cookie_value.trim();
+            if name.is_empty() {
+                continue;
+            }
+
+            // Servers remove cookies by setting them with an expiry in the
+            // past, which is done with `Max-Age=0` or an empty value in
+            // practice.
+            let is_removed = cookie_value.is_empty()
+                || parts.any(|attribute| {
+                    attribute.split_once('=').is_some_and(|(key, value)| {
+                        key.trim().eq_ignore_ascii_case("Max-Age")
+                            && value.trim().parse::<i64>().is_ok_and(|age| age <= 0)
+                    })
+                });
+
+            if is_removed {
+                cookies.remove(name);
+            } else {
+                cookies.insert(name.to_string(), cookie_value.to_string());
+            }
+        }
+    }
+
+    /// Gets the value of the `Cookie` header for the next request, if any
+    /// cookies are stored.
+    pub(crate) fn header(&self) -> Option<String> {
+        let cookies = self.lock();
+        if cookies.is_empty() {
+            return None;
+        }
+
+        Some(
+            cookies
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, String>> {
+        // The map is always left in a consistent state, so a panic while
+        // holding the lock doesn't invalidate its contents.
+        self.cookies.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for AffinityCookies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Cookie values may identify the user's session, so only list names.
+        f.debug_set().entries(self.lock().keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AffinityCookies;
+
+    #[test]
+    fn record_and_send_affinity_cookies() {
+        let cookies = AffinityCookies::new();
+        assert_eq!(cookies.header(), None);
+
+        cookies.record([
+            "X-BackEndOverrideCookie=EXAMPLE1.example.com~1942083360; path=/EWS; secure",
+            "exchangecookie=e3d9a0b1; expires=Fri, 01-Jan-2027 00:00:00 GMT; path=/",
+            "malformed",
+        ]);
+
+        let shared = cookies.clone();
+        assert_eq!(
+            shared.header().as_deref(),
+            Some(
+                "X-BackEndOverrideCookie=EXAMPLE1.example.com~1942083360; exchangecookie=e3d9a0b1"
+            )
+        );
+
+        cookies.record([
+            "X-BackEndOverrideCookie=EXAMPLE2.example.com~1942083360; path=/EWS",
+            "exchangecookie=deleted; Max-Age=0; path=/",
+        ]);
+
+        assert_eq!(
+            shared.get("X-BackEndOverrideCookie").as_deref(),
+            Some("EXAMPLE2.example.com~1942083360")
+        );
+        assert_eq!(shared.get("exchangecookie"), None);
+
+        shared.clear();
+        assert_eq!(cookies.header(), None);
+    }
+}
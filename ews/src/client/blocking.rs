@@ -0,0 +1,764 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A synchronous client for sending EWS operations over HTTP.
+//!
+//! This is intended for command-line tools and tests, where pulling in an
+//! async runtime is more trouble than it's worth. It shares its request
+//! building and response parsing with the asynchronous client.
+
+use std::{
+    io::{self, Read},
+    thread,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use crate::{
+    delete_item::{DeleteItem, DeleteItemResponseMessage},
+    find_item::FindItem,
+    get_user_configuration::GetUserConfiguration,
+    move_item::MoveItem,
+    raw::{RawOperation, RawResponse},
+    registry::{self, OperationEntry},
+    server_version::ExchangeServerVersion,
+    soap::CustomHeader,
+    sync_folder_items::{ChangeBatch, ItemSync, SyncFolderItems},
+    update_item::{ChangeKeyRefresh, UpdateItem, UpdateItemResponseMessage},
+    AttachmentId, BaseFolderId, BaseItemId, BatchOperation, CategoryList, ContactPhoto,
+    ContentFormat, DateTimeFormat, Error, FolderHierarchy, FolderTree, ItemResponseMessage,
+    Operation, OperationResponse, PagedOperation, Pager, ResponseClass, SyncState, TimeZoneContext,
+};
+
+use super::{
+    authorization_header, build_raw_request_body, build_request_body, decode_attachment_content,
+    found_item_ids, parse_challenge, parse_raw_response, parse_response, raw_soap_action,
+    retry::BusyResponse, server_busy_back_off, soap_action, AffinityCookies,
+    ChallengeAuthenticator, Credentials, Negotiation, RetryPolicy, ServerVersions, ThrottlingState,
+    Tracer, AUTHORIZATION, CONTENT_TYPE, COOKIE, MAX_HANDSHAKE_ROUNDS, RETRY_AFTER, SET_COOKIE,
+    SOAP_ACTION, SOAP_CONTENT_TYPE, WWW_AUTHENTICATE, X_ANCHOR_MAILBOX,
+};
+
+/// A synchronous client for an EWS endpoint.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn run() -> Result<(), ews::Error> {
+/// use ews::client::{blocking::EwsClient, Credentials};
+/// use ews::get_folder::GetFolder;
+/// use ews::{BaseFolderId, BaseShape, FolderShape};
+///
+/// let client = EwsClient::new(
+///     "https://exchange.example.com/EWS/Exchange.asmx",
+///     Credentials::Basic {
+///         username: "user@example.com".to_string(),
+///         password: "password".to_string(),
+///     },
+/// );
+///
+/// let response = client.send(GetFolder {
+///     folder_shape: FolderShape {
+///         base_shape: BaseShape::Default,
+///         additional_properties: None,
+///     },
+///     folder_ids: vec![BaseFolderId::DistinguishedFolderId {
+///         id: "inbox".to_string(),
+///         change_key: None,
+///     }],
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct EwsClient {
+    agent: ureq::Agent,
+    endpoint: String,
+    credentials: Credentials,
+    server_version: ExchangeServerVersion,
+    date_time_format: DateTimeFormat,
+    content_format: ContentFormat,
+    time_zone_context: Option<TimeZoneContext>,
+    custom_headers: Vec<CustomHeader>,
+    retry_policy: RetryPolicy,
+    max_batch_size: Option<usize>,
+    tracer: Option<Tracer>,
+    anchor_mailbox: Option<String>,
+    affinity_cookies: AffinityCookies,
+    throttling: ThrottlingState,
+    server_versions: ServerVersions,
+}
+
+impl EwsClient {
+    /// Creates a new client for the EWS endpoint at the given URL.
+    pub fn new(endpoint: impl Into<String>, credentials: Credentials) -> Self {
+        Self::with_agent(ureq::Agent::new(), endpoint, credentials)
+    }
+
+    /// Creates a new client for the EWS endpoint at the given URL, sending
+    /// requests through an existing [`ureq::Agent`].
+    pub fn with_agent(
+        agent: ureq::Agent,
+        endpoint: impl Into<String>,
+        credentials: Credentials,
+    ) -> Self {
+        Self {
+            agent,
+            endpoint: endpoint.into(),
+            credentials,
+            server_version: ExchangeServerVersion::default(),
+            date_time_format: DateTimeFormat::default(),
+            content_format: ContentFormat::default(),
+            time_zone_context: None,
+            custom_headers: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            max_batch_size: None,
+            tracer: None,
+            anchor_mailbox: None,
+            affinity_cookies: AffinityCookies::new(),
+            throttling: ThrottlingState::default(),
+            server_versions: ServerVersions::new(),
+        }
+    }
+
+    /// Sets the schema version targeted by requests from this client.
+    ///
+    /// Requests which the server rejects for targeting a version it doesn't
+    /// support are retried targeting older versions, and later requests
+    /// target the version negotiated with the endpoint, as recorded in the
+    /// client's [`ServerVersions`].
+    pub fn with_server_version(mut self, server_version: ExchangeServerVersion) -> Self {
+        self.server_version = server_version;
+        self
+    }
+
+    /// Sets the format in which times are written in requests from this
+    /// client, for servers which reject the default format.
+    pub fn with_date_time_format(mut self, date_time_format: DateTimeFormat) -> Self {
+        self.date_time_format = date_time_format;
+        self
+    }
+
+    /// Sets the format in which the content of bodies and MIME content is
+    /// written in requests from this client.
+    pub fn with_content_format(mut self, content_format: ContentFormat) -> Self {
+        self.content_format = content_format;
+        self
+    }
+
+    /// Sets the time zone in which the server interprets times without an
+    /// offset from UTC in requests from this client, and in which such times
+    /// in its responses are deserialized.
+    pub fn with_time_zone_context(mut self, time_zone_context: TimeZoneContext) -> Self {
+        self.time_zone_context = Some(time_zone_context);
+        self
+    }
+
+    /// Adds a header to send in the SOAP envelope of each request from this
+    /// client, after the known EWS headers.
+    pub fn with_custom_header(mut self, header: CustomHeader) -> Self {
+        self.custom_headers.push(header);
+        self
+    }
+
+    /// Sets the policy for retrying requests which the server was too busy to
+    /// process.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the largest number of inputs sent in a single request by
+    /// [`send_batch`], in place of the [`MAX_BATCH_SIZE`] of each operation,
+    /// for servers whose throttling policy accepts more or fewer inputs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_batch_size` is zero.
+    ///
+    /// [`send_batch`]: EwsClient::send_batch
+    /// [`MAX_BATCH_SIZE`]: BatchOperation::MAX_BATCH_SIZE
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        assert!(max_batch_size > 0, "batch size must not be zero");
+        self.max_batch_size = Some(max_batch_size);
+        self
+    }
+
+    /// Sets a tracer to receive the raw contents of requests and responses.
+    pub fn with_tracer(mut self, tracer: Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Sets the mailbox sent in the `X-AnchorMailbox` header of each request,
+    /// so that Exchange Online routes requests to the server hosting it.
+    ///
+    /// This is usually the primary SMTP address of the mailbox being
+    /// accessed, and is required for streaming subscriptions.
+    pub fn with_anchor_mailbox(mut self, anchor_mailbox: impl Into<String>) -> Self {
+        self.anchor_mailbox = Some(anchor_mailbox.into());
+        self
+    }
+
+    /// Sets the store of affinity cookies used by this client.
+    ///
+    /// Each client otherwise has its own store, which is shared with its
+    /// clones. Cookies set by responses are recorded in the store and sent
+    /// with each subsequent request.
+    pub fn with_affinity_cookies(mut self, affinity_cookies: AffinityCookies) -> Self {
+        self.affinity_cookies = affinity_cookies;
+        self
+    }
+
+    /// Gets the store of affinity cookies used by this client.
+    pub fn affinity_cookies(&self) -> &AffinityCookies {
+        &self.affinity_cookies
+    }
+
+    /// Sets the state in which this client tracks its usage of the server's
+    /// throttling budgets.
+    ///
+    /// Each client otherwise has its own state, tracked against the limits of
+    /// the default Exchange Online policy and shared with its clones.
+    pub fn with_throttling_state(mut self, throttling: ThrottlingState) -> Self {
+        self.throttling = throttling;
+        self
+    }
+
+    /// Gets the state in which this client tracks its usage of the server's
+    /// throttling budgets.
+    pub fn throttling_state(&self) -> &ThrottlingState {
+        &self.throttling
+    }
+
+    /// Sets the store of schema versions negotiated with EWS endpoints used
+    /// by this client.
+    ///
+    /// Each client otherwise has its own store, which is shared with its
+    /// clones.
+    pub fn with_server_versions(mut self, server_versions: ServerVersions) -> Self {
+        self.server_versions = server_versions;
+        self
+    }
+
+    /// Gets the store of schema versions negotiated with EWS endpoints used
+    /// by this client.
+    pub fn server_versions(&self) -> &ServerVersions {
+        &self.server_versions
+    }
+
+    /// Sends an operation to the EWS endpoint and returns its response.
+    ///
+    /// Requests rejected because the server is too busy are retried according
+    /// to the client's [`RetryPolicy`], blocking the current thread while
+    /// waiting, and those rejected because the server doesn't support the
+    /// targeted schema version are retried targeting older versions.
+    pub fn send<O>(&self, operation: O) -> Result<O::Response, Error>
+    where
+        O: Operation,
+    {
+        let action = soap_action(&operation);
+        let build_body = |server_version| {
+            build_request_body(
+                &operation,
+                server_version,
+                self.date_time_format,
+                self.content_format,
+                self.time_zone_context.as_ref(),
+                &self.custom_headers,
+            )
+        };
+
+        self.send_negotiating_version(
+            Some(operation.name()),
+            &action,
+            build_body,
+            |status, body| parse_response(status, body, self.time_zone_context.as_ref()),
+        )
+    }
+
+    /// Sends an operation which this crate doesn't model yet, and returns the
+    /// XML of its response.
+    ///
+    /// Requests are retried like those of [`send`], except that the messages
+    /// of the response aren't inspected for `ErrorServerBusy` codes.
+    ///
+    /// [`send`]: EwsClient::send
+    pub fn send_raw(&self, operation: &RawOperation) -> Result<RawResponse, Error> {
+        let action = raw_soap_action(operation);
+        let build_body = |server_version| {
+            build_raw_request_body(
+                operation,
+                server_version,
+                self.time_zone_context.as_ref(),
+                &self.custom_headers,
+            )
+        };
+
+        let name = registry::find_operation(operation.name()).map(OperationEntry::operation_name);
+        self.send_negotiating_version(name, &action, build_body, |status, body| {
+            parse_raw_response(status, body, operation)
+        })
+    }
+
+    /// Sends a request built for the schema version negotiated with the
+    /// endpoint, retrying it targeting older versions while the server
+    /// rejects the targeted version, and parses its response with the given
+    /// function.
+    fn send_negotiating_version<R, B, F>(
+        &self,
+        name: Option<&'static str>,
+        action: &str,
+        build_body: B,
+        parse: F,
+    ) -> Result<R, Error>
+    where
+        R: BusyResponse,
+        B: Fn(ExchangeServerVersion) -> Result<Vec<u8>, Error>,
+        F: Fn(u16, &[u8]) -> Result<R, Error>,
+    {
+        let mut negotiation =
+            Negotiation::start(&self.server_versions, &self.endpoint, self.server_version);
+        let mut body = build_body(negotiation.version)?;
+
+        loop {
+            let result = self.send_with_retries(name, action, &body, &parse);
+
+            match negotiation.retry(&result, &build_body) {
+                Some(older_body) => body = older_body,
+                None => {
+                    negotiation.finish(&result, &self.server_versions, &self.endpoint);
+                    return result;
+                }
+            }
+        }
+    }
+
+    /// Sends a serialized request, retrying it according to the client's
+    /// [`RetryPolicy`], and parses its response with the given function.
+    ///
+    /// Only requests for the named operation, if any, are tracked against
+    /// the client's throttling budgets.
+    fn send_with_retries<R, F>(
+        &self,
+        name: Option<&'static str>,
+        action: &str,
+        body: &[u8],
+        parse: F,
+    ) -> Result<R, Error>
+    where
+        R: BusyResponse,
+        F: Fn(u16, &[u8]) -> Result<R, Error>,
+    {
+        let mut retries = 0;
+        loop {
+            let result = {
+                let _in_flight = name.map(|name| self.throttling.begin_request(name));
+                self.send_request(action, body, &parse)
+            };
+
+            if let Some(back_off) = server_busy_back_off(&result) {
+                self.throttling.record_throttled(back_off);
+            }
+
+            match self.retry_policy.retry_delay(&result, retries) {
+                Some(delay) => {
+                    log::debug!("server busy, retrying {action} in {delay:?}");
+                    thread::sleep(delay);
+                    retries += 1;
+                }
+                None => return result,
+            }
+        }
+    }
+
+    /// Sends a batch operation to the EWS endpoint, split into as many
+    /// requests as needed to stay within the batch size set with
+    /// [`with_max_batch_size`] or the operation's [`MAX_BATCH_SIZE`], and
+    /// returns the response messages for the whole batch in the order of its
+    /// inputs.
+    ///
+    /// Requests are sent one after the other. If any of them fails, the
+    /// messages for the requests already sent are discarded, so callers should
+    /// be prepared for part of the batch to have taken effect.
+    ///
+    /// [`with_max_batch_size`]: EwsClient::with_max_batch_size
+    /// [`MAX_BATCH_SIZE`]: BatchOperation::MAX_BATCH_SIZE
+    pub fn send_batch<O>(
+        &self,
+        operation: O,
+    ) -> Result<Vec<ResponseClass<<O::Response as OperationResponse>::Message>>, Error>
+    where
+        O: BatchOperation + Clone,
+    {
+        let max_batch_size = self.max_batch_size.unwrap_or(O::MAX_BATCH_SIZE);
+        let responses = operation
+            .split_batch(max_batch_size)
+            .into_iter()
+            .map(|chunk| self.send(chunk))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(O::merge_response_messages(responses))
+    }
+
+    /// Iterates over the pages of results of a paged operation, such as
+    /// [`FindItem`], sending a request for each page as it is needed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn run(client: ews::client::blocking::EwsClient) -> Result<(), ews::Error> {
+    /// use ews::{find_item::FindItem, BaseFolderId};
+    ///
+    /// for items in client.pages(FindItem::in_folder(BaseFolderId::inbox()).page(100).build()) {
+    ///     for item in items? {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`FindItem`]: crate::find_item::FindItem
+    pub fn pages<O>(&self, operation: O) -> Pages<'_, O>
+    where
+        O: PagedOperation,
+    {
+        Pages {
+            client: self,
+            pager: Pager::new(operation),
+        }
+    }
+
+    /// Retrieves the changes to a folder since the synchronization state of
+    /// the operation, sending requests until the server reports that all
+    /// changes have been returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn run(client: ews::client::blocking::EwsClient, state: Option<ews::SyncState>) -> Result<(), ews::Error> {
+    /// use ews::{sync_folder_items::SyncFolderItems, BaseFolderId, ItemShape};
+    ///
+    /// let mut operation = SyncFolderItems::new(ItemShape::sync_default(), BaseFolderId::inbox(), 100);
+    /// operation.sync_state = state;
+    ///
+    /// for batch in client.sync_folder_items(operation) {
+    ///     let batch = batch?;
+    ///     for change in batch.changes {
+    ///         // ...
+    ///     }
+    ///     // Persist `batch.sync_state` to resume from here later.
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sync_folder_items(&self, operation: SyncFolderItems) -> ItemChanges<'_> {
+        ItemChanges {
+            client: self,
+            sync: ItemSync::new(operation),
+        }
+    }
+
+    /// Sends an [`UpdateItem`] operation, retrying once with the current
+    /// change keys any changes which were rejected because of a missing or
+    /// outdated change key.
+    ///
+    /// See [`ChangeKeyRefresh`] for details.
+    pub fn update_item_refreshing_change_keys(
+        &self,
+        operation: UpdateItem,
+    ) -> Result<Vec<ResponseClass<UpdateItemResponseMessage>>, Error> {
+        let mut messages = self.send(operation.clone())?.into_response_messages();
+        let Some(mut refresh) = ChangeKeyRefresh::new(&operation, &messages) else {
+            return Ok(messages);
+        };
+
+        let current = self.send(refresh.get_item())?;
+        if let Some(retry) = refresh.retry_operation(current)? {
+            let response = self.send(retry)?;
+            refresh.merge(&mut messages, response)?;
+        }
+
+        Ok(messages)
+    }
+
+    /// Gets the photo of a contact, returning `None` if it has none.
+    ///
+    /// See [`ContactPhoto`] for details.
+    pub fn get_contact_photo(&self, contact_id: BaseItemId) -> Result<Option<Vec<u8>>, Error> {
+        let photo = ContactPhoto::new(contact_id);
+        let response = self.send(photo.get_item())?;
+        let Some(attachment_id) = photo.attachment_id(response)? else {
+            return Ok(None);
+        };
+
+        let response = self.send(photo.get_attachment(attachment_id))?;
+        photo
+            .content(response)?
+            .map(|content| decode_attachment_content(&content))
+            .transpose()
+    }
+
+    /// Sets the photo of a contact, replacing any existing one, and returns
+    /// the identifier of the new photo attachment.
+    ///
+    /// See [`ContactPhoto`] for details.
+    pub fn set_contact_photo(
+        &self,
+        contact_id: BaseItemId,
+        content: &[u8],
+        content_type: impl Into<String>,
+    ) -> Result<AttachmentId, Error> {
+        let mut photo = ContactPhoto::new(contact_id);
+        let response = self.send(photo.get_item())?;
+        if let Some(attachment_id) = photo.attachment_id(response)? {
+            let response = self.send(photo.delete_attachment(attachment_id))?;
+            photo.process_deletion(response)?;
+        }
+
+        let operation = photo.create_attachment(STANDARD.encode(content), content_type);
+        let response = self.send(operation)?;
+        photo.created_attachment_id(response)
+    }
+
+    /// Gets the master category list of the mailbox.
+    ///
+    /// See [`CategoryList`] for details.
+    pub fn get_category_list(&self) -> Result<CategoryList, Error> {
+        let response = self.send(GetUserConfiguration::category_list())?;
+        let category_list = response.single()?.user_configuration.category_list()?;
+
+        Ok(category_list.unwrap_or_default())
+    }
+
+    /// Gets the hierarchy of folders below the given folder, identifying the
+    /// common distinguished folders among them.
+    ///
+    /// See [`FolderHierarchy`] for details, e.g. to identify other
+    /// distinguished folders.
+    pub fn get_folder_tree(&self, root: BaseFolderId) -> Result<FolderTree, Error> {
+        let hierarchy = FolderHierarchy::new(root);
+        let mut folders = Vec::new();
+        for page in self.pages(hierarchy.find_folder()) {
+            folders.extend(page?);
+        }
+
+        let distinguished_folders = hierarchy
+            .get_distinguished_folders()
+            .map(|operation| self.send(operation))
+            .transpose()?;
+
+        Ok(hierarchy.build(folders, distinguished_folders))
+    }
+
+    /// Recovers the items found by a search, usually of the Recoverable Items
+    /// Deletions folder as created by [`FindItem::recoverable_items`], by
+    /// moving them to the given folder.
+    ///
+    /// Every page of results is fetched before the items are moved. The
+    /// response messages carry the new identifiers of the recovered items,
+    /// in the order the items were found.
+    pub fn recover_items(
+        &self,
+        search: FindItem,
+        to_folder_id: BaseFolderId,
+    ) -> Result<Vec<ResponseClass<ItemResponseMessage>>, Error> {
+        let item_ids = found_item_ids(self.pages(search).collect::<Result<Vec<_>, _>>()?.concat());
+        if item_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.send_batch(MoveItem::recover(item_ids, to_folder_id))
+    }
+
+    /// Purges the items found by a search, usually of the Recoverable Items
+    /// Deletions folder as created by [`FindItem::recoverable_items`], so
+    /// that they can no longer be recovered.
+    ///
+    /// See [`DeleteItem::purge`] for details.
+    pub fn purge_items(
+        &self,
+        search: FindItem,
+    ) -> Result<Vec<ResponseClass<DeleteItemResponseMessage>>, Error> {
+        let item_ids = found_item_ids(self.pages(search).collect::<Result<Vec<_>, _>>()?.concat());
+        if item_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.send_batch(DeleteItem::purge(item_ids))
+    }
+
+    /// Sends a serialized request to the EWS endpoint and parses its response
+    /// with the given function.
+    fn send_request<R>(
+        &self,
+        action: &str,
+        body: &[u8],
+        parse: impl Fn(u16, &[u8]) -> Result<R, Error>,
+    ) -> Result<R, Error> {
+        if let Some(tracer) = &self.tracer {
+            tracer.request(action, body);
+        }
+
+        let response = match &self.credentials {
+            Credentials::Challenge(authenticator) => {
+                self.post_with_handshake(authenticator.as_ref(), action, body)?
+            }
+            credentials => {
+                let authorization = credentials
+                    .static_authorization_header()
+                    .ok_or(Error::UnsupportedCredentials)?;
+
+                self.post(action, body, &authorization)?
+            }
+        };
+
+        let status = response.status();
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body)?;
+
+        if let Some(tracer) = &self.tracer {
+            tracer.response(status, &body);
+        }
+
+        parse(status, &body)
+    }
+
+    /// Sends a serialized request to the EWS endpoint, performing a
+    /// challenge-response authentication handshake along the way.
+    fn post_with_handshake(
+        &self,
+        authenticator: &dyn ChallengeAuthenticator,
+        action: &str,
+        body: &[u8],
+    ) -> Result<ureq::Response, Error> {
+        let scheme = authenticator.scheme();
+        let mut handshake = authenticator.start().map_err(Error::Handshake)?;
+        let mut challenge: Option<Vec<u8>> = None;
+        let mut rounds = 0;
+
+        loop {
+            let token = handshake
+                .step(challenge.as_deref())
+                .map_err(Error::Handshake)?;
+            let response = self.post(action, body, &authorization_header(scheme, &token))?;
+            rounds += 1;
+
+            if response.status() != 401 || rounds == MAX_HANDSHAKE_ROUNDS {
+                return Ok(response);
+            }
+
+            challenge = parse_challenge(scheme, response.all(WWW_AUTHENTICATE));
+            if challenge.is_none() {
+                // The server rejected the handshake outright.
+                return Ok(response);
+            }
+
+            // Read the body of the challenge response so that the connection
+            // can be reused for the next leg of the handshake.
+            io::copy(&mut response.into_reader(), &mut io::sink())?;
+        }
+    }
+
+    /// Sends a serialized request to the EWS endpoint.
+    fn post(
+        &self,
+        action: &str,
+        body: &[u8],
+        authorization: &str,
+    ) -> Result<ureq::Response, Error> {
+        let mut request = self
+            .agent
+            .post(&self.endpoint)
+            .set(CONTENT_TYPE, SOAP_CONTENT_TYPE)
+            .set(SOAP_ACTION, action)
+            .set(AUTHORIZATION, authorization);
+
+        if let Some(anchor_mailbox) = &self.anchor_mailbox {
+            request = request.set(X_ANCHOR_MAILBOX, anchor_mailbox);
+        }
+        if let Some(cookies) = self.affinity_cookies.header() {
+            request = request.set(COOKIE, &cookies);
+        }
+
+        // `ureq` treats 4xx and 5xx statuses as errors, but EWS uses them to
+        // deliver SOAP faults, so we need the response either way.
+        let response = match request.send_bytes(body) {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(Error::HttpTransport(Box::new(transport)))
+            }
+        };
+
+        self.affinity_cookies.record(response.all(SET_COOKIE));
+        if let Some(retry_after) = response.header(RETRY_AFTER) {
+            self.throttling.record_retry_after(retry_after);
+        }
+
+        Ok(response)
+    }
+}
+
+/// The pages of results of a [`PagedOperation`], as returned by
+/// [`EwsClient::pages`].
+///
+/// No further pages are requested after an error.
+#[derive(Debug)]
+pub struct Pages<'a, O> {
+    client: &'a EwsClient,
+    pager: Pager<O>,
+}
+
+impl<O> Iterator for Pages<'_, O>
+where
+    O: PagedOperation,
+{
+    type Item = Result<Vec<O::Output>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let operation = self.pager.next_operation()?;
+
+        Some(match self.client.send(operation) {
+            Ok(response) => self.pager.process(response),
+            Err(err) => {
+                self.pager.stop();
+                Err(err)
+            }
+        })
+    }
+}
+
+/// The batches of changes to a folder, as returned by
+/// [`EwsClient::sync_folder_items`].
+///
+/// No further batches are requested after an error.
+#[derive(Debug)]
+pub struct ItemChanges<'a> {
+    client: &'a EwsClient,
+    sync: ItemSync,
+}
+
+impl ItemChanges<'_> {
+    /// Gets the synchronization state following the last batch of changes.
+    pub fn sync_state(&self) -> Option<&SyncState> {
+        self.sync.sync_state()
+    }
+}
+
+impl Iterator for ItemChanges<'_> {
+    type Item = Result<ChangeBatch, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let operation = self.sync.next_operation()?;
+
+        Some(match self.client.send(operation) {
+            Ok(response) => self.sync.process(response),
+            Err(err) => {
+                self.sync.stop();
+                Err(err)
+            }
+        })
+    }
+}
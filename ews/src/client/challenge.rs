@@ -0,0 +1,108 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Support for challenge-response HTTP authentication schemes.
+//!
+//! On-premises Exchange servers frequently require NTLM or Negotiate
+//! (Kerberos/SPNEGO) authentication. This crate does not implement either
+//! scheme itself; instead, library clients can plug in an implementation (e.g.
+//! one backed by SSPI on Windows or GSSAPI elsewhere) via
+//! [`ChallengeAuthenticator`].
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+use super::TokenError;
+
+/// The maximum number of requests sent as part of a single handshake.
+///
+/// NTLM requires two legs and Kerberos usually one, so this leaves room for
+/// schemes requiring an extra round trip without looping forever on a server
+/// which keeps challenging us.
+pub(crate) const MAX_HANDSHAKE_ROUNDS: usize = 3;
+
+/// An implementation of a challenge-response authentication scheme, such as
+/// NTLM or Negotiate.
+///
+/// For each request, the client [`start`]s a new [`Handshake`] and sends the
+/// token it produces in the `Authorization` header. Whenever the server
+/// responds with `401 Unauthorized` and a challenge for the same scheme in the
+/// `WWW-Authenticate` header, the challenge is passed back to the handshake and
+/// the request is sent again with the resulting token.
+///
+/// # Connection reuse
+///
+/// NTLM authenticates the underlying connection rather than individual
+/// requests, so every leg of the handshake must be sent over the same
+/// connection. The clients in this module read the body of each challenge
+/// response so that the connection is returned to the pool in a reusable
+/// state, but the HTTP client itself must also be configured to keep a single
+/// connection alive per host, e.g. with
+/// `reqwest::ClientBuilder::pool_max_idle_per_host(1)` and
+/// `reqwest::ClientBuilder::http1_only()`, as NTLM does not work over HTTP/2.
+///
+/// [`start`]: ChallengeAuthenticator::start
+pub trait ChallengeAuthenticator: Send + Sync {
+    /// Gets the name of the scheme as it appears in the `Authorization` and
+    /// `WWW-Authenticate` headers, e.g. `NTLM` or `Negotiate`.
+    fn scheme(&self) -> &str;
+
+    /// Starts a new handshake for a request.
+    fn start(&self) -> Result<Box<dyn Handshake>, TokenError>;
+}
+
+/// The state of a single challenge-response authentication handshake.
+pub trait Handshake: Send {
+    /// Produces the next token to send to the server.
+    ///
+    /// `challenge` is `None` for the first leg of the handshake, and otherwise
+    /// contains the decoded challenge sent by the server in response to the
+    /// previous token.
+    fn step(&mut self, challenge: Option<&[u8]>) -> Result<Vec<u8>, TokenError>;
+}
+
+/// Gets the value of the `Authorization` header carrying a handshake token.
+pub(crate) fn authorization_header(scheme: &str, token: &[u8]) -> String {
+    format!("{scheme} {}", STANDARD.encode(token))
+}
+
+/// Extracts the challenge for the given scheme from the values of the
+/// `WWW-Authenticate` headers in a response, if any.
+///
+/// A bare scheme name without a challenge (as servers send to advertise the
+/// schemes they support) is not considered a challenge.
+pub(crate) fn parse_challenge<'a>(
+    scheme: &str,
+    header_values: impl IntoIterator<Item = &'a str>,
+) -> Option<Vec<u8>> {
+    header_values
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .filter_map(|entry| entry.trim().split_once(' '))
+        .filter(|(name, _)| name.eq_ignore_ascii_case(scheme))
+        .find_map(|(_, data)| STANDARD.decode(data.trim()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{authorization_header, parse_challenge};
+
+    #[test]
+    fn handshake_headers() {
+        assert_eq!(authorization_header("NTLM", b"token"), "NTLM dG9rZW4=");
+
+        assert_eq!(
+            parse_challenge("NTLM", ["Negotiate", "NTLM"]),
+            None,
+            "advertised schemes should not be treated as challenges"
+        );
+        assert_eq!(
+            parse_challenge("NTLM", ["Negotiate oZ4=", "ntlm Y2hhbGxlbmdl"]),
+            Some(b"challenge".to_vec())
+        );
+        assert_eq!(
+            parse_challenge("Negotiate", ["Basic realm=\"exchange\", Negotiate oZ4="]),
+            Some(vec![0xa1, 0x9e])
+        );
+    }
+}
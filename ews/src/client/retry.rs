@@ -0,0 +1,276 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Retrying requests rejected because the server is too busy.
+//!
+//! Exchange Online throttles clients aggressively, rejecting requests with an
+//! `ErrorServerBusy` response code (usually alongside a requested back-off) or
+//! an HTTP `503 Service Unavailable` status. Both clients in this module retry
+//! such requests according to a [`RetryPolicy`].
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use crate::{
+    message_xml::ServerBusy, raw::RawResponse, response::ResponseCode, Error, MessageXml,
+    OperationResponse, ResponseClass,
+};
+
+/// A policy for retrying requests which the server was too busy to process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of times a single request is retried.
+    pub max_retries: u32,
+
+    /// How long to wait before retrying when the server doesn't specify a
+    /// back-off, e.g. for HTTP `503 Service Unavailable` responses.
+    pub default_back_off: Duration,
+
+    /// The longest the client is willing to wait before retrying, regardless
+    /// of the back-off requested by the server.
+    pub max_back_off: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a policy which never retries requests.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Determines whether a request should be retried given its outcome and
+    /// the number of retries already made, and if so, how long to wait first.
+    pub(crate) fn retry_delay<R: BusyResponse>(
+        &self,
+        result: &Result<R, Error>,
+        retries: u32,
+    ) -> Option<Duration> {
+        if retries >= self.max_retries {
+            return None;
+        }
+
+        let back_off = server_busy_back_off(result)?;
+        let delay = back_off.unwrap_or(self.default_back_off);
+
+        // Jitter is added before limiting the delay, so that the client never
+        // waits longer than the maximum.
+        Some((delay + jitter(delay)).min(self.max_back_off))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            default_back_off: Duration::from_secs(1),
+            max_back_off: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Determines whether the outcome of a request indicates that the server was
+/// too busy to process it.
+///
+/// Returns `None` if the request should not be retried, or the back-off
+/// requested by the server, if any, otherwise.
+pub(crate) fn server_busy_back_off<R: BusyResponse>(
+    result: &Result<R, Error>,
+) -> Option<Option<Duration>> {
+    match result {
+        Err(Error::RequestFault(fault)) => {
+            let detail = fault.detail.as_ref()?;
+            let is_busy = detail.response_code == Some(ResponseCode::ErrorServerBusy)
+                || matches!(detail.message_xml, Some(MessageXml::ServerBusy(_)));
+
+            is_busy.then(|| back_off_from_message_xml(detail.message_xml.as_ref()))
+        }
+
+        #[cfg(any(feature = "client", feature = "blocking"))]
+        Err(Error::HttpStatus { status: 503, .. }) => Some(None),
+
+        Ok(response) => response.busy_back_off(),
+
+        _ => None,
+    }
+}
+
+/// A successful response which may nonetheless indicate that the server was
+/// too busy to process the request.
+pub(crate) trait BusyResponse {
+    /// Returns `None` if the request should not be retried, or the back-off
+    /// requested by the server, if any, otherwise.
+    fn busy_back_off(&self) -> Option<Option<Duration>>;
+}
+
+impl<R: OperationResponse> BusyResponse for R {
+    fn busy_back_off(&self) -> Option<Option<Duration>> {
+        // Only retry when no message in the response succeeded, so that we
+        // don't repeat operations which have already taken effect.
+        let messages = self.response_messages();
+        let all_busy = !messages.is_empty()
+            && messages.iter().all(|message| {
+                matches!(
+                    message,
+                    ResponseClass::Error(err) if err.response_code == ResponseCode::ErrorServerBusy
+                )
+            });
+
+        all_busy.then(|| {
+            messages
+                .iter()
+                .filter_map(|message| match message {
+                    ResponseClass::Error(err) => {
+                        back_off_from_message_xml(err.message_xml.as_ref())
+                    }
+                    _ => None,
+                })
+                .max()
+        })
+    }
+}
+
+impl BusyResponse for RawResponse {
+    fn busy_back_off(&self) -> Option<Option<Duration>> {
+        // The messages of raw responses aren't parsed, so only faults and
+        // HTTP statuses are considered.
+        None
+    }
+}
+
+fn back_off_from_message_xml(message_xml: Option<&MessageXml>) -> Option<Duration> {
+    match message_xml {
+        Some(MessageXml::ServerBusy(ServerBusy {
+            back_off_milliseconds,
+        })) => Some(Duration::from_millis((*back_off_milliseconds).into())),
+        _ => None,
+    }
+}
+
+/// Gets a random duration of up to a quarter of `delay`, so that clients
+/// throttled at the same time don't all retry at the same time.
+fn jitter(delay: Duration) -> Duration {
+    // `RandomState` is randomly seeded, which is plenty for our purposes and
+    // saves pulling in a dependency for random number generation.
+    let random = RandomState::new().build_hasher().finish();
+    let max_jitter_millis = (delay.as_millis() / 4) as u64;
+
+    Duration::from_millis(random % (max_jitter_millis + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        get_folder::{GetFolderResponse, GetFolderResponseMessage},
+        message_xml::ServerBusy,
+        response::{ResponseCode, ResponseError},
+        soap::{Fault, FaultDetail},
+        Error, Folders, MessageXml, ResponseClass, ResponseMessages,
+    };
+
+    use super::RetryPolicy;
+
+    fn server_busy_error(back_off_milliseconds: u32) -> ResponseError {
+        ResponseError {
+            message_text: "The server cannot service this request right now.".to_string(),
+            response_code: ResponseCode::ErrorServerBusy,
+            message_xml: Some(MessageXml::ServerBusy(ServerBusy {
+                back_off_milliseconds,
+            })),
+        }
+    }
+
+    #[test]
+    fn retry_server_busy_fault() {
+        let policy = RetryPolicy::default();
+
+        let detail = FaultDetail {
+            response_code: Some(ResponseCode::ErrorServerBusy),
+            message_xml: Some(MessageXml::ServerBusy(ServerBusy {
+                back_off_milliseconds: 2000,
+            })),
+            ..Default::default()
+        };
+        let result: Result<GetFolderResponse, _> = Err(Error::RequestFault(Box::new(Fault {
+            faultcode: "a:ErrorServerBusy".to_string(),
+            faultstring: "The server cannot service this request right now.".to_string(),
+            faultactor: None,
+            detail: Some(detail),
+        })));
+
+        let delay = policy
+            .retry_delay(&result, 0)
+            .expect("server busy fault should be retried");
+        assert!(delay >= Duration::from_millis(2000) && delay <= Duration::from_millis(2500));
+
+        assert_eq!(
+            policy.retry_delay(&result, policy.max_retries),
+            None,
+            "retries should stop once the budget is spent"
+        );
+        assert_eq!(RetryPolicy::none().retry_delay(&result, 0), None);
+    }
+
+    #[test]
+    fn retry_server_busy_response_messages() {
+        let policy = RetryPolicy {
+            max_back_off: Duration::from_secs(10),
+            ..Default::default()
+        };
+
+        let busy: Result<GetFolderResponse, Error> = Ok(GetFolderResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![
+                    ResponseClass::Error(server_busy_error(500)),
+                    ResponseClass::Error(server_busy_error(30000)),
+                ],
+            },
+        });
+
+        let delay = policy
+            .retry_delay(&busy, 0)
+            .expect("server busy response should be retried");
+        assert_eq!(
+            delay,
+            Duration::from_secs(10),
+            "delay should be capped by the policy"
+        );
+
+        // Jitter doesn't take the delay beyond the limit either.
+        let near_limit: Result<GetFolderResponse, Error> = Ok(GetFolderResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![ResponseClass::Error(server_busy_error(9000))],
+            },
+        });
+        for _ in 0..100 {
+            let delay = policy
+                .retry_delay(&near_limit, 0)
+                .expect("server busy response should be retried");
+            assert!(delay >= Duration::from_secs(9) && delay <= Duration::from_secs(10));
+        }
+
+        let partial: Result<GetFolderResponse, Error> = Ok(GetFolderResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![
+                    ResponseClass::Success(GetFolderResponseMessage {
+                        folders: Folders { inner: vec![] },
+                    }),
+                    ResponseClass::Error(server_busy_error(500)),
+                ],
+            },
+        });
+
+        assert_eq!(
+            policy.retry_delay(&partial, 0),
+            None,
+            "partially successful responses should not be retried"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Client-side tracking of the budgets enforced by EWS throttling policies.
+//!
+//! Exchange limits the number of concurrent requests, subscriptions and
+//! search results for each user, and rejects requests exceeding those limits.
+//! A [`ThrottlingState`] tracks the client's usage of these budgets, along
+//! with any back-off requested by the server, so that long-running jobs such
+//! as synchronizing a large mailbox can slow down before the server starts
+//! rejecting their requests.
+//!
+//! See <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/ews-throttling-in-exchange>
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// The name of the header with which servers may indicate how long to wait
+/// before sending further requests.
+pub(crate) const RETRY_AFTER: &str = "Retry-After";
+
+/// The limits of an EWS throttling policy relevant to clients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThrottlingLimits {
+    /// The maximum number of requests a user may have in flight at once
+    /// (`EWSMaxConcurrency`).
+    pub max_concurrent_requests: usize,
+
+    /// The maximum number of active subscriptions a user may have at once
+    /// (`EWSMaxSubscriptions`).
+    pub max_subscriptions: usize,
+
+    /// The maximum number of items or folders returned by a single `FindItem`
+    /// or `FindFolder` request (`EWSFindCountLimit`).
+    pub find_count_limit: usize,
+}
+
+impl Default for ThrottlingLimits {
+    /// Gets the limits of the default throttling policy in Exchange Online.
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 27,
+            max_subscriptions: 20,
+            find_count_limit: 1000,
+        }
+    }
+}
+
+/// The client's usage of the budgets of an EWS throttling policy.
+///
+/// Clones of a state share the same counters, so a state can be shared
+/// between clients acting on behalf of the same user.
+#[derive(Clone, Default)]
+pub struct ThrottlingState {
+    limits: ThrottlingLimits,
+    usage: Arc<Mutex<Usage>>,
+}
+
+#[derive(Default)]
+struct Usage {
+    in_flight: HashMap<&'static str, usize>,
+    subscriptions: usize,
+    back_off_until: Option<Instant>,
+    throttled_responses: u64,
+}
+
+impl ThrottlingState {
+    /// Creates a state tracking usage against the given limits.
+    pub fn new(limits: ThrottlingLimits) -> Self {
+        Self {
+            limits,
+            usage: Default::default(),
+        }
+    }
+
+    /// Gets the limits against which usage is tracked.
+    pub fn limits(&self) -> ThrottlingLimits {
+        self.limits
+    }
+
+    /// Gets the number of requests currently in flight.
+    pub fn in_flight_requests(&self) -> usize {
+        self.lock().in_flight.values().sum()
+    }
+
+    /// Gets the number of requests for the named operation, e.g. `FindItem`,
+    /// currently in flight.
+    pub fn in_flight_operations(&self, operation: &str) -> usize {
+        self.lock().in_flight.get(operation).copied().unwrap_or(0)
+    }
+
+    /// Gets the number of subscriptions registered as active.
+    pub fn active_subscriptions(&self) -> usize {
+        self.lock().subscriptions
+    }
+
+    /// Registers a new active subscription.
+    ///
+    /// Returns `false` without registering the subscription if doing so would
+    /// exceed the policy's limit, in which case the server would be expected to
+    /// reject the subscription.
+    pub fn register_subscription(&self) -> bool {
+        let mut usage = self.lock();
+        if usage.subscriptions >= self.limits.max_subscriptions {
+            return false;
+        }
+
+        usage.subscriptions += 1;
+        true
+    }
+
+    /// Releases a subscription previously registered as active.
+    pub fn release_subscription(&self) {
+        let mut usage = self.lock();
+        usage.subscriptions = usage.subscriptions.saturating_sub(1);
+    }
+
+    /// Gets how much longer the server asked the client to wait before
+    /// sending further requests, if at all.
+    pub fn back_off_remaining(&self) -> Option<Duration> {
+        let back_off_until = self.lock().back_off_until?;
+
+        back_off_until
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Gets the number of responses indicating that the server was throttling
+    /// the client.
+    pub fn throttled_responses(&self) -> u64 {
+        self.lock().throttled_responses
+    }
+
+    /// Determines whether another request can be sent without exceeding the
+    /// concurrency limit or ignoring a back-off requested by the server.
+    pub fn has_capacity(&self) -> bool {
+        self.in_flight_requests() < self.limits.max_concurrent_requests
+            && self.back_off_remaining().is_none()
+    }
+
+    /// Limits the requested page size of a `FindItem` or `FindFolder` request
+    /// to the number of results the server will return.
+    pub fn find_page_size(&self, requested: usize) -> usize {
+        requested.min(self.limits.find_count_limit)
+    }
+
+    /// Records the start of a request for the named operation, which ends
+    /// when the returned guard is dropped.
+    pub(crate) fn begin_request(&self, operation: &'static str) -> InFlightRequest {
+        *self.lock().in_flight.entry(operation).or_default() += 1;
+
+        InFlightRequest {
+            state: self.clone(),
+            operation,
+        }
+    }
+
+    /// Records a response indicating that the server was throttling the
+    /// client, along with the back-off it requested, if any.
+    pub(crate) fn record_throttled(&self, back_off: Option<Duration>) {
+        self.lock().throttled_responses += 1;
+
+        if let Some(back_off) = back_off {
+            self.record_back_off(back_off);
+        }
+    }
+
+    /// Records that the server asked the client to wait for the given duration
+    /// before sending further requests.
+    pub(crate) fn record_back_off(&self, back_off: Duration) {
+        let mut usage = self.lock();
+        let until = Instant::now() + back_off;
+
+        usage.back_off_until = Some(match usage.back_off_until {
+            Some(current) => current.max(until),
+            None => until,
+        });
+    }
+
+    /// Records the back-off indicated by the value of a `Retry-After` header,
+    /// if it is given in seconds.
+    ///
+    /// Exchange does not use the HTTP date form of the header, so it is
+    /// ignored.
+    pub(crate) fn record_retry_after(&self, value: &str) {
+        if let Ok(seconds) = value.trim().parse() {
+            self.record_back_off(Duration::from_secs(seconds));
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Usage> {
+        // The counters are always left in a consistent state, so a panic while
+        // holding the lock doesn't invalidate them.
+        self.usage.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for ThrottlingState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThrottlingState")
+            .field("limits", &self.limits)
+            .field("in_flight_requests", &self.in_flight_requests())
+            .field("active_subscriptions", &self.active_subscriptions())
+            .field("back_off_remaining", &self.back_off_remaining())
+            .finish()
+    }
+}
+
+/// A guard marking a request as in flight until dropped.
+pub(crate) struct InFlightRequest {
+    state: ThrottlingState,
+    operation: &'static str,
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        let mut usage = self.state.lock();
+        if let Some(count) = usage.in_flight.get_mut(self.operation) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                usage.in_flight.remove(self.operation);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ThrottlingLimits, ThrottlingState};
+
+    #[test]
+    fn track_throttling_budgets() {
+        let state = ThrottlingState::new(ThrottlingLimits {
+            max_concurrent_requests: 2,
+            max_subscriptions: 1,
+            find_count_limit: 100,
+        });
+
+        let first = state.begin_request("FindItem");
+        assert!(state.has_capacity());
+
+        let second = state.clone().begin_request("GetItem");
+        assert_eq!(state.in_flight_requests(), 2);
+        assert_eq!(state.in_flight_operations("FindItem"), 1);
+        assert!(!state.has_capacity(), "concurrency limit should be reached");
+
+        drop(first);
+        drop(second);
+        assert_eq!(state.in_flight_requests(), 0);
+        assert!(state.has_capacity());
+
+        assert!(state.register_subscription());
+        assert!(!state.register_subscription());
+        state.release_subscription();
+        assert_eq!(state.active_subscriptions(), 0);
+
+        assert_eq!(state.find_page_size(250), 100);
+
+        state.record_retry_after("not a number");
+        assert_eq!(state.back_off_remaining(), None);
+
+        state.record_retry_after("30");
+        state.record_throttled(Some(Duration::from_secs(5)));
+        let remaining = state
+            .back_off_remaining()
+            .expect("back-off should be active");
+        assert!(remaining > Duration::from_secs(29) && remaining <= Duration::from_secs(30));
+        assert!(!state.has_capacity());
+        assert_eq!(state.throttled_responses(), 1);
+    }
+}
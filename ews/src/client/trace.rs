@@ -0,0 +1,231 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Hooks for observing the raw XML exchanged with EWS.
+//!
+//! Diagnosing protocol issues often requires looking at exactly what was sent
+//! to and received from the server. A [`Tracer`] attached to a client is
+//! handed the serialized bytes of each request and the raw bytes of each
+//! response before they are parsed.
+
+use std::{fmt, sync::Arc};
+
+use quick_xml::{
+    events::{BytesText, Event},
+    name::{Namespace, ResolveResult},
+    NsReader, Writer,
+};
+
+use crate::SOAP_NS_URI;
+
+/// The text substituted for the contents of redacted elements.
+const REDACTED: &str = "[redacted]";
+
+/// The local names of elements whose contents are redacted when body
+/// redaction is enabled.
+///
+/// These are the elements which may carry the contents of messages and
+/// attachments. Elements in the SOAP namespace are never redacted, so that
+/// `soap:Body` is left alone.
+const REDACTED_ELEMENTS: &[&[u8]] = &[
+    b"Body",
+    b"Content",
+    b"MimeContent",
+    b"NewBodyContent",
+    b"TextBody",
+    b"UniqueBody",
+];
+
+/// An HTTP exchange with EWS, as observed by a [`TraceHook`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub enum TraceEvent<'a> {
+    /// A request about to be sent.
+    Request {
+        /// The value of the request's `SOAPAction` header.
+        action: &'a str,
+
+        /// The serialized request document.
+        body: &'a [u8],
+    },
+
+    /// A response received from the server, before it is parsed.
+    Response {
+        /// The HTTP status of the response.
+        status: u16,
+
+        /// The raw response body.
+        body: &'a [u8],
+    },
+}
+
+/// A callback receiving the raw contents of requests and responses.
+///
+/// This is implemented for any `Fn(TraceEvent)` closure.
+pub trait TraceHook: Send + Sync {
+    /// Handles a single traced event.
+    fn trace(&self, event: TraceEvent<'_>);
+}
+
+impl<F> TraceHook for F
+where
+    F: Fn(TraceEvent<'_>) + Send + Sync,
+{
+    fn trace(&self, event: TraceEvent<'_>) {
+        self(event)
+    }
+}
+
+/// Passes the raw contents of requests and responses to a [`TraceHook`].
+///
+/// # Example
+///
+/// ```
+/// use ews::client::{TraceEvent, Tracer};
+///
+/// let tracer = Tracer::new(|event: TraceEvent<'_>| match event {
+///     TraceEvent::Request { action, body } => {
+///         eprintln!("{action}: {}", String::from_utf8_lossy(body))
+///     }
+///     TraceEvent::Response { status, body } => {
+///         eprintln!("{status}: {}", String::from_utf8_lossy(body))
+///     }
+///     _ => {}
+/// })
+/// .redact_bodies(true);
+/// ```
+#[derive(Clone)]
+pub struct Tracer {
+    hook: Arc<dyn TraceHook>,
+    redact_bodies: bool,
+}
+
+impl Tracer {
+    /// Creates a tracer passing events to the given hook.
+    pub fn new(hook: impl TraceHook + 'static) -> Self {
+        Self {
+            hook: Arc::new(hook),
+            redact_bodies: false,
+        }
+    }
+
+    /// Sets whether the contents of message bodies, MIME content and
+    /// attachments are replaced with a placeholder before being passed to the
+    /// hook.
+    ///
+    /// The structure of the document is preserved, so that traces remain
+    /// useful for diagnosing protocol issues without exposing the contents of
+    /// the user's mail.
+    pub fn redact_bodies(mut self, redact_bodies: bool) -> Self {
+        self.redact_bodies = redact_bodies;
+        self
+    }
+
+    /// Traces a request about to be sent.
+    pub(crate) fn request(&self, action: &str, body: &[u8]) {
+        let body = self.prepare(body);
+        self.hook.trace(TraceEvent::Request {
+            action,
+            body: &body,
+        });
+    }
+
+    /// Traces a response received from the server.
+    pub(crate) fn response(&self, status: u16, body: &[u8]) {
+        let body = self.prepare(body);
+        self.hook.trace(TraceEvent::Response {
+            status,
+            body: &body,
+        });
+    }
+
+    fn prepare(&self, body: &[u8]) -> Vec<u8> {
+        if self.redact_bodies {
+            redact(body)
+        } else {
+            body.to_vec()
+        }
+    }
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("redact_bodies", &self.redact_bodies)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Replaces the contents of elements which may carry message or attachment
+/// contents with a placeholder.
+///
+/// Documents which cannot be parsed are replaced entirely, since we cannot
+/// tell which parts of them are sensitive.
+fn redact(document: &[u8]) -> Vec<u8> {
+    redact_elements(document).unwrap_or_else(|_| REDACTED.as_bytes().to_vec())
+}
+
+fn redact_elements(document: &[u8]) -> Result<Vec<u8>, quick_xml::Error> {
+    let mut reader = NsReader::from_reader(document);
+    let mut writer = Writer::new(Vec::with_capacity(document.len()));
+
+    // The depth of nesting within a redacted element, if any.
+    let mut redacted_depth = 0usize;
+
+    loop {
+        let (namespace, event) = reader.read_resolved_event()?;
+        match event {
+            Event::Eof => break,
+            Event::Start(start) => {
+                if redacted_depth > 0 {
+                    redacted_depth += 1;
+                } else if REDACTED_ELEMENTS.contains(&start.local_name().as_ref())
+                    && !matches!(namespace, ResolveResult::Bound(Namespace(ns)) if ns == SOAP_NS_URI.as_bytes())
+                {
+                    redacted_depth = 1;
+                    writer.write_event(Event::Start(start))?;
+                    writer.write_event(Event::Text(BytesText::new(REDACTED)))?;
+                    continue;
+                }
+
+                if redacted_depth == 0 {
+                    writer.write_event(Event::Start(start))?;
+                }
+            }
+            Event::End(end) => {
+                redacted_depth = redacted_depth.saturating_sub(1);
+
+                if redacted_depth == 0 {
+                    writer.write_event(Event::End(end))?;
+                }
+            }
+            event => {
+                if redacted_depth == 0 {
+                    writer.write_event(event)?;
+                }
+            }
+        }
+    }
+
+    Ok(writer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact;
+
+    #[test]
+    fn redact_message_contents() {
+        let document = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><m:Items><t:Message><t:Subject>Hello</t:Subject><t:Body BodyType="HTML"><p>secret</p></t:Body><t:MimeContent CharacterSet="UTF-8">c2VjcmV0</t:MimeContent></t:Message></m:Items></s:Body></s:Envelope>"#;
+
+        let redacted = String::from_utf8(redact(document.as_bytes())).unwrap();
+
+        assert_eq!(
+            redacted,
+            r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><m:Items><t:Message><t:Subject>Hello</t:Subject><t:Body BodyType="HTML">[redacted]</t:Body><t:MimeContent CharacterSet="UTF-8">[redacted]</t:MimeContent></t:Message></m:Items></s:Body></s:Envelope>"#
+        );
+
+        assert_eq!(redact(b"<a><b></a>"), b"[redacted]");
+    }
+}
@@ -0,0 +1,251 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Negotiation of the schema version targeted by requests.
+//!
+//! Servers reject requests targeting a schema version newer than they
+//! support with an `ErrorInvalidServerVersion` or
+//! `ErrorIncorrectSchemaVersion` fault. Hybrid deployments route requests to
+//! both Exchange Online and on-premises servers, so the version accepted by
+//! an endpoint isn't always known in advance. Both clients in this module
+//! retry such requests targeting the next older version until the server
+//! accepts one, and remember the negotiated version in [`ServerVersions`].
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{server_version::ExchangeServerVersion, Error};
+
+/// A store of the schema versions negotiated with EWS endpoints.
+///
+/// Requests to an endpoint with a negotiated version target that version
+/// when it's older than the one configured on the client, so that only the
+/// first request to a server needs to be retried. Clones of a store share the same
+/// versions, so a store can be shared between clients for several
+/// endpoints.
+#[derive(Clone, Default)]
+pub struct ServerVersions {
+    versions: Arc<Mutex<HashMap<String, ExchangeServerVersion>>>,
+}
+
+impl ServerVersions {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the version negotiated with the given endpoint, if any.
+    pub fn get(&self, endpoint: &str) -> Option<ExchangeServerVersion> {
+        self.lock().get(endpoint).copied()
+    }
+
+    /// Records the version accepted by the given endpoint, e.g. to restore a
+    /// version negotiated in a previous session.
+    pub fn set(&self, endpoint: impl Into<String>, version: ExchangeServerVersion) {
+        self.lock().insert(endpoint.into(), version);
+    }
+
+    /// Forgets the versions negotiated with all endpoints, e.g. after the
+    /// servers have been upgraded.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, ExchangeServerVersion>> {
+        // The map is always left in a consistent state, so a panic while
+        // holding the lock doesn't invalidate its contents.
+        self.versions.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for ServerVersions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.lock().iter()).finish()
+    }
+}
+
+/// Determines whether the outcome of a request indicates that the server
+/// doesn't accept the schema version it targeted.
+pub(crate) fn is_server_version_rejected<R>(result: &Result<R, Error>) -> bool {
+    match result {
+        Err(Error::RequestFault(fault)) => fault
+            .detail
+            .as_ref()
+            .and_then(|detail| detail.response_code)
+            .is_some_and(|code| code.is_server_version_rejected()),
+
+        _ => false,
+    }
+}
+
+/// The schema version targeted by a request, and whether it was
+/// negotiated down from the version first targeted.
+pub(crate) struct Negotiation {
+    pub(crate) version: ExchangeServerVersion,
+    pub(crate) downgraded: bool,
+}
+
+impl Negotiation {
+    /// Starts negotiating from the version previously negotiated with the
+    /// endpoint, or the configured version if there is none or it is older.
+    ///
+    /// A store may be shared with clients configured for newer versions, so
+    /// a negotiated version never raises the version a client targets.
+    pub(crate) fn start(
+        versions: &ServerVersions,
+        endpoint: &str,
+        configured: ExchangeServerVersion,
+    ) -> Self {
+        Self {
+            version: versions
+                .get(endpoint)
+                .map_or(configured, |negotiated| negotiated.min(configured)),
+            downgraded: false,
+        }
+    }
+
+    /// Determines the version to retry a request with given its outcome,
+    /// building the request body for that version.
+    ///
+    /// Returns `None` if the request shouldn't be retried, including when
+    /// the request can't be built for an older version, e.g. because the
+    /// operation isn't supported by it.
+    pub(crate) fn retry<R, B>(
+        &mut self,
+        result: &Result<R, Error>,
+        build_body: B,
+    ) -> Option<Vec<u8>>
+    where
+        B: Fn(ExchangeServerVersion) -> Result<Vec<u8>, Error>,
+    {
+        if !is_server_version_rejected(result) {
+            return None;
+        }
+
+        let older = self.version.previous()?;
+        let body = build_body(older).ok()?;
+
+        log::debug!(
+            "server rejected version {:?}, retrying with {older:?}",
+            self.version
+        );
+        self.version = older;
+        self.downgraded = true;
+
+        Some(body)
+    }
+
+    /// Records the version accepted by the endpoint, if it was negotiated.
+    pub(crate) fn finish<R>(
+        self,
+        result: &Result<R, Error>,
+        versions: &ServerVersions,
+        endpoint: &str,
+    ) {
+        if self.downgraded && result.is_ok() {
+            versions.set(endpoint, self.version);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        response::ResponseCode,
+        server_version::ExchangeServerVersion,
+        soap::{Fault, FaultDetail},
+        Error,
+    };
+
+    use super::{Negotiation, ServerVersions};
+
+    const ENDPOINT: &str = "https://exchange.example.com/EWS/Exchange.asmx";
+
+    fn fault(response_code: ResponseCode) -> Result<(), Error> {
+        Err(Error::RequestFault(Box::new(Fault {
+            faultcode: "a:ErrorInvalidServerVersion".to_string(),
+            faultstring: "The specified server version is invalid.".to_string(),
+            faultactor: None,
+            detail: Some(FaultDetail {
+                response_code: Some(response_code),
+                ..Default::default()
+            }),
+        })))
+    }
+
+    #[test]
+    fn negotiate_server_version() {
+        let versions = ServerVersions::new();
+        let mut negotiation =
+            Negotiation::start(&versions, ENDPOINT, ExchangeServerVersion::Exchange2013_SP1);
+
+        let build_body = |version: ExchangeServerVersion| {
+            if version < ExchangeServerVersion::Exchange2010 {
+                return Err(Error::UnsupportedOperation {
+                    operation: "GetServerTimeZones",
+                    min_version: ExchangeServerVersion::Exchange2010,
+                    server_version: version,
+                });
+            }
+
+            Ok(String::from(version).into_bytes())
+        };
+
+        assert_eq!(negotiation.retry(&Ok(()), build_body), None);
+        assert_eq!(
+            negotiation.retry(&fault(ResponseCode::ErrorServerBusy), build_body),
+            None
+        );
+        assert_eq!(
+            negotiation.retry(&fault(ResponseCode::ErrorInvalidServerVersion), build_body),
+            Some(b"Exchange2013".to_vec())
+        );
+        assert_eq!(
+            negotiation.retry(
+                &fault(ResponseCode::ErrorIncorrectSchemaVersion),
+                build_body
+            ),
+            Some(b"Exchange2010_SP2".to_vec())
+        );
+
+        negotiation.finish(&Ok(()), &versions, ENDPOINT);
+        assert_eq!(
+            versions.get(ENDPOINT),
+            Some(ExchangeServerVersion::Exchange2010_SP2)
+        );
+
+        // Later requests start from the negotiated version, and stop once the
+        // operation isn't supported by an older one.
+        let mut negotiation =
+            Negotiation::start(&versions, ENDPOINT, ExchangeServerVersion::Exchange2013_SP1);
+        assert_eq!(negotiation.version, ExchangeServerVersion::Exchange2010_SP2);
+        negotiation.version = ExchangeServerVersion::Exchange2010;
+        assert_eq!(
+            negotiation.retry(&fault(ResponseCode::ErrorInvalidServerVersion), build_body),
+            None
+        );
+
+        versions.clear();
+        assert_eq!(versions.get(ENDPOINT), None);
+    }
+
+    #[test]
+    fn negotiated_version_does_not_raise_configured_version() {
+        let versions = ServerVersions::new();
+        versions.set(ENDPOINT, ExchangeServerVersion::Exchange2013);
+
+        // A client configured for an older version keeps targeting it, even
+        // if another client negotiated a newer one with the endpoint.
+        let negotiation =
+            Negotiation::start(&versions, ENDPOINT, ExchangeServerVersion::Exchange2010);
+        assert_eq!(negotiation.version, ExchangeServerVersion::Exchange2010);
+
+        let negotiation =
+            Negotiation::start(&versions, ENDPOINT, ExchangeServerVersion::Exchange2013_SP1);
+        assert_eq!(negotiation.version, ExchangeServerVersion::Exchange2013);
+    }
+}
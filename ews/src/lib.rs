@@ -4,30 +4,54 @@
 
 use thiserror::Error;
 
-#[cfg(test)]
-mod test_utils;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 mod types;
 
 pub use types::*;
 
+pub mod prelude;
+
+pub mod properties;
+
+pub mod restriction;
+
 #[cfg(feature = "interop")]
 pub mod interop;
 
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub mod client;
+
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum Error {
     #[error("failed to serialize structure as XML")]
     Serialize(#[from] xml_struct::Error),
 
-    #[error("failed to deserialize structure from XML")]
+    #[error("failed to deserialize structure from XML at `{}`: {}", .0.path(), .0.inner())]
     Deserialize(#[from] serde_path_to_error::Error<quick_xml::DeError>),
 
+    // Kept separate from `Deserialize` so that errors in deserializing a full
+    // response document can carry an excerpt of the document for debugging.
+    #[error("failed to deserialize SOAP envelope at `{}`: {} (near `{snippet}`)", .source.path(), .source.inner())]
+    DeserializeEnvelope {
+        source: serde_path_to_error::Error<quick_xml::DeError>,
+
+        /// An excerpt of the XML document ending where the deserializer
+        /// stopped.
+        snippet: String,
+    },
+
     #[error("invalid XML document")]
     InvalidXml(#[from] quick_xml::Error),
 
     #[error("unexpected response body")]
     UnexpectedResponse(Vec<u8>),
 
+    #[error("unsupported document encoding: {0}")]
+    UnsupportedEncoding(String),
+
     // The `Fault` is boxed so as to keep the in-memory size of the enum itself
     // relatively low.
     #[error("a fault occurred in the request")]
@@ -35,4 +59,102 @@ pub enum Error {
 
     #[error("unknown server version: {0}")]
     UnknownServerVersion(String),
+
+    #[error("invalid extended field URI: {0}")]
+    InvalidExtendedFieldUri(String),
+
+    #[error("invalid conversation index: {0}")]
+    InvalidConversationIndex(String),
+
+    #[error("invalid global object ID: {0}")]
+    InvalidGlobalObjectId(String),
+
+    #[error("invalid EWS identifier: {0}")]
+    InvalidId(String),
+
+    #[error("invalid category list: {0}")]
+    InvalidCategoryList(String),
+
+    #[error("unknown distinguished folder name: {0}")]
+    UnknownDistinguishedFolder(String),
+
+    #[cfg(feature = "iana-zones")]
+    #[error("invalid time zone: {0}")]
+    InvalidTimeZone(String),
+
+    #[error("invalid merged free/busy status: {0:?}")]
+    InvalidMergedFreeBusy(char),
+
+    #[cfg(feature = "client")]
+    #[error("failed to send HTTP request")]
+    Http(#[from] reqwest::Error),
+
+    #[cfg(feature = "blocking")]
+    #[error("failed to send HTTP request")]
+    HttpTransport(#[from] Box<ureq::Transport>),
+
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    #[error("unexpected HTTP status {status}")]
+    HttpStatus { status: u16, body: Vec<u8> },
+
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    #[error("the provided credentials are not supported by this client")]
+    UnsupportedCredentials,
+
+    #[cfg(feature = "client")]
+    #[error("failed to get an authentication token")]
+    TokenProvider(#[source] client::TokenError),
+
+    #[cfg(any(feature = "client", feature = "blocking"))]
+    #[error("authentication handshake failed")]
+    Handshake(#[source] client::TokenError),
+
+    #[error("expected {expected} response messages, got {actual}")]
+    ResponseMessageCountMismatch { expected: usize, actual: usize },
+
+    #[error(
+        "paging did not advance: the page at offset {offset} was followed by offset {next_offset}"
+    )]
+    PagingStalled { offset: u32, next_offset: u32 },
+
+    #[error("the server returned an error response")]
+    Response(#[from] ResponseError),
+
+    #[error(
+        "{operation} requires {min_version:?} or later, but requests target {server_version:?}"
+    )]
+    UnsupportedOperation {
+        operation: &'static str,
+        min_version: server_version::ExchangeServerVersion,
+        server_version: server_version::ExchangeServerVersion,
+    },
+
+    #[error("invalid {operation} request: {reason}")]
+    InvalidRequest {
+        operation: &'static str,
+        reason: String,
+    },
+
+    #[error("the server did not return the identifier of the created attachment")]
+    MissingAttachmentId,
+
+    #[error("attachment content is not valid base64")]
+    AttachmentDecode(#[source] base64::DecodeError),
+
+    #[error("the response did not contain attachment content")]
+    MissingAttachmentContent,
+
+    #[error("MIME content is not valid base64")]
+    MimeDecode(#[from] base64::DecodeError),
+
+    #[cfg(feature = "mime")]
+    #[error("MIME content could not be parsed as a message")]
+    InvalidMime,
+
+    #[cfg(feature = "plain-text")]
+    #[error("HTML body could not be converted to plain text: {0}")]
+    InvalidHtml(String),
 }
@@ -0,0 +1,66 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The types needed for most requests, for glob importing.
+//!
+//! ```
+//! use ews::prelude::*;
+//!
+//! let get_folder = GetFolder {
+//!     folder_shape: FolderShape {
+//!         base_shape: BaseShape::Default,
+//!         additional_properties: None,
+//!     },
+//!     folder_ids: vec![BaseFolderId::inbox()],
+//! };
+//!
+//! let envelope = Envelope::new(get_folder);
+//! ```
+
+pub use crate::copy_folder::{CopyFolder, CopyFolderResponse};
+pub use crate::copy_item::{CopyItem, CopyItemResponse};
+pub use crate::create_attachment::{CreateAttachment, CreateAttachmentResponse};
+pub use crate::create_folder::{CreateFolder, CreateFolderResponse};
+pub use crate::create_item::{CreateItem, CreateItemResponse};
+pub use crate::delete_attachment::{DeleteAttachment, DeleteAttachmentResponse};
+pub use crate::delete_folder::{DeleteFolder, DeleteFolderResponse};
+pub use crate::delete_item::{DeleteItem, DeleteItemResponse};
+pub use crate::empty_folder::{EmptyFolder, EmptyFolderResponse};
+pub use crate::find_folder::{FindFolder, FindFolderResponse};
+pub use crate::find_item::{FindItem, FindItemResponse};
+pub use crate::get_attachment::{GetAttachment, GetAttachmentResponse};
+pub use crate::get_folder::{GetFolder, GetFolderResponse};
+pub use crate::get_item::{GetItem, GetItemResponse};
+pub use crate::get_mail_tips::{GetMailTips, GetMailTipsResponse};
+pub use crate::get_server_time_zones::{GetServerTimeZones, GetServerTimeZonesResponse};
+pub use crate::get_user_availability::{GetUserAvailability, GetUserAvailabilityResponse};
+pub use crate::get_user_configuration::{GetUserConfiguration, GetUserConfigurationResponse};
+pub use crate::mark_as_read::{MarkAsRead, MarkAsReadResponse};
+pub use crate::move_folder::{MoveFolder, MoveFolderResponse};
+pub use crate::move_item::{MoveItem, MoveItemResponse};
+pub use crate::reply_to_item::{ReplyToItem, ReplyToItemResponse};
+pub use crate::resolve_names::{ResolveNames, ResolveNamesResponse};
+pub use crate::send_item::{SendItem, SendItemResponse};
+pub use crate::sync_folder_hierarchy::{SyncFolderHierarchy, SyncFolderHierarchyResponse};
+pub use crate::sync_folder_items::{SyncFolderItems, SyncFolderItemsResponse};
+pub use crate::update_folder::{UpdateFolder, UpdateFolderResponse};
+pub use crate::update_item::{UpdateItem, UpdateItemResponse};
+
+pub use crate::{
+    recipients,
+    response::ResponseCode,
+    server_version::ExchangeServerVersion,
+    soap::{Envelope, Fault},
+    ArrayOfRecipients, BaseFolderId, BaseItemId, BaseShape, BatchOperation, Body, BodyType,
+    DeleteType, DistinguishedFolderName, Error, FolderId, FolderShape, FolderTraversal, ItemId,
+    ItemLike, ItemShape, ItemTraversal, Mailbox, Message, MessageDisposition, Operation,
+    OperationResponse, PagedOperation, PathToElement, RealItem, Recipient, ResponseClass,
+    ResponseError, Restriction, SyncState,
+};
+
+#[cfg(any(feature = "client", feature = "blocking"))]
+pub use crate::client::{Credentials, RetryPolicy};
+
+#[cfg(feature = "client")]
+pub use crate::client::EwsClient;
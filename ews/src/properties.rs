@@ -0,0 +1,296 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Identifiers for commonly needed extended MAPI properties.
+//!
+//! Properties are named after their canonical MAPI names, using the `PR_`
+//! form for tagged properties and the `PID_LID_` or `PID_NAME_` forms for
+//! named properties.
+//! Each can be converted into an [`ExtendedFieldURI`] for reading or setting
+//! the property, or into a [`PathToElement`] for requesting it in a shape.
+//!
+//! # Example
+//!
+//! ```
+//! use ews::{properties, ItemShape};
+//!
+//! let shape = ItemShape {
+//!     additional_properties: Some(vec![
+//!         properties::PR_MESSAGE_FLAGS.into(),
+//!         properties::PID_LID_GLOBAL_OBJECT_ID.into(),
+//!     ]),
+//!     ..ItemShape::id_only()
+//! };
+//! ```
+//!
+//! See <https://learn.microsoft.com/en-us/office/client-developer/outlook/mapi/mapi-properties>
+
+use crate::{DistinguishedPropertySet, ExtendedFieldURI, PathToElement, PropertyType};
+
+/// The identity of an extended MAPI property, which unlike an
+/// [`ExtendedFieldURI`] can be declared as a constant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapiProperty {
+    key: PropertyKey,
+    property_type: PropertyType,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PropertyKey {
+    Tag(u16),
+    Id(DistinguishedPropertySet, u32),
+    Name(DistinguishedPropertySet, &'static str),
+}
+
+impl MapiProperty {
+    /// Declares a property given by its 16-bit property tag.
+    pub const fn tagged(tag: u16, property_type: PropertyType) -> Self {
+        Self {
+            key: PropertyKey::Tag(tag),
+            property_type,
+        }
+    }
+
+    /// Declares a named property given by its dispatch ID within a
+    /// well-known property set.
+    pub const fn with_id(
+        property_set: DistinguishedPropertySet,
+        id: u32,
+        property_type: PropertyType,
+    ) -> Self {
+        Self {
+            key: PropertyKey::Id(property_set, id),
+            property_type,
+        }
+    }
+
+    /// Declares a named property given by its name within a well-known
+    /// property set.
+    pub const fn with_name(
+        property_set: DistinguishedPropertySet,
+        name: &'static str,
+        property_type: PropertyType,
+    ) -> Self {
+        Self {
+            key: PropertyKey::Name(property_set, name),
+            property_type,
+        }
+    }
+
+    /// Gets the type of the property's value.
+    pub const fn property_type(&self) -> PropertyType {
+        self.property_type
+    }
+
+    /// Gets the identifier for the property.
+    pub fn field_uri(&self) -> ExtendedFieldURI {
+        let (distinguished_property_set_id, property_tag, property_name, property_id) =
+            match self.key {
+                PropertyKey::Tag(tag) => (None, Some(format!("0x{tag:04X}")), None, None),
+                PropertyKey::Id(set, id) => (Some(set), None, None, Some(id.to_string())),
+                PropertyKey::Name(set, name) => (Some(set), None, Some(name.to_string()), None),
+            };
+
+        ExtendedFieldURI {
+            distinguished_property_set_id,
+            property_set_id: None,
+            property_tag,
+            property_name,
+            property_id,
+            property_type: self.property_type,
+        }
+    }
+}
+
+impl From<MapiProperty> for ExtendedFieldURI {
+    fn from(value: MapiProperty) -> Self {
+        value.field_uri()
+    }
+}
+
+impl From<MapiProperty> for PathToElement {
+    fn from(value: MapiProperty) -> Self {
+        value.field_uri().into()
+    }
+}
+
+/// The status flags of a message, e.g. whether it has been read or is a
+/// draft (`PidTagMessageFlags`).
+pub const PR_MESSAGE_FLAGS: MapiProperty = MapiProperty::tagged(0x0E07, PropertyType::Integer);
+
+/// The size of a message in bytes (`PidTagMessageSize`).
+pub const PR_MESSAGE_SIZE: MapiProperty = MapiProperty::tagged(0x0E08, PropertyType::Integer);
+
+/// Whether a message has attachments (`PidTagHasAttachments`).
+pub const PR_HASATTACH: MapiProperty = MapiProperty::tagged(0x0E1B, PropertyType::Boolean);
+
+/// The time at which a message was delivered (`PidTagMessageDeliveryTime`).
+pub const PR_MESSAGE_DELIVERY_TIME: MapiProperty =
+    MapiProperty::tagged(0x0E06, PropertyType::SystemTime);
+
+/// The time at which a message was submitted by its sender
+/// (`PidTagClientSubmitTime`).
+pub const PR_CLIENT_SUBMIT_TIME: MapiProperty =
+    MapiProperty::tagged(0x0039, PropertyType::SystemTime);
+
+/// The `Message-ID` header of a message (`PidTagInternetMessageId`).
+pub const PR_INTERNET_MESSAGE_ID: MapiProperty = MapiProperty::tagged(0x1035, PropertyType::String);
+
+/// The `In-Reply-To` header of a message (`PidTagInReplyToId`).
+pub const PR_IN_REPLY_TO_ID: MapiProperty = MapiProperty::tagged(0x1042, PropertyType::String);
+
+/// The `References` header of a message (`PidTagInternetReferences`).
+pub const PR_INTERNET_REFERENCES: MapiProperty = MapiProperty::tagged(0x1039, PropertyType::String);
+
+/// The full transport headers of a message received over the Internet
+/// (`PidTagTransportMessageHeaders`).
+pub const PR_TRANSPORT_MESSAGE_HEADERS: MapiProperty =
+    MapiProperty::tagged(0x007D, PropertyType::String);
+
+/// The SMTP address of the sender of a message (`PidTagSenderSmtpAddress`).
+pub const PR_SENDER_SMTP_ADDRESS: MapiProperty = MapiProperty::tagged(0x5D01, PropertyType::String);
+
+/// The follow-up flag status of a message (`PidTagFlagStatus`).
+pub const PR_FLAG_STATUS: MapiProperty = MapiProperty::tagged(0x1090, PropertyType::Integer);
+
+/// The color of the follow-up flag of a message (`PidTagFollowupIcon`).
+pub const PR_FOLLOWUP_ICON: MapiProperty = MapiProperty::tagged(0x1095, PropertyType::Integer);
+
+/// The icon displayed for a message, e.g. replied or forwarded
+/// (`PidTagIconIndex`).
+pub const PR_ICON_INDEX: MapiProperty = MapiProperty::tagged(0x1080, PropertyType::Integer);
+
+/// The last action taken on a message, e.g. reply or forward
+/// (`PidTagLastVerbExecuted`).
+pub const PR_LAST_VERB_EXECUTED: MapiProperty = MapiProperty::tagged(0x1081, PropertyType::Integer);
+
+/// The time of the last action taken on a message
+/// (`PidTagLastVerbExecutionTime`).
+pub const PR_LAST_VERB_EXECUTION_TIME: MapiProperty =
+    MapiProperty::tagged(0x1082, PropertyType::SystemTime);
+
+/// The entry identifier of an item or folder (`PidTagEntryId`).
+pub const PR_ENTRYID: MapiProperty = MapiProperty::tagged(0x0FFF, PropertyType::Binary);
+
+/// The search key of a message, shared by its copies (`PidTagSearchKey`).
+pub const PR_SEARCH_KEY: MapiProperty = MapiProperty::tagged(0x300B, PropertyType::Binary);
+
+/// The conversation index of a message (`PidTagConversationIndex`).
+pub const PR_CONVERSATION_INDEX: MapiProperty = MapiProperty::tagged(0x0071, PropertyType::Binary);
+
+/// The content identifier of an attachment (`PidTagAttachContentId`).
+pub const PR_ATTACH_CONTENT_ID: MapiProperty = MapiProperty::tagged(0x3712, PropertyType::String);
+
+/// The class of items a folder contains, e.g. `IPF.Note`
+/// (`PidTagContainerClass`).
+pub const PR_CONTAINER_CLASS: MapiProperty = MapiProperty::tagged(0x3613, PropertyType::String);
+
+/// Whether an item or folder is hidden from the user (`PidTagAttributeHidden`).
+pub const PR_ATTR_HIDDEN: MapiProperty = MapiProperty::tagged(0x10F4, PropertyType::Boolean);
+
+/// The path of a folder from the root of the mailbox, separated by `U+FFFE`.
+///
+/// This property is specific to Exchange.
+pub const PR_FOLDER_PATH: MapiProperty = MapiProperty::tagged(0x66B5, PropertyType::String);
+
+/// The identifier of a meeting shared by all of its instances and messages
+/// (`PidLidGlobalObjectId`).
+pub const PID_LID_GLOBAL_OBJECT_ID: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Meeting,
+    0x0003,
+    PropertyType::Binary,
+);
+
+/// The identifier of a meeting, without the instance date of an exception
+/// (`PidLidCleanGlobalObjectId`).
+pub const PID_LID_CLEAN_GLOBAL_OBJECT_ID: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Meeting,
+    0x0023,
+    PropertyType::Binary,
+);
+
+/// Whether a reminder is set on an item (`PidLidReminderSet`).
+pub const PID_LID_REMINDER_SET: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Common,
+    0x8503,
+    PropertyType::Boolean,
+);
+
+/// The time at which the reminder of an item is next due
+/// (`PidLidReminderSignalTime`).
+pub const PID_LID_REMINDER_SIGNAL_TIME: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Common,
+    0x8560,
+    PropertyType::SystemTime,
+);
+
+/// The follow-up action of a flagged item, e.g. "Follow up"
+/// (`PidLidFlagRequest`).
+pub const PID_LID_FLAG_REQUEST: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Common,
+    0x8530,
+    PropertyType::String,
+);
+
+/// The start date of a task (`PidLidTaskStartDate`).
+pub const PID_LID_TASK_START_DATE: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Task,
+    0x8104,
+    PropertyType::SystemTime,
+);
+
+/// The due date of a task (`PidLidTaskDueDate`).
+pub const PID_LID_TASK_DUE_DATE: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Task,
+    0x8105,
+    PropertyType::SystemTime,
+);
+
+/// Whether a task has been completed (`PidLidTaskComplete`).
+pub const PID_LID_TASK_COMPLETE: MapiProperty = MapiProperty::with_id(
+    DistinguishedPropertySet::Task,
+    0x811C,
+    PropertyType::Boolean,
+);
+
+/// The categories of an item (`PidNameKeywords`).
+pub const PID_NAME_KEYWORDS: MapiProperty = MapiProperty::with_name(
+    DistinguishedPropertySet::PublicStrings,
+    "Keywords",
+    PropertyType::StringArray,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn well_known_properties_are_valid() {
+        let uri = PR_MESSAGE_FLAGS.field_uri();
+        assert_eq!(uri.property_tag.as_deref(), Some("0x0E07"));
+        assert_eq!(uri.property_type, PropertyType::Integer);
+
+        let uri = ExtendedFieldURI::from(PID_LID_GLOBAL_OBJECT_ID);
+        assert_eq!(
+            uri.distinguished_property_set_id,
+            Some(DistinguishedPropertySet::Meeting)
+        );
+        assert_eq!(uri.property_id.as_deref(), Some("3"));
+
+        for property in [
+            PR_MESSAGE_FLAGS,
+            PR_INTERNET_MESSAGE_ID,
+            PR_FOLLOWUP_ICON,
+            PR_FOLDER_PATH,
+            PID_LID_GLOBAL_OBJECT_ID,
+            PID_LID_REMINDER_SIGNAL_TIME,
+            PID_NAME_KEYWORDS,
+        ] {
+            property
+                .field_uri()
+                .validate()
+                .expect("well-known property should be valid");
+        }
+    }
+}
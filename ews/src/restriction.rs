@@ -0,0 +1,309 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A fluent syntax for composing search restrictions.
+//!
+//! Restrictions start from a [`field`], which is compared against a value to
+//! form an [`Expr`]. Expressions can then be combined with [`Expr::and`],
+//! [`Expr::or`] and [`Expr::not`], and converted into a [`Restriction`] for
+//! use in a request.
+//!
+//! # Example
+//!
+//! ```
+//! use ews::{restriction::field, Restriction};
+//!
+//! let restriction: Restriction = field("item:Subject")
+//!     .contains("invoice")
+//!     .and(field("message:IsRead").eq(false))
+//!     .not()
+//!     .into();
+//! ```
+//!
+//! See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/restriction>
+
+use crate::{
+    ContainmentComparison, ContainmentMode, DateTime, FieldURIOrConstant, PathToElement,
+    Restriction, RestrictionType,
+};
+
+/// Starts a restriction on the property with the given well-known name,
+/// e.g. `item:Subject`.
+pub fn field(field_uri: impl Into<String>) -> Field {
+    Field {
+        path: PathToElement::field(field_uri),
+    }
+}
+
+/// Starts a restriction on the given property, e.g. an extended property.
+pub fn path(path: impl Into<PathToElement>) -> Field {
+    Field { path: path.into() }
+}
+
+/// A property to be compared against a value.
+#[derive(Clone, Debug)]
+pub struct Field {
+    path: PathToElement,
+}
+
+impl Field {
+    /// Matches items where the property is equal to the value.
+    pub fn eq(self, value: impl RestrictionValue) -> Expr {
+        Expr(Restriction::equal_to(self.path, value.into_operand()))
+    }
+
+    /// Matches items where the property is not equal to the value.
+    pub fn ne(self, value: impl RestrictionValue) -> Expr {
+        Expr(Restriction::not_equal_to(self.path, value.into_operand()))
+    }
+
+    /// Matches items where the property is greater than the value.
+    pub fn gt(self, value: impl RestrictionValue) -> Expr {
+        Expr(Restriction::greater_than(self.path, value.into_operand()))
+    }
+
+    /// Matches items where the property is greater than or equal to the
+    /// value.
+    pub fn ge(self, value: impl RestrictionValue) -> Expr {
+        Expr(Restriction::greater_than_or_equal_to(
+            self.path,
+            value.into_operand(),
+        ))
+    }
+
+    /// Matches items where the property is less than the value.
+    pub fn lt(self, value: impl RestrictionValue) -> Expr {
+        Expr(Restriction::less_than(self.path, value.into_operand()))
+    }
+
+    /// Matches items where the property is less than or equal to the value.
+    pub fn le(self, value: impl RestrictionValue) -> Expr {
+        Expr(Restriction::less_than_or_equal_to(
+            self.path,
+            value.into_operand(),
+        ))
+    }
+
+    /// Matches items where the property contains the value, ignoring case.
+    pub fn contains(self, value: impl Into<String>) -> Expr {
+        self.contains_with(
+            value,
+            ContainmentMode::Substring,
+            ContainmentComparison::IgnoreCase,
+        )
+    }
+
+    /// Matches items where the property starts with the value, ignoring case.
+    pub fn starts_with(self, value: impl Into<String>) -> Expr {
+        self.contains_with(
+            value,
+            ContainmentMode::Prefixed,
+            ContainmentComparison::IgnoreCase,
+        )
+    }
+
+    /// Matches items where the property contains the value in the given
+    /// manner.
+    pub fn contains_with(
+        self,
+        value: impl Into<String>,
+        containment_mode: ContainmentMode,
+        containment_comparison: ContainmentComparison,
+    ) -> Expr {
+        Expr(Restriction::contains(
+            self.path,
+            value.into(),
+            containment_mode,
+            containment_comparison,
+        ))
+    }
+
+    /// Matches items which have a value for the property.
+    pub fn exists(self) -> Expr {
+        Expr(Restriction::exists(self.path))
+    }
+}
+
+/// A search expression which can be combined with others.
+#[derive(Clone, Debug)]
+pub struct Expr(Restriction);
+
+impl Expr {
+    /// Matches items matching both this and the other expression.
+    ///
+    /// Chains of `and` produce a single `And` restriction rather than nested
+    /// ones.
+    pub fn and(self, other: impl Into<Expr>) -> Expr {
+        let mut restrictions = match self.0.restriction_type {
+            RestrictionType::And(and) => and.0,
+            restriction_type => vec![Restriction { restriction_type }],
+        };
+        restrictions.push(other.into().0);
+
+        Expr(Restriction::and(restrictions))
+    }
+
+    /// Matches items matching either this or the other expression.
+    ///
+    /// Chains of `or` produce a single `Or` restriction rather than nested
+    /// ones.
+    pub fn or(self, other: impl Into<Expr>) -> Expr {
+        let mut restrictions = match self.0.restriction_type {
+            RestrictionType::Or(or) => or.0,
+            restriction_type => vec![Restriction { restriction_type }],
+        };
+        restrictions.push(other.into().0);
+
+        Expr(Restriction::or(restrictions))
+    }
+
+    /// Matches items not matching this expression.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Expr {
+        Expr(Restriction::not(self.0))
+    }
+
+    /// Converts the expression into a restriction for use in a request.
+    pub fn build(self) -> Restriction {
+        self.0
+    }
+}
+
+impl From<Restriction> for Expr {
+    fn from(value: Restriction) -> Self {
+        Expr(value)
+    }
+}
+
+impl From<Expr> for Restriction {
+    fn from(value: Expr) -> Self {
+        value.0
+    }
+}
+
+/// A value to which a property can be compared in a restriction.
+///
+/// Besides constants, this is implemented for [`Field`], so that a property
+/// can be compared against another property of the same item.
+pub trait RestrictionValue {
+    /// Converts the value into the form expected by EWS.
+    fn into_operand(self) -> FieldURIOrConstant;
+}
+
+impl RestrictionValue for Field {
+    fn into_operand(self) -> FieldURIOrConstant {
+        FieldURIOrConstant::Path(self.path)
+    }
+}
+
+impl RestrictionValue for String {
+    fn into_operand(self) -> FieldURIOrConstant {
+        self.into()
+    }
+}
+
+impl RestrictionValue for &str {
+    fn into_operand(self) -> FieldURIOrConstant {
+        self.into()
+    }
+}
+
+impl RestrictionValue for bool {
+    fn into_operand(self) -> FieldURIOrConstant {
+        self.to_string().into()
+    }
+}
+
+macro_rules! impl_restriction_value_for_integers {
+    ($($ty:ty),*) => {
+        $(
+            impl RestrictionValue for $ty {
+                fn into_operand(self) -> FieldURIOrConstant {
+                    self.to_string().into()
+                }
+            }
+        )*
+    };
+}
+
+impl_restriction_value_for_integers!(i32, i64, u32, u64, usize);
+
+impl RestrictionValue for DateTime {
+    /// Formats the time in UTC with second precision.
+    ///
+    /// This is done by hand, as it cannot fail, unlike formatting with
+    /// `time`.
+    fn into_operand(self) -> FieldURIOrConstant {
+        let time = self.0.to_offset(time::UtcOffset::UTC);
+
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            time.year(),
+            u8::from(time.month()),
+            time.day(),
+            time.hour(),
+            time.minute(),
+            time.second()
+        )
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::field;
+    use crate::{test_utils::assert_serialized_content, Restriction, RestrictionType};
+
+    #[test]
+    fn compose_restrictions() {
+        let restriction: Restriction = field("message:IsRead")
+            .eq(false)
+            .and(field("item:Size").ge(1024))
+            .and(field("item:Importance").ne("Low").not())
+            .or(field("item:HasAttachments").exists())
+            .into();
+
+        let expected = concat!(
+            r#"<Restriction><t:Or><t:And>"#,
+            r#"<t:IsEqualTo><t:FieldURI FieldURI="message:IsRead"/><t:FieldURIOrConstant><t:Constant Value="false"/></t:FieldURIOrConstant></t:IsEqualTo>"#,
+            r#"<t:IsGreaterThanOrEqualTo><t:FieldURI FieldURI="item:Size"/><t:FieldURIOrConstant><t:Constant Value="1024"/></t:FieldURIOrConstant></t:IsGreaterThanOrEqualTo>"#,
+            r#"<t:Not><t:IsNotEqualTo><t:FieldURI FieldURI="item:Importance"/><t:FieldURIOrConstant><t:Constant Value="Low"/></t:FieldURIOrConstant></t:IsNotEqualTo></t:Not>"#,
+            r#"</t:And><t:Exists><t:FieldURI FieldURI="item:HasAttachments"/></t:Exists></t:Or></Restriction>"#,
+        );
+
+        assert_serialized_content(&restriction, "Restriction", expected);
+    }
+
+    #[test]
+    fn compare_fields() {
+        let restriction = field("item:DateTimeReceived")
+            .gt(field("item:DateTimeSent"))
+            .build();
+
+        let expected = r#"<Restriction><t:IsGreaterThan><t:FieldURI FieldURI="item:DateTimeReceived"/><t:FieldURIOrConstant><t:FieldURI FieldURI="item:DateTimeSent"/></t:FieldURIOrConstant></t:IsGreaterThan></Restriction>"#;
+        assert_serialized_content(&restriction, "Restriction", expected);
+    }
+
+    #[test]
+    fn contains_restriction() {
+        let restriction = field("item:Subject").contains("invoice").not().build();
+
+        let RestrictionType::Not(not) = &restriction.restriction_type else {
+            panic!("restriction should be negated");
+        };
+        let RestrictionType::Contains(contains) = &not.0.restriction_type else {
+            panic!("negated restriction should be Contains");
+        };
+
+        assert_eq!(contains.constant.value, "invoice");
+        assert_eq!(
+            contains.containment_mode,
+            Some(crate::ContainmentMode::Substring)
+        );
+        assert_eq!(
+            contains.containment_comparison,
+            Some(crate::ContainmentComparison::IgnoreCase)
+        );
+    }
+}
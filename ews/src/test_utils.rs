@@ -2,36 +2,58 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+//! Assertions for testing the XML representation of EWS types, e.g. against
+//! fixtures of requests and responses captured from a server.
+//!
+//! Captured response documents can also be collected into a corpus, a
+//! directory checked by [`assert_fixture_corpus`], so that responses which
+//! once failed to deserialize are covered by regression tests.
+//!
+//! Clients built on this crate can be tested against a [`MockServer`], which
+//! stands in for an EWS endpoint.
+//!
+//! This module is only available with the `test-utils` feature.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
 use quick_xml::{de::Deserializer, Writer};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::Error;
+use crate::{
+    registry::{body_element_name, find_response},
+    Error,
+};
+
+mod mock_server;
+pub use self::mock_server::{MockExchange, MockRequest, MockServer};
 
-/// Assert the expected result of XML serialization.
+pub mod strategies;
+
+/// Asserts that serializing `data` as an element with the given name
+/// produces exactly the expected XML.
+///
+/// # Panics
+///
+/// Panics if serialization fails or produces different XML.
 pub fn assert_serialized_content<T: XmlSerialize>(
     data: &T,
     root_tag_name: &str,
     expected_xml_content: &str,
 ) {
-    // Serialize into XML.
-    let mut writer = {
-        let inner: Vec<u8> = Default::default();
-        Writer::new(inner)
-    };
-    data.serialize_as_element(&mut writer, root_tag_name)
-        .unwrap();
-
-    // Read the contents of the `Writer`'s buffer.
-    let buf = writer.into_inner();
-    let actual_xml_content = std::str::from_utf8(buf.as_slice())
-        .map_err(|e| Error::UnexpectedResponse(e.to_string().into_bytes()))
-        .unwrap();
+    let actual_xml_content = serialize(data, root_tag_name).unwrap();
 
     assert_eq!(actual_xml_content, expected_xml_content);
 }
 
-/// Assert the expected result of XML deserialization.
+/// Asserts that deserializing the given XML produces the expected value.
+///
+/// # Panics
+///
+/// Panics if deserialization fails or produces a different value.
 pub fn assert_deserialized_content<T>(content: &str, expected: T)
 where
     T: for<'a> Deserialize<'a> + Eq + std::fmt::Debug,
@@ -40,3 +62,182 @@ where
     let deserialized_data: T = serde_path_to_error::deserialize(&mut deserializer).unwrap();
     assert_eq!(deserialized_data, expected);
 }
+
+/// Serializes `data` as an element with the given name.
+pub fn serialize<T: XmlSerialize>(data: &T, root_tag_name: &str) -> Result<String, Error> {
+    let mut writer = Writer::new(Vec::new());
+    data.serialize_as_element(&mut writer, root_tag_name)?;
+
+    String::from_utf8(writer.into_inner())
+        .map_err(|err| Error::UnexpectedResponse(err.into_bytes()))
+}
+
+/// Serializes `data` as an element with the given name, then deserializes
+/// the resulting XML.
+///
+/// A value which is not equal to `data` indicates that the serialization and
+/// deserialization of the type disagree, e.g. on the name or namespace of a
+/// field.
+pub fn roundtrip<T>(data: &T, root_tag_name: &str) -> Result<T, Error>
+where
+    T: XmlSerialize + for<'de> Deserialize<'de>,
+{
+    let xml = serialize(data, root_tag_name)?;
+    let mut deserializer = Deserializer::from_reader(xml.as_bytes());
+
+    Ok(serde_path_to_error::deserialize(&mut deserializer)?)
+}
+
+/// Serializes an enum whose variants are written as elements, such as
+/// [`Folder`], then deserializes the resulting XML.
+///
+/// See [`roundtrip`] for details.
+///
+/// [`Folder`]: crate::Folder
+pub fn roundtrip_variant<T>(data: &T) -> Result<T, Error>
+where
+    T: XmlSerialize + for<'de> Deserialize<'de>,
+{
+    let mut writer = Writer::new(Vec::new());
+    data.serialize_child_nodes(&mut writer)?;
+
+    let xml = writer.into_inner();
+    let mut deserializer = Deserializer::from_reader(xml.as_slice());
+
+    Ok(serde_path_to_error::deserialize(&mut deserializer)?)
+}
+
+/// A fixture which failed to deserialize.
+#[derive(Debug)]
+pub struct FixtureFailure {
+    /// The path to the fixture.
+    pub path: PathBuf,
+
+    /// The error encountered when deserializing the fixture.
+    pub error: Error,
+}
+
+/// The result of checking a corpus of fixtures.
+#[derive(Debug, Default)]
+pub struct FixtureReport {
+    /// The paths to the fixtures which were checked, in order.
+    pub checked: Vec<PathBuf>,
+
+    /// The fixtures which failed to deserialize.
+    pub failures: Vec<FixtureFailure>,
+}
+
+/// Checks that a captured response document deserializes as the response
+/// type named by the element in its SOAP body, e.g. `GetItemResponse`.
+///
+/// Returns the name of that element. Documents which don't contain the
+/// response to an operation supported by this crate, including SOAP faults,
+/// fail with [`Error::UnexpectedResponse`].
+pub fn check_fixture(document: &[u8]) -> Result<String, Error> {
+    let name = body_element_name(document)?;
+    let entry = find_response(&name).ok_or_else(|| Error::UnexpectedResponse(document.to_vec()))?;
+    entry.deserialize_response(document)?;
+
+    Ok(name)
+}
+
+/// Checks every `.xml` file in the given directory and its subdirectories
+/// with [`check_fixture`].
+///
+/// Fixtures are checked in order of their paths.
+pub fn check_fixture_corpus(dir: impl AsRef<Path>) -> io::Result<FixtureReport> {
+    let mut paths = Vec::new();
+    collect_fixtures(dir.as_ref(), &mut paths)?;
+    paths.sort();
+
+    let mut report = FixtureReport::default();
+    for path in paths {
+        let result = fs::read(&path)
+            .map_err(Error::from)
+            .and_then(|document| check_fixture(&document));
+        if let Err(error) = result {
+            report.failures.push(FixtureFailure {
+                path: path.clone(),
+                error,
+            });
+        }
+        report.checked.push(path);
+    }
+
+    Ok(report)
+}
+
+/// Asserts that every fixture in the given corpus directory deserializes
+/// as the response it contains.
+///
+/// # Panics
+///
+/// Panics if the directory can't be read, contains no fixtures, or contains
+/// fixtures which fail to deserialize, listing each of them.
+pub fn assert_fixture_corpus(dir: impl AsRef<Path>) {
+    let dir = dir.as_ref();
+    let report = check_fixture_corpus(dir)
+        .unwrap_or_else(|err| panic!("failed to read fixtures in {}: {err}", dir.display()));
+
+    assert!(
+        !report.checked.is_empty(),
+        "no fixtures found in {}",
+        dir.display()
+    );
+
+    if !report.failures.is_empty() {
+        let failures: Vec<String> = report
+            .failures
+            .iter()
+            .map(|failure| format!("{}: {}", failure.path.display(), failure.error))
+            .collect();
+
+        panic!(
+            "{} of {} fixtures failed to deserialize:\n{}",
+            failures.len(),
+            report.checked.len(),
+            failures.join("\n")
+        );
+    }
+}
+
+/// Collects the paths to the `.xml` files in the given directory and its
+/// subdirectories.
+fn collect_fixtures(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_fixtures(&path, paths)?;
+        } else if path.extension().is_some_and(|extension| extension == "xml") {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+
+    use super::{assert_fixture_corpus, check_fixture};
+
+    #[test]
+    fn fixture_corpus() {
+        assert_fixture_corpus(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures"));
+    }
+
+    #[test]
+    fn unsupported_fixture() {
+        let document = concat!(
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>"#,
+            r#"<s:Fault><faultcode>a:ErrorSchemaValidation</faultcode><faultstring>The request failed schema validation.</faultstring></s:Fault>"#,
+            r#"</s:Body></s:Envelope>"#,
+        );
+
+        assert!(matches!(
+            check_fixture(document.as_bytes()),
+            Err(Error::UnexpectedResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,682 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::{self, JoinHandle},
+};
+
+use quick_xml::Writer;
+
+use crate::Operation;
+
+use super::body_element_name;
+
+/// The path at which the mock server accepts requests, as for Exchange.
+const EWS_PATH: &str = "/EWS/Exchange.asmx";
+
+/// An in-process HTTP server standing in for an EWS endpoint in tests.
+///
+/// The server replies to each request with the response of the next
+/// [`MockExchange`] expected with [`MockServer::expect`], after checking that
+/// the request is for the expected operation and matches any expectations on
+/// its XML. Requests which aren't expected get a `501 Not Implemented`
+/// response.
+///
+/// # Example
+///
+/// ```no_run
+/// use ews::test_utils::{MockExchange, MockServer};
+/// # const GET_FOLDER_RESPONSE: &str = "";
+///
+/// let server = MockServer::start().unwrap();
+/// server.expect(
+///     MockExchange::new("GetFolder")
+///         .with_body_containing(r#"<t:DistinguishedFolderId Id="inbox"/>"#)
+///         .respond_with(GET_FOLDER_RESPONSE),
+/// );
+///
+/// // Send a `GetFolder` request for the inbox to `server.url()` with the
+/// // client under test.
+///
+/// server.verify();
+/// ```
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    shutdown: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+/// A request received by a [`MockServer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockRequest {
+    /// The value of the request's `SOAPAction` header, if any.
+    pub soap_action: Option<String>,
+
+    /// The name of the operation in the body of the request, e.g. `GetItem`.
+    pub operation: Option<String>,
+
+    /// The body of the request.
+    pub body: String,
+}
+
+/// A request expected by a [`MockServer`], and the response to it.
+#[derive(Clone, Debug)]
+pub struct MockExchange {
+    operation: String,
+    body: Option<String>,
+    fragments: Vec<String>,
+    status: u16,
+    response: String,
+}
+
+/// The state shared between a [`MockServer`] and its connections.
+#[derive(Debug, Default)]
+struct MockState {
+    expected: VecDeque<MockExchange>,
+    requests: Vec<MockRequest>,
+    failures: Vec<String>,
+}
+
+impl MockExchange {
+    /// Expects a request for the operation with the given name, e.g.
+    /// `GetItem`, to which the server responds with an empty body.
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+            body: None,
+            fragments: Vec::new(),
+            status: 200,
+            response: String::new(),
+        }
+    }
+
+    /// Expects the body of the request to contain exactly the given
+    /// operation, as serialized by this crate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the operation can't be serialized.
+    pub fn with_request<O: Operation>(mut self, operation: &O) -> Self {
+        let mut writer = Writer::new(Vec::new());
+        operation
+            .serialize_as_element(&mut writer, operation.name())
+            .expect("operation should serialize");
+
+        self.body =
+            Some(String::from_utf8(writer.into_inner()).expect("serialized XML should be UTF-8"));
+        self
+    }
+
+    /// Expects the body of the request to contain the given XML fragment.
+    pub fn with_body_containing(mut self, fragment: impl Into<String>) -> Self {
+        self.fragments.push(fragment.into());
+        self
+    }
+
+    /// Responds to the request with the given SOAP envelope.
+    pub fn respond_with(self, envelope: impl Into<String>) -> Self {
+        self.respond_with_status(200, envelope)
+    }
+
+    /// Responds to the request with the given HTTP status and body, e.g. a
+    /// SOAP fault with the `500` status used by EWS.
+    pub fn respond_with_status(mut self, status: u16, body: impl Into<String>) -> Self {
+        self.status = status;
+        self.response = body.into();
+        self
+    }
+
+    /// Checks the given request against this exchange, returning a
+    /// description of each mismatch.
+    fn mismatches(&self, request: &MockRequest) -> Vec<String> {
+        let mut mismatches = Vec::new();
+
+        if request.operation.as_deref() != Some(self.operation.as_str()) {
+            mismatches.push(format!(
+                "expected a {} request, got {:?}",
+                self.operation, request.operation
+            ));
+        }
+
+        if let Some(body) = &self.body {
+            let soap_body = format!("<soap:Body>{body}</soap:Body>");
+            if !request.body.contains(&soap_body) {
+                mismatches.push(format!(
+                    "expected a request body of {body}, got {}",
+                    request.body
+                ));
+            }
+        }
+
+        for fragment in &self.fragments {
+            if !request.body.contains(fragment.as_str()) {
+                mismatches.push(format!(
+                    "expected the request to contain {fragment}, got {}",
+                    request.body
+                ));
+            }
+        }
+
+        mismatches
+    }
+}
+
+impl MockServer {
+    /// Starts a server listening on a free port of the loopback interface.
+    pub fn start() -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState::default()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let listener = {
+            let state = Arc::clone(&state);
+            let shutdown = Arc::clone(&shutdown);
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    let Ok(stream) = stream else {
+                        continue;
+                    };
+                    let state = Arc::clone(&state);
+                    thread::spawn(move || {
+                        if let Err(err) = serve_connection(stream, &state) {
+                            lock(&state)
+                                .failures
+                                .push(format!("failed to serve a connection: {err}"));
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            address,
+            state,
+            shutdown,
+            listener: Some(listener),
+        })
+    }
+
+    /// Gets the URL of the EWS endpoint served by this server.
+    pub fn url(&self) -> String {
+        format!("http://{}{EWS_PATH}", self.address)
+    }
+
+    /// Expects the given exchange after any previously expected ones.
+    pub fn expect(&self, exchange: MockExchange) {
+        lock(&self.state).expected.push_back(exchange);
+    }
+
+    /// Gets the requests received so far, in order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        lock(&self.state).requests.clone()
+    }
+
+    /// Asserts that every expected request has been received, and that no
+    /// unexpected or mismatched requests have been.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a description of each failure if not.
+    pub fn verify(&self) {
+        let state = lock(&self.state);
+
+        let mut failures = state.failures.clone();
+        failures.extend(
+            state
+                .expected
+                .iter()
+                .map(|exchange| format!("expected a {} request, got none", exchange.operation)),
+        );
+
+        assert!(
+            failures.is_empty(),
+            "mock EWS server failures:\n{}",
+            failures.join("\n")
+        );
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        // Wake the listener up so that it notices it should stop.
+        self.shutdown.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+/// Locks the shared state, ignoring poisoning by panicking test threads.
+fn lock(state: &Mutex<MockState>) -> MutexGuard<'_, MockState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Serves a single request sent over a connection, then closes it.
+fn serve_connection(stream: TcpStream, state: &Mutex<MockState>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    let Some(request) = read_request(&mut reader)? else {
+        return Ok(());
+    };
+
+    let (status, body) = respond(state, request);
+    write!(
+        writer,
+        "HTTP/1.1 {status} {}\r\nContent-Type: text/xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason_phrase(status),
+        body.len()
+    )?;
+
+    writer.flush()
+}
+
+/// Reads the next HTTP request from a connection, or `None` if it has been
+/// closed.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<MockRequest>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+
+    let mut content_length = 0;
+    let mut soap_action = None;
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("Content-Length") {
+            content_length = value
+                .parse()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        } else if name.eq_ignore_ascii_case("SOAPAction") {
+            soap_action = Some(value.trim_matches('"').to_string());
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Some(MockRequest {
+        soap_action,
+        operation: body_element_name(&body).ok(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }))
+}
+
+/// Records a request and determines the status and body of the response to
+/// it.
+fn respond(state: &Mutex<MockState>, request: MockRequest) -> (u16, String) {
+    let mut state = lock(state);
+
+    let response = match state.expected.pop_front() {
+        Some(exchange) => {
+            let mismatches = exchange.mismatches(&request);
+            if mismatches.is_empty() {
+                (exchange.status, exchange.response)
+            } else {
+                state.failures.extend(mismatches);
+                (501, String::new())
+            }
+        }
+        None => {
+            state.failures.push(format!(
+                "unexpected {} request",
+                request.operation.as_deref().unwrap_or("unknown")
+            ));
+            (501, String::new())
+        }
+    };
+
+    state.requests.push(request);
+
+    response
+}
+
+/// Gets the reason phrase for the HTTP statuses commonly used by EWS.
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        401 => "Unauthorized",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+    };
+
+    use crate::{get_folder::GetFolder, soap::Envelope, BaseFolderId, BaseShape, FolderShape};
+
+    use super::{MockExchange, MockServer};
+
+    const GET_FOLDER_RESPONSE: &str = concat!(
+        r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header>"#,
+        r#"<h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/>"#,
+        r#"</s:Header><s:Body>"#,
+        r#"<m:GetFolderResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+        r#"<m:GetFolderResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Folders>"#,
+        r#"<t:Folder><t:FolderId Id="inbox-id"/><t:DisplayName>Inbox</t:DisplayName></t:Folder>"#,
+        r#"</m:Folders></m:GetFolderResponseMessage></m:ResponseMessages></m:GetFolderResponse></s:Body></s:Envelope>"#,
+    );
+
+    fn get_inbox() -> GetFolder {
+        GetFolder {
+            folder_shape: FolderShape {
+                base_shape: BaseShape::IdOnly,
+                additional_properties: None,
+            },
+            folder_ids: vec![BaseFolderId::DistinguishedFolderId {
+                id: "inbox".to_string(),
+                change_key: None,
+            }],
+        }
+    }
+
+    /// Posts a request over a raw connection, returning the response.
+    fn post(server: &MockServer, body: &[u8]) -> String {
+        let address = server.url();
+        let address = address
+            .trim_start_matches("http://")
+            .split('/')
+            .next()
+            .unwrap();
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "POST /EWS/Exchange.asmx HTTP/1.1\r\nHost: {address}\r\nContent-Type: text/xml; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serve_expected_request() {
+        let server = MockServer::start().unwrap();
+        server.expect(
+            MockExchange::new("GetFolder")
+                .with_request(&get_inbox())
+                .with_body_containing(r#"<t:DistinguishedFolderId Id="inbox"/>"#)
+                .respond_with(GET_FOLDER_RESPONSE),
+        );
+
+        let request = Envelope::new(get_inbox()).as_xml_document().unwrap();
+        let response = post(&server, &request);
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(GET_FOLDER_RESPONSE));
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(server.requests()[0].operation.as_deref(), Some("GetFolder"));
+        server.verify();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a GetItem request")]
+    fn reject_unexpected_request() {
+        let server = MockServer::start().unwrap();
+        server.expect(MockExchange::new("GetItem").respond_with(GET_FOLDER_RESPONSE));
+
+        let request = Envelope::new(get_inbox()).as_xml_document().unwrap();
+        let response = post(&server, &request);
+
+        assert!(response.starts_with("HTTP/1.1 501 Not Implemented\r\n"));
+        server.verify();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn serve_blocking_client() {
+        use crate::client::{blocking::EwsClient, Credentials};
+
+        let server = MockServer::start().unwrap();
+        server.expect(
+            MockExchange::new("GetFolder")
+                .with_request(&get_inbox())
+                .respond_with(GET_FOLDER_RESPONSE),
+        );
+
+        let client = EwsClient::new(server.url(), Credentials::Bearer("token".to_string()));
+        let response = client.send(get_inbox()).expect("request should succeed");
+
+        assert_eq!(response.response_messages.response_messages.len(), 1);
+        assert_eq!(
+            server.requests()[0].soap_action.as_deref(),
+            Some("http://schemas.microsoft.com/exchange/services/2006/messages/GetFolder")
+        );
+        server.verify();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn negotiate_server_version_with_blocking_client() {
+        use crate::{
+            client::{blocking::EwsClient, Credentials, ServerVersions},
+            server_version::ExchangeServerVersion,
+        };
+
+        const INVALID_SERVER_VERSION: &str = concat!(
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault>"#,
+            r#"<faultcode xmlns:a="http://schemas.microsoft.com/exchange/services/2006/types">a:ErrorInvalidServerVersion</faultcode>"#,
+            r#"<faultstring xml:lang="en-US">The specified server version is invalid.</faultstring>"#,
+            r#"<detail><e:ResponseCode xmlns:e="http://schemas.microsoft.com/exchange/services/2006/errors">ErrorInvalidServerVersion</e:ResponseCode></detail>"#,
+            r#"</s:Fault></s:Body></s:Envelope>"#,
+        );
+
+        let server = MockServer::start().unwrap();
+        server.expect(
+            MockExchange::new("GetFolder")
+                .with_body_containing(r#"Version="Exchange2010_SP1""#)
+                .respond_with_status(500, INVALID_SERVER_VERSION),
+        );
+        server.expect(
+            MockExchange::new("GetFolder")
+                .with_body_containing(r#"Version="Exchange2010""#)
+                .respond_with(GET_FOLDER_RESPONSE),
+        );
+        server.expect(
+            MockExchange::new("GetFolder")
+                .with_body_containing(r#"Version="Exchange2010""#)
+                .respond_with(GET_FOLDER_RESPONSE),
+        );
+
+        let versions = ServerVersions::new();
+        let client = EwsClient::new(server.url(), Credentials::Bearer("token".to_string()))
+            .with_server_version(ExchangeServerVersion::Exchange2010_SP1)
+            .with_server_versions(versions.clone());
+
+        client.send(get_inbox()).expect("request should succeed");
+        assert_eq!(
+            versions.get(&server.url()),
+            Some(ExchangeServerVersion::Exchange2010)
+        );
+
+        // The negotiated version is used from the start for later requests.
+        client.send(get_inbox()).expect("request should succeed");
+        assert_eq!(server.requests().len(), 3);
+        server.verify();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn recover_and_purge_recoverable_items() {
+        use crate::{
+            client::{blocking::EwsClient, Credentials},
+            find_item::FindItem,
+            BaseFolderId, ResponseClass,
+        };
+
+        const DUMPSTER: &str =
+            include_str!("../../fixtures/find_item_recoverable_items_deletions.xml");
+        const FOUND_ITEM_IDS: &str = concat!(
+            r#"<ItemIds><t:ItemId Id="AAMkADdumpster1=" ChangeKey="CQAAABYAAAD1"/>"#,
+            r#"<t:ItemId Id="AAMkADdumpster2=" ChangeKey="DwAAABYAAAD2"/>"#,
+            r#"<t:ItemId Id="AAMkADdumpster3=" ChangeKey="CQAAABYAAAD3"/></ItemIds>"#,
+        );
+
+        let moved = |id: &str| {
+            format!(
+                r#"<m:MoveItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Items><t:Message><t:ItemId Id="{id}" ChangeKey="CQAAABYAAAE="/></t:Message></m:Items></m:MoveItemResponseMessage>"#
+            )
+        };
+        let move_response = format!(
+            concat!(
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header>"#,
+                r#"<h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/>"#,
+                r#"</s:Header><s:Body>"#,
+                r#"<m:MoveItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+                "{}{}{}",
+                r#"</m:ResponseMessages></m:MoveItemResponse></s:Body></s:Envelope>"#,
+            ),
+            moved("AAMkADinbox1="),
+            moved("AAMkADinbox2="),
+            moved("AAMkADinbox3="),
+        );
+
+        let deleted = r#"<m:DeleteItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode></m:DeleteItemResponseMessage>"#;
+        let delete_response = format!(
+            concat!(
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header>"#,
+                r#"<h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/>"#,
+                r#"</s:Header><s:Body>"#,
+                r#"<m:DeleteItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+                "{0}{0}{0}",
+                r#"</m:ResponseMessages></m:DeleteItemResponse></s:Body></s:Envelope>"#,
+            ),
+            deleted,
+        );
+
+        let server = MockServer::start().unwrap();
+        for (operation, fragment, response) in [
+            (
+                "FindItem",
+                r#"<t:DistinguishedFolderId Id="recoverableitemsdeletions"/>"#,
+                DUMPSTER.to_string(),
+            ),
+            (
+                "MoveItem",
+                r#"<ToFolderId><t:DistinguishedFolderId Id="inbox"/></ToFolderId>"#,
+                move_response,
+            ),
+            (
+                "FindItem",
+                r#"<t:DistinguishedFolderId Id="recoverableitemsdeletions"/>"#,
+                DUMPSTER.to_string(),
+            ),
+            ("DeleteItem", r#"DeleteType="HardDelete""#, delete_response),
+        ] {
+            let exchange = MockExchange::new(operation).with_body_containing(fragment);
+            let exchange = match operation {
+                "MoveItem" | "DeleteItem" => exchange.with_body_containing(FOUND_ITEM_IDS),
+                _ => exchange,
+            };
+            server.expect(exchange.respond_with(response));
+        }
+
+        let client = EwsClient::new(server.url(), Credentials::Bearer("token".to_string()));
+
+        let recovered = client
+            .recover_items(FindItem::recoverable_items().build(), BaseFolderId::inbox())
+            .expect("items should be recovered");
+        let new_ids: Vec<_> = recovered
+            .into_iter()
+            .map(|message| match message {
+                ResponseClass::Success(message) => {
+                    message.items.inner[0]
+                        .inner_message()
+                        .and_then(|message| message.item_id.clone())
+                        .expect("recovered item should have an ID")
+                        .id
+                }
+                other => panic!("expected a successful message, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            new_ids,
+            vec!["AAMkADinbox1=", "AAMkADinbox2=", "AAMkADinbox3="]
+        );
+
+        let purged = client
+            .purge_items(FindItem::recoverable_items().build())
+            .expect("items should be purged");
+        assert_eq!(purged.len(), 3);
+        assert!(purged
+            .iter()
+            .all(|message| matches!(message, ResponseClass::Success(_))));
+
+        server.verify();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn serve_raw_operation() {
+        use crate::{
+            client::{blocking::EwsClient, Credentials},
+            raw::RawOperation,
+        };
+
+        let server = MockServer::start().unwrap();
+        server.expect(
+            MockExchange::new("GetFolder")
+                .with_body_containing(r#"<FolderIds><t:DistinguishedFolderId Id="inbox"/>"#)
+                .respond_with(GET_FOLDER_RESPONSE),
+        );
+
+        let operation = RawOperation::new(
+            "GetFolder",
+            concat!(
+                r#"<GetFolder xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+                r#"<FolderShape><t:BaseShape>IdOnly</t:BaseShape></FolderShape>"#,
+                r#"<FolderIds><t:DistinguishedFolderId Id="inbox"/></FolderIds></GetFolder>"#,
+            ),
+        );
+
+        let client = EwsClient::new(server.url(), Credentials::Bearer("token".to_string()));
+        let response = client.send_raw(&operation).expect("request should succeed");
+
+        assert!(response.as_str().starts_with("<m:GetFolderResponse "));
+        assert!(response.as_str().ends_with("</m:GetFolderResponse>"));
+        assert_eq!(
+            server.requests()[0].soap_action.as_deref(),
+            Some("http://schemas.microsoft.com/exchange/services/2006/messages/GetFolder")
+        );
+        server.verify();
+    }
+}
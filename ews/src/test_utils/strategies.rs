@@ -0,0 +1,399 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! [`proptest`](mod@proptest) strategies generating values of core types.
+//!
+//! Combined with [`roundtrip`], these check that the [`XmlSerialize`] and
+//! [`Deserialize`] implementations of a type agree on the names and
+//! namespaces of its fields, for values which can be represented in both
+//! requests and responses.
+//!
+//! Generated strings are restricted to those which survive a trip through
+//! XML text unchanged, i.e. without leading or trailing whitespace, but
+//! include characters which must be escaped.
+//!
+//! [`roundtrip`]: super::roundtrip
+//! [`XmlSerialize`]: xml_struct::XmlSerialize
+//! [`Deserialize`]: serde::Deserialize
+
+use proptest::{collection::vec, option, prelude::*};
+
+use crate::{
+    restriction::{field, Expr},
+    ArrayOfRecipients, ArrayOfStrings, BaseShape, Body, BodyType, DateTime, Folder, FolderId,
+    FolderProperties, FolderShape, Importance, ItemId, ItemShape, Mailbox, MailboxType, Message,
+    PathToElement, Recipient, Restriction, RoutingType, Sensitivity,
+};
+
+/// Generates text which is unchanged by a trip through XML.
+pub fn text() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9&<>\"'][A-Za-z0-9 &<>\"'.,:;!?@#()-]{0,30}[A-Za-z0-9.!?)]|[A-Za-z0-9]"
+}
+
+/// Generates an opaque Exchange identifier, such as that of an item.
+pub fn identifier() -> impl Strategy<Value = String> {
+    "[A-Za-z0-9+/]{8,40}={0,2}"
+}
+
+/// Generates an email address.
+pub fn email_address() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9.]{0,15}@[a-z]{1,10}\\.(com|org|net)"
+}
+
+/// Generates a time, in UTC and with nanosecond precision.
+pub fn date_time() -> impl Strategy<Value = DateTime> {
+    (0_i64..4_102_444_800, 0_u32..1_000_000_000).prop_map(|(seconds, nanoseconds)| {
+        let time = time::OffsetDateTime::from_unix_timestamp(seconds)
+            .expect("timestamp should be in range")
+            + time::Duration::nanoseconds(nanoseconds.into());
+
+        DateTime(time)
+    })
+}
+
+/// Generates the identifier of an item.
+pub fn item_id() -> impl Strategy<Value = ItemId> {
+    (identifier(), option::of(identifier())).prop_map(|(id, change_key)| ItemId { id, change_key })
+}
+
+/// Generates the identifier of a folder.
+pub fn folder_id() -> impl Strategy<Value = FolderId> {
+    (identifier(), option::of(identifier()))
+        .prop_map(|(id, change_key)| FolderId { id, change_key })
+}
+
+/// Generates a mailbox.
+pub fn mailbox() -> impl Strategy<Value = Mailbox> {
+    (
+        option::of(text()),
+        option::of(email_address()),
+        option::of(prop_oneof![
+            Just(RoutingType::SMTP),
+            Just(RoutingType::EX),
+            Just(RoutingType::Other("MAPIPDL".to_string())),
+        ]),
+        option::of(prop_oneof![
+            Just(MailboxType::Mailbox),
+            Just(MailboxType::PublicDL),
+            Just(MailboxType::PrivateDL),
+            Just(MailboxType::Contact),
+            Just(MailboxType::OneOff),
+        ]),
+        option::of(item_id()),
+    )
+        .prop_map(
+            |(name, email_address, routing_type, mailbox_type, item_id)| Mailbox {
+                name,
+                email_address,
+                routing_type,
+                mailbox_type,
+                item_id,
+            },
+        )
+}
+
+/// Generates a recipient.
+pub fn recipient() -> impl Strategy<Value = Recipient> {
+    mailbox().prop_map(|mailbox| Recipient { mailbox })
+}
+
+/// Generates the body of an item.
+pub fn body() -> impl Strategy<Value = Body> {
+    (
+        prop_oneof![Just(BodyType::HTML), Just(BodyType::Text)],
+        option::of(any::<bool>()),
+        option::of(text()),
+    )
+        .prop_map(|(body_type, is_truncated, content)| Body {
+            body_type,
+            is_truncated,
+            content,
+        })
+}
+
+/// Generates a message with commonly used properties.
+pub fn message() -> impl Strategy<Value = Message> {
+    let identity = (
+        option::of(item_id()),
+        option::of(folder_id()),
+        option::of(text()),
+        option::of(text()),
+    );
+    let content = (
+        option::of(prop_oneof![
+            Just(Sensitivity::Normal),
+            Just(Sensitivity::Personal),
+            Just(Sensitivity::Private),
+            Just(Sensitivity::Confidential),
+        ]),
+        option::of(body()),
+        option::of(vec(text(), 1..4).prop_map(ArrayOfStrings)),
+        option::of(prop_oneof![
+            Just(Importance::Low),
+            Just(Importance::Normal),
+            Just(Importance::High),
+        ]),
+    );
+    let state = (
+        option::of(date_time()),
+        option::of(any::<bool>()),
+        option::of(any::<bool>()),
+        option::of(text()),
+    );
+    let recipients = (
+        option::of(recipient()),
+        option::of(vec(recipient(), 1..4).prop_map(ArrayOfRecipients)),
+        option::of(vec(recipient(), 1..4).prop_map(ArrayOfRecipients)),
+    );
+
+    (identity, content, state, recipients).prop_map(
+        |(
+            (item_id, parent_folder_id, item_class, subject),
+            (sensitivity, body, categories, importance),
+            (date_time_received, is_draft, is_read, internet_message_id),
+            (from, to_recipients, cc_recipients),
+        )| Message {
+            item_id,
+            parent_folder_id,
+            item_class,
+            subject,
+            sensitivity,
+            body,
+            date_time_received,
+            categories,
+            importance,
+            is_draft,
+            from,
+            to_recipients,
+            cc_recipients,
+            internet_message_id,
+            is_read,
+            ..Default::default()
+        },
+    )
+}
+
+/// Generates a generic mail folder.
+pub fn folder() -> impl Strategy<Value = Folder> {
+    (
+        option::of(folder_id()),
+        option::of(folder_id()),
+        option::of(text()),
+        option::of(text()),
+        option::of(any::<u32>()),
+        option::of(any::<u32>()),
+        option::of(any::<u32>()),
+    )
+        .prop_map(
+            |(
+                folder_id,
+                parent_folder_id,
+                folder_class,
+                display_name,
+                total_count,
+                child_folder_count,
+                unread_count,
+            )| Folder::Folder {
+                properties: FolderProperties {
+                    folder_id,
+                    parent_folder_id,
+                    folder_class,
+                    display_name,
+                    total_count,
+                    child_folder_count,
+                    extended_property: None,
+                },
+                unread_count,
+            },
+        )
+}
+
+/// Generates a base shape.
+pub fn base_shape() -> impl Strategy<Value = BaseShape> {
+    prop_oneof![
+        Just(BaseShape::IdOnly),
+        Just(BaseShape::Default),
+        Just(BaseShape::AllProperties),
+    ]
+}
+
+/// Generates a folder shape, which may request additional properties.
+pub fn folder_shape() -> impl Strategy<Value = FolderShape> {
+    (
+        base_shape(),
+        option::of(vec(field_uri().prop_map(PathToElement::field), 1..4)),
+    )
+        .prop_map(|(base_shape, additional_properties)| FolderShape {
+            base_shape,
+            additional_properties,
+        })
+}
+
+/// Generates an item shape, which may request additional properties.
+pub fn item_shape() -> impl Strategy<Value = ItemShape> {
+    (
+        base_shape(),
+        option::of(any::<bool>()),
+        option::of(prop_oneof![Just(BodyType::HTML), Just(BodyType::Text)]),
+        option::of(any::<bool>()),
+        option::of(any::<bool>()),
+        option::of(vec(field_uri().prop_map(PathToElement::field), 1..4)),
+    )
+        .prop_map(
+            |(
+                base_shape,
+                include_mime_content,
+                body_type,
+                filter_html_content,
+                block_external_images,
+                additional_properties,
+            )| ItemShape {
+                base_shape,
+                include_mime_content,
+                body_type,
+                filter_html_content,
+                block_external_images,
+                additional_properties,
+                ..ItemShape::id_only()
+            },
+        )
+}
+
+/// Generates a restriction, possibly combining several comparisons.
+pub fn restriction() -> impl Strategy<Value = Restriction> {
+    let comparison = (field_uri(), text(), 0..5_u8).prop_map(|(field_uri, value, kind)| {
+        let field = field(field_uri);
+        match kind {
+            0 => field.eq(value),
+            1 => field.ne(value),
+            2 => field.contains(value),
+            3 => field.starts_with(value),
+            _ => field.exists(),
+        }
+    });
+
+    comparison
+        .prop_recursive(3, 16, 3, |inner| {
+            prop_oneof![
+                (inner.clone(), inner.clone()).prop_map(|(left, right)| left.and(right)),
+                (inner.clone(), inner.clone()).prop_map(|(left, right)| left.or(right)),
+                inner.prop_map(Expr::not),
+            ]
+        })
+        .prop_map(Expr::build)
+}
+
+/// Generates the well-known name of a property, e.g. `item:Subject`.
+fn field_uri() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("item:Subject"),
+        Just("item:DateTimeReceived"),
+        Just("item:Categories"),
+        Just("message:IsRead"),
+        Just("message:From"),
+        Just("folder:DisplayName"),
+    ]
+    .prop_map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prelude::*, test_runner::TestCaseError};
+    use quick_xml::{events::Event, Reader};
+    use xml_struct::XmlSerialize;
+
+    use crate::test_utils::{roundtrip, roundtrip_variant, serialize};
+
+    use super::{
+        body, folder, folder_id, folder_shape, item_id, item_shape, mailbox, message, recipient,
+        restriction,
+    };
+
+    /// Checks that the given value survives a trip through XML.
+    fn check_roundtrip<T>(value: T, root_tag_name: &str) -> Result<(), TestCaseError>
+    where
+        T: XmlSerialize + for<'de> serde::Deserialize<'de> + PartialEq + std::fmt::Debug,
+    {
+        let deserialized =
+            roundtrip(&value, root_tag_name).map_err(|err| TestCaseError::fail(err.to_string()))?;
+        prop_assert_eq!(deserialized, value);
+
+        Ok(())
+    }
+
+    /// Checks that the given request-only value serializes as well-formed
+    /// XML.
+    fn check_well_formed<T: XmlSerialize>(
+        value: T,
+        root_tag_name: &str,
+    ) -> Result<(), TestCaseError> {
+        let xml =
+            serialize(&value, root_tag_name).map_err(|err| TestCaseError::fail(err.to_string()))?;
+
+        let mut reader = Reader::from_str(&xml);
+        reader.check_end_names(true);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Eof) => return Ok(()),
+                Ok(_) => {}
+                Err(err) => return Err(TestCaseError::fail(format!("{err} in {xml}"))),
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn item_id_roundtrip(value in item_id()) {
+            check_roundtrip(value, "t:ItemId")?;
+        }
+
+        #[test]
+        fn folder_id_roundtrip(value in folder_id()) {
+            check_roundtrip(value, "t:FolderId")?;
+        }
+
+        #[test]
+        fn mailbox_roundtrip(value in mailbox()) {
+            check_roundtrip(value, "t:Mailbox")?;
+        }
+
+        #[test]
+        fn recipient_roundtrip(value in recipient()) {
+            check_roundtrip(value, "t:From")?;
+        }
+
+        #[test]
+        fn body_roundtrip(value in body()) {
+            check_roundtrip(value, "t:Body")?;
+        }
+
+        #[test]
+        fn message_roundtrip(value in message()) {
+            check_roundtrip(value, "t:Message")?;
+        }
+
+        #[test]
+        fn folder_roundtrip(value in folder()) {
+            let deserialized = roundtrip_variant(&value)
+                .map_err(|err| TestCaseError::fail(err.to_string()))?;
+            prop_assert_eq!(deserialized, value);
+        }
+
+        #[test]
+        fn shapes_are_well_formed(folder_shape in folder_shape(), item_shape in item_shape()) {
+            check_well_formed(folder_shape, "FolderShape")?;
+            check_well_formed(item_shape, "ItemShape")?;
+        }
+
+        #[test]
+        fn restrictions_are_well_formed(value in restriction()) {
+            check_well_formed(value, "Restriction")?;
+        }
+
+        #[test]
+        fn restriction_roundtrip(value in restriction()) {
+            check_roundtrip(value, "Restriction")?;
+        }
+    }
+}
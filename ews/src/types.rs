@@ -7,13 +7,17 @@ mod operations;
 
 pub use common::*;
 pub use operations::*;
+pub mod raw;
+pub mod registry;
 pub mod soap;
+pub mod streaming;
 
 pub mod copy_folder;
 pub mod copy_item;
 pub mod create_attachment;
 pub mod create_folder;
 pub mod create_item;
+pub mod delete_attachment;
 pub mod delete_folder;
 pub mod delete_item;
 pub mod empty_folder;
@@ -25,6 +29,7 @@ pub mod get_item;
 pub mod get_mail_tips;
 pub mod get_server_time_zones;
 pub mod get_user_availability;
+pub mod get_user_configuration;
 pub mod mark_as_read;
 pub mod move_folder;
 pub mod move_item;
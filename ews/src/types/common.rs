@@ -2,16 +2,87 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use std::ops::{Deref, DerefMut};
-
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    ops::{Deref, DerefMut, Index},
+    str::FromStr,
+};
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use serde::{Deserialize, Deserializer};
-use time::format_description::well_known::Iso8601;
 use xml_struct::XmlSerialize;
 
+use crate::{require_non_empty, Error};
+
 pub mod response;
-pub use self::response::{ResponseClass, ResponseMessages};
+pub use self::response::{ResponseClass, ResponseError, ResponseMessages};
 pub mod message_xml;
 pub use self::message_xml::MessageXml;
+mod item_like;
+pub use self::item_like::ItemLike;
+mod message_builder;
+pub use self::message_builder::MessageBuilder;
+mod time_zone;
+pub(crate) use self::time_zone::NaiveTimes;
+pub use self::time_zone::{MeetingTimeZone, TimeZoneContext, TimeZoneReference};
+mod contact;
+pub use self::contact::{
+    CompleteName, Contact, EmailAddressEntry, EmailAddresses, PhoneNumberEntry, PhoneNumbers,
+    PhysicalAddressEntry, PhysicalAddresses,
+};
+mod contact_photo;
+pub use self::contact_photo::ContactPhoto;
+mod category_list;
+pub use self::category_list::{Category, CategoryColor, CategoryList};
+mod content_format;
+pub use self::content_format::ContentFormat;
+mod content_id;
+mod conversation_index;
+pub use self::conversation_index::{
+    thread_messages, ConversationIndex, ConversationNode, ConversationResponse,
+};
+mod global_object_id;
+pub use self::global_object_id::{correlate_meetings, GlobalObjectId, MeetingMessages};
+mod date_time_format;
+pub use self::date_time_format::{DateTimeFormat, DateTimeOffset, DateTimePrecision};
+mod ews_id;
+pub use self::ews_id::{EntryId, EwsId, IdProcessingInstruction, IdStorageType, LongTermId};
+mod folder;
+mod folder_tree;
+pub use self::folder_tree::{FolderHierarchy, FolderNode, FolderTree};
+mod meeting;
+pub use self::meeting::{ChangeHighlights, MeetingRequest, MeetingRequestType};
+mod notification;
+pub use self::notification::{
+    ModifiedEvent, MovedCopiedEvent, Notification, NotificationEvent, ObjectChangedEvent,
+    StatusEvent,
+};
+mod subscription;
+pub use self::subscription::{Resubscribe, SubscriptionId, SubscriptionTracker, Watermark};
+mod recurrence;
+pub use self::recurrence::{
+    AbsoluteMonthlyRecurrence, AbsoluteYearlyRecurrence, DailyRecurrence, DayOfWeekIndex,
+    EndDateRecurrence, Month, NoEndRecurrence, NumberedRecurrence, Recurrence, RecurrencePattern,
+    RecurrenceRange, RegenerationRecurrence, RelativeMonthlyRecurrence, RelativeYearlyRecurrence,
+    WeeklyRecurrence,
+};
+mod restriction;
+mod task;
+pub use self::task::{Task, TaskStatus};
+#[cfg(feature = "mime")]
+mod mime;
+#[cfg(feature = "mime")]
+pub use self::mime::{MimeAttachment, MimeHeader, ParsedMimeMessage};
+#[cfg(feature = "plain-text")]
+mod plain_text;
+#[cfg(feature = "windows-zones")]
+mod windows_zones;
+#[cfg(feature = "windows-zones")]
+pub use self::windows_zones::WindowsZone;
+mod redact;
+pub(crate) use self::redact::Redacted;
 
 pub(crate) const MESSAGES_NS_URI: &str =
     "http://schemas.microsoft.com/exchange/services/2006/messages";
@@ -25,11 +96,28 @@ pub(crate) const TYPES_NS_URI: &str = "http://schemas.microsoft.com/exchange/ser
 pub struct FolderShape {
     #[xml_struct(ns_prefix = "t")]
     pub base_shape: BaseShape,
+
+    /// A list of properties which should be included in addition to those
+    /// implied by the base shape, e.g. `folder:UnreadCount` or extended
+    /// properties.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/additionalproperties>
+    #[xml_struct(ns_prefix = "t")]
+    pub additional_properties: Option<Vec<PathToElement>>,
+}
+
+impl FolderShape {
+    /// Checks the shape for mistakes which the server would reject in the
+    /// given operation.
+    pub(crate) fn validate(&self, operation: &'static str) -> Result<(), Error> {
+        validate_additional_properties(operation, self.additional_properties.as_deref())
+    }
 }
 
 /// The item properties which should be included in the response.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/itemshape>.
+#[allow(non_snake_case)]
 #[derive(Clone, Debug, XmlSerialize)]
 pub struct ItemShape {
     /// The base set of properties to include, which may be extended by other
@@ -45,6 +133,33 @@ pub struct ItemShape {
     #[xml_struct(ns_prefix = "t")]
     pub include_mime_content: Option<bool>,
 
+    /// The format in which to return the bodies of items.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/bodytype>
+    #[xml_struct(ns_prefix = "t")]
+    pub body_type: Option<BodyType>,
+
+    /// Whether to remove potentially unsafe content, such as scripts, from
+    /// HTML bodies.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/filterhtmlcontent>
+    #[xml_struct(ns_prefix = "t")]
+    pub filter_html_content: Option<bool>,
+
+    /// Whether to convert HTML bodies to UTF-8 from the code page they are
+    /// stored in.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/converthtmlcodepagetoutf8>
+    #[xml_struct(ns_prefix = "t")]
+    pub convert_html_code_page_to_UTF8: Option<bool>,
+
+    /// Whether to prevent HTML bodies from loading images from external
+    /// servers, e.g. to avoid disclosing that a message was read.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/blockexternalimages>
+    #[xml_struct(ns_prefix = "t")]
+    pub block_external_images: Option<bool>,
+
     /// A list of properties which should be included in addition to those
     /// implied by other fields.
     ///
@@ -58,13 +173,95 @@ impl Default for ItemShape {
         Self {
             base_shape: BaseShape::IdOnly,
             include_mime_content: None,
+            body_type: None,
+            filter_html_content: None,
+            convert_html_code_page_to_UTF8: None,
+            block_external_images: None,
             additional_properties: None,
         }
     }
 }
 
+impl ItemShape {
+    /// Creates a shape including only the identifiers of items.
+    pub fn id_only() -> Self {
+        Self::default()
+    }
+
+    /// Creates a shape suited to [`SyncFolderItems`] requests.
+    ///
+    /// Microsoft recommends requesting only identifiers when synchronizing
+    /// items and fetching further properties with [`GetItem`], since
+    /// `SyncFolderItems` cannot return some properties (such as bodies) and
+    /// returns fewer changes per request as the shape grows. The item class is
+    /// included so that clients can tell what kind of item each change
+    /// concerns without a further request.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/how-to-synchronize-items-by-using-ews-in-exchange>
+    ///
+    /// [`SyncFolderItems`]: crate::sync_folder_items::SyncFolderItems
+    /// [`GetItem`]: crate::get_item::GetItem
+    pub fn sync_default() -> Self {
+        Self {
+            additional_properties: Some(vec![PathToElement::field("item:ItemClass")]),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a shape including all properties of items along with their
+    /// MIME content, e.g. for downloading full copies of messages.
+    pub fn full_with_mime() -> Self {
+        Self {
+            base_shape: BaseShape::AllProperties,
+            include_mime_content: Some(true),
+            ..Self::default()
+        }
+    }
+
+    /// Creates a shape including the default properties of items, with HTML
+    /// bodies sanitized by the server for display, e.g. of untrusted
+    /// messages.
+    ///
+    /// Scripts and other potentially unsafe content are removed and external
+    /// images are blocked.
+    pub fn sanitized_html() -> Self {
+        Self {
+            base_shape: BaseShape::Default,
+            body_type: Some(BodyType::HTML),
+            filter_html_content: Some(true),
+            convert_html_code_page_to_UTF8: Some(true),
+            block_external_images: Some(true),
+            ..Self::default()
+        }
+    }
+
+    /// Checks the shape for mistakes which the server would reject in the
+    /// given operation.
+    pub(crate) fn validate(&self, operation: &'static str) -> Result<(), Error> {
+        validate_additional_properties(operation, self.additional_properties.as_deref())
+    }
+}
+
+/// Checks the additional properties requested by a shape, which the schema
+/// requires to be a non-empty list if present.
+fn validate_additional_properties(
+    operation: &'static str,
+    additional_properties: Option<&[PathToElement]>,
+) -> Result<(), Error> {
+    if let Some(additional_properties) = additional_properties {
+        require_non_empty(operation, "additional_properties", additional_properties)?;
+        additional_properties
+            .iter()
+            .try_for_each(PathToElement::validate)?;
+    }
+
+    Ok(())
+}
+
 /// An identifier for a property on an Exchange entity.
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(variant_ns_prefix = "t")]
 pub enum PathToElement {
     /// An identifier for an extended MAPI property.
@@ -82,11 +279,13 @@ pub enum PathToElement {
     ExtendedFieldURI {
         /// A well-known identifier for a property set.
         #[xml_struct(attribute)]
+        #[serde(rename = "@DistinguishedPropertySetId")]
         distinguished_property_set_id: Option<DistinguishedPropertySet>,
 
         /// A GUID representing a property set.
         // TODO: This could use a strong type for representing a GUID.
         #[xml_struct(attribute)]
+        #[serde(rename = "@PropertySetId")]
         property_set_id: Option<String>,
 
         /// Specifies a property by integer tag.
@@ -94,18 +293,22 @@ pub enum PathToElement {
         // representation is preferred, and we should restrict the possible
         // values per the docs.
         #[xml_struct(attribute)]
+        #[serde(rename = "@PropertyTag")]
         property_tag: Option<String>,
 
         /// The name of a property within a specified property set.
         #[xml_struct(attribute)]
+        #[serde(rename = "@PropertyName")]
         property_name: Option<String>,
 
         /// The dispatch ID of a property within a specified property set.
         #[xml_struct(attribute)]
+        #[serde(rename = "@PropertyId")]
         property_id: Option<String>,
 
         /// The value type of the desired property.
         #[xml_struct(attribute)]
+        #[serde(rename = "@PropertyType")]
         property_type: PropertyType,
     },
 
@@ -121,6 +324,7 @@ pub enum PathToElement {
         // TODO: We could use an enum for this field. It's just large and not
         // worth typing out by hand.
         #[xml_struct(attribute)]
+        #[serde(rename = "@FieldURI")]
         field_URI: String,
     },
 
@@ -131,18 +335,72 @@ pub enum PathToElement {
     IndexedFieldURI {
         /// The well-known string identifier of the property.
         #[xml_struct(attribute)]
+        #[serde(rename = "@FieldURI")]
         field_URI: String,
 
         /// The member within the dictionary to access.
         #[xml_struct(attribute)]
+        #[serde(rename = "@FieldIndex")]
         field_index: String,
     },
 }
 
+impl PathToElement {
+    /// Creates an identifier for a property given by its well-known name,
+    /// e.g. `item:Subject`.
+    pub fn field(field_uri: impl Into<String>) -> Self {
+        PathToElement::FieldURI {
+            field_URI: field_uri.into(),
+        }
+    }
+
+    /// Creates an identifier for a member of a dictionary-based property,
+    /// e.g. `contacts:EmailAddress` with index `EmailAddress1`.
+    pub fn indexed(field_uri: impl Into<String>, field_index: impl Into<String>) -> Self {
+        PathToElement::IndexedFieldURI {
+            field_URI: field_uri.into(),
+            field_index: field_index.into(),
+        }
+    }
+
+    /// Creates an identifier for an extended MAPI property.
+    pub fn extended(field_uri: ExtendedFieldURI) -> Self {
+        field_uri.into()
+    }
+
+    /// Checks that an identifier for an extended MAPI property sets a valid
+    /// combination of attributes, as with [`ExtendedFieldURI::validate`].
+    ///
+    /// Other identifiers are always valid.
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            PathToElement::ExtendedFieldURI {
+                distinguished_property_set_id,
+                property_set_id,
+                property_tag,
+                property_name,
+                property_id,
+                property_type,
+            } => ExtendedFieldURI {
+                distinguished_property_set_id: *distinguished_property_set_id,
+                property_set_id: property_set_id.clone(),
+                property_tag: property_tag.clone(),
+                property_name: property_name.clone(),
+                property_id: property_id.clone(),
+                property_type: *property_type,
+            }
+            .validate(),
+            PathToElement::FieldURI { .. } | PathToElement::IndexedFieldURI { .. } => Ok(()),
+        }
+    }
+}
+
 /// Response objects available for a message item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/responseobjects>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct ResponseObjects {
     /// Available reply action for the message.
@@ -156,6 +414,12 @@ pub struct ResponseObjects {
     /// Available forward action for the message.
     #[xml_struct(ns_prefix = "t")]
     pub forward_item: Option<MessageResponseObject>,
+
+    /// Available action for proposing a new time for a meeting.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/proposenewtime>
+    #[xml_struct(ns_prefix = "t")]
+    pub propose_new_time: Option<MessageResponseObject>,
 }
 
 /// A response object representing an available action on a message.
@@ -163,6 +427,8 @@ pub struct ResponseObjects {
 /// These are different from the operation types and represent the available
 /// actions that can be performed on a message as returned in GetItem responses.
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MessageResponseObject {
     // This struct is intentionally empty as the XML elements typically
     // only indicate availability of the action through their presence
@@ -176,37 +442,256 @@ pub struct MessageResponseObject {
 // support using a nested structure to define an element's attributes, see
 // https://github.com/thunderbird/xml-struct-rs/issues/9
 #[derive(Clone, Debug, Deserialize, XmlSerialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExtendedFieldURI {
     /// A well-known identifier for a property set.
     #[xml_struct(attribute)]
+    #[serde(rename = "@DistinguishedPropertySetId")]
     pub distinguished_property_set_id: Option<DistinguishedPropertySet>,
 
     /// A GUID representing a property set.
     // TODO: This could use a strong type for representing a GUID.
     #[xml_struct(attribute)]
+    #[serde(rename = "@PropertySetId")]
     pub property_set_id: Option<String>,
 
     /// Specifies a property by integer tag.
     #[xml_struct(attribute)]
+    #[serde(rename = "@PropertyTag")]
     pub property_tag: Option<String>,
 
     /// The name of a property within a specified property set.
     #[xml_struct(attribute)]
+    #[serde(rename = "@PropertyName")]
     pub property_name: Option<String>,
 
     /// The dispatch ID of a property within a specified property set.
     #[xml_struct(attribute)]
+    #[serde(rename = "@PropertyId")]
     pub property_id: Option<String>,
 
     /// The value type of the desired property.
     #[xml_struct(attribute)]
+    #[serde(rename = "@PropertyType")]
     pub property_type: PropertyType,
 }
 
+impl ExtendedFieldURI {
+    /// Creates an identifier for a property given by its property tag.
+    ///
+    /// The tag must not include the type part, i.e. it is the 16-bit property
+    /// identifier, such as `0x0E07` for `PR_MESSAGE_FLAGS`.
+    pub fn by_tag(tag: u32, property_type: PropertyType) -> Result<Self, Error> {
+        if tag > 0xFFFF {
+            return Err(Error::InvalidExtendedFieldUri(format!(
+                "property tag {tag:#010X} must not include the property type"
+            )));
+        }
+
+        Ok(Self {
+            distinguished_property_set_id: None,
+            property_set_id: None,
+            property_tag: Some(format!("0x{tag:04X}")),
+            property_name: None,
+            property_id: None,
+            property_type,
+        })
+    }
+
+    /// Creates an identifier for a named property given by its name within a
+    /// property set.
+    pub fn by_name(
+        property_set: impl Into<PropertySet>,
+        name: impl Into<String>,
+        property_type: PropertyType,
+    ) -> Result<Self, Error> {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(Error::InvalidExtendedFieldUri(
+                "property name must not be empty".to_string(),
+            ));
+        }
+
+        let (distinguished_property_set_id, property_set_id) =
+            property_set.into().into_attributes()?;
+
+        Ok(Self {
+            distinguished_property_set_id,
+            property_set_id,
+            property_tag: None,
+            property_name: Some(name),
+            property_id: None,
+            property_type,
+        })
+    }
+
+    /// Creates an identifier for a named property given by its dispatch ID
+    /// within a property set.
+    pub fn by_id(
+        property_set: impl Into<PropertySet>,
+        id: u32,
+        property_type: PropertyType,
+    ) -> Result<Self, Error> {
+        let (distinguished_property_set_id, property_set_id) =
+            property_set.into().into_attributes()?;
+
+        Ok(Self {
+            distinguished_property_set_id,
+            property_set_id,
+            property_tag: None,
+            property_name: None,
+            property_id: Some(id.to_string()),
+            property_type,
+        })
+    }
+
+    /// Checks that the identifier sets a valid combination of attributes.
+    ///
+    /// A property is identified either by its tag alone, or by exactly one of
+    /// a distinguished property set or property set GUID along with exactly
+    /// one of a name or dispatch ID.
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |reason: &str| Err(Error::InvalidExtendedFieldUri(reason.to_string()));
+
+        let has_set =
+            self.distinguished_property_set_id.is_some() || self.property_set_id.is_some();
+        let has_name_or_id = self.property_name.is_some() || self.property_id.is_some();
+
+        if let Some(tag) = &self.property_tag {
+            if has_set || has_name_or_id {
+                return invalid(
+                    "a property tag cannot be combined with a property set, name or ID",
+                );
+            }
+
+            let parsed = match tag.strip_prefix("0x").or_else(|| tag.strip_prefix("0X")) {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => tag.parse(),
+            };
+            return match parsed {
+                Ok(tag) if tag <= 0xFFFF => Ok(()),
+                _ => invalid("a property tag must be a 16-bit integer"),
+            };
+        }
+
+        if self.distinguished_property_set_id.is_some() && self.property_set_id.is_some() {
+            return invalid(
+                "a distinguished property set ID cannot be combined with a property set ID",
+            );
+        }
+
+        if self.property_name.is_some() && self.property_id.is_some() {
+            return invalid("a property name cannot be combined with a property ID");
+        }
+
+        if !has_set || !has_name_or_id {
+            return invalid("a property requires either a tag, or a property set and a name or ID");
+        }
+
+        if let Some(guid) = &self.property_set_id {
+            validate_guid(guid)?;
+        }
+
+        Ok(())
+    }
+
+    /// Determines whether two identifiers refer to the same property.
+    ///
+    /// Unlike comparing for equality, this accepts differing representations
+    /// of the same property tag, e.g. `0xe07` as returned by servers and
+    /// `0x0E07`, and compares property set GUIDs without regard to case.
+    pub fn identifies_same(&self, other: &ExtendedFieldURI) -> bool {
+        let tag = |uri: &ExtendedFieldURI| {
+            uri.property_tag.as_deref().map(|tag| {
+                match tag.strip_prefix("0x").or_else(|| tag.strip_prefix("0X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => tag.parse().ok(),
+                }
+            })
+        };
+
+        let same_set_id = match (&self.property_set_id, &other.property_set_id) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            (a, b) => a == b,
+        };
+
+        tag(self) == tag(other)
+            && same_set_id
+            && self.distinguished_property_set_id == other.distinguished_property_set_id
+            && self.property_name == other.property_name
+            && self.property_id == other.property_id
+            && self.property_type == other.property_type
+    }
+}
+
+impl From<ExtendedFieldURI> for PathToElement {
+    fn from(value: ExtendedFieldURI) -> Self {
+        PathToElement::ExtendedFieldURI {
+            distinguished_property_set_id: value.distinguished_property_set_id,
+            property_set_id: value.property_set_id,
+            property_tag: value.property_tag,
+            property_name: value.property_name,
+            property_id: value.property_id,
+            property_type: value.property_type,
+        }
+    }
+}
+
+/// The property set containing a named MAPI property.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PropertySet {
+    /// A well-known property set.
+    Distinguished(DistinguishedPropertySet),
+
+    /// A property set identified by its GUID, e.g.
+    /// `00062008-0000-0000-C000-000000000046`.
+    Guid(String),
+}
+
+impl PropertySet {
+    fn into_attributes(self) -> Result<(Option<DistinguishedPropertySet>, Option<String>), Error> {
+        match self {
+            PropertySet::Distinguished(set) => Ok((Some(set), None)),
+            PropertySet::Guid(guid) => {
+                validate_guid(&guid)?;
+                Ok((None, Some(guid)))
+            }
+        }
+    }
+}
+
+impl From<DistinguishedPropertySet> for PropertySet {
+    fn from(value: DistinguishedPropertySet) -> Self {
+        PropertySet::Distinguished(value)
+    }
+}
+
+/// Checks that a property set ID is a GUID in its hyphenated form, without
+/// braces.
+fn validate_guid(guid: &str) -> Result<(), Error> {
+    let groups: Vec<&str> = guid.split('-').collect();
+    let is_valid = groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.bytes().all(|b| b.is_ascii_hexdigit()));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidExtendedFieldUri(format!(
+            "property set ID `{guid}` is not a GUID"
+        )))
+    }
+}
+
 /// A well-known MAPI property set identifier.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/extendedfielduri#distinguishedpropertysetid-attribute>
 #[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(text)]
 pub enum DistinguishedPropertySet {
     Address,
@@ -237,6 +722,8 @@ pub enum MessageDisposition {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/extendedfielduri#propertytype-attribute>
 #[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(text)]
 pub enum PropertyType {
     ApplicationTime,
@@ -285,14 +772,38 @@ pub enum BaseShape {
     AllProperties,
 }
 
-/// The traversal method for a find operation.
+/// The traversal method for a `FindFolder` operation.
 ///
-/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/findfolder-operation>
-#[derive(Clone, Copy, Debug, XmlSerialize)]
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/findfolder>
+#[derive(Clone, Copy, Debug, Default, XmlSerialize)]
+#[xml_struct(text)]
+pub enum FolderTraversal {
+    /// Finds only the immediate subfolders of the parent folders.
+    #[default]
+    Shallow,
+
+    /// Finds all subfolders of the parent folders, at any depth.
+    Deep,
+
+    /// Finds only soft-deleted subfolders of the parent folders.
+    SoftDeleted,
+}
+
+/// The traversal method for a `FindItem` operation.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/finditem>
+#[derive(Clone, Copy, Debug, Default, XmlSerialize)]
 #[xml_struct(text)]
-pub enum Traversal {
+pub enum ItemTraversal {
+    /// Finds only the items directly in the parent folders.
+    #[default]
     Shallow,
+
+    /// Finds only soft-deleted items in the parent folders.
     SoftDeleted,
+
+    /// Finds only folder associated items, i.e. hidden items such as
+    /// configuration data, in the parent folders.
     Associated,
 }
 
@@ -350,47 +861,141 @@ pub enum BasePoint {
 /// A restriction or filter for a search operation.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/restriction>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Restriction {
     #[xml_struct(flatten)]
+    #[serde(rename = "$value")]
     pub restriction_type: RestrictionType,
 }
 
-#[derive(Clone, Debug, XmlSerialize)]
+// `Deserialize` is implemented by hand, see `restriction.rs`.
+#[derive(Clone, Debug, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(variant_ns_prefix = "t")]
 pub enum RestrictionType {
     And(AndRestriction),
     Or(OrRestriction),
-    // TODO: Not
+    Not(NotRestriction),
     IsEqualTo(FieldEqualTo),
-    // TODO: IsNotEqualTo, IsGreaterThan, IsGreaterThanOrEqualTo, IsLessThan, IsLessThanOrEqualTo
-    // TODO: Contains, Excludes
+    IsNotEqualTo(FieldEqualTo),
+    IsGreaterThan(FieldEqualTo),
+    IsGreaterThanOrEqualTo(FieldEqualTo),
+    IsLessThan(FieldEqualTo),
+    IsLessThanOrEqualTo(FieldEqualTo),
+    Contains(ContainsRestriction),
+    // TODO: Excludes
     Exists(PathToElement),
 }
 
 /// Represents a logical AND operation between multiple restrictions.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/and>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AndRestriction(#[xml_struct(ns_prefix = "t")] pub Vec<Restriction>);
 
 /// Represents a logical OR operation between multiple restrictions.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/or>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct OrRestriction(#[xml_struct(ns_prefix = "t")] pub Vec<Restriction>);
 
-// TODO: Implement NOT restriction once Box<T> serialization is resolved
-// /// Represents a logical NOT operation that negates another restriction.
-// ///
-// /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/not>
-// #[derive(Clone, Debug, XmlSerialize)]
-// pub struct NotRestriction {
-//     #[xml_struct(flatten, ns_prefix = "t")]
-//     pub restriction: Box<Restriction>,
-// }
+/// Represents a logical NOT operation that negates another restriction.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/not>
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct NotRestriction(pub Box<Restriction>);
 
-#[derive(Clone, Debug, XmlSerialize)]
+// `xml_struct` can't derive serialization through a `Box`, so serialize the
+// negated expression by hand.
+impl XmlSerialize for NotRestriction {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        self.0.serialize_child_nodes(writer)
+    }
+}
+
+/// Represents a search for a string within a text property.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contains>
+#[derive(Clone, Debug, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ContainsRestriction {
+    /// The part of the property's value in which to search.
+    #[xml_struct(attribute)]
+    pub containment_mode: Option<ContainmentMode>,
+
+    /// How strictly the property's value must match the constant.
+    #[xml_struct(attribute)]
+    pub containment_comparison: Option<ContainmentComparison>,
+
+    #[xml_struct(flatten, ns_prefix = "t")]
+    pub path: PathToElement,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub constant: Constant,
+}
+
+/// The part of a property's value in which a [`ContainsRestriction`]
+/// searches.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contains#containmentmode-attribute>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, XmlSerialize)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum ContainmentMode {
+    /// The constant must match the full value.
+    FullString,
+
+    /// The constant must match the start of the value.
+    Prefixed,
+
+    /// The constant may appear anywhere in the value.
+    Substring,
+
+    /// The constant must match the start of a word in the value.
+    PrefixOnWords,
+
+    /// The constant must appear as a phrase in the value.
+    ExactPhrase,
+}
+
+/// How strictly a [`ContainsRestriction`] compares a property's value.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contains#containmentcomparison-attribute>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, XmlSerialize)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum ContainmentComparison {
+    Exact,
+    IgnoreCase,
+    IgnoreNonSpacingCharacters,
+    Loose,
+    IgnoreCaseAndNonSpacingCharacters,
+    LooseAndIgnoreCase,
+    LooseAndIgnoreNonSpace,
+    LooseAndIgnoreCaseAndIgnoreNonSpace,
+}
+
+#[derive(Clone, Debug, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[allow(non_snake_case)]
 pub struct FieldEqualTo {
     #[xml_struct(flatten, ns_prefix = "t")]
@@ -399,15 +1004,71 @@ pub struct FieldEqualTo {
     pub FieldURIOrConstant: FieldURIOrConstant,
 }
 
-#[derive(Clone, Debug, XmlSerialize)]
-pub struct FieldURIOrConstant {
-    #[xml_struct(ns_prefix = "t")]
-    pub constant: Constant,
+/// The value against which a property is compared, either a constant or the
+/// value of another property of the same item.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/fielduriorconstant>
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum FieldURIOrConstant {
+    /// A constant value.
+    Constant(Constant),
+
+    /// The value of another property.
+    Path(PathToElement),
 }
 
-#[derive(Clone, Debug, XmlSerialize)]
+// A property is written as its own identifier element, e.g. `t:FieldURI`,
+// rather than wrapped in an element named for the variant, which the derive
+// can't express.
+impl XmlSerialize for FieldURIOrConstant {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        match self {
+            FieldURIOrConstant::Constant(constant) => {
+                constant.serialize_as_element(writer, "t:Constant")
+            }
+            FieldURIOrConstant::Path(path) => path.serialize_child_nodes(writer),
+        }
+    }
+}
+
+impl From<String> for FieldURIOrConstant {
+    fn from(value: String) -> Self {
+        FieldURIOrConstant::Constant(Constant { value })
+    }
+}
+
+impl From<&str> for FieldURIOrConstant {
+    fn from(value: &str) -> Self {
+        value.to_string().into()
+    }
+}
+
+impl From<Constant> for FieldURIOrConstant {
+    fn from(value: Constant) -> Self {
+        FieldURIOrConstant::Constant(value)
+    }
+}
+
+impl From<PathToElement> for FieldURIOrConstant {
+    fn from(value: PathToElement) -> Self {
+        FieldURIOrConstant::Path(value)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Constant {
     #[xml_struct(attribute)]
+    #[serde(rename = "@Value")]
     pub value: String,
 }
 
@@ -426,59 +1087,213 @@ impl Restriction {
         }
     }
 
-    // TODO: Implement NOT helper once Box<T> serialization is resolved
-    // /// Creates a new NOT restriction that negates another restriction.
-    // pub fn not(restriction: Restriction) -> Self {
-    //     Self {
-    //         restriction_type: RestrictionType::Not(NotRestriction {
-    //             restriction: Box::new(restriction),
-    //         }),
-    //     }
-    // }
+    /// Creates a new NOT restriction that negates another restriction.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(restriction: Restriction) -> Self {
+        Self {
+            restriction_type: RestrictionType::Not(NotRestriction(Box::new(restriction))),
+        }
+    }
 
     /// Creates a new IsEqualTo restriction for field equality.
-    pub fn equal_to(path: PathToElement, value: String) -> Self {
+    pub fn equal_to(path: PathToElement, value: impl Into<FieldURIOrConstant>) -> Self {
         Self {
             restriction_type: RestrictionType::IsEqualTo(FieldEqualTo {
                 path,
-                FieldURIOrConstant: FieldURIOrConstant {
-                    constant: Constant { value },
-                },
+                FieldURIOrConstant: value.into(),
             }),
         }
     }
 
-    /// Creates a new Exists restriction to check field presence.
-    pub fn exists(path: PathToElement) -> Self {
+    /// Creates a new IsNotEqualTo restriction for field inequality.
+    pub fn not_equal_to(path: PathToElement, value: impl Into<FieldURIOrConstant>) -> Self {
         Self {
-            restriction_type: RestrictionType::Exists(path),
+            restriction_type: RestrictionType::IsNotEqualTo(FieldEqualTo {
+                path,
+                FieldURIOrConstant: value.into(),
+            }),
         }
     }
-}
-
-/// Represents a single field by which to sort the results of a search.
-///
-/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/fieldorder>
-#[derive(Clone, Debug, XmlSerialize)]
-pub struct FieldOrder {
-    #[xml_struct(flatten)]
-    pub path: PathToElement,
-    #[xml_struct(attribute)]
-    pub order: SortDirection,
-}
 
-/// The direction in which to sort the results of a search.
-///
-/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/sortdirection>
-#[derive(Clone, Copy, Debug, XmlSerialize)]
-#[xml_struct(text)]
-pub enum SortDirection {
-    Ascending,
-    Descending,
-}
+    /// Creates a new IsGreaterThan restriction matching fields with values
+    /// greater than the given value.
+    pub fn greater_than(path: PathToElement, value: impl Into<FieldURIOrConstant>) -> Self {
+        Self {
+            restriction_type: RestrictionType::IsGreaterThan(FieldEqualTo {
+                path,
+                FieldURIOrConstant: value.into(),
+            }),
+        }
+    }
 
-/// The common format for item move and copy operations.
-#[derive(Clone, Debug, XmlSerialize)]
+    /// Creates a new IsLessThan restriction matching fields with values less
+    /// than the given value.
+    pub fn less_than(path: PathToElement, value: impl Into<FieldURIOrConstant>) -> Self {
+        Self {
+            restriction_type: RestrictionType::IsLessThan(FieldEqualTo {
+                path,
+                FieldURIOrConstant: value.into(),
+            }),
+        }
+    }
+
+    /// Creates a new IsGreaterThanOrEqualTo restriction matching fields with
+    /// values greater than or equal to the given value.
+    pub fn greater_than_or_equal_to(
+        path: PathToElement,
+        value: impl Into<FieldURIOrConstant>,
+    ) -> Self {
+        Self {
+            restriction_type: RestrictionType::IsGreaterThanOrEqualTo(FieldEqualTo {
+                path,
+                FieldURIOrConstant: value.into(),
+            }),
+        }
+    }
+
+    /// Creates a new IsLessThanOrEqualTo restriction matching fields with
+    /// values less than or equal to the given value.
+    pub fn less_than_or_equal_to(
+        path: PathToElement,
+        value: impl Into<FieldURIOrConstant>,
+    ) -> Self {
+        Self {
+            restriction_type: RestrictionType::IsLessThanOrEqualTo(FieldEqualTo {
+                path,
+                FieldURIOrConstant: value.into(),
+            }),
+        }
+    }
+
+    /// Creates a new Contains restriction matching text fields containing
+    /// the given constant.
+    pub fn contains(
+        path: PathToElement,
+        value: String,
+        containment_mode: ContainmentMode,
+        containment_comparison: ContainmentComparison,
+    ) -> Self {
+        Self {
+            restriction_type: RestrictionType::Contains(ContainsRestriction {
+                containment_mode: Some(containment_mode),
+                containment_comparison: Some(containment_comparison),
+                path,
+                constant: Constant { value },
+            }),
+        }
+    }
+
+    /// Creates a new Exists restriction to check field presence.
+    pub fn exists(path: PathToElement) -> Self {
+        Self {
+            restriction_type: RestrictionType::Exists(path),
+        }
+    }
+
+    /// Checks the restriction for mistakes which the server would reject in
+    /// the given operation.
+    pub(crate) fn validate(&self, operation: &'static str) -> Result<(), Error> {
+        match &self.restriction_type {
+            RestrictionType::And(AndRestriction(restrictions))
+            | RestrictionType::Or(OrRestriction(restrictions)) => {
+                require_non_empty(operation, "restriction", restrictions)?;
+                restrictions
+                    .iter()
+                    .try_for_each(|restriction| restriction.validate(operation))
+            }
+            RestrictionType::Not(NotRestriction(restriction)) => restriction.validate(operation),
+            RestrictionType::IsEqualTo(comparison)
+            | RestrictionType::IsNotEqualTo(comparison)
+            | RestrictionType::IsGreaterThan(comparison)
+            | RestrictionType::IsGreaterThanOrEqualTo(comparison)
+            | RestrictionType::IsLessThan(comparison)
+            | RestrictionType::IsLessThanOrEqualTo(comparison) => {
+                comparison.path.validate()?;
+                match &comparison.FieldURIOrConstant {
+                    FieldURIOrConstant::Path(path) => path.validate(),
+                    FieldURIOrConstant::Constant(_) => Ok(()),
+                }
+            }
+            RestrictionType::Contains(contains) => contains.path.validate(),
+            RestrictionType::Exists(path) => path.validate(),
+        }
+    }
+}
+
+/// Represents a single field by which to sort the results of a search.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/fieldorder>
+#[derive(Clone, Debug, XmlSerialize)]
+pub struct FieldOrder {
+    #[xml_struct(flatten)]
+    pub path: PathToElement,
+    #[xml_struct(attribute)]
+    pub order: SortDirection,
+}
+
+/// The fields by which to sort the results of a search, in order of
+/// precedence.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/sortorder>
+#[derive(Clone, Debug, Default)]
+pub struct SortOrder(pub Vec<FieldOrder>);
+
+impl From<Vec<FieldOrder>> for SortOrder {
+    fn from(value: Vec<FieldOrder>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<FieldOrder> for SortOrder {
+    fn from(value: FieldOrder) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl Deref for SortOrder {
+    type Target = Vec<FieldOrder>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SortOrder {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+// Each field is written as its own `t:FieldOrder` element, which the derive
+// can't express for a list of structs.
+impl XmlSerialize for SortOrder {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        for field_order in &self.0 {
+            field_order.serialize_as_element(writer, "t:FieldOrder")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The direction in which to sort the results of a search.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/sortdirection>
+#[derive(Clone, Copy, Debug, XmlSerialize)]
+#[xml_struct(text)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// The common format for item move and copy operations.
+#[derive(Clone, Debug, XmlSerialize)]
 pub struct CopyMoveItemData {
     /// The destination folder for the copied/moved item.
     ///
@@ -510,6 +1325,8 @@ pub struct CopyMoveFolderData {
 
 /// The common format of folder response messages.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct FolderResponseMessage {
     pub folders: Folders,
@@ -517,13 +1334,17 @@ pub struct FolderResponseMessage {
 
 /// The common format of item response messages.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct ItemResponseMessage {
     pub items: Items,
 }
 
 /// An identifier for an Exchange folder.
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(variant_ns_prefix = "t")]
 pub enum BaseFolderId {
     /// An identifier for an arbitrary folder.
@@ -531,9 +1352,11 @@ pub enum BaseFolderId {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/folderid>.
     FolderId {
         #[xml_struct(attribute)]
+        #[serde(rename = "@Id")]
         id: String,
 
         #[xml_struct(attribute)]
+        #[serde(rename = "@ChangeKey")]
         change_key: Option<String>,
     },
 
@@ -543,22 +1366,215 @@ pub enum BaseFolderId {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/distinguishedfolderid>.
     DistinguishedFolderId {
         #[xml_struct(attribute)]
+        #[serde(rename = "@Id")]
         id: String,
 
         #[xml_struct(attribute)]
+        #[serde(rename = "@ChangeKey")]
         change_key: Option<String>,
     },
 }
 
+impl BaseFolderId {
+    /// Creates an identifier for a distinguished folder.
+    pub fn distinguished(name: DistinguishedFolderName) -> Self {
+        BaseFolderId::DistinguishedFolderId {
+            id: name.as_str().to_string(),
+            change_key: None,
+        }
+    }
+
+    /// Creates an identifier for the Inbox folder.
+    pub fn inbox() -> Self {
+        Self::distinguished(DistinguishedFolderName::Inbox)
+    }
+
+    /// Creates an identifier for the Drafts folder.
+    pub fn drafts() -> Self {
+        Self::distinguished(DistinguishedFolderName::Drafts)
+    }
+
+    /// Creates an identifier for the Sent Items folder.
+    pub fn sent_items() -> Self {
+        Self::distinguished(DistinguishedFolderName::SentItems)
+    }
+
+    /// Creates an identifier for the Deleted Items folder.
+    pub fn deleted_items() -> Self {
+        Self::distinguished(DistinguishedFolderName::DeletedItems)
+    }
+
+    /// Creates an identifier for the Junk Email folder.
+    pub fn junk_email() -> Self {
+        Self::distinguished(DistinguishedFolderName::JunkEmail)
+    }
+
+    /// Creates an identifier for the root of the message folder hierarchy.
+    pub fn msg_folder_root() -> Self {
+        Self::distinguished(DistinguishedFolderName::MsgFolderRoot)
+    }
+
+    /// Creates an identifier for the Recoverable Items Deletions folder.
+    pub fn recoverable_items_deletions() -> Self {
+        Self::distinguished(DistinguishedFolderName::RecoverableItemsDeletions)
+    }
+}
+
+impl From<DistinguishedFolderName> for BaseFolderId {
+    fn from(value: DistinguishedFolderName) -> Self {
+        Self::distinguished(value)
+    }
+}
+
+/// Declares [`DistinguishedFolderName`] along with the mapping between its
+/// variants and their identifiers.
+macro_rules! distinguished_folder_names {
+    ($($(#[$attr:meta])* $variant:ident => $id:literal,)*) => {
+        /// The name of a well-known folder which can be referenced without
+        /// knowing its identifier.
+        ///
+        /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/distinguishedfolderid#attributes>
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum DistinguishedFolderName {
+            $($(#[$attr])* $variant,)*
+        }
+
+        impl DistinguishedFolderName {
+            /// All known distinguished folder names.
+            pub const ALL: &'static [DistinguishedFolderName] =
+                &[$(DistinguishedFolderName::$variant,)*];
+
+            /// Gets the identifier of the folder as used in requests, e.g.
+            /// `junkemail`.
+            pub const fn as_str(&self) -> &'static str {
+                match self {
+                    $(DistinguishedFolderName::$variant => $id,)*
+                }
+            }
+        }
+    };
+}
+
+distinguished_folder_names! {
+    /// The default calendar folder.
+    Calendar => "calendar",
+    /// The default contacts folder.
+    Contacts => "contacts",
+    /// The Deleted Items folder.
+    DeletedItems => "deleteditems",
+    /// The Drafts folder.
+    Drafts => "drafts",
+    /// The Inbox folder.
+    Inbox => "inbox",
+    /// The Journal folder.
+    Journal => "journal",
+    /// The Notes folder.
+    Notes => "notes",
+    /// The Outbox folder.
+    Outbox => "outbox",
+    /// The Sent Items folder.
+    SentItems => "sentitems",
+    /// The Tasks folder.
+    Tasks => "tasks",
+    /// The root of the message folder hierarchy, i.e. the Top of Information Store.
+    MsgFolderRoot => "msgfolderroot",
+    /// The root of the public folders hierarchy.
+    PublicFoldersRoot => "publicfoldersroot",
+    /// The root of the mailbox.
+    Root => "root",
+    /// The Junk Email folder.
+    JunkEmail => "junkemail",
+    /// The Search Folders folder.
+    SearchFolders => "searchfolders",
+    /// The Voice Mail folder.
+    VoiceMail => "voicemail",
+    /// The root of the Recoverable Items folders.
+    RecoverableItemsRoot => "recoverableitemsroot",
+    /// The Recoverable Items Deletions folder.
+    RecoverableItemsDeletions => "recoverableitemsdeletions",
+    /// The Recoverable Items Versions folder.
+    RecoverableItemsVersions => "recoverableitemsversions",
+    /// The Recoverable Items Purges folder.
+    RecoverableItemsPurges => "recoverableitemspurges",
+    /// The root of the archive mailbox.
+    ArchiveRoot => "archiveroot",
+    /// The root of the archive mailbox's message folder hierarchy.
+    ArchiveMsgFolderRoot => "archivemsgfolderroot",
+    /// The archive mailbox's Deleted Items folder.
+    ArchiveDeletedItems => "archivedeleteditems",
+    /// The archive mailbox's Inbox folder.
+    ArchiveInbox => "archiveinbox",
+    /// The root of the archive mailbox's Recoverable Items folders.
+    ArchiveRecoverableItemsRoot => "archiverecoverableitemsroot",
+    /// The archive mailbox's Recoverable Items Deletions folder.
+    ArchiveRecoverableItemsDeletions => "archiverecoverableitemsdeletions",
+    /// The archive mailbox's Recoverable Items Versions folder.
+    ArchiveRecoverableItemsVersions => "archiverecoverableitemsversions",
+    /// The archive mailbox's Recoverable Items Purges folder.
+    ArchiveRecoverableItemsPurges => "archiverecoverableitemspurges",
+    /// The Sync Issues folder.
+    SyncIssues => "syncissues",
+    /// The Conflicts folder.
+    Conflicts => "conflicts",
+    /// The Local Failures folder.
+    LocalFailures => "localfailures",
+    /// The Server Failures folder.
+    ServerFailures => "serverfailures",
+    /// The recipient cache folder.
+    RecipientCache => "recipientcache",
+    /// The Quick Contacts folder.
+    QuickContacts => "quickcontacts",
+    /// The Conversation History folder.
+    ConversationHistory => "conversationhistory",
+    /// The Admin Audit Logs folder.
+    AdminAuditLogs => "adminauditlogs",
+    /// The To-Do search folder.
+    ToDoSearch => "todosearch",
+    /// The My Contacts folder.
+    MyContacts => "mycontacts",
+    /// The directory folder.
+    Directory => "directory",
+    /// The instant messaging contact list folder.
+    ImContactList => "imcontactlist",
+    /// The People Connect folder.
+    PeopleConnect => "peopleconnect",
+    /// The Favorites folder.
+    Favorites => "favorites",
+}
+
+impl fmt::Display for DistinguishedFolderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DistinguishedFolderName {
+    type Err = Error;
+
+    /// Parses a distinguished folder identifier, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|name| name.as_str().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::UnknownDistinguishedFolder(s.to_string()))
+    }
+}
+
 /// The unique identifier of a folder.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/folderid>
 #[derive(Clone, Debug, Deserialize, PartialEq, XmlSerialize, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct FolderId {
     #[serde(rename = "@Id")]
+    #[xml_struct(attribute)]
     pub id: String,
 
     #[serde(rename = "@ChangeKey")]
+    #[xml_struct(attribute)]
     pub change_key: Option<String>,
 }
 
@@ -596,6 +1612,8 @@ pub enum BaseItemId {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/itemid>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ItemId {
     #[xml_struct(attribute)]
     #[serde(rename = "@Id")]
@@ -624,8 +1642,150 @@ impl ItemId {
     }
 }
 
+impl From<ItemId> for BaseItemId {
+    fn from(value: ItemId) -> Self {
+        BaseItemId::ItemId {
+            id: value.id,
+            change_key: value.change_key,
+        }
+    }
+}
+
+impl From<BaseItemId> for ItemId {
+    fn from(value: BaseItemId) -> Self {
+        match value {
+            BaseItemId::ItemId { id, change_key } => Self { id, change_key },
+        }
+    }
+}
+
+/// The criteria by which a search folder finds its items.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/searchparameters>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct SearchParameters {
+    /// Whether the search includes the subfolders of the base folders.
+    #[xml_struct(attribute)]
+    #[serde(rename = "@Traversal")]
+    pub traversal: Option<SearchFolderTraversal>,
+
+    /// The restriction which items must match to appear in the folder.
+    #[xml_struct(ns_prefix = "t")]
+    #[serde(rename = "Restriction")]
+    pub restriction: Restriction,
+
+    /// The folders in which to search.
+    #[xml_struct(ns_prefix = "t")]
+    #[serde(
+        rename = "BaseFolderIds",
+        deserialize_with = "deserialize_base_folder_ids"
+    )]
+    pub base_folder_ids: Vec<BaseFolderId>,
+}
+
+/// Deserializes the identifiers of the folders a search folder searches.
+fn deserialize_base_folder_ids<'de, D>(deserializer: D) -> Result<Vec<BaseFolderId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct BaseFolderIds {
+        #[serde(rename = "$value", default)]
+        ids: Vec<BaseFolderId>,
+    }
+
+    Ok(BaseFolderIds::deserialize(deserializer)?.ids)
+}
+
+impl SearchParameters {
+    /// Creates parameters for a search of the given folders, without their
+    /// subfolders, for items matching the restriction.
+    pub fn new(restriction: impl Into<Restriction>, base_folder_ids: Vec<BaseFolderId>) -> Self {
+        Self {
+            traversal: Some(SearchFolderTraversal::Shallow),
+            restriction: restriction.into(),
+            base_folder_ids,
+        }
+    }
+
+    /// Sets whether the search includes the subfolders of the base folders.
+    pub fn with_traversal(mut self, traversal: SearchFolderTraversal) -> Self {
+        self.traversal = Some(traversal);
+        self
+    }
+
+    /// Checks the parameters for mistakes which the server would reject in
+    /// the given operation.
+    pub(crate) fn validate(&self, operation: &'static str) -> Result<(), Error> {
+        require_non_empty(operation, "base_folder_ids", &self.base_folder_ids)?;
+        self.restriction.validate(operation)
+    }
+}
+
+/// Whether a search folder includes the subfolders of its base folders.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/searchparameters>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, XmlSerialize)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum SearchFolderTraversal {
+    /// Searches only the base folders themselves.
+    #[default]
+    Shallow,
+
+    /// Searches the base folders and all of their subfolders.
+    Deep,
+}
+
+/// The properties shared by every kind of folder.
+///
+/// These are written before the properties specific to the kind of folder,
+/// e.g. the unread count of a [`Folder::Folder`].
+#[derive(Clone, Debug, Default, Deserialize, XmlSerialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct FolderProperties {
+    #[xml_struct(ns_prefix = "t")]
+    pub folder_id: Option<FolderId>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub parent_folder_id: Option<FolderId>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub folder_class: Option<String>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub display_name: Option<String>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub total_count: Option<u32>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub child_folder_count: Option<u32>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub extended_property: Option<Vec<ExtendedProperty>>,
+}
+
+impl FolderProperties {
+    /// Creates properties for a new folder with the given display name.
+    pub fn new(display_name: impl Into<String>) -> Self {
+        Self {
+            display_name: Some(display_name.into()),
+            ..Default::default()
+        }
+    }
+}
+
 /// The representation of a folder in an EWS operation.
 #[derive(Clone, Debug, Deserialize, XmlSerialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(from = "folder::FolderElement")]
 #[xml_struct(variant_ns_prefix = "t")]
 pub enum Folder {
     /// A calendar folder in a mailbox.
@@ -633,26 +1793,9 @@ pub enum Folder {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/calendarfolder>
     #[serde(rename_all = "PascalCase")]
     CalendarFolder {
-        #[xml_struct(ns_prefix = "t")]
-        folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        parent_folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        folder_class: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        display_name: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        total_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        child_folder_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        extended_property: Option<Vec<ExtendedProperty>>,
+        #[xml_struct(flatten)]
+        #[serde(flatten)]
+        properties: FolderProperties,
     },
 
     /// A contacts folder in a mailbox.
@@ -660,26 +1803,9 @@ pub enum Folder {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contactsfolder>
     #[serde(rename_all = "PascalCase")]
     ContactsFolder {
-        #[xml_struct(ns_prefix = "t")]
-        folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        parent_folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        folder_class: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        display_name: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        total_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        child_folder_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        extended_property: Option<Vec<ExtendedProperty>>,
+        #[xml_struct(flatten)]
+        #[serde(flatten)]
+        properties: FolderProperties,
     },
 
     /// A folder in a mailbox.
@@ -687,26 +1813,9 @@ pub enum Folder {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/folder>
     #[serde(rename_all = "PascalCase")]
     Folder {
-        #[xml_struct(ns_prefix = "t")]
-        folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        parent_folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        folder_class: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        display_name: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        total_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        child_folder_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        extended_property: Option<Vec<ExtendedProperty>>,
+        #[xml_struct(flatten)]
+        #[serde(flatten)]
+        properties: FolderProperties,
 
         #[xml_struct(ns_prefix = "t")]
         unread_count: Option<u32>,
@@ -717,26 +1826,12 @@ pub enum Folder {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/searchfolder>
     #[serde(rename_all = "PascalCase")]
     SearchFolder {
-        #[xml_struct(ns_prefix = "t")]
-        folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        parent_folder_id: Option<FolderId>,
-
-        #[xml_struct(ns_prefix = "t")]
-        folder_class: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        display_name: Option<String>,
-
-        #[xml_struct(ns_prefix = "t")]
-        total_count: Option<u32>,
-
-        #[xml_struct(ns_prefix = "t")]
-        child_folder_count: Option<u32>,
+        #[xml_struct(flatten)]
+        #[serde(flatten)]
+        properties: FolderProperties,
 
         #[xml_struct(ns_prefix = "t")]
-        extended_property: Option<Vec<ExtendedProperty>>,
+        search_parameters: Option<Box<SearchParameters>>,
     },
 
     /// A task folder in a mailbox.
@@ -744,94 +1839,111 @@ pub enum Folder {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/tasksfolder>
     #[serde(rename_all = "PascalCase")]
     TasksFolder {
-        #[xml_struct(ns_prefix = "t")]
-        folder_id: Option<FolderId>,
+        #[xml_struct(flatten)]
+        #[serde(flatten)]
+        properties: FolderProperties,
+    },
+}
 
-        #[xml_struct(ns_prefix = "t")]
-        parent_folder_id: Option<FolderId>,
+impl Folder {
+    /// Gets the properties shared by every kind of folder.
+    pub fn properties(&self) -> &FolderProperties {
+        match self {
+            Folder::CalendarFolder { properties }
+            | Folder::ContactsFolder { properties }
+            | Folder::Folder { properties, .. }
+            | Folder::SearchFolder { properties, .. }
+            | Folder::TasksFolder { properties } => properties,
+        }
+    }
 
-        #[xml_struct(ns_prefix = "t")]
-        folder_class: Option<String>,
+    /// Gets mutable access to the properties shared by every kind of folder.
+    pub fn properties_mut(&mut self) -> &mut FolderProperties {
+        match self {
+            Folder::CalendarFolder { properties }
+            | Folder::ContactsFolder { properties }
+            | Folder::Folder { properties, .. }
+            | Folder::SearchFolder { properties, .. }
+            | Folder::TasksFolder { properties } => properties,
+        }
+    }
 
-        #[xml_struct(ns_prefix = "t")]
-        display_name: Option<String>,
+    /// Gets the identifier of the folder, if it was returned or set.
+    pub fn folder_id(&self) -> Option<&FolderId> {
+        self.properties().folder_id.as_ref()
+    }
 
-        #[xml_struct(ns_prefix = "t")]
-        total_count: Option<u32>,
+    /// Gets the identifier of the folder's parent, if it was returned or set.
+    pub fn parent_folder_id(&self) -> Option<&FolderId> {
+        self.properties().parent_folder_id.as_ref()
+    }
 
-        #[xml_struct(ns_prefix = "t")]
-        child_folder_count: Option<u32>,
+    /// Gets the display name of the folder, if it was returned or set.
+    pub fn display_name(&self) -> Option<&str> {
+        self.properties().display_name.as_deref()
+    }
 
-        #[xml_struct(ns_prefix = "t")]
-        extended_property: Option<Vec<ExtendedProperty>>,
-    },
-}
+    /// Gets the class of the folder, e.g. `IPF.Note`, if it was returned or set.
+    pub fn folder_class(&self) -> Option<&str> {
+        self.properties().folder_class.as_deref()
+    }
 
-impl Folder {
     pub fn new_folder(display_name: impl Into<String>) -> Self {
         Self::Folder {
-            folder_id: None,
-            parent_folder_id: None,
-            folder_class: None,
-            display_name: Some(display_name.into()),
-            total_count: None,
-            child_folder_count: None,
-            extended_property: None,
+            properties: FolderProperties::new(display_name),
             unread_count: None,
         }
     }
 
     pub fn new_calendar_folder(display_name: impl Into<String>) -> Self {
         Self::CalendarFolder {
-            folder_id: None,
-            parent_folder_id: None,
-            folder_class: None,
-            display_name: Some(display_name.into()),
-            total_count: None,
-            child_folder_count: None,
-            extended_property: None,
+            properties: FolderProperties::new(display_name),
         }
     }
 
     pub fn new_contacts_folder(display_name: impl Into<String>) -> Self {
         Self::ContactsFolder {
-            folder_id: None,
-            parent_folder_id: None,
-            folder_class: None,
-            display_name: Some(display_name.into()),
-            total_count: None,
-            child_folder_count: None,
-            extended_property: None,
+            properties: FolderProperties::new(display_name),
+        }
+    }
+
+    /// Creates a search folder which finds items matching the given
+    /// parameters.
+    pub fn new_search_folder_with_parameters(
+        display_name: impl Into<String>,
+        search_parameters: SearchParameters,
+    ) -> Self {
+        Self::SearchFolder {
+            properties: FolderProperties::new(display_name),
+            search_parameters: Some(Box::new(search_parameters)),
         }
     }
 
+    /// Creates a search folder without search parameters.
+    ///
+    /// Search folders can't be created without parameters, so such a folder
+    /// fails validation in [`CreateFolder`].
+    ///
+    /// [`CreateFolder`]: crate::create_folder::CreateFolder
+    #[deprecated(note = "use `Folder::new_search_folder_with_parameters` instead")]
     pub fn new_search_folder(display_name: impl Into<String>) -> Self {
         Self::SearchFolder {
-            folder_id: None,
-            parent_folder_id: None,
-            folder_class: None,
-            display_name: Some(display_name.into()),
-            total_count: None,
-            child_folder_count: None,
-            extended_property: None,
+            properties: FolderProperties::new(display_name),
+            search_parameters: None,
         }
     }
 
     pub fn new_tasks_folder(display_name: impl Into<String>) -> Self {
         Self::TasksFolder {
-            folder_id: None,
-            parent_folder_id: None,
-            folder_class: None,
-            display_name: Some(display_name.into()),
-            total_count: None,
-            child_folder_count: None,
-            extended_property: None,
+            properties: FolderProperties::new(display_name),
         }
     }
 }
 
 /// An array of items.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Items {
     #[serde(rename = "$value", default)]
     pub inner: Vec<RealItem>,
@@ -841,49 +1953,174 @@ pub struct Items {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/folders-ex15websvcsotherref>
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Folders {
     #[serde(rename = "$value", default)]
     pub inner: Vec<Folder>,
 }
 
+/// Implements access to the elements of a wrapper around a vector, so that
+/// consumers don't have to go through its `inner` field.
+macro_rules! impl_collection {
+    ($($ty:ty => $item:ty),*) => {$(
+        impl $ty {
+            /// Returns an iterator over the elements.
+            pub fn iter(&self) -> std::slice::Iter<'_, $item> {
+                self.inner.iter()
+            }
+
+            /// Returns the number of elements.
+            pub fn len(&self) -> usize {
+                self.inner.len()
+            }
+
+            /// Returns `true` if there are no elements.
+            pub fn is_empty(&self) -> bool {
+                self.inner.is_empty()
+            }
+        }
+
+        impl Index<usize> for $ty {
+            type Output = $item;
+
+            fn index(&self, index: usize) -> &Self::Output {
+                &self.inner[index]
+            }
+        }
+
+        impl IntoIterator for $ty {
+            type Item = $item;
+            type IntoIter = std::vec::IntoIter<$item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.inner.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $ty {
+            type Item = &'a $item;
+            type IntoIter = std::slice::Iter<'a, $item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.inner.iter()
+            }
+        }
+    )*};
+}
+
+impl_collection!(Items => RealItem, Folders => Folder, Attachments => Attachment);
+
 /// An item which may appear as the result of a request to read or modify an
 /// Exchange item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/items>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(variant_ns_prefix = "t")]
 #[non_exhaustive]
 pub enum RealItem {
     Message(Message),
     CalendarItem(Message),
     MeetingMessage(Message),
-    MeetingRequest(Message),
+    MeetingRequest(MeetingRequest),
     MeetingResponse(Message),
     MeetingCancellation(Message),
+
+    /// A response object tentatively accepting a meeting request, optionally
+    /// proposing a new time.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/tentativelyacceptitem>
+    TentativelyAcceptItem(Message),
+
+    /// A response object declining a meeting request, optionally proposing
+    /// a new time.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/declineitem>
+    DeclineItem(Message),
+
+    /// A contact in the address book.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contact>
+    Contact(Contact),
+
+    /// A task in the to-do list.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/task>
+    Task(Task),
 }
 
 impl RealItem {
+    /// Creates a response object tentatively accepting the meeting request
+    /// with the given ID while proposing a new time for the meeting, for use
+    /// with [`CreateItem`].
+    ///
+    /// Proposing a new time requires Exchange Server 2013 or later.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/how-to-propose-a-new-meeting-time-by-using-ews-in-exchange>
+    ///
+    /// [`CreateItem`]: crate::create_item::CreateItem
+    pub fn propose_new_time(
+        reference_item_id: ItemId,
+        proposed_start: DateTime,
+        proposed_end: DateTime,
+    ) -> Self {
+        RealItem::TentativelyAcceptItem(Message {
+            reference_item_id: Some(Box::new(reference_item_id)),
+            proposed_start: Some(proposed_start),
+            proposed_end: Some(proposed_end),
+            ..Default::default()
+        })
+    }
+
     /// Return the [`Message`] object contained within this [`RealItem`].
-    pub fn inner_message(&self) -> &Message {
+    ///
+    /// Returns `None` for items which aren't represented by a [`Message`],
+    /// such as contacts and tasks. Use [`ItemLike`] to access the properties
+    /// shared by all items.
+    pub fn inner_message(&self) -> Option<&Message> {
         match self {
             RealItem::Message(message)
             | RealItem::CalendarItem(message)
             | RealItem::MeetingMessage(message)
-            | RealItem::MeetingRequest(message)
             | RealItem::MeetingResponse(message)
-            | RealItem::MeetingCancellation(message) => message,
+            | RealItem::MeetingCancellation(message)
+            | RealItem::TentativelyAcceptItem(message)
+            | RealItem::DeclineItem(message) => Some(message),
+            RealItem::MeetingRequest(request) => Some(request),
+            RealItem::Contact(_) | RealItem::Task(_) => None,
         }
     }
 
-    /// Take ownership of the inner [`Message`].
-    pub fn into_inner_message(self) -> Message {
+    /// Take ownership of the inner [`Message`], if any.
+    pub fn into_inner_message(self) -> Option<Message> {
         match self {
             RealItem::Message(message)
             | RealItem::CalendarItem(message)
             | RealItem::MeetingMessage(message)
-            | RealItem::MeetingRequest(message)
             | RealItem::MeetingResponse(message)
-            | RealItem::MeetingCancellation(message) => message,
+            | RealItem::MeetingCancellation(message)
+            | RealItem::TentativelyAcceptItem(message)
+            | RealItem::DeclineItem(message) => Some(message),
+            RealItem::MeetingRequest(request) => Some(request.into_message()),
+            RealItem::Contact(_) | RealItem::Task(_) => None,
+        }
+    }
+
+    /// Gets the item of this variant, for accessing shared properties.
+    pub(crate) fn as_item_like(&self) -> &dyn ItemLike {
+        match self {
+            RealItem::Message(message)
+            | RealItem::CalendarItem(message)
+            | RealItem::MeetingMessage(message)
+            | RealItem::MeetingResponse(message)
+            | RealItem::MeetingCancellation(message)
+            | RealItem::TentativelyAcceptItem(message)
+            | RealItem::DeclineItem(message) => message,
+            RealItem::MeetingRequest(request) => &request.message,
+            RealItem::Contact(contact) => contact,
+            RealItem::Task(task) => task,
         }
     }
 }
@@ -893,19 +2130,76 @@ impl RealItem {
 /// See [`Attachment::ItemAttachment`] for details.
 // N.B.: Commented-out variants are not yet implemented.
 #[non_exhaustive]
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, XmlSerialize)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[xml_struct(variant_ns_prefix = "t")]
 pub enum AttachmentItem {
     // Item(Item),
     Message(Message),
     CalendarItem(Message),
-    // Contact(Contact),
-    // Task(Task),
+    Contact(Contact),
+    Task(Task),
     MeetingMessage(Message),
-    MeetingRequest(Message),
+    MeetingRequest(MeetingRequest),
     MeetingResponse(Message),
     MeetingCancellation(Message),
 }
 
+/// An opaque token identifying the synchronization state of a folder or
+/// folder hierarchy, as returned by `SyncFolderItems` and
+/// `SyncFolderHierarchy`.
+///
+/// The token should be persisted as-is and passed back in the next request
+/// to retrieve only the changes which happened since.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/syncstate-ex15websvcsotherref>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(transparent)]
+pub struct SyncState(String);
+
+impl SyncState {
+    /// Creates a synchronization state from a token previously returned by
+    /// the server.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+
+    /// Gets the token, e.g. for persisting it.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the synchronization state, returning the token.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for SyncState {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SyncState {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl XmlSerialize for SyncState {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        self.0.serialize_child_nodes(writer)
+    }
+}
+
 /// A date and time with second precision.
 // `time` provides an `Option<OffsetDateTime>` deserializer, but it does not
 // work with map fields which may be omitted, as in our case.
@@ -913,6 +2207,24 @@ pub enum AttachmentItem {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DateTime(pub time::OffsetDateTime);
 
+// `time` doesn't implement `Arbitrary`, so generate UTC timestamps with
+// second precision, in the range of years which can be written in XML.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DateTime {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // From 0001-01-01T00:00:00Z to 9999-12-31T23:59:59Z.
+        let timestamp = u.int_in_range(-62_135_596_800..=253_402_300_799)?;
+        let date_time = time::OffsetDateTime::from_unix_timestamp(timestamp)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+
+        Ok(Self(date_time))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(8))
+    }
+}
+
 // Helper module for flexible datetime deserialization
 mod flexible_datetime {
     use serde::{Deserialize, Deserializer};
@@ -931,7 +2243,9 @@ mod flexible_datetime {
             return Ok(dt);
         }
 
-        // If no timezone, parse as PrimitiveDateTime and assume UTC
+        // If no timezone, parse as PrimitiveDateTime and assume UTC. Times
+        // in the time zone of a `TimeZoneContext` are given their offset
+        // before deserialization.
         // This handles the Exchange Server case where timezone is omitted
         if let Ok(pdt) = time::PrimitiveDateTime::parse(
             &s,
@@ -955,9 +2269,31 @@ impl<'de> serde::Deserialize<'de> for DateTime {
     }
 }
 
+#[cfg(feature = "serde-serialize")]
+impl serde::Serialize for DateTime {
+    /// Serializes a `DateTime` as an ISO 8601-compliant string, as it is
+    /// represented in XML.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let time = DateTimeFormat::new()
+            .format(self.0)
+            .map_err(serde::ser::Error::custom)?;
+
+        serializer.serialize_str(&time)
+    }
+}
+
 impl XmlSerialize for DateTime {
     /// Serializes a `DateTime` as an XML text content node by formatting the
-    /// inner [`time::OffsetDateTime`] as an ISO 8601-compliant string.
+    /// inner [`time::OffsetDateTime`] as an ISO 8601-compliant string, in the
+    /// default [`DateTimeFormat`].
+    ///
+    /// Times are rewritten in the format of an envelope when it is
+    /// serialized, see [`Envelope::date_time_format`].
+    ///
+    /// [`Envelope::date_time_format`]: crate::soap::Envelope::date_time_format
     fn serialize_child_nodes<W>(
         &self,
         writer: &mut quick_xml::Writer<W>,
@@ -965,24 +2301,164 @@ impl XmlSerialize for DateTime {
     where
         W: std::io::Write,
     {
-        let time = self
-            .0
-            .format(&Iso8601::DEFAULT)
+        let time = DateTimeFormat::new()
+            .format(self.0)
             .map_err(|err| xml_struct::Error::Value(err.into()))?;
 
         time.serialize_child_nodes(writer)
     }
 }
 
+/// A calendar date without a time of day, such as the day of an all-day
+/// event.
+///
+/// It is written as an `xs:date`, e.g. `2024-06-01`. Any time zone suffix is
+/// ignored when deserializing, as the date is always interpreted in the time
+/// zone of the item it belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(pub time::Date);
+
+impl Date {
+    /// Gets the time at which this date starts in a time zone with the given
+    /// offset from UTC.
+    ///
+    /// This is how EWS represents the start and end of all-day events, which
+    /// begin and end at midnight in the time zone of the item.
+    pub fn midnight(self, offset: time::UtcOffset) -> DateTime {
+        DateTime(self.0.midnight().assume_offset(offset))
+    }
+
+    /// Gets the date on which the given time falls in a time zone with the
+    /// given offset from UTC.
+    pub fn of(time: &DateTime, offset: time::UtcOffset) -> Self {
+        Self(time.0.to_offset(offset).date())
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            self.0.year(),
+            u8::from(self.0.month()),
+            self.0.day()
+        )
+    }
+}
+
+impl FromStr for Date {
+    type Err = time::error::Parse;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Strip the optional time zone of an `xs:date`, e.g. `Z` or `-07:00`,
+        // which follows the ten characters of the date itself.
+        let date = s.get(..10).unwrap_or(s);
+
+        time::Date::parse(
+            date,
+            &time::format_description::well_known::Iso8601::DEFAULT,
+        )
+        .map(Date)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl serde::Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl XmlSerialize for Date {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        self.to_string().serialize_child_nodes(writer)
+    }
+}
+
+/// Implements [`XmlSerialize`] for boxed values of the given types, which
+/// are serialized as the values themselves.
+///
+/// `xml_struct` only implements [`XmlSerialize`] for references, options and
+/// vectors, so boxed fields need an implementation for each boxed type.
+macro_rules! impl_xml_serialize_for_box {
+    ($($ty:ty),*) => {$(
+        impl XmlSerialize for Box<$ty> {
+            fn serialize_as_element<W>(
+                &self,
+                writer: &mut quick_xml::Writer<W>,
+                name: &str,
+            ) -> Result<(), xml_struct::Error>
+            where
+                W: std::io::Write,
+            {
+                self.as_ref().serialize_as_element(writer, name)
+            }
+
+            fn serialize_child_nodes<W>(
+                &self,
+                writer: &mut quick_xml::Writer<W>,
+            ) -> Result<(), xml_struct::Error>
+            where
+                W: std::io::Write,
+            {
+                self.as_ref().serialize_child_nodes(writer)
+            }
+        }
+    )*};
+}
+
+impl_xml_serialize_for_box!(
+    Attachments,
+    ChangeHighlights,
+    InternetMessageHeaders,
+    ItemId,
+    MeetingTimeZone,
+    MimeContent,
+    Recipient,
+    Recurrence,
+    ResponseObjects,
+    SearchParameters,
+    TimeZoneReference
+);
+
 /// An email message.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/message-ex15websvcsotherref>
+///
+/// Large properties which are rarely populated, such as the recipients a
+/// message was received by or the recurrence of a calendar item, are boxed
+/// so that messages stay small in large batches, e.g. when syncing with an
+/// `IdOnly` shape.
+#[allow(non_snake_case)]
 #[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct Message {
     /// The MIME content of the item.
     #[xml_struct(ns_prefix = "t")]
-    pub mime_content: Option<MimeContent>,
+    pub mime_content: Option<Box<MimeContent>>,
 
     /// The item's Exchange identifier.
     #[xml_struct(ns_prefix = "t")]
@@ -1013,7 +2489,7 @@ pub struct Message {
     pub body: Option<Body>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub attachments: Option<Attachments>,
+    pub attachments: Option<Box<Attachments>>,
 
     #[xml_struct(ns_prefix = "t")]
     pub date_time_received: Option<DateTime>,
@@ -1025,7 +2501,7 @@ pub struct Message {
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/categories-ex15websvcsotherref>
     #[xml_struct(ns_prefix = "t")]
-    pub categories: Option<Vec<StringElement>>,
+    pub categories: Option<ArrayOfStrings>,
 
     // Extended MAPI properties of the message.
     #[xml_struct(ns_prefix = "t")]
@@ -1053,7 +2529,7 @@ pub struct Message {
     pub is_unmodified: Option<bool>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub internet_message_headers: Option<InternetMessageHeaders>,
+    pub internet_message_headers: Option<Box<InternetMessageHeaders>>,
 
     #[xml_struct(ns_prefix = "t")]
     pub date_time_sent: Option<DateTime>,
@@ -1082,15 +2558,33 @@ pub struct Message {
     /// (embedded images, etc.) are considered "hidden attachments" and do NOT affect
     /// this property. If an item only has inline attachments, this will be `false`.
     ///
-    /// To check for any attachments including inline ones, use `has_any_attachments()`.
-    /// To access all attachments including inline ones, check the `attachments` field directly.
+    /// To check for any attachments including inline ones, use `has_any_attachments()`.
+    /// To access all attachments including inline ones, check the `attachments` field directly.
+    ///
+    /// See: <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/attachments-and-ews-in-exchange>
+    #[xml_struct(ns_prefix = "t")]
+    pub has_attachments: Option<bool>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub culture: Option<String>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub last_modified_name: Option<String>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub last_modified_time: Option<DateTime>,
+
+    /// Whether the item is a folder associated item, i.e. a hidden item
+    /// holding data such as the settings of a folder.
+    ///
+    /// This can only be set when creating an item.
     ///
-    /// See: <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/attachments-and-ews-in-exchange>
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/isassociated>
     #[xml_struct(ns_prefix = "t")]
-    pub has_attachments: Option<bool>,
+    pub is_associated: Option<bool>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub culture: Option<String>,
+    pub conversation_id: Option<ItemId>,
 
     #[xml_struct(ns_prefix = "t")]
     pub sender: Option<Recipient>,
@@ -1129,34 +2623,105 @@ pub struct Message {
     pub is_response_requested: Option<bool>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub reply_to: Option<Recipient>,
+    pub reply_to: Option<Box<Recipient>>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub received_by: Option<Recipient>,
+    pub received_by: Option<Box<Recipient>>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub received_representing: Option<Recipient>,
+    pub received_representing: Option<Box<Recipient>>,
 
     #[xml_struct(ns_prefix = "t")]
-    pub last_modified_name: Option<String>,
+    pub references: Option<String>,
 
+    /// Response objects available for this message.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/responseobjects>
     #[xml_struct(ns_prefix = "t")]
-    pub last_modified_time: Option<DateTime>,
+    pub response_objects: Option<Box<ResponseObjects>>,
 
+    /// The item to which this item responds, when used as a response object
+    /// such as a [`RealItem::TentativelyAcceptItem`].
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/referenceitemid>
     #[xml_struct(ns_prefix = "t")]
-    pub is_associated: Option<bool>,
+    pub reference_item_id: Option<Box<ItemId>>,
 
+    /// The iCalendar UID of a meeting, shared by the calendar items and
+    /// meeting messages of all of its instances.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/uid>
     #[xml_struct(ns_prefix = "t")]
-    pub conversation_id: Option<ItemId>,
+    #[serde(rename = "UID")]
+    pub UID: Option<String>,
 
+    /// The start of the new time proposed by an attendee in response to a
+    /// meeting request.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/proposedstart>
     #[xml_struct(ns_prefix = "t")]
-    pub references: Option<String>,
+    pub proposed_start: Option<DateTime>,
 
-    /// Response objects available for this message.
+    /// The end of the new time proposed by an attendee in response to a
+    /// meeting request.
     ///
-    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/responseobjects>
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/proposedend>
+    #[xml_struct(ns_prefix = "t")]
+    pub proposed_end: Option<DateTime>,
+
+    // The properties from here on are written by `MeetingRequest` itself, so
+    // that its own properties can precede them.
+    /// The start of a calendar item.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/start>
+    #[xml_struct(ns_prefix = "t")]
+    pub start: Option<DateTime>,
+
+    /// The end of a calendar item.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/end-ex15websvcsotherref>
+    #[xml_struct(ns_prefix = "t")]
+    pub end: Option<DateTime>,
+
+    /// Whether a calendar item is an all-day event, which starts and ends at
+    /// midnight in the time zone of the item.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/isalldayevent>
+    #[xml_struct(ns_prefix = "t")]
+    pub is_all_day_event: Option<bool>,
+
+    /// The organizer of a meeting.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/organizer>
+    #[xml_struct(ns_prefix = "t")]
+    pub organizer: Option<Box<Recipient>>,
+
+    /// The recurrence of a calendar item or meeting.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/recurrence-recurrencetype>
+    #[xml_struct(ns_prefix = "t")]
+    pub recurrence: Option<Box<Recurrence>>,
+
+    /// The time zone of a calendar item, for Exchange Server 2007.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/meetingtimezone>
+    #[xml_struct(ns_prefix = "t")]
+    pub meeting_time_zone: Option<Box<MeetingTimeZone>>,
+
+    /// The time zone in which a calendar item starts.
+    ///
+    /// Without it, the server interprets the start in UTC, which shifts
+    /// recurring items across daylight saving time transitions.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/starttimezone>
+    #[xml_struct(ns_prefix = "t")]
+    pub start_time_zone: Option<Box<TimeZoneReference>>,
+
+    /// The time zone in which a calendar item ends.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/endtimezone>
     #[xml_struct(ns_prefix = "t")]
-    pub response_objects: Option<ResponseObjects>,
+    pub end_time_zone: Option<Box<TimeZoneReference>>,
 }
 
 impl Message {
@@ -1213,6 +2778,18 @@ impl Message {
             conversation_id: None,
             references: None,
             response_objects: None,
+            reference_item_id: None,
+            UID: None,
+            proposed_start: None,
+            proposed_end: None,
+            start: None,
+            end: None,
+            is_all_day_event: None,
+            organizer: None,
+            recurrence: None,
+            meeting_time_zone: None,
+            start_time_zone: None,
+            end_time_zone: None,
         }
     }
 
@@ -1229,6 +2806,22 @@ impl Message {
         message
     }
 
+    /// Gets the first day and the day after the last day of an all-day
+    /// event, given the offset from UTC of the item's time zone.
+    ///
+    /// Returns `None` if the item isn't an all-day event or its start or end
+    /// wasn't requested.
+    pub fn all_day_dates(&self, offset: time::UtcOffset) -> Option<(Date, Date)> {
+        if self.is_all_day_event != Some(true) {
+            return None;
+        }
+
+        let start = self.start.as_ref()?;
+        let end = self.end.as_ref()?;
+
+        Some((Date::of(start, offset), Date::of(end, offset)))
+    }
+
     /// Returns true if the message has any attachments, including inline attachments.
     ///
     /// This method checks the actual `attachments` collection rather than relying on the
@@ -1275,6 +2868,53 @@ impl Message {
             })
             .unwrap_or(false)
     }
+
+    /// Maps the content IDs referenced by `cid:` URLs in the HTML body, e.g.
+    /// in the `src` of an image, to the file attachments they refer to.
+    ///
+    /// The keys are the references as written in the body, without the
+    /// `cid:` scheme. A reference matches an attachment whose `ContentId` is
+    /// the same ignoring case, angle brackets and percent-encoding, or failing
+    /// that, whose `ContentId` only differs in its `@domain` suffix.
+    /// References without a matching attachment are left out, as are all
+    /// references if the body isn't HTML.
+    pub fn inline_attachment_map(&self) -> HashMap<String, &Attachment> {
+        let (Some(body), Some(attachments)) = (&self.body, &self.attachments) else {
+            return HashMap::new();
+        };
+        if body.body_type != BodyType::HTML {
+            return HashMap::new();
+        }
+
+        let candidates: Vec<(String, &Attachment)> = attachments
+            .iter()
+            .filter_map(|attachment| match attachment {
+                Attachment::FileAttachment {
+                    content_id: Some(content_id),
+                    ..
+                } => Some((content_id::normalize(content_id), attachment)),
+                _ => None,
+            })
+            .collect();
+
+        content_id::references(body.content_str())
+            .into_iter()
+            .filter_map(|reference| {
+                let normalized = content_id::normalize(reference);
+                let local_part = content_id::local_part(&normalized);
+
+                candidates
+                    .iter()
+                    .find(|(content_id, _)| *content_id == normalized)
+                    .or_else(|| {
+                        candidates.iter().find(|(content_id, _)| {
+                            content_id::local_part(content_id) == local_part
+                        })
+                    })
+                    .map(|(_, attachment)| (reference.to_string(), *attachment))
+            })
+            .collect()
+    }
 }
 
 /// An extended MAPI property of an Exchange item or folder.
@@ -1282,11 +2922,15 @@ impl Message {
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/extendedproperty>
 #[allow(non_snake_case)]
 #[derive(Clone, Debug, Deserialize, XmlSerialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExtendedProperty {
     #[xml_struct(ns_prefix = "t")]
+    #[serde(rename = "ExtendedFieldURI")]
     pub extended_field_URI: ExtendedFieldURI,
 
     #[xml_struct(ns_prefix = "t")]
+    #[serde(rename = "Value")]
     pub value: String,
 }
 
@@ -1294,8 +2938,10 @@ pub struct ExtendedProperty {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachments-ex15websvcsotherref>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Attachments {
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", default)]
     #[xml_struct(flatten)]
     pub inner: Vec<Attachment>,
 }
@@ -1303,6 +2949,8 @@ pub struct Attachments {
 /// A newtype around a vector of `Recipient`s, that is deserialized using
 /// `deserialize_recipients`.
 #[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ArrayOfRecipients(
     #[serde(deserialize_with = "deserialize_recipients")] pub Vec<Recipient>,
 );
@@ -1321,8 +2969,54 @@ impl DerefMut for ArrayOfRecipients {
     }
 }
 
+impl<R> FromIterator<R> for ArrayOfRecipients
+where
+    R: Into<Recipient>,
+{
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<Vec<Recipient>> for ArrayOfRecipients {
+    fn from(value: Vec<Recipient>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Vec<&str>> for ArrayOfRecipients {
+    fn from(value: Vec<&str>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl From<Vec<Mailbox>> for ArrayOfRecipients {
+    fn from(value: Vec<Mailbox>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+/// Builds an [`ArrayOfRecipients`] from email addresses and `(name, email
+/// address)` pairs, or anything else which converts into a [`Recipient`].
+///
+/// ```
+/// use ews::recipients;
+///
+/// let recipients = recipients!["alice@example.com", ("Bob", "bob@example.com")];
+///
+/// assert_eq!(recipients[1].mailbox.name.as_deref(), Some("Bob"));
+/// ```
+#[macro_export]
+macro_rules! recipients {
+    ($($recipient:expr),* $(,)?) => {
+        $crate::ArrayOfRecipients(vec![$($crate::Recipient::from($recipient)),*])
+    };
+}
+
 /// A single mailbox.
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct Recipient {
     #[xml_struct(ns_prefix = "t")]
@@ -1335,7 +3029,7 @@ impl Recipient {
         Self {
             mailbox: Mailbox {
                 name: None,
-                email_address: email_address.into(),
+                email_address: Some(email_address.into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
@@ -1348,7 +3042,7 @@ impl Recipient {
         Self {
             mailbox: Mailbox {
                 name: Some(name.into()),
-                email_address: email_address.into(),
+                email_address: Some(email_address.into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
@@ -1362,6 +3056,35 @@ impl Recipient {
     }
 }
 
+impl From<Mailbox> for Recipient {
+    fn from(value: Mailbox) -> Self {
+        Self::from_mailbox(value)
+    }
+}
+
+impl From<&str> for Recipient {
+    fn from(email_address: &str) -> Self {
+        Self::new(email_address)
+    }
+}
+
+impl From<String> for Recipient {
+    fn from(email_address: String) -> Self {
+        Self::new(email_address)
+    }
+}
+
+/// Creates a recipient from a `(name, email address)` pair.
+impl<N, A> From<(N, A)> for Recipient
+where
+    N: Into<String>,
+    A: Into<String>,
+{
+    fn from((name, email_address): (N, A)) -> Self {
+        Self::with_name(email_address, name)
+    }
+}
+
 /// Deserializes a list of recipients.
 ///
 /// `quick-xml`'s `serde` implementation requires the presence of an
@@ -1392,6 +3115,8 @@ where
 
 /// A list of Internet Message Format headers.
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct InternetMessageHeaders {
     pub internet_message_header: Vec<InternetMessageHeader>,
@@ -1401,6 +3126,8 @@ pub struct InternetMessageHeaders {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mailbox>
 #[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct Mailbox {
     /// The name of this mailbox's user.
@@ -1408,21 +3135,27 @@ pub struct Mailbox {
     pub name: Option<String>,
 
     /// The email address for this mailbox.
+    ///
+    /// Exchange omits the address of some mailboxes, such as private
+    /// distribution lists returned by `ResolveNames`.
     #[xml_struct(ns_prefix = "t")]
-    pub email_address: String,
+    pub email_address: Option<String>,
 
     /// The protocol used in routing to this mailbox.
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/routingtype-emailaddress>
+    #[xml_struct(ns_prefix = "t")]
     pub routing_type: Option<RoutingType>,
 
     /// The type of sender/recipient represented by this mailbox.
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mailboxtype>
+    #[xml_struct(ns_prefix = "t")]
     pub mailbox_type: Option<MailboxType>,
 
     /// An identifier for a contact or list of contacts corresponding to this
     /// mailbox.
+    #[xml_struct(ns_prefix = "t")]
     pub item_id: Option<ItemId>,
 }
 
@@ -1431,7 +3164,7 @@ impl Mailbox {
     pub fn new(email_address: impl Into<String>) -> Self {
         Self {
             name: None,
-            email_address: email_address.into(),
+            email_address: Some(email_address.into()),
             routing_type: None,
             mailbox_type: None,
             item_id: None,
@@ -1442,7 +3175,7 @@ impl Mailbox {
     pub fn with_name(email_address: impl Into<String>, name: impl Into<String>) -> Self {
         Self {
             name: Some(name.into()),
-            email_address: email_address.into(),
+            email_address: Some(email_address.into()),
             routing_type: None,
             mailbox_type: None,
             item_id: None,
@@ -1453,18 +3186,76 @@ impl Mailbox {
 /// A protocol used in routing mail.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/routingtype-emailaddress>
-#[derive(Clone, Copy, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
-#[xml_struct(text)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum RoutingType {
     #[default]
     SMTP,
     EX,
+
+    /// Any other routing type, such as `MAPIPDL` for the private
+    /// distribution lists returned by `ResolveNames`.
+    Other(String),
+}
+
+impl RoutingType {
+    /// Gets the name of the routing type as it is written in XML.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RoutingType::SMTP => "SMTP",
+            RoutingType::EX => "EX",
+            RoutingType::Other(routing_type) => routing_type,
+        }
+    }
+}
+
+impl From<String> for RoutingType {
+    fn from(routing_type: String) -> Self {
+        match routing_type.as_str() {
+            "SMTP" => RoutingType::SMTP,
+            "EX" => RoutingType::EX,
+            _ => RoutingType::Other(routing_type),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RoutingType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(RoutingType::from)
+    }
+}
+
+#[cfg(feature = "serde-serialize")]
+impl serde::Serialize for RoutingType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl XmlSerialize for RoutingType {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        self.as_str().serialize_child_nodes(writer)
+    }
 }
 
 /// The type of sender or recipient a mailbox represents.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mailboxtype>
 #[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(text)]
 pub enum MailboxType {
     Mailbox,
@@ -1481,6 +3272,8 @@ pub enum MailboxType {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/importance>
 #[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(text)]
 pub enum Importance {
     Low,
@@ -1492,16 +3285,106 @@ pub enum Importance {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/string>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct StringElement {
     /// The string content.
+    #[xml_struct(ns_prefix = "t")]
     pub string: String,
 }
 
+/// A list of strings, such as the categories of an item, each of which is
+/// written as a `t:String` element.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/string>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ArrayOfStrings(#[serde(deserialize_with = "deserialize_strings")] pub Vec<String>);
+
+impl Deref for ArrayOfStrings {
+    type Target = Vec<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ArrayOfStrings {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Vec<String>> for ArrayOfStrings {
+    fn from(value: Vec<String>) -> Self {
+        Self(value)
+    }
+}
+
+impl<S> FromIterator<S> for ArrayOfStrings
+where
+    S: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl IntoIterator for ArrayOfStrings {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ArrayOfStrings {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl XmlSerialize for ArrayOfStrings {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        for string in &self.0 {
+            string.serialize_as_element(writer, "t:String")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes a list of `String` elements.
+fn deserialize_strings<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct StringSequence {
+        #[serde(rename = "String", default)]
+        strings: Vec<String>,
+    }
+
+    Ok(StringSequence::deserialize(deserializer)?.strings)
+}
+
 /// The sensitivity of the contents of an item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/sensitivity>
 #[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(text)]
 pub enum Sensitivity {
     Normal,
@@ -1513,7 +3396,9 @@ pub enum Sensitivity {
 /// The body of an item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/body>
-#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[derive(Clone, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Body {
     /// The content type of the body.
     #[serde(rename = "@BodyType")]
@@ -1533,6 +3418,17 @@ pub struct Body {
     pub content: Option<String>,
 }
 
+// The content is redacted so that logging an item doesn't leak its body.
+impl fmt::Debug for Body {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Body")
+            .field("body_type", &self.body_type)
+            .field("is_truncated", &self.is_truncated)
+            .field("content", &Redacted::text(&self.content))
+            .finish()
+    }
+}
+
 impl Body {
     /// Create a new Body with text content.
     pub fn text(content: impl Into<String>) -> Self {
@@ -1560,12 +3456,93 @@ impl Body {
             is_truncated: None,
         }
     }
+
+    /// Gets the content of the body, or an empty string if the body is empty.
+    ///
+    /// Unlike MIME content, bodies are sent as XML text rather than encoded,
+    /// so the content is ready to use once unescaped by the XML parser. This
+    /// is what should be used in most cases.
+    pub fn content_str(&self) -> &str {
+        self.content.as_deref().unwrap_or_default()
+    }
+
+    /// Gets the content of the body with XML entity and numeric character
+    /// references decoded a second time, or an empty string if the body is
+    /// empty.
+    ///
+    /// This is only meant for content which is known to still be escaped
+    /// once deserialized, as some servers and gateways escape bodies, most
+    /// often complex HTML ones, twice, leaving references such as
+    /// `&lt;html&gt;` or `&#xD;` in the content. Using it on other content
+    /// turns escaped text, such as `&lt;b&gt;` in an HTML body, into markup.
+    /// References which aren't valid in XML, such as the HTML `&nbsp;`, are
+    /// left unchanged.
+    pub fn decoded_content(&self) -> Cow<'_, str> {
+        decode_references(self.content_str())
+    }
+}
+
+/// Decodes the predefined XML entity references and numeric character
+/// references in `text`, leaving any other sequence starting with `&`
+/// unchanged.
+fn decode_references(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let character = rest.find(';').and_then(|end| {
+            let character = match &rest[1..end] {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                reference => {
+                    let (digits, radix) = match reference.strip_prefix("#x") {
+                        Some(digits) => (digits, 16),
+                        None => (reference.strip_prefix('#')?, 10),
+                    };
+                    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                        return None;
+                    }
+
+                    u32::from_str_radix(digits, radix)
+                        .ok()
+                        .and_then(char::from_u32)
+                }
+            };
+
+            character.map(|character| (character, end))
+        });
+
+        match character {
+            Some((character, end)) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    Cow::Owned(decoded)
 }
 
 /// The content type of an item's body.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/body>
 #[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[xml_struct(text)]
 pub enum BodyType {
     HTML,
@@ -1575,7 +3552,9 @@ pub enum BodyType {
 /// An attachment to an Exchange item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachments-ex15websvcsotherref>
-#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[derive(Clone, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Attachment {
     /// An attachment containing an Exchange item.
     ///
@@ -1646,8 +3625,10 @@ pub enum Attachment {
 
         /// The MIME type of the attachment's content.
         ///
+        /// Some connectors create file attachments without a content type.
+        ///
         /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contenttype>
-        content_type: String,
+        content_type: Option<String>,
 
         /// An arbitrary identifier for the attachment.
         ///
@@ -1689,10 +3670,65 @@ pub enum Attachment {
     },
 }
 
+// The content of file attachments is redacted so that logging an item doesn't
+// leak it.
+impl fmt::Debug for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Attachment::ItemAttachment {
+                attachment_id,
+                name,
+                content_type,
+                content_id,
+                content_location,
+                size,
+                last_modified_time,
+                is_inline,
+            } => f
+                .debug_struct("ItemAttachment")
+                .field("attachment_id", attachment_id)
+                .field("name", name)
+                .field("content_type", content_type)
+                .field("content_id", content_id)
+                .field("content_location", content_location)
+                .field("size", size)
+                .field("last_modified_time", last_modified_time)
+                .field("is_inline", is_inline)
+                .finish(),
+            Attachment::FileAttachment {
+                attachment_id,
+                name,
+                content_type,
+                content_id,
+                content_location,
+                size,
+                last_modified_time,
+                is_inline,
+                is_contact_photo,
+                content,
+            } => f
+                .debug_struct("FileAttachment")
+                .field("attachment_id", attachment_id)
+                .field("name", name)
+                .field("content_type", content_type)
+                .field("content_id", content_id)
+                .field("content_location", content_location)
+                .field("size", size)
+                .field("last_modified_time", last_modified_time)
+                .field("is_inline", is_inline)
+                .field("is_contact_photo", is_contact_photo)
+                .field("content", &Redacted::text(content))
+                .finish(),
+        }
+    }
+}
+
 /// An identifier for an attachment.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachmentid>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AttachmentId {
     /// A unique identifier for the attachment.
     #[serde(rename = "@Id")]
@@ -1710,11 +3746,57 @@ pub struct AttachmentId {
     pub root_item_change_key: Option<String>,
 }
 
+/// A list of attachment identifiers, as sent in requests.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachmentids>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttachmentIds(pub Vec<AttachmentId>);
+
+impl From<Vec<AttachmentId>> for AttachmentIds {
+    fn from(value: Vec<AttachmentId>) -> Self {
+        Self(value)
+    }
+}
+
+impl Deref for AttachmentIds {
+    type Target = Vec<AttachmentId>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for AttachmentIds {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+// Each identifier is written as its own `t:AttachmentId` element, which the
+// derive can't express for a list of structs.
+impl XmlSerialize for AttachmentIds {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        for id in &self.0 {
+            id.serialize_as_element(writer, "t:AttachmentId")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// The content of an item, represented according to MIME (Multipurpose Internet
 /// Mail Extensions).
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mimecontent>
-#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[derive(Clone, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MimeContent {
     /// The character set of the MIME content if it contains [RFC 2045]-encoded
     /// text.
@@ -1730,10 +3812,58 @@ pub struct MimeContent {
     pub content: String,
 }
 
+// The content is redacted so that logging an item doesn't leak the message.
+impl fmt::Debug for MimeContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MimeContent")
+            .field("character_set", &self.character_set)
+            .field("content", &Redacted::from(self.content.as_str()))
+            .finish()
+    }
+}
+
+impl MimeContent {
+    /// Gets the base64-encoded content as received from the server.
+    pub fn raw(&self) -> &str {
+        &self.content
+    }
+
+    /// Decodes the base64-encoded content into the raw [RFC 5322] message.
+    ///
+    /// The content is kept encoded until it is needed and decoded on each
+    /// call, so that operations which never look at it don't pay for
+    /// decoding it.
+    ///
+    /// [RFC 5322]: https://datatracker.ietf.org/doc/html/rfc5322
+    pub fn decoded_bytes(&self) -> Result<Vec<u8>, Error> {
+        // Servers may wrap the encoded content across lines.
+        let encoded: Vec<u8> = self
+            .content
+            .bytes()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect();
+
+        Ok(BASE64_STANDARD.decode(encoded)?)
+    }
+
+    /// Decodes the base64-encoded content into the text of the raw message,
+    /// replacing any invalid UTF-8 sequences.
+    pub fn decoded_text(&self) -> Result<String, Error> {
+        let bytes = self.decoded_bytes()?;
+
+        Ok(match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+        })
+    }
+}
+
 /// The headers of an Exchange item's MIME content.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/internetmessageheader>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct InternetMessageHeader {
     /// The name of the header.
@@ -1751,7 +3881,398 @@ pub struct InternetMessageHeader {
 mod tests {
 
     use super::*;
-    use crate::{test_utils::assert_serialized_content, Error};
+    use crate::test_utils::assert_serialized_content;
+
+    #[test]
+    fn test_message_size() {
+        fn field_size<T>(_: impl Fn(&Message) -> &T) -> usize {
+            std::mem::size_of::<T>()
+        }
+
+        // Large, rarely populated properties are boxed so that large batches
+        // of messages stay small; this guards against unboxing them again.
+        // Properties added to `Message` which are rarely populated belong in
+        // this list too.
+        let boxed = [
+            field_size(|message| &message.mime_content),
+            field_size(|message| &message.attachments),
+            field_size(|message| &message.internet_message_headers),
+            field_size(|message| &message.reply_to),
+            field_size(|message| &message.received_by),
+            field_size(|message| &message.received_representing),
+            field_size(|message| &message.response_objects),
+            field_size(|message| &message.reference_item_id),
+            field_size(|message| &message.organizer),
+            field_size(|message| &message.recurrence),
+            field_size(|message| &message.meeting_time_zone),
+            field_size(|message| &message.start_time_zone),
+            field_size(|message| &message.end_time_zone),
+        ];
+
+        for size in boxed {
+            assert_eq!(size, std::mem::size_of::<usize>());
+        }
+    }
+
+    #[test]
+    fn test_body_decoded_content() -> Result<(), Error> {
+        use crate::{get_item::GetItemResponse, soap::Envelope};
+
+        // Inline images are referenced from bodies which are otherwise
+        // unremarkable once deserialized, but escaped again by some gateways.
+        let document = include_bytes!("../../fixtures/get_item_html_inline_images.xml");
+        let envelope = Envelope::<GetItemResponse>::from_xml_document(document, None)?;
+        let message = match envelope.body.into_result()?.remove(0).items.inner.remove(0) {
+            RealItem::Message(message) => message,
+            other => panic!("unexpected item {other:?}"),
+        };
+        let body = message.body.expect("message should have a body");
+
+        assert!(body
+            .content_str()
+            .contains("<p>Figures &amp; charts below:</p>"));
+        assert!(body.decoded_content().contains(
+            r#"<p>Figures & charts below:</p>
+<p><img src="cid:image001.png@01DA9B2C.5E3F1A20""#
+        ));
+
+        let escaped = Body::html(
+            "&lt;html&gt;&#xD;\n&lt;p&gt;Caf&#233; &amp;amp; &quot;bar&quot;&nbsp;&#x;&#+65;&amp&lt;/p&gt;",
+        );
+        assert_eq!(
+            escaped.decoded_content(),
+            "<html>\r\n<p>Café &amp; \"bar\"&nbsp;&#x;&#+65;&amp</p>"
+        );
+
+        // The content itself is left as received.
+        assert!(escaped.content_str().starts_with("&lt;html&gt;&#xD;"));
+
+        assert!(matches!(
+            Body::text("No references here").decoded_content(),
+            Cow::Borrowed("No references here")
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_extended_properties() -> Result<(), Error> {
+        use crate::{get_item::GetItemResponse, soap::Envelope};
+
+        // The identifying attributes of each property are only read when
+        // they're named as attributes.
+        let document = include_bytes!("../../fixtures/get_item_extended_properties.xml");
+        let envelope = Envelope::<GetItemResponse>::from_xml_document(document, None)?;
+        let message = match envelope.body.into_result()?.remove(0).items.inner.remove(0) {
+            RealItem::Message(message) => message,
+            other => panic!("unexpected item {other:?}"),
+        };
+
+        assert_eq!(
+            message.extended_property,
+            Some(vec![
+                ExtendedProperty {
+                    extended_field_URI: ExtendedFieldURI {
+                        distinguished_property_set_id: None,
+                        property_set_id: None,
+                        property_tag: Some("0x1090".to_string()),
+                        property_name: None,
+                        property_id: None,
+                        property_type: PropertyType::Integer,
+                    },
+                    value: "2".to_string(),
+                },
+                ExtendedProperty {
+                    extended_field_URI: ExtendedFieldURI {
+                        distinguished_property_set_id: Some(
+                            DistinguishedPropertySet::PublicStrings
+                        ),
+                        property_set_id: None,
+                        property_tag: None,
+                        property_name: Some(
+                            "urn:schemas-microsoft-com:office:office#Keywords".to_string()
+                        ),
+                        property_id: None,
+                        property_type: PropertyType::String,
+                    },
+                    value: "Follow up".to_string(),
+                },
+                ExtendedProperty {
+                    extended_field_URI: ExtendedFieldURI {
+                        distinguished_property_set_id: None,
+                        property_set_id: Some("00062008-0000-0000-C000-000000000046".to_string()),
+                        property_tag: None,
+                        property_name: None,
+                        property_id: Some("34054".to_string()),
+                        property_type: PropertyType::Boolean,
+                    },
+                    value: "true".to_string(),
+                },
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inline_attachment_map() -> Result<(), Error> {
+        use crate::{get_item::GetItemResponse, soap::Envelope};
+
+        let document = include_bytes!("../../fixtures/get_item_html_inline_images.xml");
+        let envelope = Envelope::<GetItemResponse>::from_xml_document(document, None)?;
+        let mut message = match envelope.body.into_result()?.remove(0).items.inner.remove(0) {
+            RealItem::Message(message) => message,
+            other => panic!("unexpected item {other:?}"),
+        };
+
+        let attachment_name = |attachment: &Attachment| match attachment {
+            Attachment::FileAttachment { name, .. } => name.clone(),
+            other => panic!("unexpected attachment {other:?}"),
+        };
+
+        let map = message.inline_attachment_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(
+            attachment_name(map["image001.png@01DA9B2C.5E3F1A20"]),
+            "image001.png"
+        );
+        assert_eq!(
+            attachment_name(map["image002.jpg@01DA9B2C.5E3F1A20"]),
+            "image002.jpg"
+        );
+
+        // Content IDs are still matched when only one side keeps its domain.
+        let attachments = message.attachments.as_mut().expect("attachments");
+        for attachment in &mut attachments.inner {
+            if let Attachment::FileAttachment { content_id, .. } = attachment {
+                *content_id = content_id
+                    .as_deref()
+                    .and_then(|id| id.split_once('@'))
+                    .map(|(local, _)| format!("<{local}>"));
+            }
+        }
+        let map = message.inline_attachment_map();
+        assert_eq!(
+            attachment_name(map["image002.jpg@01DA9B2C.5E3F1A20"]),
+            "image002.jpg"
+        );
+
+        // Escaped markup in the text of the body isn't a reference.
+        message.body = Some(Body::html(
+            "<p>Embed images with &lt;img src=&quot;cid:image002.jpg&quot;&gt;</p>",
+        ));
+        assert!(message.inline_attachment_map().is_empty());
+
+        message.body = Some(Body::text("cid:image001.png@01DA9B2C.5E3F1A20"));
+        assert!(message.inline_attachment_map().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_content_decoding() -> Result<(), Error> {
+        let content = r#"<Message xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">
+            <t:MimeContent CharacterSet="UTF-8">U3ViamVjdDogSGkNCg0K
+SGVsbG8NCg==</t:MimeContent>
+            <t:Body BodyType="Text">Fish &amp; chips</t:Body>
+        </Message>"#;
+
+        let mut de = quick_xml::de::Deserializer::from_reader(content.as_bytes());
+        let message: Message = serde_path_to_error::deserialize(&mut de)?;
+
+        let mime_content = message
+            .mime_content
+            .as_deref()
+            .expect("message has MIME content");
+        assert!(mime_content.raw().starts_with("U3ViamVjdDogSGkNCg0K"));
+        assert_eq!(mime_content.decoded_text()?, "Subject: Hi\r\n\r\nHello\r\n");
+
+        let body = message.body.expect("message has a body");
+        assert_eq!(body.content_str(), "Fish & chips");
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde-serialize")]
+    #[test]
+    fn serialize_message_as_json() {
+        let message = Message {
+            subject: Some("Hello".to_string()),
+            date_time_sent: Some(DateTime(
+                time::OffsetDateTime::from_unix_timestamp(1_714_552_200).unwrap(),
+            )),
+            to_recipients: Some(ArrayOfRecipients(vec![Recipient::new("alice@example.com")])),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&message).unwrap();
+
+        assert_eq!(json["Subject"], "Hello");
+        assert_eq!(json["DateTimeSent"], "2024-05-01T08:30:00.000000000Z");
+        assert_eq!(
+            json["ToRecipients"][0]["Mailbox"]["EmailAddress"],
+            "alice@example.com"
+        );
+    }
+
+    #[test]
+    fn construct_extended_field_uris() {
+        let by_tag = ExtendedFieldURI::by_tag(0x0E07, PropertyType::Integer).unwrap();
+        assert_eq!(by_tag.property_tag.as_deref(), Some("0x0E07"));
+        assert!(by_tag.validate().is_ok());
+
+        assert!(matches!(
+            ExtendedFieldURI::by_tag(0x0E070003, PropertyType::Integer),
+            Err(Error::InvalidExtendedFieldUri(_))
+        ));
+
+        let by_name = ExtendedFieldURI::by_name(
+            DistinguishedPropertySet::PublicStrings,
+            "Keywords",
+            PropertyType::StringArray,
+        )
+        .unwrap();
+        assert_eq!(
+            by_name.distinguished_property_set_id,
+            Some(DistinguishedPropertySet::PublicStrings)
+        );
+        assert!(by_name.validate().is_ok());
+
+        let by_id = ExtendedFieldURI::by_id(
+            PropertySet::Guid("00062008-0000-0000-C000-000000000046".to_string()),
+            0x8503,
+            PropertyType::Boolean,
+        )
+        .unwrap();
+        assert_eq!(by_id.property_id.as_deref(), Some("34051"));
+        assert!(by_id.validate().is_ok());
+
+        assert!(ExtendedFieldURI::by_id(
+            PropertySet::Guid("{00062008-0000-0000-C000-000000000046}".to_string()),
+            0x8503,
+            PropertyType::Boolean,
+        )
+        .is_err());
+        assert!(ExtendedFieldURI::by_name(
+            DistinguishedPropertySet::Common,
+            "",
+            PropertyType::String
+        )
+        .is_err());
+
+        let mixed = ExtendedFieldURI {
+            property_tag: Some("0x0E07".to_string()),
+            ..by_name.clone()
+        };
+        assert!(mixed.validate().is_err());
+
+        let missing_set = ExtendedFieldURI {
+            distinguished_property_set_id: None,
+            ..by_name
+        };
+        assert!(missing_set.validate().is_err());
+    }
+
+    #[test]
+    fn distinguished_folder_names() {
+        assert_eq!(
+            "JunkEmail".parse::<DistinguishedFolderName>().unwrap(),
+            DistinguishedFolderName::JunkEmail
+        );
+        assert_eq!(
+            DistinguishedFolderName::RecoverableItemsDeletions.to_string(),
+            "recoverableitemsdeletions"
+        );
+        assert!(matches!(
+            "inobx".parse::<DistinguishedFolderName>(),
+            Err(Error::UnknownDistinguishedFolder(name)) if name == "inobx"
+        ));
+
+        for name in DistinguishedFolderName::ALL {
+            assert_eq!(
+                name.as_str().parse::<DistinguishedFolderName>().unwrap(),
+                *name
+            );
+        }
+
+        assert_serialized_content(
+            &BaseFolderId::inbox(),
+            "ParentFolderId",
+            r#"<ParentFolderId><t:DistinguishedFolderId Id="inbox"/></ParentFolderId>"#,
+        );
+    }
+
+    /// Tests that the identifier of a folder is written in attributes, as it
+    /// is read.
+    #[test]
+    fn folder_id_attributes() -> Result<(), Error> {
+        let folder_id = FolderId {
+            id: "AQMkADAwATM0MDAAMS1hNmE3LTFk".to_string(),
+            change_key: Some("AQAAABYAAAAuSLPJ".to_string()),
+        };
+
+        assert_serialized_content(
+            &folder_id,
+            "ParentFolderId",
+            r#"<ParentFolderId Id="AQMkADAwATM0MDAAMS1hNmE3LTFk" ChangeKey="AQAAABYAAAAuSLPJ"/>"#,
+        );
+        assert_eq!(
+            crate::test_utils::roundtrip(&folder_id, "ParentFolderId")?,
+            folder_id
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sanitized_html_item_shape() {
+        assert_serialized_content(
+            &ItemShape::sanitized_html(),
+            "ItemShape",
+            concat!(
+                "<ItemShape><t:BaseShape>Default</t:BaseShape><t:BodyType>HTML</t:BodyType>",
+                "<t:FilterHtmlContent>true</t:FilterHtmlContent>",
+                "<t:ConvertHtmlCodePageToUTF8>true</t:ConvertHtmlCodePageToUTF8>",
+                "<t:BlockExternalImages>true</t:BlockExternalImages></ItemShape>",
+            ),
+        );
+    }
+
+    #[test]
+    fn path_to_element_helpers() {
+        let shape = ItemShape {
+            additional_properties: Some(vec![
+                PathToElement::field("item:Subject"),
+                PathToElement::indexed("contacts:EmailAddress", "EmailAddress1"),
+                PathToElement::extended(
+                    ExtendedFieldURI::by_tag(0x0E07, PropertyType::Integer).unwrap(),
+                ),
+            ]),
+            ..ItemShape::id_only()
+        };
+
+        let expected = r#"<ItemShape><t:BaseShape>IdOnly</t:BaseShape><t:AdditionalProperties><t:FieldURI FieldURI="item:Subject"/><t:IndexedFieldURI FieldURI="contacts:EmailAddress" FieldIndex="EmailAddress1"/><t:ExtendedFieldURI PropertyTag="0x0E07" PropertyType="Integer"/></t:AdditionalProperties></ItemShape>"#;
+        assert_serialized_content(&shape, "ItemShape", expected);
+    }
+
+    #[test]
+    fn item_shape_presets() {
+        assert_serialized_content(
+            &ItemShape::id_only(),
+            "ItemShape",
+            "<ItemShape><t:BaseShape>IdOnly</t:BaseShape></ItemShape>",
+        );
+        assert_serialized_content(
+            &ItemShape::sync_default(),
+            "ItemShape",
+            r#"<ItemShape><t:BaseShape>IdOnly</t:BaseShape><t:AdditionalProperties><t:FieldURI FieldURI="item:ItemClass"/></t:AdditionalProperties></ItemShape>"#,
+        );
+        assert_serialized_content(
+            &ItemShape::full_with_mime(),
+            "ItemShape",
+            "<ItemShape><t:BaseShape>AllProperties</t:BaseShape><t:IncludeMimeContent>true</t:IncludeMimeContent></ItemShape>",
+        );
+    }
 
     /// Tests that an [`ArrayOfRecipients`] correctly serializes into XML. It
     /// should serialize as multiple `<t:Mailbox>` elements, one per [`Recipient`].
@@ -1761,7 +4282,7 @@ mod tests {
         let alice = Recipient {
             mailbox: Mailbox {
                 name: Some("Alice Test".into()),
-                email_address: "alice@test.com".into(),
+                email_address: Some("alice@test.com".into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
@@ -1771,7 +4292,7 @@ mod tests {
         let bob = Recipient {
             mailbox: Mailbox {
                 name: Some("Bob Test".into()),
-                email_address: "bob@test.com".into(),
+                email_address: Some("bob@test.com".into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
@@ -1788,6 +4309,36 @@ mod tests {
         Ok(())
     }
 
+    /// Tests the shorthands for building an [`ArrayOfRecipients`].
+    #[test]
+    fn build_array_of_recipients() {
+        let expected = ArrayOfRecipients(vec![
+            Recipient::new("alice@test.com"),
+            Recipient::with_name("bob@test.com", "Bob Test"),
+        ]);
+
+        assert_eq!(
+            crate::recipients!["alice@test.com", ("Bob Test", "bob@test.com")],
+            expected
+        );
+        assert_eq!(
+            ArrayOfRecipients::from(vec![
+                Mailbox::new("alice@test.com"),
+                Mailbox::with_name("bob@test.com", "Bob Test"),
+            ]),
+            expected
+        );
+        assert_eq!(
+            ArrayOfRecipients::from(vec!["alice@test.com", "bob@test.com"]).len(),
+            2
+        );
+        assert_eq!(
+            expected.iter().cloned().collect::<ArrayOfRecipients>(),
+            expected
+        );
+        assert!(crate::recipients![].is_empty());
+    }
+
     /// Tests that deserializing a sequence of `<t:Mailbox>` XML elements
     /// results in an [`ArrayOfRecipients`] with one [`Recipient`] per
     /// `<t:Mailbox>` element.
@@ -1811,7 +4362,7 @@ mod tests {
             &Recipient {
                 mailbox: Mailbox {
                     name: Some("Alice Test".into()),
-                    email_address: "alice@test.com".into(),
+                    email_address: Some("alice@test.com".into()),
                     routing_type: None,
                     mailbox_type: None,
                     item_id: None,
@@ -1825,7 +4376,7 @@ mod tests {
             &Recipient {
                 mailbox: Mailbox {
                     name: Some("Bob Test".into()),
-                    email_address: "bob@test.com".into(),
+                    email_address: Some("bob@test.com".into()),
                     routing_type: None,
                     mailbox_type: None,
                     item_id: None,
@@ -1882,23 +4433,24 @@ mod tests {
         Ok(())
     }
 
-    // TODO: Uncomment once NOT restriction is implemented
-    // /// Tests the creation and serialization of NOT compound restrictions.
-    // #[test]
-    // fn test_not_restriction() -> Result<(), Error> {
-    //     let subject_restriction = Restriction::equal_to(
-    //         PathToElement::FieldURI {
-    //             field_URI: "item:Subject".to_string(),
-    //         },
-    //         "Spam".to_string(),
-    //     );
+    /// Tests the creation and serialization of NOT compound restrictions.
+    #[test]
+    fn test_not_restriction() -> Result<(), Error> {
+        let subject_restriction = Restriction::equal_to(
+            PathToElement::FieldURI {
+                field_URI: "item:Subject".to_string(),
+            },
+            "Spam".to_string(),
+        );
 
-    //     let not_restriction = Restriction::not(subject_restriction);
+        let not_restriction = Restriction::not(subject_restriction);
 
-    //     let expected = r#"<Restriction><t:Not><t:Restriction><t:IsEqualTo><t:FieldURI FieldURI="item:Subject"/><t:FieldURIOrConstant><t:Constant Value="Spam"/></t:FieldURIOrConstant></t:IsEqualTo></t:Restriction></t:Not></Restriction>"#;
-    //     assert_serialized_content(&not_restriction, "Restriction", expected);
-    //     Ok(())
-    // }
+        // The negated expression is a direct child of `Not`, see
+        // <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/not>
+        let expected = r#"<Restriction><t:Not><t:IsEqualTo><t:FieldURI FieldURI="item:Subject"/><t:FieldURIOrConstant><t:Constant Value="Spam"/></t:FieldURIOrConstant></t:IsEqualTo></t:Not></Restriction>"#;
+        assert_serialized_content(&not_restriction, "Restriction", expected);
+        Ok(())
+    }
 
     /// Tests the creation and serialization of nested compound restrictions.
     #[test]
@@ -1946,6 +4498,174 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_search_folder_search_parameters() -> Result<(), Error> {
+        use crate::test_utils::roundtrip_variant;
+
+        let xml = concat!(
+            r#"<t:SearchFolder xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+            r#"<t:FolderId Id="AAMkAD" ChangeKey="AQAAAB"/><t:DisplayName>Flagged or unread</t:DisplayName>"#,
+            r#"<t:SearchParameters Traversal="Deep"><t:Restriction><t:And><t:Or>"#,
+            r#"<t:IsEqualTo><t:FieldURI FieldURI="message:IsRead"/><t:FieldURIOrConstant><t:Constant Value="false"/></t:FieldURIOrConstant></t:IsEqualTo>"#,
+            r#"<t:IsEqualTo><t:ExtendedFieldURI PropertyTag="0x1090" PropertyType="Integer"/><t:FieldURIOrConstant><t:Constant Value="2"/></t:FieldURIOrConstant></t:IsEqualTo>"#,
+            r#"</t:Or><t:Not><t:Contains ContainmentMode="Substring" ContainmentComparison="IgnoreCase"><t:FieldURI FieldURI="item:Subject"/><t:Constant Value="newsletter"/></t:Contains></t:Not>"#,
+            r#"<t:IsGreaterThan><t:FieldURI FieldURI="item:DateTimeReceived"/><t:FieldURIOrConstant><t:FieldURI FieldURI="item:DateTimeSent"/></t:FieldURIOrConstant></t:IsGreaterThan>"#,
+            r#"<t:Exists><t:IndexedFieldURI FieldURI="contacts:EmailAddress" FieldIndex="EmailAddress1"/></t:Exists>"#,
+            r#"</t:And></t:Restriction><t:BaseFolderIds><t:DistinguishedFolderId Id="inbox"/><t:FolderId Id="AAMkAE"/></t:BaseFolderIds>"#,
+            r#"</t:SearchParameters></t:SearchFolder>"#,
+        );
+
+        let folder: Folder =
+            quick_xml::de::from_str(xml).expect("search folder should deserialize");
+        let Folder::SearchFolder {
+            search_parameters: Some(search_parameters),
+            ..
+        } = &folder
+        else {
+            panic!("expected search folder with search parameters, got {folder:?}");
+        };
+
+        let flag_status = ExtendedFieldURI::by_tag(0x1090, PropertyType::Integer)?;
+        let expected_restriction = Restriction::and(vec![
+            Restriction::or(vec![
+                Restriction::equal_to(PathToElement::field("message:IsRead"), "false"),
+                Restriction::equal_to(flag_status.into(), "2"),
+            ]),
+            Restriction::not(Restriction {
+                restriction_type: RestrictionType::Contains(ContainsRestriction {
+                    containment_mode: Some(ContainmentMode::Substring),
+                    containment_comparison: Some(ContainmentComparison::IgnoreCase),
+                    path: PathToElement::field("item:Subject"),
+                    constant: Constant {
+                        value: "newsletter".to_string(),
+                    },
+                }),
+            }),
+            Restriction::greater_than(
+                PathToElement::field("item:DateTimeReceived"),
+                PathToElement::field("item:DateTimeSent"),
+            ),
+            Restriction::exists(PathToElement::IndexedFieldURI {
+                field_URI: "contacts:EmailAddress".to_string(),
+                field_index: "EmailAddress1".to_string(),
+            }),
+        ]);
+        assert_eq!(
+            &**search_parameters,
+            &SearchParameters {
+                traversal: Some(SearchFolderTraversal::Deep),
+                restriction: expected_restriction,
+                base_folder_ids: vec![
+                    BaseFolderId::DistinguishedFolderId {
+                        id: "inbox".to_string(),
+                        change_key: None,
+                    },
+                    BaseFolderId::FolderId {
+                        id: "AAMkAE".to_string(),
+                        change_key: None,
+                    },
+                ],
+            }
+        );
+
+        assert_eq!(roundtrip_variant(&folder)?, folder);
+
+        // A comparison must be against a value, not a bare property.
+        let malformed = r#"<t:Restriction xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><t:IsEqualTo><t:FieldURI FieldURI="item:Subject"/><t:FieldURI FieldURI="item:Subject"/></t:IsEqualTo></t:Restriction>"#;
+        assert!(quick_xml::de::from_str::<Restriction>(malformed).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collection_access() {
+        let folders = Folders {
+            inner: vec![
+                Folder::new_folder("Receipts"),
+                Folder::new_calendar_folder("Holidays"),
+            ],
+        };
+
+        assert_eq!(folders.len(), 2);
+        assert!(!folders.is_empty());
+        assert_eq!(folders[1].display_name(), Some("Holidays"));
+        assert_eq!(
+            folders
+                .iter()
+                .filter_map(Folder::display_name)
+                .collect::<Vec<_>>(),
+            ["Receipts", "Holidays"]
+        );
+        assert_eq!((&folders).into_iter().count(), 2);
+        assert_eq!(
+            folders.into_iter().last(),
+            Some(Folder::new_calendar_folder("Holidays"))
+        );
+
+        let responses = ResponseMessages {
+            response_messages: vec![ResponseClass::Success(()), ResponseClass::Warning(())],
+        };
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[1], ResponseClass::Warning(()));
+        assert!(responses
+            .into_iter()
+            .all(|response| response.into_result().is_ok()));
+        assert!(Items::default().is_empty());
+    }
+
+    #[test]
+    fn test_folder_properties() -> Result<(), Error> {
+        use crate::test_utils::roundtrip_variant;
+
+        let mut folder = Folder::new_folder("Receipts");
+        folder.properties_mut().folder_class = Some("IPF.Note".to_string());
+        if let Folder::Folder { unread_count, .. } = &mut folder {
+            *unread_count = Some(3);
+        }
+
+        // The shared properties are written before the unread count, as
+        // required by the schema.
+        let mut writer = quick_xml::Writer::new(Vec::new());
+        folder.serialize_child_nodes(&mut writer)?;
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).expect("XML should be UTF-8"),
+            concat!(
+                "<t:Folder><t:FolderClass>IPF.Note</t:FolderClass>",
+                "<t:DisplayName>Receipts</t:DisplayName>",
+                "<t:UnreadCount>3</t:UnreadCount></t:Folder>",
+            )
+        );
+
+        let xml = concat!(
+            r#"<t:CalendarFolder xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+            r#"<t:FolderId Id="AAMkAD" ChangeKey="AQAAAB"/><t:DisplayName>Calendar</t:DisplayName>"#,
+            r#"<t:TotalCount>12</t:TotalCount><t:ChildFolderCount>0</t:ChildFolderCount>"#,
+            r#"<t:UnreadCount>0</t:UnreadCount></t:CalendarFolder>"#,
+        );
+        let calendar: Folder =
+            quick_xml::de::from_str(xml).expect("calendar folder should deserialize");
+
+        assert_eq!(
+            calendar,
+            Folder::CalendarFolder {
+                properties: FolderProperties {
+                    folder_id: Some(FolderId {
+                        id: "AAMkAD".to_string(),
+                        change_key: Some("AQAAAB".to_string()),
+                    }),
+                    display_name: Some("Calendar".to_string()),
+                    total_count: Some(12),
+                    child_folder_count: Some(0),
+                    ..Default::default()
+                },
+            }
+        );
+        assert_eq!(roundtrip_variant(&calendar)?, calendar);
+        assert_eq!(roundtrip_variant(&folder)?, folder);
+
+        Ok(())
+    }
+
     /// Tests deserialization of a message with HasAttachments=false but inline attachments present.
     /// This is expected EWS behavior where inline attachments are "hidden attachments" and do not
     /// affect the HasAttachments property according to Microsoft's EWS specification.
@@ -2019,6 +4739,38 @@ mod tests {
         Ok(())
     }
 
+    /// Tests that every category of an item is read and written.
+    #[test]
+    fn test_categories() -> Result<(), Error> {
+        let xml = r#"<Items xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">
+            <t:Message>
+                <t:Categories>
+                    <t:String>Work</t:String>
+                    <t:String>Travel</t:String>
+                </t:Categories>
+            </t:Message>
+        </Items>"#;
+
+        let mut de = quick_xml::de::Deserializer::from_reader(xml.as_bytes());
+        let items: Items = serde_path_to_error::deserialize(&mut de)?;
+
+        let RealItem::Message(message) = &items.inner[0] else {
+            panic!("Expected Message but got a different variant");
+        };
+        let categories = message
+            .categories
+            .as_ref()
+            .expect("categories should be set");
+        assert_eq!(categories.0, ["Work", "Travel"]);
+
+        let categories: ArrayOfStrings = ["Work", "Travel"].into_iter().collect();
+        let expected =
+            r#"<t:Categories><t:String>Work</t:String><t:String>Travel</t:String></t:Categories>"#;
+        assert_serialized_content(&categories, "t:Categories", expected);
+
+        Ok(())
+    }
+
     /// Tests deserialization of various meeting-related items.
     #[test]
     fn test_meeting_item_variants_deserialization() -> Result<(), Error> {
@@ -2062,6 +4814,204 @@ mod tests {
         Ok(())
     }
 
+    /// Tests deserialization of the properties specific to meeting requests.
+    #[test]
+    fn test_meeting_request_properties() -> Result<(), Error> {
+        let xml = r#"<Items xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">
+            <t:MeetingRequest>
+                <t:Subject>Weekly sync</t:Subject>
+                <t:MeetingRequestType>FullUpdate</t:MeetingRequestType>
+                <t:IntendedFreeBusyStatus>Busy</t:IntendedFreeBusyStatus>
+                <t:Start>2024-05-06T16:00:00Z</t:Start>
+                <t:End>2024-05-06T16:30:00Z</t:End>
+                <t:Organizer>
+                    <t:Mailbox>
+                        <t:Name>Alice</t:Name>
+                        <t:EmailAddress>alice@example.com</t:EmailAddress>
+                    </t:Mailbox>
+                </t:Organizer>
+                <t:Recurrence>
+                    <t:WeeklyRecurrence>
+                        <t:Interval>1</t:Interval>
+                        <t:DaysOfWeek>Monday</t:DaysOfWeek>
+                    </t:WeeklyRecurrence>
+                    <t:NumberedRecurrence>
+                        <t:StartDate>2024-05-06Z</t:StartDate>
+                        <t:NumberOfOccurrences>10</t:NumberOfOccurrences>
+                    </t:NumberedRecurrence>
+                </t:Recurrence>
+                <t:ChangeHighlights>
+                    <t:HasLocationChanged>false</t:HasLocationChanged>
+                    <t:HasStartTimeChanged>true</t:HasStartTimeChanged>
+                    <t:Start>2024-05-06T16:00:00Z</t:Start>
+                    <t:HasEndTimeChanged>true</t:HasEndTimeChanged>
+                    <t:End>2024-05-06T16:30:00Z</t:End>
+                </t:ChangeHighlights>
+            </t:MeetingRequest>
+        </Items>"#;
+
+        let mut de = quick_xml::de::Deserializer::from_reader(xml.as_bytes());
+        let items: Items = serde_path_to_error::deserialize(&mut de)?;
+
+        let RealItem::MeetingRequest(request) = &items.inner[0] else {
+            panic!("expected a meeting request");
+        };
+        assert!(request.is_update());
+        assert_eq!(
+            request.intended_free_busy_status,
+            Some(crate::get_user_availability::LegacyFreeBusyStatus::Busy)
+        );
+        assert_eq!(
+            request
+                .organizer
+                .as_ref()
+                .and_then(|organizer| organizer.mailbox.email_address.as_deref()),
+            Some("alice@example.com")
+        );
+        assert_eq!(
+            request.recurrence.as_deref(),
+            Some(&Recurrence {
+                pattern: RecurrencePattern::WeeklyRecurrence(WeeklyRecurrence {
+                    interval: 1,
+                    days_of_week: "Monday".to_string(),
+                    first_day_of_week: None,
+                }),
+                range: RecurrenceRange::NumberedRecurrence(NumberedRecurrence {
+                    start_date: "2024-05-06Z".to_string(),
+                    number_of_occurrences: 10,
+                }),
+            })
+        );
+
+        let highlights = request
+            .change_highlights
+            .as_ref()
+            .expect("change highlights should be present");
+        assert_eq!(highlights.has_location_changed, Some(false));
+        assert_eq!(highlights.has_start_time_changed, Some(true));
+        assert_eq!(highlights.start, request.start);
+        assert_eq!(request.subject.as_deref(), Some("Weekly sync"));
+
+        let item = &items.inner[0];
+        assert_eq!(&crate::test_utils::roundtrip_variant(item)?, item);
+
+        Ok(())
+    }
+
+    /// Tests that the properties specific to meeting requests are written at
+    /// their positions in the schema.
+    #[test]
+    fn test_serialize_meeting_request() {
+        let start = time::OffsetDateTime::from_unix_timestamp(1_714_996_800).unwrap();
+        let request = MeetingRequest {
+            message: Message {
+                subject: Some("Weekly sync".to_string()),
+                start: Some(DateTime(start)),
+                end: Some(DateTime(start + time::Duration::minutes(30))),
+                is_all_day_event: Some(false),
+                ..Default::default()
+            },
+            meeting_request_type: Some(MeetingRequestType::FullUpdate),
+            intended_free_busy_status: Some(
+                crate::get_user_availability::LegacyFreeBusyStatus::Busy,
+            ),
+            change_highlights: Some(Box::new(ChangeHighlights {
+                has_start_time_changed: Some(true),
+                ..Default::default()
+            })),
+        };
+
+        let expected = concat!(
+            "<MeetingRequest><t:Subject>Weekly sync</t:Subject>",
+            "<t:MeetingRequestType>FullUpdate</t:MeetingRequestType>",
+            "<t:IntendedFreeBusyStatus>Busy</t:IntendedFreeBusyStatus>",
+            "<t:Start>2024-05-06T12:00:00.000000000Z</t:Start>",
+            "<t:End>2024-05-06T12:30:00.000000000Z</t:End>",
+            "<t:IsAllDayEvent>false</t:IsAllDayEvent>",
+            "<t:ChangeHighlights><t:HasStartTimeChanged>true</t:HasStartTimeChanged></t:ChangeHighlights>",
+            "</MeetingRequest>",
+        );
+
+        assert_serialized_content(&request, "MeetingRequest", expected);
+    }
+
+    /// Tests serialization of the recurrence of a calendar item.
+    #[test]
+    fn test_serialize_recurrence() {
+        let recurrence = Recurrence {
+            pattern: RecurrencePattern::RelativeMonthlyRecurrence(RelativeMonthlyRecurrence {
+                interval: 1,
+                days_of_week: "Tuesday".to_string(),
+                day_of_week_index: DayOfWeekIndex::Second,
+            }),
+            range: RecurrenceRange::EndDateRecurrence(EndDateRecurrence {
+                start_date: "2024-01-01".to_string(),
+                end_date: "2024-12-31".to_string(),
+            }),
+        };
+
+        let expected = r#"<Recurrence><t:RelativeMonthlyRecurrence><t:Interval>1</t:Interval><t:DaysOfWeek>Tuesday</t:DaysOfWeek><t:DayOfWeekIndex>Second</t:DayOfWeekIndex></t:RelativeMonthlyRecurrence><t:EndDateRecurrence><t:StartDate>2024-01-01</t:StartDate><t:EndDate>2024-12-31</t:EndDate></t:EndDateRecurrence></Recurrence>"#;
+
+        assert_serialized_content(&recurrence, "Recurrence", expected);
+    }
+
+    /// Tests sending and receiving proposals of a new meeting time.
+    #[test]
+    fn test_propose_new_time() -> Result<(), Error> {
+        let proposal = RealItem::propose_new_time(
+            ItemId {
+                id: "meeting-request-id".to_string(),
+                change_key: None,
+            },
+            DateTime(time::OffsetDateTime::from_unix_timestamp(1_714_552_200).unwrap()),
+            DateTime(time::OffsetDateTime::from_unix_timestamp(1_714_554_000).unwrap()),
+        );
+
+        let RealItem::TentativelyAcceptItem(message) = &proposal else {
+            panic!("proposal should tentatively accept the meeting");
+        };
+        assert_serialized_content(
+            &message.reference_item_id,
+            "t:ReferenceItemId",
+            r#"<t:ReferenceItemId Id="meeting-request-id"/>"#,
+        );
+        assert_serialized_content(
+            &message.proposed_start,
+            "t:ProposedStart",
+            "<t:ProposedStart>2024-05-01T08:30:00.000000000Z</t:ProposedStart>",
+        );
+
+        let meeting_response_xml = r#"<Items xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">
+            <t:MeetingResponse>
+                <t:Subject>New Time Proposed: Project Review Meeting</t:Subject>
+                <t:ProposedStart>2024-05-01T08:30:00Z</t:ProposedStart>
+                <t:ProposedEnd>2024-05-01T09:00:00Z</t:ProposedEnd>
+            </t:MeetingResponse>
+        </Items>"#;
+
+        let mut de = quick_xml::de::Deserializer::from_reader(meeting_response_xml.as_bytes());
+        let items: Items = serde_path_to_error::deserialize(&mut de)?;
+        let RealItem::MeetingResponse(response) = &items.inner[0] else {
+            panic!("Expected MeetingResponse but got a different variant");
+        };
+        assert_eq!(
+            response
+                .proposed_start
+                .as_ref()
+                .map(|start| start.0.unix_timestamp()),
+            Some(1_714_552_200)
+        );
+        assert_eq!(
+            response
+                .proposed_end
+                .as_ref()
+                .map(|end| end.0.unix_timestamp()),
+            Some(1_714_554_000)
+        );
+
+        Ok(())
+    }
+
     /// Tests the convenience methods for checking different types of attachments.
     #[test]
     fn test_message_attachment_convenience_methods() -> Result<(), Error> {
@@ -2192,7 +5142,7 @@ mod tests {
         {
             assert_eq!(name, "test-image.png");
             assert_eq!(*is_inline, Some(true));
-            assert_eq!(content_type, "image/png");
+            assert_eq!(content_type.as_deref(), Some("image/png"));
             assert_eq!(content_id.as_ref().unwrap(), "test-image.png@example.test");
         } else {
             panic!("Expected FileAttachment");
@@ -0,0 +1,255 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use serde::Deserialize;
+
+use crate::{soap::transcode_to_utf8, Error};
+
+/// The master category list of a mailbox, i.e. the categories which can be
+/// assigned to items, along with their colors.
+///
+/// Outlook stores the list as XML in the `CategoryList` user configuration
+/// object of the calendar folder, which can be requested with
+/// [`GetUserConfiguration::category_list`] and parsed with
+/// [`UserConfiguration::category_list`]. Properties of categories which
+/// aren't modeled here, such as usage statistics, are ignored.
+///
+/// See <https://learn.microsoft.com/en-us/openspecs/exchange_server_protocols/ms-oxocfg>
+///
+/// [`GetUserConfiguration::category_list`]: crate::get_user_configuration::GetUserConfiguration::category_list
+/// [`UserConfiguration::category_list`]: crate::get_user_configuration::UserConfiguration::category_list
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct CategoryList {
+    /// The name of the category assigned by the Quick Click action, if any.
+    #[serde(rename = "@default")]
+    pub default: Option<String>,
+
+    /// The categories in the list.
+    #[serde(rename = "category", default)]
+    pub categories: Vec<Category>,
+}
+
+impl CategoryList {
+    /// Parses the XML document in which the list is stored.
+    pub fn parse(document: &[u8]) -> Result<Self, Error> {
+        let document = transcode_to_utf8(document)?;
+        let document = std::str::from_utf8(&document)
+            .map_err(|err| Error::InvalidCategoryList(err.to_string()))?;
+
+        quick_xml::de::from_str(document).map_err(|err| Error::InvalidCategoryList(err.to_string()))
+    }
+
+    /// Finds the category with the given name, ignoring case as Outlook
+    /// does.
+    pub fn find(&self, name: &str) -> Option<&Category> {
+        self.categories
+            .iter()
+            .find(|category| category.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A category in the master category list of a mailbox.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct Category {
+    /// The name of the category, as assigned to items.
+    #[serde(rename = "@name")]
+    pub name: String,
+
+    /// The index of the color of the category among Outlook's preset colors,
+    /// or -1 if it has no color.
+    ///
+    /// See [`Category::color`] for the named color.
+    #[serde(rename = "@color", default = "no_color")]
+    pub color_index: i32,
+
+    /// The keyboard shortcut assigning the category, from 1 for Ctrl+F2 to
+    /// 11 for Ctrl+F12, or 0 if it has none.
+    #[serde(rename = "@keyboardShortcut", default)]
+    pub keyboard_shortcut: u8,
+
+    /// The GUID identifying the category, e.g.
+    /// `{5E1BE2A0-1C58-4A0B-8B4B-3E7E0E3C8D12}`.
+    #[serde(rename = "@guid")]
+    pub guid: Option<String>,
+}
+
+/// The color index of categories without a color.
+fn no_color() -> i32 {
+    -1
+}
+
+impl Category {
+    /// Gets the preset color of the category, or `None` if it has no color.
+    pub fn color(&self) -> Option<CategoryColor> {
+        usize::try_from(self.color_index)
+            .ok()
+            .and_then(|index| CategoryColor::ALL.get(index))
+            .copied()
+    }
+
+    /// Gets the number of the function key which, with Ctrl, assigns the
+    /// category, e.g. 2 for Ctrl+F2, or `None` if it has no shortcut.
+    pub fn shortcut_function_key(&self) -> Option<u8> {
+        match self.keyboard_shortcut {
+            1..=11 => Some(self.keyboard_shortcut + 1),
+            _ => None,
+        }
+    }
+}
+
+/// One of the preset colors which Outlook assigns to categories.
+///
+/// Colors are stored in the `color` attribute of categories by their index,
+/// from 0 for red, with -1 for categories without a color, as described in
+/// the category list section of MS-OXOCFG.
+///
+/// See <https://learn.microsoft.com/en-us/openspecs/exchange_server_protocols/ms-oxocfg>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub enum CategoryColor {
+    Red,
+    Orange,
+    Peach,
+    Yellow,
+    Green,
+    Teal,
+    Olive,
+    Blue,
+    Purple,
+    Maroon,
+    Steel,
+    DarkSteel,
+    Gray,
+    DarkGray,
+    Black,
+    DarkRed,
+    DarkOrange,
+    DarkPeach,
+    DarkYellow,
+    DarkGreen,
+    DarkTeal,
+    DarkOlive,
+    DarkBlue,
+    DarkPurple,
+    DarkMaroon,
+}
+
+impl CategoryColor {
+    /// Every preset color, in the order of their indices.
+    pub const ALL: &'static [CategoryColor] = &[
+        CategoryColor::Red,
+        CategoryColor::Orange,
+        CategoryColor::Peach,
+        CategoryColor::Yellow,
+        CategoryColor::Green,
+        CategoryColor::Teal,
+        CategoryColor::Olive,
+        CategoryColor::Blue,
+        CategoryColor::Purple,
+        CategoryColor::Maroon,
+        CategoryColor::Steel,
+        CategoryColor::DarkSteel,
+        CategoryColor::Gray,
+        CategoryColor::DarkGray,
+        CategoryColor::Black,
+        CategoryColor::DarkRed,
+        CategoryColor::DarkOrange,
+        CategoryColor::DarkPeach,
+        CategoryColor::DarkYellow,
+        CategoryColor::DarkGreen,
+        CategoryColor::DarkTeal,
+        CategoryColor::DarkOlive,
+        CategoryColor::DarkBlue,
+        CategoryColor::DarkPurple,
+        CategoryColor::DarkMaroon,
+    ];
+
+    /// Gets the index of the color, as stored in the category list.
+    pub fn index(self) -> i32 {
+        self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+    use crate::{
+        get_user_configuration::{UserConfiguration, UserConfigurationName},
+        BaseFolderId, Error,
+    };
+
+    use super::{Category, CategoryColor, CategoryList};
+
+    #[test]
+    fn parse_category_list() -> Result<(), Error> {
+        let document = concat!(
+            "\u{FEFF}",
+            r#"<?xml version="1.0"?>"#,
+            r#"<categories default="Red Category" lastSavedSession="2" lastSavedTime="2024-03-01T09:12:44.000" xmlns="CategoryList.xsd">"#,
+            r#"<category name="Red Category" color="0" keyboardShortcut="0" usageCount="12" guid="{0A8C5E5C-1A2B-4C3D-8E9F-0123456789AB}" renameOnFirstUse="1"/>"#,
+            r#"<category name="Travel" color="22" keyboardShortcut="3" guid="{1B9D6F6D-2B3C-4D4E-9FA0-123456789ABC}"/>"#,
+            r#"<category name="Uncolored"/>"#,
+            r#"</categories>"#,
+        );
+
+        let configuration = UserConfiguration {
+            user_configuration_name: UserConfigurationName {
+                name: "CategoryList".to_string(),
+                folder_id: BaseFolderId::FolderId {
+                    id: "AAMkAD".to_string(),
+                    change_key: None,
+                },
+            },
+            item_id: None,
+            dictionary: None,
+            xml_data: Some(BASE64_STANDARD.encode(document)),
+            binary_data: None,
+        };
+        let list = configuration
+            .category_list()?
+            .expect("category list should be present");
+
+        assert_eq!(list.default.as_deref(), Some("Red Category"));
+        assert_eq!(
+            list.categories,
+            vec![
+                Category {
+                    name: "Red Category".to_string(),
+                    color_index: 0,
+                    keyboard_shortcut: 0,
+                    guid: Some("{0A8C5E5C-1A2B-4C3D-8E9F-0123456789AB}".to_string()),
+                },
+                Category {
+                    name: "Travel".to_string(),
+                    color_index: 22,
+                    keyboard_shortcut: 3,
+                    guid: Some("{1B9D6F6D-2B3C-4D4E-9FA0-123456789ABC}".to_string()),
+                },
+                Category {
+                    name: "Uncolored".to_string(),
+                    color_index: -1,
+                    keyboard_shortcut: 0,
+                    guid: None,
+                },
+            ]
+        );
+
+        let travel = list.find("travel").expect("category should be found");
+        assert_eq!(travel.color(), Some(CategoryColor::DarkBlue));
+        assert_eq!(travel.shortcut_function_key(), Some(4));
+        assert_eq!(list.categories[0].color(), Some(CategoryColor::Red));
+        assert_eq!(list.categories[0].shortcut_function_key(), None);
+        assert_eq!(list.categories[2].color(), None);
+
+        assert!(matches!(
+            CategoryList::parse(b"<categories><category/></categories>"),
+            Err(Error::InvalidCategoryList(_))
+        ));
+
+        Ok(())
+    }
+}
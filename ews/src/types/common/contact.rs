@@ -0,0 +1,341 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use serde::Deserialize;
+use xml_struct::XmlSerialize;
+
+use crate::{ArrayOfStrings, Attachment, AttachmentId, Attachments, ExtendedProperty, ItemId};
+
+/// A contact in a mailbox's address book, or the contact information of a
+/// name resolved by `ResolveNames`.
+///
+/// Fields are declared in schema order, which Exchange requires when
+/// creating contacts.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contact>
+#[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct Contact {
+    /// The contact's Exchange identifier.
+    #[xml_struct(ns_prefix = "t")]
+    pub item_id: Option<ItemId>,
+
+    /// The Exchange class value of the item, e.g. `IPM.Contact`.
+    #[xml_struct(ns_prefix = "t")]
+    pub item_class: Option<String>,
+
+    /// The subject of the item, usually the same as its `file_as` value.
+    #[xml_struct(ns_prefix = "t")]
+    pub subject: Option<String>,
+
+    /// The attachments of the contact, including its photo if it has one.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachments-ex15websvcsotherref>
+    #[xml_struct(ns_prefix = "t")]
+    pub attachments: Option<Attachments>,
+
+    /// A list of categories describing the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub categories: Option<ArrayOfStrings>,
+
+    /// Extended MAPI properties of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub extended_property: Option<Vec<ExtendedProperty>>,
+
+    /// How the contact is filed in the address book, e.g. `Doe, John`.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/fileas>
+    #[xml_struct(ns_prefix = "t")]
+    pub file_as: Option<String>,
+
+    /// The display name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub display_name: Option<String>,
+
+    /// The given name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub given_name: Option<String>,
+
+    /// The initials of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub initials: Option<String>,
+
+    /// The middle name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub middle_name: Option<String>,
+
+    /// The nickname of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub nickname: Option<String>,
+
+    /// The complete name of the contact.
+    ///
+    /// This is computed by the server and cannot be set when creating a
+    /// contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub complete_name: Option<CompleteName>,
+
+    /// The company name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub company_name: Option<String>,
+
+    /// Email addresses for the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub email_addresses: Option<EmailAddresses>,
+
+    /// Physical addresses for the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub physical_addresses: Option<PhysicalAddresses>,
+
+    /// Phone numbers for the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub phone_numbers: Option<PhoneNumbers>,
+
+    /// The job title of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub job_title: Option<String>,
+
+    /// The surname of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub surname: Option<String>,
+}
+
+impl Contact {
+    /// Gets the identifier of the attachment holding the contact's photo, if
+    /// its attachments were requested and it has one.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/iscontactphoto>
+    pub fn photo_attachment_id(&self) -> Option<&AttachmentId> {
+        self.attachments
+            .as_ref()?
+            .inner
+            .iter()
+            .find_map(|attachment| match attachment {
+                Attachment::FileAttachment {
+                    attachment_id,
+                    is_contact_photo: Some(true),
+                    ..
+                } => Some(attachment_id),
+                _ => None,
+            })
+    }
+}
+
+/// Complete name information for a contact.
+#[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct CompleteName {
+    /// The title of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub title: Option<String>,
+
+    /// The first name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub first_name: Option<String>,
+
+    /// The middle name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub middle_name: Option<String>,
+
+    /// The last name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub last_name: Option<String>,
+
+    /// The suffix of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub suffix: Option<String>,
+
+    /// The initials of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub initials: Option<String>,
+
+    /// The full name of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub full_name: Option<String>,
+
+    /// The nickname of the contact.
+    #[xml_struct(ns_prefix = "t")]
+    pub nickname: Option<String>,
+
+    /// The Yomi first name.
+    #[xml_struct(ns_prefix = "t")]
+    pub yomi_first_name: Option<String>,
+
+    /// The Yomi last name.
+    #[xml_struct(ns_prefix = "t")]
+    pub yomi_last_name: Option<String>,
+}
+
+/// Email addresses for a contact.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/emailaddresses>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct EmailAddresses {
+    /// Collection of email address entries.
+    pub entry: Vec<EmailAddressEntry>,
+}
+
+/// An email address entry.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/entry-emailaddress>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct EmailAddressEntry {
+    /// The key identifying the email address type, one of `EmailAddress1`,
+    /// `EmailAddress2` or `EmailAddress3`.
+    #[serde(rename = "@Key")]
+    #[xml_struct(attribute)]
+    pub key: String,
+
+    /// The email address value.
+    #[serde(rename = "$text")]
+    #[xml_struct(flatten)]
+    pub value: String,
+}
+
+impl EmailAddressEntry {
+    /// Creates an email address entry with the given key.
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Phone numbers for a contact.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/phonenumbers>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct PhoneNumbers {
+    /// Collection of phone number entries.
+    pub entry: Vec<PhoneNumberEntry>,
+}
+
+/// A phone number entry.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/entry-phonenumber>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct PhoneNumberEntry {
+    /// The key identifying the phone number type, e.g. `BusinessPhone` or
+    /// `MobilePhone`.
+    #[serde(rename = "@Key")]
+    #[xml_struct(attribute)]
+    pub key: String,
+
+    /// The phone number value.
+    #[serde(rename = "$text")]
+    #[xml_struct(flatten)]
+    pub value: String,
+}
+
+impl PhoneNumberEntry {
+    /// Creates a phone number entry with the given key.
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Physical addresses for a contact.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/physicaladdresses>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct PhysicalAddresses {
+    /// Collection of physical address entries.
+    pub entry: Vec<PhysicalAddressEntry>,
+}
+
+/// Implements [`XmlSerialize`] for a collection of dictionary entries, which
+/// are written as sibling `t:Entry` elements each carrying its own key.
+macro_rules! serialize_entries {
+    ($($ty:ty),*) => {$(
+        impl XmlSerialize for $ty {
+            fn serialize_child_nodes<W>(
+                &self,
+                writer: &mut quick_xml::Writer<W>,
+            ) -> Result<(), xml_struct::Error>
+            where
+                W: std::io::Write,
+            {
+                for entry in &self.entry {
+                    entry.serialize_as_element(writer, "t:Entry")?;
+                }
+
+                Ok(())
+            }
+        }
+    )*};
+}
+
+serialize_entries!(EmailAddresses, PhoneNumbers, PhysicalAddresses);
+
+/// A physical address entry.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/entry-physicaladdress>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct PhysicalAddressEntry {
+    /// The key identifying the address type, one of `Home`, `Business` or
+    /// `Other`.
+    #[serde(rename = "@Key")]
+    #[xml_struct(attribute)]
+    pub key: String,
+
+    /// The street address.
+    #[xml_struct(ns_prefix = "t")]
+    pub street: Option<String>,
+
+    /// The city.
+    #[xml_struct(ns_prefix = "t")]
+    pub city: Option<String>,
+
+    /// The state or province.
+    #[xml_struct(ns_prefix = "t")]
+    pub state: Option<String>,
+
+    /// The country or region.
+    #[xml_struct(ns_prefix = "t")]
+    pub country_or_region: Option<String>,
+
+    /// The postal code.
+    #[xml_struct(ns_prefix = "t")]
+    pub postal_code: Option<String>,
+}
+
+impl PhysicalAddressEntry {
+    /// Creates an empty physical address entry with the given key.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            street: None,
+            city: None,
+            state: None,
+            country_or_region: None,
+            postal_code: None,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use crate::{
+    create_attachment::{
+        CreateAttachment, CreateAttachmentResponse, CreatedAttachment, NewAttachment,
+    },
+    delete_attachment::{DeleteAttachment, DeleteAttachmentResponse},
+    get_attachment::{GetAttachment, GetAttachmentResponse},
+    get_item::{GetItem, GetItemResponse},
+    Attachment, AttachmentId, BaseItemId, Error, ItemShape, PathToElement, RealItem,
+};
+
+/// The name Outlook gives to contact photo attachments.
+const PHOTO_NAME: &str = "ContactPicture.jpg";
+
+/// Reads or replaces the photo of a contact.
+///
+/// Exchange stores the photo of a contact as a file attachment flagged with
+/// `IsContactPhoto`. Reading it takes a [`GetItem`] request for the
+/// contact's attachments followed by a [`GetAttachment`] request for the
+/// content of the photo. Replacing it deletes any existing photo with a
+/// [`DeleteAttachment`] request before creating the new one with a
+/// [`CreateAttachment`] request. This does not send requests itself, so that
+/// it can be used with any client; the clients in [`client`] wrap this in
+/// `get_contact_photo` and `set_contact_photo` methods which also take care
+/// of base64 encoding.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/iscontactphoto>
+///
+/// [`client`]: crate::client
+#[derive(Clone, Debug)]
+pub struct ContactPhoto {
+    contact_id: BaseItemId,
+}
+
+impl ContactPhoto {
+    /// Creates a helper for the photo of the given contact.
+    pub fn new(contact_id: BaseItemId) -> Self {
+        Self { contact_id }
+    }
+
+    /// The identifier of the contact, including its latest change key once
+    /// a photo has been deleted.
+    pub fn contact_id(&self) -> &BaseItemId {
+        &self.contact_id
+    }
+
+    /// Gets the request for the attachments of the contact.
+    pub fn get_item(&self) -> GetItem {
+        GetItem::new(
+            ItemShape {
+                additional_properties: Some(vec![PathToElement::field("item:Attachments")]),
+                ..ItemShape::id_only()
+            },
+            vec![self.contact_id.clone()],
+        )
+    }
+
+    /// Finds the identifier of the contact's photo in the response to the
+    /// request given by [`get_item`], returning `None` if the contact has no
+    /// photo.
+    ///
+    /// [`get_item`]: ContactPhoto::get_item
+    pub fn attachment_id(&self, response: GetItemResponse) -> Result<Option<AttachmentId>, Error> {
+        let message = response.single()?;
+
+        Ok(message.items.inner.into_iter().find_map(|item| match item {
+            RealItem::Contact(contact) => contact.photo_attachment_id().cloned(),
+            _ => None,
+        }))
+    }
+
+    /// Gets the request for the content of the photo with the given
+    /// identifier.
+    pub fn get_attachment(&self, attachment_id: AttachmentId) -> GetAttachment {
+        GetAttachment::new(vec![attachment_id].into())
+    }
+
+    /// Takes the base64-encoded content of the photo from the response to
+    /// the request given by [`get_attachment`].
+    ///
+    /// [`get_attachment`]: ContactPhoto::get_attachment
+    pub fn content(&self, response: GetAttachmentResponse) -> Result<Option<String>, Error> {
+        let message = response.single()?;
+
+        Ok(message
+            .attachments
+            .into_iter()
+            .flat_map(|attachments| attachments.inner)
+            .find_map(|attachment| match attachment {
+                Attachment::FileAttachment { content, .. } => content,
+                _ => None,
+            }))
+    }
+
+    /// Gets the request deleting the existing photo with the given
+    /// identifier.
+    pub fn delete_attachment(&self, attachment_id: AttachmentId) -> DeleteAttachment {
+        DeleteAttachment::new(vec![attachment_id].into())
+    }
+
+    /// Processes the response to the request given by [`delete_attachment`],
+    /// updating the change key of the contact.
+    ///
+    /// [`delete_attachment`]: ContactPhoto::delete_attachment
+    pub fn process_deletion(&mut self, response: DeleteAttachmentResponse) -> Result<(), Error> {
+        let message = response.single()?;
+        if let Some(root_item_id) = message.root_item_id {
+            self.contact_id = root_item_id.into();
+        }
+
+        Ok(())
+    }
+
+    /// Gets the request creating a photo with the given base64-encoded
+    /// content and MIME type, e.g. `image/jpeg`.
+    pub fn create_attachment(
+        &self,
+        content: String,
+        content_type: impl Into<String>,
+    ) -> CreateAttachment {
+        CreateAttachment::new(
+            self.contact_id.clone().into(),
+            vec![NewAttachment::FileAttachment {
+                name: PHOTO_NAME.to_string(),
+                content_type: Some(content_type.into()),
+                content_id: None,
+                content_location: None,
+                is_inline: None,
+                is_contact_photo: Some(true),
+                content,
+            }],
+        )
+    }
+
+    /// Takes the identifier of the new photo from the response to the
+    /// request given by [`create_attachment`].
+    ///
+    /// [`create_attachment`]: ContactPhoto::create_attachment
+    pub fn created_attachment_id(
+        &self,
+        response: CreateAttachmentResponse,
+    ) -> Result<AttachmentId, Error> {
+        let message = response.single()?;
+
+        message
+            .attachments
+            .into_iter()
+            .flat_map(|attachments| attachments.inner)
+            .map(|attachment| match attachment {
+                CreatedAttachment::FileAttachment { attachment_id }
+                | CreatedAttachment::ItemAttachment { attachment_id } => attachment_id,
+            })
+            .next()
+            .ok_or(Error::MissingAttachmentId)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{create_attachment::NewAttachment, BaseItemId};
+
+    use super::ContactPhoto;
+
+    #[test]
+    fn test_contact_photo_round_trip() {
+        let mut photo = ContactPhoto::new(BaseItemId::ItemId {
+            id: "contact".to_string(),
+            change_key: Some("ck1".to_string()),
+        });
+
+        let contact = quick_xml::de::from_str(concat!(
+            r#"<GetItemResponse><ResponseMessages>"#,
+            r#"<GetItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<Items><Contact><ItemId Id="contact" ChangeKey="ck1"/><Attachments>"#,
+            r#"<FileAttachment><AttachmentId Id="notes"/><Name>notes.txt</Name><ContentType>text/plain</ContentType><IsContactPhoto>false</IsContactPhoto></FileAttachment>"#,
+            r#"<FileAttachment><AttachmentId Id="photo"/><Name>ContactPicture.jpg</Name><ContentType>image/jpeg</ContentType><IsContactPhoto>true</IsContactPhoto></FileAttachment>"#,
+            r#"</Attachments></Contact></Items>"#,
+            r#"</GetItemResponseMessage></ResponseMessages></GetItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        let attachment_id = photo
+            .attachment_id(contact)
+            .expect("response should be valid")
+            .expect("photo should be found");
+        assert_eq!(attachment_id.id, "photo");
+
+        let deletion = quick_xml::de::from_str(concat!(
+            r#"<DeleteAttachmentResponse><ResponseMessages>"#,
+            r#"<DeleteAttachmentResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<RootItemId RootItemId="contact" RootItemChangeKey="ck2"/>"#,
+            r#"</DeleteAttachmentResponseMessage></ResponseMessages></DeleteAttachmentResponse>"#,
+        ))
+        .expect("response should deserialize");
+        photo
+            .process_deletion(deletion)
+            .expect("deletion should succeed");
+
+        let operation = photo.create_attachment("AAEC".to_string(), "image/png");
+        assert_eq!(operation.parent_item_id.change_key.as_deref(), Some("ck2"));
+        assert!(matches!(
+            &operation.attachments[..],
+            [NewAttachment::FileAttachment {
+                is_contact_photo: Some(true),
+                ..
+            }]
+        ));
+    }
+}
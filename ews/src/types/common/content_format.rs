@@ -0,0 +1,157 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use quick_xml::{
+    events::{BytesCData, Event},
+    name::{Namespace, ResolveResult},
+    NsReader, Writer,
+};
+
+use crate::{Error, SOAP_NS_URI};
+
+/// The local names of elements whose content is written in the
+/// [`ContentFormat`] of an envelope.
+///
+/// Elements in the SOAP namespace are left alone, so that `soap:Body` isn't
+/// mistaken for the body of an item.
+const CONTENT_ELEMENTS: &[&[u8]] = &[b"Body", b"MimeContent", b"NewBodyContent"];
+
+/// How the content of [`Body`] and [`MimeContent`] values is written when
+/// serializing requests.
+///
+/// By default, content is written as XML text, with markup characters
+/// escaped. Large HTML bodies contain so many of these that escaping them
+/// can noticeably grow requests, which writing content in CDATA sections
+/// avoids. Content is accepted in either form when deserializing responses.
+///
+/// The format of a request is set with [`Envelope::content_format`].
+///
+/// [`Body`]: crate::Body
+/// [`MimeContent`]: crate::MimeContent
+/// [`Envelope::content_format`]: crate::soap::Envelope::content_format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContentFormat {
+    /// Content is written as escaped text.
+    #[default]
+    Escaped,
+
+    /// Content is written in CDATA sections, e.g.
+    /// `<![CDATA[<p>Hello</p>]]>`.
+    CData,
+}
+
+impl ContentFormat {
+    /// Rewrites the content of bodies and MIME content in a serialized
+    /// document in this format.
+    pub(crate) fn apply(self, document: Vec<u8>) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Escaped => Ok(document),
+            Self::CData => Ok(write_cdata_content(&document)?),
+        }
+    }
+}
+
+fn write_cdata_content(document: &[u8]) -> Result<Vec<u8>, quick_xml::Error> {
+    let mut reader = NsReader::from_reader(document);
+    let mut writer = Writer::new(Vec::with_capacity(document.len()));
+
+    // Whether the last event was the start of a content element.
+    let mut in_content = false;
+
+    loop {
+        let (namespace, event) = reader.read_resolved_event()?;
+        match event {
+            Event::Eof => break,
+            Event::Start(start) => {
+                in_content = CONTENT_ELEMENTS.contains(&start.local_name().as_ref())
+                    && !matches!(namespace, ResolveResult::Bound(Namespace(ns)) if ns == SOAP_NS_URI.as_bytes());
+                writer.write_event(Event::Start(start))?;
+            }
+            Event::Text(text) if in_content => {
+                // A CDATA section can't contain its own terminator, so end the
+                // section in the middle of it and start a new one.
+                let content = text.unescape()?;
+                let mut rest = content.as_ref();
+                while let Some(end) = rest.find("]]>") {
+                    writer.write_event(Event::CData(BytesCData::new(&rest[..end + 2])))?;
+                    rest = &rest[end + 2..];
+                }
+                writer.write_event(Event::CData(BytesCData::new(rest)))?;
+            }
+            event => {
+                in_content = false;
+                writer.write_event(event)?;
+            }
+        }
+    }
+
+    Ok(writer.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        create_item::CreateItem, soap::Envelope, test_utils::roundtrip, Body, Error, Message,
+        MessageDisposition, MimeContent, RealItem,
+    };
+
+    use super::ContentFormat;
+
+    fn create_message(body: Body, mime_content: MimeContent) -> CreateItem {
+        CreateItem {
+            items: vec![RealItem::Message(Message {
+                body: Some(body),
+                mime_content: Some(Box::new(mime_content)),
+                ..Default::default()
+            })],
+            message_disposition: Some(MessageDisposition::SaveOnly),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn envelope_cdata_content() -> Result<(), Error> {
+        let body = Body::html(r#"<p class="note">Fish &amp; chips</p><!-- a]]>b -->"#);
+        let mime_content = MimeContent {
+            character_set: Some("UTF-8".to_string()),
+            content: "U3ViamVjdDogSGkNCg0K".to_string(),
+        };
+
+        let mut envelope = Envelope::new(create_message(body, mime_content));
+        let escaped = String::from_utf8(envelope.as_xml_document()?).unwrap();
+        assert!(escaped.contains(
+            r#"<t:Body BodyType="HTML">&lt;p class=&quot;note&quot;&gt;Fish &amp;amp; chips&lt;/p&gt;&lt;!-- a]]&gt;b --&gt;</t:Body>"#
+        ));
+
+        envelope.content_format = ContentFormat::CData;
+        let cdata = String::from_utf8(envelope.as_xml_document()?).unwrap();
+        assert!(cdata.contains(
+            r#"<t:MimeContent CharacterSet="UTF-8"><![CDATA[U3ViamVjdDogSGkNCg0K]]></t:MimeContent>"#
+        ));
+        assert!(cdata.contains(
+            r#"<t:Body BodyType="HTML"><![CDATA[<p class="note">Fish &amp; chips</p><!-- a]]]]><![CDATA[>b -->]]></t:Body>"#
+        ));
+        assert!(cdata.starts_with(r#"<?xml version="1.0" encoding="utf-8"?><soap:Envelope"#));
+        assert!(cdata.contains("<soap:Body><CreateItem"));
+
+        // The pretty-printed document shows the content as it is sent.
+        assert!(envelope
+            .to_pretty_xml()?
+            .contains("<![CDATA[U3ViamVjdDogSGkNCg0K]]>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn cdata_content_roundtrip() -> Result<(), Error> {
+        let body = Body::html(r#"<p>a]]>b</p>"#);
+        assert_eq!(roundtrip(&body, "Body")?, body);
+
+        let document = br#"<Body BodyType="HTML"><![CDATA[<p>a]]]]><![CDATA[>b</p>]]></Body>"#;
+        let deserialized: Body = quick_xml::de::from_reader(&document[..]).unwrap();
+        assert_eq!(deserialized, body);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Matching of `cid:` URLs in HTML bodies against the content IDs of
+//! attachments.
+//!
+//! See <https://www.rfc-editor.org/rfc/rfc2392>
+
+/// The scheme of URLs referencing a body part by its content ID.
+const CID_SCHEME: &str = "cid:";
+
+/// Finds the content IDs referenced by `cid:` URLs in an HTML document, in
+/// order of first appearance and without the scheme.
+pub(super) fn references(html: &str) -> Vec<&str> {
+    let mut references: Vec<&str> = Vec::new();
+    let bytes = html.as_bytes();
+
+    let mut position = 0;
+    while let Some(offset) = find_scheme(&bytes[position..]) {
+        let start = position + offset + CID_SCHEME.len();
+
+        // Skip e.g. `acid:`, which isn't a URL with the `cid` scheme.
+        let preceded_by_word = (position + offset)
+            .checked_sub(1)
+            .is_some_and(|index| bytes[index].is_ascii_alphanumeric());
+
+        let length = bytes[start..]
+            .iter()
+            .position(|byte| {
+                byte.is_ascii_whitespace() || matches!(byte, b'"' | b'\'' | b'<' | b'>' | b')')
+            })
+            .unwrap_or(bytes.len() - start);
+        position = start + length;
+
+        let reference = &html[start..position];
+        if !preceded_by_word && !reference.is_empty() && !references.contains(&reference) {
+            references.push(reference);
+        }
+    }
+
+    references
+}
+
+/// Finds the next occurrence of the `cid:` scheme, ignoring case.
+fn find_scheme(bytes: &[u8]) -> Option<usize> {
+    bytes
+        .windows(CID_SCHEME.len())
+        .position(|window| window.eq_ignore_ascii_case(CID_SCHEME.as_bytes()))
+}
+
+/// Normalizes a content ID for comparison, removing the angle brackets of
+/// the `Content-ID` header and the percent-encoding of `cid:` URLs.
+pub(super) fn normalize(content_id: &str) -> String {
+    let content_id = content_id.trim();
+    let content_id = content_id
+        .strip_prefix('<')
+        .and_then(|content_id| content_id.strip_suffix('>'))
+        .unwrap_or(content_id);
+
+    percent_decode(content_id).to_ascii_lowercase()
+}
+
+/// Gets the part of a normalized content ID before its `@domain` suffix.
+///
+/// Clients disagree on whether the suffix is kept when converting between
+/// `Content-ID` headers and `cid:` URLs, so IDs which are otherwise equal
+/// should still be matched.
+pub(super) fn local_part(content_id: &str) -> &str {
+    content_id
+        .split_once('@')
+        .map_or(content_id, |(local, _)| local)
+}
+
+/// Decodes `%XX` escapes, leaving invalid escapes unchanged.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| bytes.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{local_part, normalize, references};
+
+    #[test]
+    fn find_and_normalize_references() {
+        let html = concat!(
+            r#"<img src="cid:image001.png@01DA9B2C.5E3F1A20"><img src='CID:logo%20small.gif'>"#,
+            r#"<div style="background: url(cid:bg)">acid:test cid:image001.png@01DA9B2C.5E3F1A20</div>"#,
+        );
+
+        assert_eq!(
+            references(html),
+            ["image001.png@01DA9B2C.5E3F1A20", "logo%20small.gif", "bg"]
+        );
+        assert_eq!(normalize(" <Logo%20Small.gif> "), "logo small.gif");
+        assert_eq!(normalize("100%"), "100%");
+        assert_eq!(local_part("image001.png@01da9b2c.5e3f1a20"), "image001.png");
+        assert_eq!(local_part("image001.png"), "image001.png");
+    }
+}
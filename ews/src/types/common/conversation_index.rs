@@ -0,0 +1,396 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+use crate::{DateTime, Error, Message};
+
+/// The length of the header block of a conversation index.
+const HEADER_LEN: usize = 22;
+
+/// The length of each response block following the header.
+const RESPONSE_LEN: usize = 5;
+
+/// The number of 100-nanosecond intervals between the `FILETIME` epoch,
+/// 1601-01-01, and the Unix epoch.
+const FILETIME_UNIX_EPOCH: i128 = 116_444_736_000_000_000;
+
+/// The position of a message within a conversation, as set by Outlook and
+/// Exchange in the `ConversationIndex` of messages.
+///
+/// The index starts with a header identifying the conversation and the time
+/// it started, followed by one block per reply leading to the message. A
+/// reply's index is the index of the message it replies to with a block
+/// appended, so messages can be arranged in a tree without parsing Internet
+/// headers, see [`thread_messages`].
+///
+/// See <https://learn.microsoft.com/en-us/office/client-developer/outlook/mapi/tracking-conversations>
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConversationIndex {
+    bytes: Vec<u8>,
+}
+
+/// A block of a [`ConversationIndex`] recording a reply within the
+/// conversation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversationResponse {
+    /// The approximate time of the reply.
+    pub time: DateTime,
+
+    /// Random bits distinguishing replies made at the same time.
+    pub random: u8,
+
+    /// A sequence number distinguishing replies made at the same time.
+    pub sequence: u8,
+}
+
+impl ConversationIndex {
+    /// Decodes a conversation index from its base64 representation, as
+    /// returned by EWS.
+    pub fn decode(value: &str) -> Result<Self, Error> {
+        let bytes = BASE64_STANDARD.decode(value).map_err(|err| {
+            Error::InvalidConversationIndex(format!("index is not valid base64: {err}"))
+        })?;
+
+        Self::from_bytes(bytes)
+    }
+
+    /// Creates a conversation index from its binary representation.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LEN || !(bytes.len() - HEADER_LEN).is_multiple_of(RESPONSE_LEN) {
+            return Err(Error::InvalidConversationIndex(format!(
+                "index has unexpected length {}",
+                bytes.len()
+            )));
+        }
+
+        let index = Self { bytes };
+
+        // Check that every time can be represented, so that accessors don't
+        // need to fail.
+        index
+            .responses_with_ticks()
+            .try_for_each(|(ticks, _)| filetime_to_date_time(ticks).map(|_| ()))?;
+        filetime_to_date_time(index.header_ticks())?;
+
+        Ok(index)
+    }
+
+    /// Gets the binary representation of the index.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Gets the time at which the conversation started, with a precision of
+    /// about 6.5 milliseconds.
+    pub fn time(&self) -> DateTime {
+        filetime_to_date_time(self.header_ticks()).expect("time should have been checked")
+    }
+
+    /// Gets the GUID identifying the conversation, as stored.
+    pub fn guid(&self) -> [u8; 16] {
+        self.bytes[6..HEADER_LEN]
+            .try_into()
+            .expect("header should contain a GUID")
+    }
+
+    /// Gets the replies leading from the start of the conversation to the
+    /// message, in order.
+    pub fn responses(&self) -> Vec<ConversationResponse> {
+        self.responses_with_ticks()
+            .map(|(ticks, (random, sequence))| ConversationResponse {
+                time: filetime_to_date_time(ticks).expect("time should have been checked"),
+                random,
+                sequence,
+            })
+            .collect()
+    }
+
+    /// Gets the number of replies leading from the start of the conversation
+    /// to the message, i.e. zero for the message starting it.
+    pub fn depth(&self) -> usize {
+        (self.bytes.len() - HEADER_LEN) / RESPONSE_LEN
+    }
+
+    /// Gets the index of the message this message replies to, if any.
+    pub fn parent(&self) -> Option<ConversationIndex> {
+        (self.depth() > 0).then(|| Self {
+            bytes: self.bytes[..self.bytes.len() - RESPONSE_LEN].to_vec(),
+        })
+    }
+
+    /// Determines whether this message is in the same conversation as
+    /// another and precedes it in the chain of replies leading to it.
+    pub fn is_ancestor_of(&self, other: &ConversationIndex) -> bool {
+        self.bytes.len() < other.bytes.len() && other.bytes.starts_with(&self.bytes)
+    }
+
+    /// Gets the time at which the conversation started, as a `FILETIME`.
+    ///
+    /// The header holds the upper 48 bits of the time.
+    fn header_ticks(&self) -> u64 {
+        let mut ticks = [0; 8];
+        ticks[..6].copy_from_slice(&self.bytes[..6]);
+
+        u64::from_be_bytes(ticks)
+    }
+
+    /// Iterates over the response blocks, along with the `FILETIME` of each
+    /// reply, which is given as a delta from the previous one.
+    fn responses_with_ticks(&self) -> impl Iterator<Item = (u64, (u8, u8))> + '_ {
+        self.bytes[HEADER_LEN..].chunks_exact(RESPONSE_LEN).scan(
+            self.header_ticks(),
+            |ticks, block| {
+                let mut value = [0; 8];
+                value[3..].copy_from_slice(block);
+                let value = u64::from_be_bytes(value);
+
+                // The first bit selects the resolution of the delta in the
+                // following 31 bits.
+                let delta = (value >> 8) & 0x7FFF_FFFF;
+                let delta = if value & (1 << 39) == 0 {
+                    delta << 18
+                } else {
+                    delta << 23
+                };
+                *ticks = ticks.saturating_add(delta);
+
+                let random = ((value >> 4) & 0xF) as u8;
+                let sequence = (value & 0xF) as u8;
+
+                Some((*ticks, (random, sequence)))
+            },
+        )
+    }
+}
+
+/// Converts a `FILETIME`, i.e. a number of 100-nanosecond intervals since
+/// 1601-01-01, to a time.
+fn filetime_to_date_time(ticks: u64) -> Result<DateTime, Error> {
+    let nanoseconds = (i128::from(ticks) - FILETIME_UNIX_EPOCH) * 100;
+
+    time::OffsetDateTime::from_unix_timestamp_nanos(nanoseconds)
+        .map(DateTime)
+        .map_err(|_| Error::InvalidConversationIndex("time is out of range".to_string()))
+}
+
+impl Message {
+    /// Decodes the conversation index of the message, if it has one.
+    pub fn parse_conversation_index(&self) -> Result<Option<ConversationIndex>, Error> {
+        self.conversation_index
+            .as_deref()
+            .map(ConversationIndex::decode)
+            .transpose()
+    }
+}
+
+/// A message in a tree of replies built by [`thread_messages`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversationNode<'a> {
+    /// The message.
+    pub message: &'a Message,
+
+    /// The replies to the message, in the order they were made.
+    pub replies: Vec<ConversationNode<'a>>,
+}
+
+/// Arranges messages into trees of replies using their conversation indexes.
+///
+/// Each message is placed under the closest message among those given which
+/// precedes it in its conversation, so that replies to messages which are
+/// missing, e.g. because they were deleted, are still placed in the right
+/// conversation. Messages which don't reply to any of those given are
+/// returned as roots.
+///
+/// Roots and replies are ordered by the time recorded in their index.
+/// Messages without a valid conversation index are returned as roots after
+/// all others, in the order they were given.
+pub fn thread_messages(messages: &[Message]) -> Vec<ConversationNode<'_>> {
+    let indexes: Vec<Option<ConversationIndex>> = messages
+        .iter()
+        .map(|message| message.parse_conversation_index().ok().flatten())
+        .collect();
+
+    // The first message with a given index represents it, so that messages
+    // sharing an index are threaded as siblings.
+    let mut positions: HashMap<&[u8], usize> = HashMap::new();
+    for (position, index) in indexes.iter().enumerate() {
+        if let Some(index) = index {
+            positions.entry(index.as_bytes()).or_insert(position);
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut unindexed = Vec::new();
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); messages.len()];
+    for (position, index) in indexes.iter().enumerate() {
+        let Some(index) = index else {
+            unindexed.push(position);
+            continue;
+        };
+
+        let bytes = index.as_bytes();
+        let parent = (1..=index.depth())
+            .map(|ancestors| &bytes[..bytes.len() - ancestors * RESPONSE_LEN])
+            .find_map(|ancestor| positions.get(ancestor).copied());
+
+        match parent {
+            Some(parent) => children[parent].push(position),
+            None => roots.push(position),
+        }
+    }
+
+    let sort_key = |position: &usize| {
+        let index = indexes[*position]
+            .as_ref()
+            .expect("threaded messages should have an index");
+        let time = match index.responses().pop() {
+            Some(response) => response.time,
+            None => index.time(),
+        };
+
+        (time.0, *position)
+    };
+
+    fn build<'a>(
+        position: usize,
+        messages: &'a [Message],
+        children: &mut [Vec<usize>],
+    ) -> ConversationNode<'a> {
+        let replies = std::mem::take(&mut children[position]);
+
+        ConversationNode {
+            message: &messages[position],
+            replies: replies
+                .into_iter()
+                .map(|reply| build(reply, messages, children))
+                .collect(),
+        }
+    }
+
+    roots.sort_by_key(sort_key);
+    for replies in &mut children {
+        replies.sort_by_key(sort_key);
+    }
+
+    roots
+        .into_iter()
+        .chain(unindexed)
+        .map(|position| build(position, messages, &mut children))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+    use crate::{Error, Message};
+
+    use super::{thread_messages, ConversationIndex, ConversationNode};
+
+    /// The header of a conversation started at 2024-01-01T00:00:00Z, which
+    /// is truncated to 2023-12-31T23:59:59.995Z.
+    const HEADER: [u8; 22] = [
+        0x01, 0xDA, 0x3C, 0x45, 0x76, 0x89, // FILETIME 0x01DA3C4576890000
+        0x5A, 0x1F, 0x0C, 0x3E, 0x9B, 0x21, 0x4D, 0x8A, 0x91, 0x7C, 0x60, 0x2B, 0xE4, 0x13, 0x58,
+        0x6D,
+    ];
+
+    fn index(responses: &[[u8; 5]]) -> String {
+        let mut bytes = HEADER.to_vec();
+        for response in responses {
+            bytes.extend(response);
+        }
+
+        BASE64_STANDARD.encode(bytes)
+    }
+
+    fn message(subject: &str, conversation_index: Option<String>) -> Message {
+        Message {
+            subject: Some(subject.to_string()),
+            conversation_index,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decode_conversation_index() -> Result<(), Error> {
+        // A reply about a second after the start of the conversation, with a
+        // delta in units of 2^18 ticks, and another about 79 seconds later,
+        // with a delta in units of 2^23 ticks.
+        let index = ConversationIndex::decode(&index(&[
+            [0x00, 0x00, 0x00, 0x26, 0x1A],
+            [0x80, 0x00, 0x00, 0x5E, 0x03],
+        ]))?;
+
+        assert_eq!(index.depth(), 2);
+        assert_eq!(index.guid()[..2], [0x5A, 0x1F]);
+
+        let start = index.time().0;
+        assert_eq!(start.unix_timestamp(), 1_704_067_199);
+
+        let responses = index.responses();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(
+            (responses[0].time.0 - start).whole_nanoseconds(),
+            0x26 * (1 << 18) * 100
+        );
+        assert_eq!((responses[0].random, responses[0].sequence), (1, 0xA));
+        assert_eq!(
+            (responses[1].time.0 - responses[0].time.0).whole_nanoseconds(),
+            0x5E * (1 << 23) * 100
+        );
+
+        let parent = index.parent().expect("reply should have a parent");
+        assert!(parent.is_ancestor_of(&index));
+        assert!(!index.is_ancestor_of(&parent));
+        assert_eq!(parent.parent().and_then(|root| root.parent()), None);
+
+        assert!(matches!(
+            ConversationIndex::decode(&BASE64_STANDARD.encode([0x01; 25])),
+            Err(Error::InvalidConversationIndex(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn thread_messages_by_conversation_index() {
+        let late_reply = [0x00, 0x00, 0x01, 0x00, 0x00];
+        let early_reply = [0x00, 0x00, 0x00, 0x10, 0x00];
+        let messages = vec![
+            message("Re: Re: Lunch", Some(index(&[late_reply, early_reply]))),
+            message("Unrelated", None),
+            message("Re: Lunch (late)", Some(index(&[late_reply]))),
+            message("Lunch", Some(index(&[]))),
+            message("Re: Lunch (early)", Some(index(&[early_reply]))),
+            // Replies to a missing message are placed under its parent.
+            message(
+                "Re: Re: Lunch (missing parent)",
+                Some(index(&[early_reply, late_reply, late_reply])),
+            ),
+        ];
+
+        let threads = thread_messages(&messages);
+        fn subjects<'a>(nodes: &[ConversationNode<'a>]) -> Vec<&'a str> {
+            nodes
+                .iter()
+                .map(|node| node.message.subject.as_deref().unwrap())
+                .collect()
+        }
+
+        assert_eq!(subjects(&threads), ["Lunch", "Unrelated"]);
+        assert_eq!(
+            subjects(&threads[0].replies),
+            ["Re: Lunch (early)", "Re: Lunch (late)"]
+        );
+        assert_eq!(
+            subjects(&threads[0].replies[0].replies),
+            ["Re: Re: Lunch (missing parent)"]
+        );
+        assert_eq!(subjects(&threads[0].replies[1].replies), ["Re: Re: Lunch"]);
+    }
+}
@@ -0,0 +1,255 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use quick_xml::{
+    events::{BytesText, Event},
+    Reader, Writer,
+};
+use time::{
+    format_description::well_known::{
+        iso8601::{Config, EncodedConfig, TimePrecision},
+        Iso8601,
+    },
+    OffsetDateTime, UtcOffset,
+};
+
+use crate::Error;
+
+/// An ISO 8601 configuration which omits fractional seconds.
+const WITHOUT_SUBSECONDS: EncodedConfig = Config::DEFAULT
+    .set_time_precision(TimePrecision::Second {
+        decimal_digits: None,
+    })
+    .encode();
+
+/// How [`DateTime`] values are written when serializing requests.
+///
+/// By default, times are written with nanosecond precision in the offset
+/// they were created with, e.g. `2024-05-01T08:30:00.000000000Z`. Some
+/// Exchange Server builds reject fractional seconds or numeric offsets such
+/// as `+00:00`, and can be accommodated by changing the format used for the
+/// requests sent to them.
+///
+/// The format of a request is set with [`Envelope::date_time_format`]. Times
+/// are written in the default format and rewritten when the envelope is
+/// serialized, so any text in a request which is a time in the default
+/// format, e.g. the value of an extended property, is rewritten as well.
+///
+/// [`DateTime`]: crate::DateTime
+/// [`Envelope::date_time_format`]: crate::soap::Envelope::date_time_format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DateTimeFormat {
+    /// The precision with which seconds are written.
+    pub precision: DateTimePrecision,
+
+    /// The offset in which times are written.
+    pub offset: DateTimeOffset,
+}
+
+/// The precision with which a [`DateTimeFormat`] writes seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateTimePrecision {
+    /// Seconds are written with nine decimal digits.
+    #[default]
+    Nanoseconds,
+
+    /// Fractional seconds are omitted.
+    Seconds,
+}
+
+/// The offset in which a [`DateTimeFormat`] writes times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateTimeOffset {
+    /// Times are written in the offset they were created with.
+    #[default]
+    Preserve,
+
+    /// Times are converted to UTC, which is written as `Z`.
+    Utc,
+
+    /// Times are converted to the given offset, e.g. the local offset of the
+    /// server.
+    Fixed(UtcOffset),
+}
+
+impl DateTimeFormat {
+    /// Creates the default format.
+    pub const fn new() -> Self {
+        Self {
+            precision: DateTimePrecision::Nanoseconds,
+            offset: DateTimeOffset::Preserve,
+        }
+    }
+
+    /// Omits fractional seconds.
+    pub const fn without_subseconds(mut self) -> Self {
+        self.precision = DateTimePrecision::Seconds;
+        self
+    }
+
+    /// Converts times to UTC, written as `Z`.
+    pub const fn utc(mut self) -> Self {
+        self.offset = DateTimeOffset::Utc;
+        self
+    }
+
+    /// Converts times to the given offset.
+    pub const fn with_offset(mut self, offset: UtcOffset) -> Self {
+        self.offset = DateTimeOffset::Fixed(offset);
+        self
+    }
+
+    /// Formats the given time as an ISO 8601-compliant string.
+    pub fn format(&self, time: OffsetDateTime) -> Result<String, time::error::Format> {
+        let time = match self.offset {
+            DateTimeOffset::Preserve => time,
+            DateTimeOffset::Utc => time.to_offset(UtcOffset::UTC),
+            DateTimeOffset::Fixed(offset) => time.to_offset(offset),
+        };
+
+        let mut formatted = time.format(&Iso8601::<WITHOUT_SUBSECONDS>)?;
+        if self.precision == DateTimePrecision::Nanoseconds {
+            // The time crate writes fractional seconds by way of a float,
+            // which can be off by a nanosecond, so write them ourselves.
+            let end_of_seconds = formatted
+                .find('T')
+                .map_or(formatted.len(), |start| start + "THH:MM:SS".len());
+            formatted.insert_str(end_of_seconds, &format!(".{:09}", time.nanosecond()));
+        }
+
+        Ok(formatted)
+    }
+
+    /// Rewrites the times in a serialized document, written in the default
+    /// format, in this format.
+    pub(crate) fn apply(self, document: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if self == Self::new() {
+            return Ok(document);
+        }
+
+        let mut reader = Reader::from_reader(document.as_slice());
+        let mut writer = Writer::new(Vec::with_capacity(document.len()));
+
+        loop {
+            match reader.read_event()? {
+                Event::Eof => break,
+                Event::Text(text) => match parse_default_format(&text.unescape()?) {
+                    Some(time) => {
+                        let formatted = self
+                            .format(time)
+                            .map_err(|err| xml_struct::Error::Value(err.into()))?;
+                        writer.write_event(Event::Text(BytesText::new(&formatted)))?;
+                    }
+                    None => writer.write_event(Event::Text(text))?,
+                },
+                event => writer.write_event(event)?,
+            }
+        }
+
+        Ok(writer.into_inner())
+    }
+}
+
+/// Parses text which is exactly a time written in the default format.
+fn parse_default_format(text: &str) -> Option<OffsetDateTime> {
+    // Times in the default format have a fixed length, which rules out most
+    // text without parsing it.
+    if !matches!(text.len(), 30 | 35) {
+        return None;
+    }
+
+    let time = OffsetDateTime::parse(text, &Iso8601::DEFAULT).ok()?;
+    let formatted = DateTimeFormat::new().format(time).ok()?;
+
+    (formatted == text).then_some(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Duration, OffsetDateTime, UtcOffset};
+
+    use crate::{
+        create_item::CreateItem, soap::Envelope, DateTime, Error, ExtendedFieldURI,
+        ExtendedProperty, PropertyType, RealItem, Task,
+    };
+
+    use super::DateTimeFormat;
+
+    #[test]
+    fn test_date_time_formats() {
+        let time = (OffsetDateTime::from_unix_timestamp(1_714_552_200).unwrap()
+            + Duration::milliseconds(250))
+        .to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+
+        let cases = [
+            (DateTimeFormat::new(), "2024-05-01T10:30:00.250000000+02:00"),
+            (
+                DateTimeFormat::new().without_subseconds(),
+                "2024-05-01T10:30:00+02:00",
+            ),
+            (
+                DateTimeFormat::new().utc(),
+                "2024-05-01T08:30:00.250000000Z",
+            ),
+            (
+                DateTimeFormat::new().without_subseconds().utc(),
+                "2024-05-01T08:30:00Z",
+            ),
+            (
+                DateTimeFormat::new().with_offset(UtcOffset::from_hms(-5, 0, 0).unwrap()),
+                "2024-05-01T03:30:00.250000000-05:00",
+            ),
+        ];
+
+        for (format, expected) in cases {
+            assert_eq!(format.format(time).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_exact_nanoseconds() {
+        // Formatting fractional seconds by way of a float writes this time as
+        // `.001971830`.
+        let time = OffsetDateTime::from_unix_timestamp(1_714_552_200)
+            .unwrap()
+            .replace_nanosecond(1_971_831)
+            .unwrap();
+
+        assert_eq!(
+            DateTimeFormat::new().format(time).unwrap(),
+            "2024-05-01T08:30:00.001971831Z"
+        );
+    }
+
+    #[test]
+    fn test_envelope_date_time_format() -> Result<(), Error> {
+        let due = OffsetDateTime::from_unix_timestamp(1_717_200_000)
+            .unwrap()
+            .to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+        let operation = CreateItem::builder(vec![RealItem::Task(Task {
+            due_date: Some(DateTime(due)),
+            extended_property: Some(vec![ExtendedProperty {
+                extended_field_URI: ExtendedFieldURI::by_tag(0x0E06, PropertyType::SystemTime)
+                    .unwrap(),
+                value: "2024-06-01T02:00:00.000000000+02:00".to_string(),
+            }]),
+            ..Task::new("2024-06-01T02:00:00.000+02:00")
+        })])
+        .build();
+
+        let mut envelope = Envelope::new(operation);
+        let document = String::from_utf8(envelope.as_xml_document()?).unwrap();
+        assert!(document.contains("<t:DueDate>2024-06-01T02:00:00.000000000+02:00</t:DueDate>"));
+
+        envelope.date_time_format = DateTimeFormat::new().without_subseconds().utc();
+        let document = String::from_utf8(envelope.as_xml_document()?).unwrap();
+        assert!(document.contains("<t:DueDate>2024-06-01T00:00:00Z</t:DueDate>"));
+        assert!(document.contains("<t:Value>2024-06-01T00:00:00Z</t:Value>"));
+
+        // Text which isn't a time in the default format is left alone.
+        assert!(document.contains("<t:Subject>2024-06-01T02:00:00.000+02:00</t:Subject>"));
+
+        Ok(())
+    }
+}
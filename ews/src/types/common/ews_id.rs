@@ -0,0 +1,446 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Local decoding of the identifiers returned by EWS.
+//!
+//! EWS identifiers are opaque to clients, but they are the base64 encoding
+//! of a structure naming the mailbox and the MAPI entry ID of the item or
+//! folder. Decoding them locally, rather than with a `ConvertId` request,
+//! allows e.g. recognizing the same item reached through several mailboxes.
+//! The structure is undocumented, so it should only be relied upon for
+//! optimizations which can fall back to a request.
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+use crate::{Error, FolderId, ItemId};
+
+/// The kind of store an EWS identifier refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IdStorageType {
+    /// An item or folder in a mailbox given by its SMTP address.
+    MailboxItemSmtpAddressBased,
+
+    /// A public folder.
+    PublicFolder,
+
+    /// An item in a public folder.
+    PublicFolderItem,
+
+    /// An item or folder in a mailbox given by its GUID.
+    MailboxItemMailboxGuidBased,
+
+    /// A conversation in a mailbox given by its GUID.
+    ConversationIdMailboxGuidBased,
+
+    /// An object in Active Directory, such as a contact.
+    ActiveDirectoryObject,
+}
+
+/// How the server treats the item an EWS identifier refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IdProcessingInstruction {
+    /// The identifier refers to the item itself.
+    Normal,
+
+    /// The identifier refers to an occurrence of a recurring calendar item.
+    Recurrence,
+
+    /// The identifier refers to the series of a recurring calendar item.
+    Series,
+}
+
+/// The decoded structure of an EWS item or folder identifier.
+///
+/// # Example
+///
+/// ```
+/// use ews::EwsId;
+///
+/// # fn main() -> Result<(), ews::Error> {
+/// let id = EwsId::decode(
+///     "AQMkADRiZGNhMWIxLWIwOGMtNDQAZjktODk3OS0zZWIxODJjNmI4NWYALgAAA8ZmIFRjoG9PpiagjztHaIcBAFSUeaisgPtKo3c6hV+VzpcAAAIBCAAAAA==",
+/// )?;
+/// assert_eq!(id.mailbox_guid(), Some("4bdca1b1-b08c-44f9-8979-3eb182c6b85f"));
+///
+/// let entry_id = id.entry_id()?;
+/// assert_eq!(entry_id.folder.global_counter, 0x108);
+/// assert!(entry_id.message.is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EwsId {
+    /// The kind of store the identifier refers to.
+    pub storage_type: IdStorageType,
+
+    /// The mailbox containing the item or folder, as a GUID or SMTP address
+    /// depending on the storage type.
+    pub moniker: String,
+
+    /// How the server treats the item.
+    pub processing_instruction: IdProcessingInstruction,
+
+    /// The identifier of the item or folder within its store, usually a
+    /// MAPI entry ID.
+    pub store_id: Vec<u8>,
+}
+
+impl EwsId {
+    /// Decodes an EWS identifier, e.g. the `Id` attribute of an `ItemId`.
+    ///
+    /// Only identifiers of items, folders and conversations in mailboxes
+    /// can be decoded.
+    pub fn decode(id: &str) -> Result<Self, Error> {
+        let bytes = BASE64_STANDARD
+            .decode(id)
+            .map_err(|err| Error::InvalidId(format!("identifier is not valid base64: {err}")))?;
+
+        let bytes = match bytes.split_first() {
+            Some((0, rest)) => rest.to_vec(),
+            Some((1, rest)) => decompress(rest)?,
+            Some((compression, _)) => {
+                return Err(Error::InvalidId(format!(
+                    "unknown compression type {compression}"
+                )))
+            }
+            None => return Err(Error::InvalidId("identifier is empty".to_string())),
+        };
+
+        let mut reader = Reader(&bytes);
+        let storage_type = match reader.byte()? {
+            0 => IdStorageType::MailboxItemSmtpAddressBased,
+            1 => IdStorageType::PublicFolder,
+            2 => IdStorageType::PublicFolderItem,
+            3 => IdStorageType::MailboxItemMailboxGuidBased,
+            4 => IdStorageType::ConversationIdMailboxGuidBased,
+            5 => IdStorageType::ActiveDirectoryObject,
+            other => return Err(Error::InvalidId(format!("unknown storage type {other}"))),
+        };
+
+        if !matches!(
+            storage_type,
+            IdStorageType::MailboxItemSmtpAddressBased
+                | IdStorageType::MailboxItemMailboxGuidBased
+                | IdStorageType::ConversationIdMailboxGuidBased
+        ) {
+            return Err(Error::InvalidId(format!(
+                "identifiers with storage type {storage_type:?} are not supported"
+            )));
+        }
+
+        let moniker = reader.length_prefixed()?;
+        let moniker = String::from_utf8(moniker.to_vec())
+            .map_err(|_| Error::InvalidId("mailbox moniker is not valid UTF-8".to_string()))?;
+
+        let processing_instruction = match reader.byte()? {
+            0 => IdProcessingInstruction::Normal,
+            1 => IdProcessingInstruction::Recurrence,
+            2 => IdProcessingInstruction::Series,
+            other => {
+                return Err(Error::InvalidId(format!(
+                    "unknown processing instruction {other}"
+                )))
+            }
+        };
+
+        let store_id = reader.length_prefixed()?.to_vec();
+
+        Ok(Self {
+            storage_type,
+            moniker,
+            processing_instruction,
+            store_id,
+        })
+    }
+
+    /// Gets the GUID of the mailbox containing the item or folder, if the
+    /// identifier names the mailbox by GUID.
+    pub fn mailbox_guid(&self) -> Option<&str> {
+        match self.storage_type {
+            IdStorageType::MailboxItemMailboxGuidBased
+            | IdStorageType::ConversationIdMailboxGuidBased => Some(&self.moniker),
+            _ => None,
+        }
+    }
+
+    /// Decodes the store identifier as a MAPI entry ID.
+    ///
+    /// Conversation identifiers don't contain an entry ID.
+    pub fn entry_id(&self) -> Result<EntryId, Error> {
+        EntryId::decode(&self.store_id)
+    }
+}
+
+/// The long-term identifier of a folder or message, which is stable for the
+/// lifetime of the object within its mailbox database.
+///
+/// This is the `LongTermID` structure described in the [MS-OXCDATA]
+/// specification.
+///
+/// [MS-OXCDATA]: https://learn.microsoft.com/en-us/openspecs/exchange_server_protocols/ms-oxcdata
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LongTermId {
+    /// The GUID of the database which created the object, as stored.
+    pub database_guid: [u8; 16],
+
+    /// The counter identifying the object within the database.
+    pub global_counter: u64,
+}
+
+/// A MAPI entry ID of a folder or message in a mailbox.
+///
+/// This is the `Folder EntryID` or `Message EntryID` structure described in
+/// the [MS-OXCDATA] specification.
+///
+/// [MS-OXCDATA]: https://learn.microsoft.com/en-us/openspecs/exchange_server_protocols/ms-oxcdata
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EntryId {
+    /// Flags describing how the entry ID may be used.
+    pub flags: [u8; 4],
+
+    /// The identifier of the store provider, which is specific to the
+    /// mailbox.
+    pub provider_uid: [u8; 16],
+
+    /// The type of the folder or message.
+    pub entry_type: u16,
+
+    /// The folder itself, or the folder containing the message.
+    pub folder: LongTermId,
+
+    /// The message, if the entry ID refers to a message.
+    pub message: Option<LongTermId>,
+}
+
+impl EntryId {
+    /// The length of a folder entry ID.
+    const FOLDER_LEN: usize = 46;
+
+    /// The length of a message entry ID.
+    const MESSAGE_LEN: usize = 70;
+
+    /// Decodes a binary entry ID, e.g. the store identifier of an [`EwsId`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::FOLDER_LEN && bytes.len() != Self::MESSAGE_LEN {
+            return Err(Error::InvalidId(format!(
+                "entry ID has unexpected length {}",
+                bytes.len()
+            )));
+        }
+
+        let mut reader = Reader(bytes);
+        let flags = reader.array()?;
+        let provider_uid = reader.array()?;
+        let entry_type = u16::from_le_bytes(reader.array()?);
+        let folder = reader.long_term_id()?;
+        let message = if reader.0.is_empty() {
+            None
+        } else {
+            Some(reader.long_term_id()?)
+        };
+
+        Ok(Self {
+            flags,
+            provider_uid,
+            entry_type,
+            folder,
+            message,
+        })
+    }
+
+    /// Decodes an entry ID given in hexadecimal, as returned for the
+    /// `HexEntryId` format by `ConvertId` or for `PR_ENTRYID` by Outlook.
+    pub fn decode_hex(hex: &str) -> Result<Self, Error> {
+        if !hex.len().is_multiple_of(2) {
+            return Err(Error::InvalidId(
+                "hexadecimal entry ID has an odd length".to_string(),
+            ));
+        }
+
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| {
+                hex.get(i..i + 2)
+                    .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                    .ok_or_else(|| {
+                        Error::InvalidId("entry ID is not valid hexadecimal".to_string())
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::decode(&bytes)
+    }
+}
+
+impl ItemId {
+    /// Decodes the structure of the identifier locally.
+    ///
+    /// See [`EwsId`] for details.
+    pub fn decode(&self) -> Result<EwsId, Error> {
+        EwsId::decode(&self.id)
+    }
+}
+
+impl FolderId {
+    /// Decodes the structure of the identifier locally.
+    ///
+    /// See [`EwsId`] for details.
+    pub fn decode(&self) -> Result<EwsId, Error> {
+        EwsId::decode(&self.id)
+    }
+}
+
+/// Reverses the run-length encoding of compressed identifiers, in which a
+/// byte appearing twice in a row is followed by the number of further
+/// repetitions.
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decompressed = Vec::with_capacity(bytes.len() * 2);
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        decompressed.push(byte);
+        i += 1;
+
+        if bytes.get(i) == Some(&byte) {
+            let count = *bytes.get(i + 1).ok_or_else(|| {
+                Error::InvalidId("compressed identifier is truncated".to_string())
+            })?;
+            decompressed.extend(std::iter::repeat_n(byte, usize::from(count) + 1));
+            i += 2;
+        }
+    }
+
+    Ok(decompressed)
+}
+
+/// Reads the fields of a decoded identifier in order.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(Error::InvalidId("identifier is truncated".to_string()));
+        }
+
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("slice should have the requested length"))
+    }
+
+    /// Reads a field preceded by its length as a little-endian 16-bit
+    /// integer.
+    fn length_prefixed(&mut self) -> Result<&'a [u8], Error> {
+        let len = u16::from_le_bytes(self.array()?);
+        self.take(usize::from(len))
+    }
+
+    /// Reads a database GUID followed by a 48-bit big-endian counter and two
+    /// bytes of padding.
+    fn long_term_id(&mut self) -> Result<LongTermId, Error> {
+        let database_guid = self.array()?;
+
+        let mut counter = [0; 8];
+        counter[2..].copy_from_slice(self.take(6)?);
+        self.take(2)?;
+
+        Ok(LongTermId {
+            database_guid,
+            global_counter: u64::from_be_bytes(counter),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+    use crate::{Error, ItemId};
+
+    use super::{EntryId, EwsId, IdProcessingInstruction, IdStorageType};
+
+    #[test]
+    fn decode_compressed_folder_id() -> Result<(), Error> {
+        let id = EwsId::decode(
+            "AQMkADRiZGNhMWIxLWIwOGMtNDQAZjktODk3OS0zZWIxODJjNmI4NWYALgAAA8ZmIFRjoG9PpiagjztHaIcBAFSUeaisgPtKo3c6hV+VzpcAAAIBCAAAAA==",
+        )?;
+
+        assert_eq!(id.storage_type, IdStorageType::MailboxItemMailboxGuidBased);
+        assert_eq!(
+            id.mailbox_guid(),
+            Some("4bdca1b1-b08c-44f9-8979-3eb182c6b85f")
+        );
+        assert_eq!(id.processing_instruction, IdProcessingInstruction::Normal);
+        assert_eq!(id.store_id.len(), 46);
+
+        let entry_id = id.entry_id()?;
+        assert_eq!(entry_id.flags, [0; 4]);
+        assert_eq!(entry_id.entry_type, 1);
+        assert_eq!(entry_id.folder.database_guid[..4], [0x54, 0x94, 0x79, 0xa8]);
+        assert_eq!(entry_id.folder.global_counter, 0x108);
+        assert_eq!(entry_id.message, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_message_id() -> Result<(), Error> {
+        let mut entry_id = vec![0, 0, 0, 0];
+        entry_id.extend([0xAA; 16]);
+        entry_id.extend([0x07, 0x00]);
+        entry_id.extend([0x11; 16]);
+        entry_id.extend([0, 0, 0, 0, 0x01, 0x08, 0, 0]);
+        entry_id.extend([0x22; 16]);
+        entry_id.extend([0, 0, 0, 0x12, 0x34, 0x56, 0, 0]);
+
+        let guid = "0d3ad8a6-9d9e-4b94-a0c1-6ba1f8e0c5d2";
+        let mut bytes = vec![0, 3, guid.len() as u8, 0];
+        bytes.extend(guid.as_bytes());
+        bytes.push(1);
+        bytes.extend([entry_id.len() as u8, 0]);
+        bytes.extend(&entry_id);
+
+        let item_id = ItemId::new(BASE64_STANDARD.encode(&bytes));
+        let id = item_id.decode()?;
+        assert_eq!(id.mailbox_guid(), Some(guid));
+        assert_eq!(
+            id.processing_instruction,
+            IdProcessingInstruction::Recurrence
+        );
+
+        let hex: String = entry_id.iter().map(|byte| format!("{byte:02X}")).collect();
+        let decoded = EntryId::decode_hex(&hex)?;
+        assert_eq!(id.entry_id()?, decoded);
+        assert_eq!(decoded.entry_type, 7);
+        assert_eq!(decoded.folder.global_counter, 0x108);
+
+        let message = decoded.message.expect("entry ID should refer to a message");
+        assert_eq!(message.database_guid, [0x22; 16]);
+        assert_eq!(message.global_counter, 0x123456);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_ids() {
+        for id in ["not base64!", "", "AQ==", "AAMkAD", "AAE="] {
+            assert!(
+                matches!(EwsId::decode(id), Err(Error::InvalidId(_))),
+                "{id:?} should be rejected"
+            );
+        }
+    }
+}
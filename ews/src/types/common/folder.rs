@@ -0,0 +1,89 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Deserialization of [`Folder`]s.
+//!
+//! The properties shared by every kind of folder are embedded in each variant
+//! as [`FolderProperties`], but are written as siblings of the properties
+//! specific to the kind of folder. The XML deserializer doesn't support
+//! flattening structs, so each folder is read into an intermediate
+//! representation holding every property, then split up.
+
+use serde::Deserialize;
+
+use crate::{ExtendedProperty, Folder, FolderId, FolderProperties, SearchParameters};
+
+/// A folder as it appears in XML, named after its element.
+#[derive(Deserialize)]
+pub(super) enum FolderElement {
+    CalendarFolder(FolderFields),
+    ContactsFolder(FolderFields),
+    Folder(FolderFields),
+    SearchFolder(FolderFields),
+    TasksFolder(FolderFields),
+}
+
+/// The children of a folder element. Properties which don't apply to the
+/// kind of folder are dropped.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct FolderFields {
+    folder_id: Option<FolderId>,
+    parent_folder_id: Option<FolderId>,
+    folder_class: Option<String>,
+    display_name: Option<String>,
+    total_count: Option<u32>,
+    child_folder_count: Option<u32>,
+    extended_property: Option<Vec<ExtendedProperty>>,
+    unread_count: Option<u32>,
+    search_parameters: Option<Box<SearchParameters>>,
+}
+
+impl FolderFields {
+    /// Splits the fields into the shared properties and the remaining
+    /// fields.
+    fn into_parts(self) -> (FolderProperties, Option<u32>, Option<Box<SearchParameters>>) {
+        let properties = FolderProperties {
+            folder_id: self.folder_id,
+            parent_folder_id: self.parent_folder_id,
+            folder_class: self.folder_class,
+            display_name: self.display_name,
+            total_count: self.total_count,
+            child_folder_count: self.child_folder_count,
+            extended_property: self.extended_property,
+        };
+
+        (properties, self.unread_count, self.search_parameters)
+    }
+}
+
+impl From<FolderElement> for Folder {
+    fn from(element: FolderElement) -> Self {
+        match element {
+            FolderElement::CalendarFolder(fields) => Folder::CalendarFolder {
+                properties: fields.into_parts().0,
+            },
+            FolderElement::ContactsFolder(fields) => Folder::ContactsFolder {
+                properties: fields.into_parts().0,
+            },
+            FolderElement::Folder(fields) => {
+                let (properties, unread_count, _) = fields.into_parts();
+                Folder::Folder {
+                    properties,
+                    unread_count,
+                }
+            }
+            FolderElement::SearchFolder(fields) => {
+                let (properties, _, search_parameters) = fields.into_parts();
+                Folder::SearchFolder {
+                    properties,
+                    search_parameters,
+                }
+            }
+            FolderElement::TasksFolder(fields) => Folder::TasksFolder {
+                properties: fields.into_parts().0,
+            },
+        }
+    }
+}
@@ -0,0 +1,405 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+
+use crate::{
+    find_folder::FindFolder,
+    get_folder::{GetFolder, GetFolderResponse},
+    BaseFolderId, BaseShape, DistinguishedFolderName, Folder, FolderShape, FolderTraversal,
+    IndexedPaging, Paging, ResponseClass,
+};
+
+/// The distinguished folders identified by default, which every version of
+/// Exchange supported by EWS knows.
+const DEFAULT_DISTINGUISHED_FOLDERS: &[DistinguishedFolderName] = &[
+    DistinguishedFolderName::Root,
+    DistinguishedFolderName::MsgFolderRoot,
+    DistinguishedFolderName::Inbox,
+    DistinguishedFolderName::Drafts,
+    DistinguishedFolderName::SentItems,
+    DistinguishedFolderName::DeletedItems,
+    DistinguishedFolderName::Outbox,
+    DistinguishedFolderName::JunkEmail,
+    DistinguishedFolderName::Calendar,
+    DistinguishedFolderName::Contacts,
+    DistinguishedFolderName::Tasks,
+    DistinguishedFolderName::Notes,
+    DistinguishedFolderName::Journal,
+    DistinguishedFolderName::SearchFolders,
+];
+
+/// Retrieves the folders below a folder and assembles them into a
+/// [`FolderTree`].
+///
+/// The folders are found with a paged [`FindFolder`] request with deep
+/// traversal, and the distinguished folders among them, e.g. the Inbox, are
+/// identified with a [`GetFolder`] request for their identifiers. This does
+/// not send requests itself, so that it can be used with any client; the
+/// clients in [`client`] wrap this in a `get_folder_tree` method.
+///
+/// [`client`]: crate::client
+#[derive(Clone, Debug)]
+pub struct FolderHierarchy {
+    root: BaseFolderId,
+    distinguished_folders: Vec<DistinguishedFolderName>,
+}
+
+impl FolderHierarchy {
+    /// Creates a helper for the folders below the given folder, e.g.
+    /// [`BaseFolderId::msg_folder_root`].
+    pub fn new(root: BaseFolderId) -> Self {
+        Self {
+            root,
+            distinguished_folders: DEFAULT_DISTINGUISHED_FOLDERS.to_vec(),
+        }
+    }
+
+    /// Sets the distinguished folders to identify in the tree.
+    ///
+    /// By default, only the common folders known to every version of
+    /// Exchange are identified, as older versions reject requests for names
+    /// they don't know.
+    pub fn with_distinguished_folders(
+        mut self,
+        distinguished_folders: Vec<DistinguishedFolderName>,
+    ) -> Self {
+        self.distinguished_folders = distinguished_folders;
+        self
+    }
+
+    /// Gets the request for the first page of the folders below the root,
+    /// to be retrieved with a [`Pager`].
+    ///
+    /// [`Pager`]: crate::Pager
+    pub fn find_folder(&self) -> FindFolder {
+        FindFolder::builder(
+            FolderTraversal::Deep,
+            FolderShape {
+                base_shape: BaseShape::AllProperties,
+                additional_properties: None,
+            },
+            vec![self.root.clone()],
+        )
+        .paging(Paging::IndexedPageItemView(IndexedPaging::default()))
+        .build()
+    }
+
+    /// Gets the request for the identifiers of the distinguished folders, or
+    /// `None` if no distinguished folders are to be identified.
+    pub fn get_distinguished_folders(&self) -> Option<GetFolder> {
+        if self.distinguished_folders.is_empty() {
+            return None;
+        }
+
+        Some(GetFolder::new(
+            FolderShape {
+                base_shape: BaseShape::IdOnly,
+                additional_properties: None,
+            },
+            self.distinguished_folders
+                .iter()
+                .copied()
+                .map(BaseFolderId::from)
+                .collect(),
+        ))
+    }
+
+    /// Assembles the folders retrieved with the request given by
+    /// [`find_folder`] into a tree, identifying the distinguished folders
+    /// from the response to the request given by
+    /// [`get_distinguished_folders`], if any.
+    ///
+    /// Distinguished folders which the mailbox doesn't have are skipped.
+    ///
+    /// [`find_folder`]: FolderHierarchy::find_folder
+    /// [`get_distinguished_folders`]: FolderHierarchy::get_distinguished_folders
+    pub fn build(
+        &self,
+        folders: Vec<Folder>,
+        distinguished_folders: Option<GetFolderResponse>,
+    ) -> FolderTree {
+        let distinguished_ids = distinguished_folders
+            .map(|response| {
+                self.distinguished_folders
+                    .iter()
+                    .zip(response.response_messages.response_messages)
+                    .filter_map(|(name, message)| match message {
+                        ResponseClass::Success(message) | ResponseClass::Warning(message) => {
+                            let folder = message.folders.inner.into_iter().next()?;
+                            Some((folder.folder_id()?.id.clone(), *name))
+                        }
+                        ResponseClass::Error(_) => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        FolderTree::new(folders, distinguished_ids)
+    }
+}
+
+/// A folder hierarchy, with links between parent and child folders.
+///
+/// Folders are referred to by their index in the tree. Folders whose parent
+/// isn't in the tree, e.g. the children of the folder whose hierarchy was
+/// retrieved, are the roots of the tree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FolderTree {
+    nodes: Vec<FolderNode>,
+    roots: Vec<usize>,
+}
+
+/// A folder in a [`FolderTree`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FolderNode {
+    /// The folder.
+    pub folder: Folder,
+
+    /// The name of the folder if it is a distinguished folder, e.g. the
+    /// Inbox.
+    pub distinguished: Option<DistinguishedFolderName>,
+
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+impl FolderNode {
+    /// Gets the index of the folder's parent, or `None` if it is a root of
+    /// the tree.
+    pub fn parent(&self) -> Option<usize> {
+        self.parent
+    }
+
+    /// Gets the indices of the folder's children, in the order in which they
+    /// were retrieved.
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+}
+
+impl FolderTree {
+    /// Assembles folders into a tree, tagging those whose identifiers are
+    /// mapped to the name of a distinguished folder.
+    ///
+    /// Folders without an identifier can't be linked to their children, so
+    /// are skipped, as are folders with the same identifier as an earlier
+    /// one.
+    pub fn new(
+        folders: Vec<Folder>,
+        distinguished_ids: HashMap<String, DistinguishedFolderName>,
+    ) -> Self {
+        let mut nodes = Vec::with_capacity(folders.len());
+        let mut indices = HashMap::with_capacity(folders.len());
+        for folder in folders {
+            let Some(id) = folder.folder_id().map(|id| id.id.clone()) else {
+                continue;
+            };
+            if indices.contains_key(&id) {
+                continue;
+            }
+
+            indices.insert(id.clone(), nodes.len());
+            nodes.push(FolderNode {
+                distinguished: distinguished_ids.get(&id).copied(),
+                folder,
+                parent: None,
+                children: Vec::new(),
+            });
+        }
+
+        let mut roots = Vec::new();
+        for index in 0..nodes.len() {
+            let parent = nodes[index]
+                .folder
+                .parent_folder_id()
+                .and_then(|parent_id| indices.get(&parent_id.id).copied())
+                .filter(|&parent| parent != index);
+
+            match parent {
+                Some(parent) => {
+                    nodes[index].parent = Some(parent);
+                    nodes[parent].children.push(index);
+                }
+                None => roots.push(index),
+            }
+        }
+
+        Self { nodes, roots }
+    }
+
+    /// Gets the folders in the tree, in the order in which they were
+    /// retrieved.
+    pub fn nodes(&self) -> &[FolderNode] {
+        &self.nodes
+    }
+
+    /// Gets the folder with the given index.
+    pub fn node(&self, index: usize) -> Option<&FolderNode> {
+        self.nodes.get(index)
+    }
+
+    /// Gets the indices of the folders whose parent isn't in the tree.
+    pub fn roots(&self) -> &[usize] {
+        &self.roots
+    }
+
+    /// Finds the index of the folder with the given identifier.
+    pub fn find(&self, id: &str) -> Option<usize> {
+        self.nodes.iter().position(|node| {
+            node.folder
+                .folder_id()
+                .is_some_and(|folder_id| folder_id.id == id)
+        })
+    }
+
+    /// Finds the index of the given distinguished folder.
+    pub fn find_distinguished(&self, name: DistinguishedFolderName) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.distinguished == Some(name))
+    }
+
+    /// Gets the display names of the folders from a root of the tree down to
+    /// the folder with the given index.
+    pub fn path(&self, index: usize) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut current = self.nodes.get(index);
+        while let Some(node) = current {
+            path.push(node.folder.display_name().unwrap_or_default());
+
+            // Guard against cycles in malformed hierarchies.
+            if path.len() > self.nodes.len() {
+                break;
+            }
+            current = node.parent.and_then(|parent| self.nodes.get(parent));
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// Gets the number of folders in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Determines whether the tree has no folders.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        get_folder::{GetFolderResponse, GetFolderResponseMessage},
+        response::ResponseCode,
+        BaseFolderId, DistinguishedFolderName, Folder, FolderId, FolderProperties, FolderTraversal,
+        Folders, ResponseClass, ResponseError, ResponseMessages,
+    };
+
+    use super::FolderHierarchy;
+
+    fn folder_id(id: &str) -> FolderId {
+        FolderId {
+            id: id.to_string(),
+            change_key: None,
+        }
+    }
+
+    fn folder(id: &str, parent_id: &str, display_name: &str) -> Folder {
+        Folder::Folder {
+            properties: FolderProperties {
+                folder_id: Some(folder_id(id)),
+                parent_folder_id: Some(folder_id(parent_id)),
+                folder_class: Some("IPF.Note".to_string()),
+                display_name: Some(display_name.to_string()),
+                total_count: None,
+                child_folder_count: None,
+                extended_property: None,
+            },
+            unread_count: None,
+        }
+    }
+
+    #[test]
+    fn build_folder_tree() {
+        let hierarchy = FolderHierarchy::new(BaseFolderId::msg_folder_root())
+            .with_distinguished_folders(vec![
+                DistinguishedFolderName::Inbox,
+                DistinguishedFolderName::ArchiveInbox,
+                DistinguishedFolderName::SentItems,
+            ]);
+
+        let find_folder = hierarchy.find_folder();
+        assert!(matches!(find_folder.traversal, FolderTraversal::Deep));
+        assert!(find_folder.paging.is_some());
+
+        // The hierarchy is returned in no particular order, so children may
+        // precede their parents.
+        let folders = vec![
+            folder("projects-a", "projects", "Project A"),
+            folder("inbox", "root", "Inbox"),
+            folder("projects", "inbox", "Projects"),
+            folder("sent", "root", "Sent Items"),
+        ];
+
+        let found = |id: &str| {
+            ResponseClass::Success(GetFolderResponseMessage {
+                folders: Folders {
+                    inner: vec![Folder::Folder {
+                        properties: FolderProperties {
+                            folder_id: Some(folder_id(id)),
+                            parent_folder_id: None,
+                            folder_class: None,
+                            display_name: None,
+                            total_count: None,
+                            child_folder_count: None,
+                            extended_property: None,
+                        },
+                        unread_count: None,
+                    }],
+                },
+            })
+        };
+        let distinguished_folders = GetFolderResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![
+                    found("inbox"),
+                    ResponseClass::Error(ResponseError {
+                        message_text: "The specified folder could not be found in the store."
+                            .to_string(),
+                        response_code: ResponseCode::ErrorFolderNotFound,
+                        message_xml: None,
+                    }),
+                    found("sent"),
+                ],
+            },
+        };
+
+        let tree = hierarchy.build(folders, Some(distinguished_folders));
+        assert_eq!(tree.len(), 4);
+
+        let inbox = tree
+            .find_distinguished(DistinguishedFolderName::Inbox)
+            .expect("inbox should be tagged");
+        let sent = tree
+            .find_distinguished(DistinguishedFolderName::SentItems)
+            .expect("sent items should be tagged");
+        assert_eq!(tree.roots(), &[inbox, sent]);
+
+        let project = tree.find("projects-a").expect("folder should be found");
+        assert_eq!(tree.path(project), vec!["Inbox", "Projects", "Project A"]);
+        assert!(tree.nodes()[project].children().is_empty());
+
+        let projects = tree.find("projects").expect("folder should be found");
+        assert_eq!(tree.nodes()[inbox].children(), &[projects]);
+        assert_eq!(tree.nodes()[projects].parent(), Some(inbox));
+        assert_eq!(
+            tree.find_distinguished(DistinguishedFolderName::ArchiveInbox),
+            None
+        );
+    }
+}
@@ -0,0 +1,359 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+use crate::{
+    properties::{PID_LID_CLEAN_GLOBAL_OBJECT_ID, PID_LID_GLOBAL_OBJECT_ID},
+    Error, ItemLike, ItemShape, Message, PathToElement,
+};
+
+/// The identifier with which every global object ID starts.
+const CLASS_ID: [u8; 16] = [
+    0x04, 0x00, 0x00, 0x00, 0x82, 0x00, 0xE0, 0x00, 0x74, 0xC5, 0xB7, 0x10, 0x1A, 0x82, 0xE0, 0x08,
+];
+
+/// The position of the instance date, which follows the class identifier.
+const INSTANCE_DATE: std::ops::Range<usize> = 16..20;
+
+/// The length of the fixed part of a global object ID, up to and including
+/// the size of the data which follows it.
+const HEADER_LEN: usize = 40;
+
+/// The marker at the start of the data of IDs created from the UID of an
+/// iCalendar object, which is followed by that UID.
+const VCAL_UID_MARKER: &[u8] = b"vCal-Uid\x01\x00\x00\x00";
+
+/// The identifier of a meeting, as set by Outlook and Exchange in the
+/// `PidLidGlobalObjectId` and `PidLidCleanGlobalObjectId` properties of
+/// calendar items and meeting messages.
+///
+/// The ID is shared by all calendar items and meeting messages of a meeting,
+/// except that those of an exception to a recurring meeting also record the
+/// date of the instance they concern. The *clean* ID leaves that date out,
+/// so that it is the same for all instances.
+///
+/// See <https://learn.microsoft.com/en-us/office/client-developer/outlook/mapi/pidlidglobalobjectid-canonical-property>
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlobalObjectId {
+    bytes: Vec<u8>,
+}
+
+impl GlobalObjectId {
+    /// Decodes a global object ID from its base64 representation, as
+    /// returned by EWS.
+    pub fn decode(value: &str) -> Result<Self, Error> {
+        let bytes = BASE64_STANDARD.decode(value).map_err(|err| {
+            Error::InvalidGlobalObjectId(format!("ID is not valid base64: {err}"))
+        })?;
+
+        Self::from_bytes(bytes)
+    }
+
+    /// Creates a global object ID from its binary representation.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LEN || bytes[..CLASS_ID.len()] != CLASS_ID {
+            return Err(Error::InvalidGlobalObjectId(
+                "ID does not start with the expected class identifier".to_string(),
+            ));
+        }
+
+        let data_len = u32::from_le_bytes(
+            bytes[HEADER_LEN - 4..HEADER_LEN]
+                .try_into()
+                .expect("header should contain the size of the data"),
+        );
+        if usize::try_from(data_len).ok() != Some(bytes.len() - HEADER_LEN) {
+            return Err(Error::InvalidGlobalObjectId(format!(
+                "ID has {} bytes of data but claims {data_len}",
+                bytes.len() - HEADER_LEN
+            )));
+        }
+
+        let id = Self { bytes };
+
+        // Check that the instance date is valid, so that the accessor doesn't
+        // need to fail.
+        id.parse_instance_date()?;
+
+        Ok(id)
+    }
+
+    /// Gets the binary representation of the ID.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Gets the date of the instance of a recurring meeting this ID
+    /// concerns, if it identifies an exception.
+    pub fn instance_date(&self) -> Option<time::Date> {
+        self.parse_instance_date()
+            .expect("instance date should have been checked")
+    }
+
+    /// Gets the ID without an instance date, i.e. the value of the
+    /// `PidLidCleanGlobalObjectId` property.
+    pub fn clean(&self) -> GlobalObjectId {
+        let mut bytes = self.bytes.clone();
+        bytes[INSTANCE_DATE].fill(0);
+
+        Self { bytes }
+    }
+
+    /// Gets the UID of the meeting, as returned by EWS in the UID of its
+    /// calendar items and meeting messages.
+    ///
+    /// Meetings received over iCalendar carry their original UID in the ID.
+    /// Others, e.g. those created in Outlook, are identified by the
+    /// hexadecimal representation of their clean ID.
+    pub fn uid(&self) -> String {
+        let data = &self.bytes[HEADER_LEN..];
+        if let Some(uid) = data.strip_prefix(VCAL_UID_MARKER) {
+            let uid = uid.split(|&byte| byte == 0).next().unwrap_or_default();
+
+            return String::from_utf8_lossy(uid).into_owned();
+        }
+
+        self.clean()
+            .bytes
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect()
+    }
+
+    fn parse_instance_date(&self) -> Result<Option<time::Date>, Error> {
+        let [year_high, year_low, month, day] = self.bytes[INSTANCE_DATE]
+            .try_into()
+            .expect("header should contain an instance date");
+        if [year_high, year_low, month, day] == [0; 4] {
+            return Ok(None);
+        }
+
+        let year = i32::from(u16::from_be_bytes([year_high, year_low]));
+        let invalid =
+            || Error::InvalidGlobalObjectId(format!("invalid instance date {year}-{month}-{day}"));
+        let month = time::Month::try_from(month).map_err(|_| invalid())?;
+
+        time::Date::from_calendar_date(year, month, day)
+            .map(Some)
+            .map_err(|_| invalid())
+    }
+}
+
+impl Message {
+    /// Decodes the global object ID of the meeting this calendar item or
+    /// meeting message belongs to, if it was returned.
+    ///
+    /// The clean ID is preferred when both `PidLidCleanGlobalObjectId` and
+    /// `PidLidGlobalObjectId` were returned.
+    pub fn parse_global_object_id(&self) -> Result<Option<GlobalObjectId>, Error> {
+        [PID_LID_CLEAN_GLOBAL_OBJECT_ID, PID_LID_GLOBAL_OBJECT_ID]
+            .iter()
+            .find_map(|property| self.extended_property(&property.field_uri()))
+            .map(GlobalObjectId::decode)
+            .transpose()
+    }
+
+    /// Gets the UID of the meeting this calendar item or meeting message
+    /// belongs to, from its global object ID if it was returned and valid,
+    /// or from its [`UID`](Message::UID) otherwise.
+    pub fn meeting_uid(&self) -> Option<String> {
+        match self.parse_global_object_id() {
+            Ok(Some(id)) => Some(id.uid()),
+            _ => self.UID.clone(),
+        }
+    }
+}
+
+impl ItemShape {
+    /// Adds the properties identifying the meeting an item belongs to, i.e.
+    /// its UID and global object IDs, to the shape.
+    ///
+    /// These are needed to correlate meeting messages with
+    /// [`correlate_meetings`].
+    pub fn with_meeting_ids(mut self) -> Self {
+        self.additional_properties
+            .get_or_insert_with(Vec::new)
+            .extend([
+                PathToElement::field("calendar:UID"),
+                PID_LID_GLOBAL_OBJECT_ID.into(),
+                PID_LID_CLEAN_GLOBAL_OBJECT_ID.into(),
+            ]);
+
+        self
+    }
+}
+
+/// The calendar items and meeting messages belonging to a meeting, as
+/// grouped by [`correlate_meetings`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MeetingMessages<'a> {
+    /// The UID of the meeting.
+    pub uid: String,
+
+    /// The items belonging to the meeting, in the order they were given.
+    pub messages: Vec<&'a Message>,
+}
+
+/// Groups calendar items and meeting messages, such as meeting requests,
+/// responses and cancellations, by the meeting they belong to, e.g. to
+/// deduplicate invitations to the same meeting.
+///
+/// Items are identified by [`Message::meeting_uid`], so they should be
+/// fetched with a shape including [`ItemShape::with_meeting_ids`]. Items of
+/// all instances of a recurring meeting are grouped together. Items without
+/// a UID are left out.
+///
+/// Groups are returned in the order their first item was given.
+pub fn correlate_meetings(messages: &[Message]) -> Vec<MeetingMessages<'_>> {
+    let mut groups: Vec<MeetingMessages<'_>> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for message in messages {
+        let Some(uid) = message.meeting_uid() else {
+            continue;
+        };
+
+        let position = *positions.entry(uid.clone()).or_insert_with(|| {
+            groups.push(MeetingMessages {
+                uid,
+                messages: Vec::new(),
+            });
+
+            groups.len() - 1
+        });
+        groups[position].messages.push(message);
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+    use crate::{
+        properties::{PID_LID_CLEAN_GLOBAL_OBJECT_ID, PID_LID_GLOBAL_OBJECT_ID},
+        Error, ExtendedProperty, ItemShape, Message,
+    };
+
+    use super::{correlate_meetings, GlobalObjectId, CLASS_ID};
+
+    /// Builds a global object ID with the given instance date and data.
+    fn global_object_id(instance_date: [u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = CLASS_ID.to_vec();
+        bytes.extend(instance_date);
+        bytes.extend(0x01D9_A0B1_C2D3_E4F5_u64.to_le_bytes());
+        bytes.extend([0; 8]);
+        bytes.extend(u32::try_from(data.len()).unwrap().to_le_bytes());
+        bytes.extend(data);
+
+        bytes
+    }
+
+    fn message_with_id(property: crate::properties::MapiProperty, bytes: &[u8]) -> Message {
+        Message {
+            extended_property: Some(vec![ExtendedProperty {
+                extended_field_URI: property.field_uri(),
+                value: BASE64_STANDARD.encode(bytes),
+            }]),
+            ..Message::new()
+        }
+    }
+
+    #[test]
+    fn decode_global_object_id() {
+        let data = [0x10, 0x00, 0x00, 0x00, 0xAB, 0xCD];
+        let exception = GlobalObjectId::from_bytes(global_object_id([0x07, 0xE8, 5, 6], &data))
+            .expect("ID should be valid");
+        assert_eq!(
+            exception.instance_date(),
+            Some(time::Date::from_calendar_date(2024, time::Month::May, 6).unwrap())
+        );
+
+        let clean = exception.clean();
+        assert_eq!(clean.instance_date(), None);
+        assert_eq!(clean.as_bytes(), global_object_id([0; 4], &data));
+        assert_eq!(exception.uid(), clean.uid());
+        assert!(clean
+            .uid()
+            .starts_with("040000008200E00074C5B7101A82E00800000000"));
+        assert!(clean.uid().ends_with("0600000010000000ABCD"));
+
+        let vcal = GlobalObjectId::from_bytes(global_object_id(
+            [0; 4],
+            b"vCal-Uid\x01\x00\x00\x00meeting-1234@example.com\x00",
+        ))
+        .expect("ID should be valid");
+        assert_eq!(vcal.uid(), "meeting-1234@example.com");
+
+        assert!(matches!(
+            GlobalObjectId::from_bytes(global_object_id([0x07, 0xE8, 13, 1], &data)),
+            Err(Error::InvalidGlobalObjectId(_))
+        ));
+        let mut truncated = global_object_id([0; 4], &data);
+        truncated.pop();
+        assert!(matches!(
+            GlobalObjectId::from_bytes(truncated),
+            Err(Error::InvalidGlobalObjectId(_))
+        ));
+        assert!(matches!(
+            GlobalObjectId::decode("not base64!"),
+            Err(Error::InvalidGlobalObjectId(_))
+        ));
+    }
+
+    #[test]
+    fn correlate_meeting_messages() {
+        let data = b"vCal-Uid\x01\x00\x00\x00standup@example.com\x00";
+
+        // A request for the series, a cancellation of one instance and a
+        // response for which only the UID was returned.
+        let request = message_with_id(PID_LID_GLOBAL_OBJECT_ID, &global_object_id([0; 4], data));
+        let cancellation = message_with_id(
+            PID_LID_CLEAN_GLOBAL_OBJECT_ID,
+            &global_object_id([0x07, 0xE8, 5, 6], data),
+        );
+        let response = Message {
+            UID: Some("standup@example.com".to_string()),
+            ..Message::new()
+        };
+        let other = Message {
+            UID: Some("retro@example.com".to_string()),
+            ..Message::new()
+        };
+        let unidentified = Message::new();
+
+        let messages = [request, other, unidentified, cancellation, response];
+        let groups = correlate_meetings(&messages);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].uid, "standup@example.com");
+        assert_eq!(
+            groups[0].messages,
+            vec![&messages[0], &messages[3], &messages[4]]
+        );
+        assert_eq!(groups[1].uid, "retro@example.com");
+        assert_eq!(groups[1].messages, vec![&messages[1]]);
+    }
+
+    #[test]
+    fn serialize_shape_with_meeting_ids() {
+        let expected = concat!(
+            r#"<ItemShape><t:BaseShape>IdOnly</t:BaseShape><t:AdditionalProperties>"#,
+            r#"<t:FieldURI FieldURI="calendar:UID"/>"#,
+            r#"<t:ExtendedFieldURI DistinguishedPropertySetId="Meeting" PropertyId="3" PropertyType="Binary"/>"#,
+            r#"<t:ExtendedFieldURI DistinguishedPropertySetId="Meeting" PropertyId="35" PropertyType="Binary"/>"#,
+            r#"</t:AdditionalProperties></ItemShape>"#,
+        );
+
+        crate::test_utils::assert_serialized_content(
+            &ItemShape::id_only().with_meeting_ids(),
+            "ItemShape",
+            expected,
+        );
+    }
+}
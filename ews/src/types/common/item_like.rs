@@ -0,0 +1,212 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use crate::{
+    AttachmentItem, Contact, ExtendedFieldURI, ExtendedProperty, ItemId, Message, RealItem, Task,
+};
+
+/// Accessors for the properties shared by all kinds of Exchange items.
+///
+/// This allows processing items of different kinds, such as those returned
+/// by a `SyncFolderItems` request, without matching on each kind.
+///
+/// # Example
+///
+/// ```
+/// use ews::{ItemLike, RealItem};
+///
+/// fn describe(items: &[RealItem]) -> Vec<String> {
+///     items
+///         .iter()
+///         .map(|item| {
+///             format!(
+///                 "{}: {}",
+///                 item.item_class().unwrap_or("IPM"),
+///                 item.subject().unwrap_or_default()
+///             )
+///         })
+///         .collect()
+/// }
+/// ```
+pub trait ItemLike {
+    /// Gets the item's Exchange identifier.
+    fn item_id(&self) -> Option<&ItemId>;
+
+    /// Gets the Exchange class value of the item, e.g. `IPM.Note`.
+    fn item_class(&self) -> Option<&str>;
+
+    /// Gets the subject of the item.
+    fn subject(&self) -> Option<&str>;
+
+    /// Gets the categories describing the item.
+    fn categories(&self) -> &[String];
+
+    /// Gets the extended MAPI properties returned for the item.
+    fn extended_properties(&self) -> &[ExtendedProperty];
+
+    /// Gets the change key of the item, identifying its current version.
+    fn change_key(&self) -> Option<&str> {
+        self.item_id()?.change_key.as_deref()
+    }
+
+    /// Gets the value of the extended MAPI property with the given
+    /// identifier, if it was returned for the item.
+    fn extended_property(&self, field_uri: &ExtendedFieldURI) -> Option<&str> {
+        self.extended_properties()
+            .iter()
+            .find(|property| property.extended_field_URI.identifies_same(field_uri))
+            .map(|property| property.value.as_str())
+    }
+}
+
+/// Implements [`ItemLike`] for a kind of item from its fields of the same
+/// names.
+macro_rules! impl_item_like {
+    ($($ty:ty),*) => {$(
+        impl ItemLike for $ty {
+            fn item_id(&self) -> Option<&ItemId> {
+                self.item_id.as_ref()
+            }
+
+            fn item_class(&self) -> Option<&str> {
+                self.item_class.as_deref()
+            }
+
+            fn subject(&self) -> Option<&str> {
+                self.subject.as_deref()
+            }
+
+            fn categories(&self) -> &[String] {
+                self.categories.as_deref().map_or(&[], Vec::as_slice)
+            }
+
+            fn extended_properties(&self) -> &[ExtendedProperty] {
+                self.extended_property.as_deref().unwrap_or_default()
+            }
+        }
+    )*};
+}
+
+impl_item_like!(Message, Contact, Task);
+
+/// Implements [`ItemLike`] for an enum of item kinds by delegating to the
+/// item of each variant.
+macro_rules! delegate_item_like {
+    ($ty:ty, $inner:ident) => {
+        impl ItemLike for $ty {
+            fn item_id(&self) -> Option<&ItemId> {
+                self.$inner().item_id()
+            }
+
+            fn item_class(&self) -> Option<&str> {
+                self.$inner().item_class()
+            }
+
+            fn subject(&self) -> Option<&str> {
+                self.$inner().subject()
+            }
+
+            fn categories(&self) -> &[String] {
+                self.$inner().categories()
+            }
+
+            fn extended_properties(&self) -> &[ExtendedProperty] {
+                self.$inner().extended_properties()
+            }
+        }
+    };
+}
+
+delegate_item_like!(RealItem, as_item_like);
+delegate_item_like!(AttachmentItem, as_item_like);
+
+impl AttachmentItem {
+    /// Return the [`Message`] object contained within this [`AttachmentItem`].
+    ///
+    /// Returns `None` for items which aren't represented by a [`Message`],
+    /// such as contacts and tasks.
+    pub fn inner_message(&self) -> Option<&Message> {
+        match self {
+            AttachmentItem::Message(message)
+            | AttachmentItem::CalendarItem(message)
+            | AttachmentItem::MeetingMessage(message)
+            | AttachmentItem::MeetingResponse(message)
+            | AttachmentItem::MeetingCancellation(message) => Some(message),
+            AttachmentItem::MeetingRequest(request) => Some(request),
+            AttachmentItem::Contact(_) | AttachmentItem::Task(_) => None,
+        }
+    }
+
+    fn as_item_like(&self) -> &dyn ItemLike {
+        match self {
+            AttachmentItem::Message(message)
+            | AttachmentItem::CalendarItem(message)
+            | AttachmentItem::MeetingMessage(message)
+            | AttachmentItem::MeetingResponse(message)
+            | AttachmentItem::MeetingCancellation(message) => message,
+            AttachmentItem::MeetingRequest(request) => &request.message,
+            AttachmentItem::Contact(contact) => contact,
+            AttachmentItem::Task(task) => task,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ItemLike;
+    use crate::{
+        properties, ExtendedFieldURI, ExtendedProperty, ItemId, Message, PropertyType, RealItem,
+    };
+
+    #[test]
+    fn access_shared_item_properties() {
+        let items = [
+            RealItem::Message(Message {
+                item_id: Some(ItemId {
+                    id: "message-id".to_string(),
+                    change_key: Some("ck1".to_string()),
+                }),
+                item_class: Some("IPM.Note".to_string()),
+                subject: Some("Hello".to_string()),
+                categories: Some(vec!["Work".to_string()].into()),
+                // Servers return property tags in lowercase and without
+                // padding.
+                extended_property: Some(vec![ExtendedProperty {
+                    extended_field_URI: ExtendedFieldURI {
+                        distinguished_property_set_id: None,
+                        property_set_id: None,
+                        property_tag: Some("0xe07".to_string()),
+                        property_name: None,
+                        property_id: None,
+                        property_type: PropertyType::Integer,
+                    },
+                    value: "1".to_string(),
+                }]),
+                ..Default::default()
+            }),
+            RealItem::CalendarItem(Message {
+                item_class: Some("IPM.Appointment".to_string()),
+                ..Default::default()
+            }),
+        ];
+
+        let message = &items[0];
+        assert_eq!(message.change_key(), Some("ck1"));
+        assert_eq!(message.subject(), Some("Hello"));
+        assert_eq!(message.categories().len(), 1);
+        assert_eq!(
+            message.extended_property(&properties::PR_MESSAGE_FLAGS.field_uri()),
+            Some("1")
+        );
+        assert_eq!(
+            message.extended_property(&properties::PR_MESSAGE_SIZE.field_uri()),
+            None
+        );
+
+        let classes: Vec<_> = items.iter().map(ItemLike::item_class).collect();
+        assert_eq!(classes, vec![Some("IPM.Note"), Some("IPM.Appointment")]);
+        assert_eq!(items[1].item_id(), None);
+        assert!(items[1].categories().is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Meeting requests, and the properties specific to them.
+//!
+//! The properties specific to meeting requests are written as siblings of
+//! the properties they share with other items. The XML deserializer doesn't
+//! support flattening structs, so the fields of a meeting request are read
+//! through a map which picks out its own properties and passes the rest on
+//! to [`Message`]. When serializing, the properties are written at their
+//! positions in the schema, among those of the message.
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{value::MapAccessDeserializer, DeserializeSeed, IntoDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use xml_struct::XmlSerialize;
+
+use crate::{get_user_availability::LegacyFreeBusyStatus, DateTime, Message};
+
+/// A meeting request received by an attendee.
+///
+/// Meeting requests share most of their properties with other items, so
+/// this holds a [`Message`] and dereferences to it, alongside the properties
+/// which are only returned for meeting requests.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/meetingrequest>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde-serialize", serde(rename_all = "PascalCase"))]
+pub struct MeetingRequest {
+    /// The properties shared with other items.
+    #[cfg_attr(feature = "serde-serialize", serde(flatten))]
+    pub message: Message,
+
+    /// The kind of the meeting request, e.g. whether it updates an existing
+    /// meeting.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/meetingrequesttype>
+    pub meeting_request_type: Option<MeetingRequestType>,
+
+    /// The free/busy status the organizer intends for the meeting in the
+    /// attendee's calendar.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/intendedfreebusystatus>
+    pub intended_free_busy_status: Option<LegacyFreeBusyStatus>,
+
+    /// The properties of the meeting which have changed, in an updated
+    /// meeting request.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/changehighlights>
+    pub change_highlights: Option<Box<ChangeHighlights>>,
+}
+
+impl MeetingRequest {
+    /// Consumes the meeting request, returning the underlying item.
+    pub fn into_message(self) -> Message {
+        self.message
+    }
+
+    /// Whether this request updates a meeting the attendee was previously
+    /// invited to, rather than inviting them to a new one.
+    pub fn is_update(&self) -> bool {
+        matches!(
+            self.meeting_request_type,
+            Some(
+                MeetingRequestType::FullUpdate
+                    | MeetingRequestType::InformationalUpdate
+                    | MeetingRequestType::SilentUpdate
+            )
+        )
+    }
+}
+
+impl From<Message> for MeetingRequest {
+    fn from(value: Message) -> Self {
+        Self {
+            message: value,
+            ..Default::default()
+        }
+    }
+}
+
+impl Deref for MeetingRequest {
+    type Target = Message;
+
+    fn deref(&self) -> &Self::Target {
+        &self.message
+    }
+}
+
+impl DerefMut for MeetingRequest {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.message
+    }
+}
+
+// Serialize by hand so that the properties specific to meeting requests are
+// written in schema order: the kind and intended status of the request come
+// before the calendar properties of the message, and the change highlights
+// after them.
+impl XmlSerialize for MeetingRequest {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        let Message {
+            start,
+            end,
+            is_all_day_event,
+            organizer,
+            recurrence,
+            meeting_time_zone,
+            start_time_zone,
+            end_time_zone,
+            ..
+        } = &self.message;
+
+        // Meeting requests are only serialized in tests and when attached to
+        // other items, so the cost of copying the message is of no concern.
+        let leading = Message {
+            start: None,
+            end: None,
+            is_all_day_event: None,
+            organizer: None,
+            recurrence: None,
+            meeting_time_zone: None,
+            start_time_zone: None,
+            end_time_zone: None,
+            ..self.message.clone()
+        };
+        leading.serialize_child_nodes(writer)?;
+
+        self.meeting_request_type
+            .serialize_as_element(writer, "t:MeetingRequestType")?;
+        self.intended_free_busy_status
+            .serialize_as_element(writer, "t:IntendedFreeBusyStatus")?;
+
+        start.serialize_as_element(writer, "t:Start")?;
+        end.serialize_as_element(writer, "t:End")?;
+        is_all_day_event.serialize_as_element(writer, "t:IsAllDayEvent")?;
+        organizer.serialize_as_element(writer, "t:Organizer")?;
+        recurrence.serialize_as_element(writer, "t:Recurrence")?;
+        meeting_time_zone.serialize_as_element(writer, "t:MeetingTimeZone")?;
+        start_time_zone.serialize_as_element(writer, "t:StartTimeZone")?;
+        end_time_zone.serialize_as_element(writer, "t:EndTimeZone")?;
+
+        self.change_highlights
+            .serialize_as_element(writer, "t:ChangeHighlights")
+    }
+}
+
+impl<'de> Deserialize<'de> for MeetingRequest {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MeetingRequestVisitor;
+
+        impl<'de> Visitor<'de> for MeetingRequestVisitor {
+            type Value = MeetingRequest;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a meeting request")
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut request = MeetingRequest::default();
+                let fields = MeetingRequestFields {
+                    map,
+                    request: &mut request,
+                };
+                request.message = Message::deserialize(MapAccessDeserializer::new(fields))?;
+
+                Ok(request)
+            }
+        }
+
+        deserializer.deserialize_map(MeetingRequestVisitor)
+    }
+}
+
+/// The fields of a meeting request element, which stores the properties
+/// specific to meeting requests as they are read and yields the others.
+struct MeetingRequestFields<'a, A> {
+    map: A,
+    request: &'a mut MeetingRequest,
+}
+
+impl<'de, A> MapAccess<'de> for MeetingRequestFields<'_, A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        while let Some(key) = self.map.next_key::<String>()? {
+            match key.as_str() {
+                "MeetingRequestType" => {
+                    self.request.meeting_request_type = self.map.next_value()?
+                }
+                "IntendedFreeBusyStatus" => {
+                    self.request.intended_free_busy_status = self.map.next_value()?
+                }
+                "ChangeHighlights" => self.request.change_highlights = self.map.next_value()?,
+                _ => return seed.deserialize(key.into_deserializer()).map(Some),
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+}
+
+/// The kind of a meeting request.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/meetingrequesttype>
+#[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum MeetingRequestType {
+    None,
+
+    /// The meeting request is outdated.
+    Outdated,
+
+    /// The meeting has been updated in a way that requires attendees to
+    /// respond again, such as a change of time.
+    FullUpdate,
+
+    /// The meeting has been updated in a way that doesn't require a new
+    /// response.
+    InformationalUpdate,
+
+    /// The meeting has been updated without notifying attendees.
+    SilentUpdate,
+
+    /// A request forwarded to a delegate's principal.
+    PrincipalWantsCopy,
+
+    /// A request for a new meeting.
+    NewMeetingRequest,
+}
+
+/// The properties of a meeting which have changed in an updated meeting
+/// request.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/changehighlights>
+#[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct ChangeHighlights {
+    #[xml_struct(ns_prefix = "t")]
+    pub has_location_changed: Option<bool>,
+
+    /// The new location, if it has changed.
+    #[xml_struct(ns_prefix = "t")]
+    pub location: Option<String>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub has_start_time_changed: Option<bool>,
+
+    /// The new start, if it has changed.
+    #[xml_struct(ns_prefix = "t")]
+    pub start: Option<DateTime>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub has_end_time_changed: Option<bool>,
+
+    /// The new end, if it has changed.
+    #[xml_struct(ns_prefix = "t")]
+    pub end: Option<DateTime>,
+}
@@ -0,0 +1,328 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use time::UtcOffset;
+
+use crate::{
+    ArrayOfRecipients, Attachment, Attachments, Body, Date, DateTime, ExtendedProperty, Importance,
+    InternetMessageHeader, InternetMessageHeaders, MeetingTimeZone, Message, Recipient,
+    Sensitivity, TimeZoneReference,
+};
+
+/// A builder for a [`Message`], e.g. to be created with [`CreateItem`].
+///
+/// Properties which aren't set are left out of the message, and recipients,
+/// categories, headers and attachments accumulate across calls.
+///
+/// # Example
+///
+/// ```
+/// use ews::{Body, Importance, Message, Recipient};
+///
+/// let message = Message::builder()
+///     .subject("Quarterly report")
+///     .body(Body::text("Please find the report attached."))
+///     .to(Recipient::with_name("alice@example.com", "Alice"))
+///     .cc(Recipient::new("bob@example.com"))
+///     .importance(Importance::High)
+///     .header("X-Report-Period", "Q3")
+///     .build();
+///
+/// assert_eq!(message.subject.as_deref(), Some("Quarterly report"));
+/// ```
+///
+/// [`CreateItem`]: crate::create_item::CreateItem
+#[derive(Clone, Debug, Default)]
+pub struct MessageBuilder {
+    message: Message,
+}
+
+impl MessageBuilder {
+    /// Creates a builder for a message with no properties set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the subject of the message.
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.message.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets the body of the message.
+    pub fn body(mut self, body: Body) -> Self {
+        self.message.body = Some(body);
+        self
+    }
+
+    /// Adds a primary recipient to the message.
+    pub fn to(mut self, recipient: Recipient) -> Self {
+        push_recipient(&mut self.message.to_recipients, recipient);
+        self
+    }
+
+    /// Adds a carbon copy recipient to the message.
+    pub fn cc(mut self, recipient: Recipient) -> Self {
+        push_recipient(&mut self.message.cc_recipients, recipient);
+        self
+    }
+
+    /// Adds a blind carbon copy recipient to the message.
+    pub fn bcc(mut self, recipient: Recipient) -> Self {
+        push_recipient(&mut self.message.bcc_recipients, recipient);
+        self
+    }
+
+    /// Sets the mailbox the message is sent from.
+    pub fn from(mut self, from: Recipient) -> Self {
+        self.message.from = Some(from);
+        self
+    }
+
+    /// Sets the mailbox to which replies to the message should be sent.
+    pub fn reply_to(mut self, reply_to: Recipient) -> Self {
+        self.message.reply_to = Some(Box::new(reply_to));
+        self
+    }
+
+    /// Sets the importance of the message.
+    pub fn importance(mut self, importance: Importance) -> Self {
+        self.message.importance = Some(importance);
+        self
+    }
+
+    /// Sets the sensitivity of the message.
+    pub fn sensitivity(mut self, sensitivity: Sensitivity) -> Self {
+        self.message.sensitivity = Some(sensitivity);
+        self
+    }
+
+    /// Adds a category to the message.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.message
+            .categories
+            .get_or_insert_with(Default::default)
+            .push(category.into());
+        self
+    }
+
+    /// Adds an Internet Message Format header to the message.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.message
+            .internet_message_headers
+            .get_or_insert_with(|| {
+                Box::new(InternetMessageHeaders {
+                    internet_message_header: Vec::new(),
+                })
+            })
+            .internet_message_header
+            .push(InternetMessageHeader {
+                header_name: name.into(),
+                value: value.into(),
+            });
+        self
+    }
+
+    /// Adds an extended MAPI property to the message.
+    pub fn extended_property(mut self, property: ExtendedProperty) -> Self {
+        self.message
+            .extended_property
+            .get_or_insert_with(Vec::new)
+            .push(property);
+        self
+    }
+
+    /// Adds an attachment to the message.
+    pub fn attachment(mut self, attachment: Attachment) -> Self {
+        self.message
+            .attachments
+            .get_or_insert_with(|| Box::new(Attachments { inner: Vec::new() }))
+            .inner
+            .push(attachment);
+        self
+    }
+
+    /// Sets whether the sender requests a read receipt for the message.
+    pub fn read_receipt_requested(mut self, requested: bool) -> Self {
+        self.message.is_read_receipt_requested = Some(requested);
+        self
+    }
+
+    /// Sets whether the sender requests a delivery receipt for the message.
+    pub fn delivery_receipt_requested(mut self, requested: bool) -> Self {
+        self.message.is_delivery_receipt_requested = Some(requested);
+        self
+    }
+
+    /// Sets the start and end of a calendar item.
+    pub fn time_span(mut self, start: DateTime, end: DateTime) -> Self {
+        self.message.start = Some(start);
+        self.message.end = Some(end);
+        self
+    }
+
+    /// Makes a calendar item an all-day event from `start` until the day
+    /// before `end`.
+    ///
+    /// All-day events start and end at midnight in the time zone of the
+    /// item, so `offset` should be the offset from UTC of the time zone set
+    /// with [`time_zone`] on those dates.
+    ///
+    /// [`time_zone`]: MessageBuilder::time_zone
+    pub fn all_day(mut self, start: Date, end: Date, offset: UtcOffset) -> Self {
+        self.message.start = Some(start.midnight(offset));
+        self.message.end = Some(end.midnight(offset));
+        self.message.is_all_day_event = Some(true);
+        self
+    }
+
+    /// Sets the time zone in which a calendar item starts and ends.
+    ///
+    /// Exchange Server 2007 predates these time zones, so [`CreateItem`]
+    /// sends them to it as the equivalent [`meeting_time_zone`] instead.
+    ///
+    /// [`CreateItem`]: crate::create_item::CreateItem
+    /// [`meeting_time_zone`]: MessageBuilder::meeting_time_zone
+    pub fn time_zone(mut self, time_zone: TimeZoneReference) -> Self {
+        self.message.start_time_zone = Some(Box::new(time_zone.clone()));
+        self.message.end_time_zone = Some(Box::new(time_zone));
+        self
+    }
+
+    /// Sets the time zone of a calendar item for Exchange Server 2007.
+    pub fn meeting_time_zone(mut self, time_zone: MeetingTimeZone) -> Self {
+        self.message.meeting_time_zone = Some(Box::new(time_zone));
+        self
+    }
+
+    /// Builds the message.
+    pub fn build(self) -> Message {
+        self.message
+    }
+}
+
+fn push_recipient(recipients: &mut Option<ArrayOfRecipients>, recipient: Recipient) {
+    recipients
+        .get_or_insert_with(Default::default)
+        .push(recipient);
+}
+
+impl Message {
+    /// Creates a builder for a new message.
+    pub fn builder() -> MessageBuilder {
+        MessageBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::assert_serialized_content, Body, Date, DateTime, Importance,
+        InternetMessageHeader, InternetMessageHeaders, MeetingTimeZone, Message, Recipient,
+        TimeZoneReference,
+    };
+
+    #[test]
+    fn build_message() {
+        let message = Message::builder()
+            .subject("Hello")
+            .body(Body::text("Hi there"))
+            .to(Recipient::new("alice@example.com"))
+            .to(Recipient::new("bob@example.com"))
+            .bcc(Recipient::new("carol@example.com"))
+            .importance(Importance::High)
+            .category("Work")
+            .header("X-Custom", "value")
+            .build();
+
+        assert_eq!(message.to_recipients.as_ref().map(|r| r.len()), Some(2));
+        assert_eq!(message.cc_recipients, None);
+        assert_eq!(message.categories, Some(vec!["Work".to_string()].into()));
+        assert_eq!(
+            message.internet_message_headers.as_deref(),
+            Some(&InternetMessageHeaders {
+                internet_message_header: vec![InternetMessageHeader {
+                    header_name: "X-Custom".to_string(),
+                    value: "value".to_string(),
+                }],
+            })
+        );
+
+        let message = Message {
+            categories: None,
+            internet_message_headers: None,
+            ..message
+        };
+
+        let expected = r#"<Message><t:Subject>Hello</t:Subject><t:Body BodyType="Text">Hi there</t:Body><t:Importance>High</t:Importance><t:ToRecipients><t:Mailbox><t:EmailAddress>alice@example.com</t:EmailAddress></t:Mailbox><t:Mailbox><t:EmailAddress>bob@example.com</t:EmailAddress></t:Mailbox></t:ToRecipients><t:BccRecipients><t:Mailbox><t:EmailAddress>carol@example.com</t:EmailAddress></t:Mailbox></t:BccRecipients></Message>"#;
+
+        assert_serialized_content(&message, "Message", expected);
+    }
+
+    #[test]
+    fn build_calendar_item_in_time_zone() {
+        let start = time::OffsetDateTime::from_unix_timestamp(1_714_552_200).unwrap();
+        let event = Message::builder()
+            .subject("Standup")
+            .time_span(
+                DateTime(start),
+                DateTime(start + time::Duration::minutes(15)),
+            )
+            .time_zone(TimeZoneReference::new("Eastern Standard Time"))
+            .build();
+
+        let expected = concat!(
+            r#"<CalendarItem><t:Subject>Standup</t:Subject>"#,
+            r#"<t:Start>2024-05-01T08:30:00.000000000Z</t:Start><t:End>2024-05-01T08:45:00.000000000Z</t:End>"#,
+            r#"<t:StartTimeZone Id="Eastern Standard Time"/><t:EndTimeZone Id="Eastern Standard Time"/>"#,
+            r#"</CalendarItem>"#,
+        );
+        assert_serialized_content(&event, "CalendarItem", expected);
+
+        let legacy = Message::builder()
+            .meeting_time_zone(MeetingTimeZone {
+                base_offset: Some("PT5H".to_string()),
+                ..MeetingTimeZone::new("Eastern Standard Time")
+            })
+            .build();
+        assert_serialized_content(
+            &legacy.meeting_time_zone,
+            "t:MeetingTimeZone",
+            r#"<t:MeetingTimeZone TimeZoneName="Eastern Standard Time"><t:BaseOffset>PT5H</t:BaseOffset></t:MeetingTimeZone>"#,
+        );
+    }
+
+    #[test]
+    fn build_all_day_event() {
+        let offset = time::UtcOffset::from_hms(-4, 0, 0).unwrap();
+        let first_day = Date(time::Date::from_calendar_date(2024, time::Month::June, 1).unwrap());
+        let event = Message::builder()
+            .subject("Conference")
+            .all_day(first_day, "2024-06-03".parse().unwrap(), offset)
+            .time_zone(TimeZoneReference::new("Eastern Standard Time"))
+            .build();
+
+        let expected = concat!(
+            r#"<CalendarItem><t:Subject>Conference</t:Subject>"#,
+            r#"<t:Start>2024-06-01T00:00:00.000000000-04:00</t:Start><t:End>2024-06-03T00:00:00.000000000-04:00</t:End>"#,
+            r#"<t:IsAllDayEvent>true</t:IsAllDayEvent>"#,
+            r#"<t:StartTimeZone Id="Eastern Standard Time"/><t:EndTimeZone Id="Eastern Standard Time"/>"#,
+            r#"</CalendarItem>"#,
+        );
+        assert_serialized_content(&event, "CalendarItem", expected);
+
+        // The server returns the start and end of the event in UTC.
+        let event: Message = quick_xml::de::from_str(concat!(
+            r#"<CalendarItem><Start>2024-06-01T04:00:00Z</Start><End>2024-06-03T04:00:00Z</End>"#,
+            r#"<IsAllDayEvent>true</IsAllDayEvent></CalendarItem>"#,
+        ))
+        .expect("calendar item should deserialize");
+        let (start, end) = event
+            .all_day_dates(offset)
+            .expect("item should be an all-day event");
+        assert_eq!(start, first_day);
+        assert_eq!(end.to_string(), "2024-06-03");
+        assert_eq!("2024-06-01-04:00".parse::<Date>().unwrap(), first_day);
+    }
+}
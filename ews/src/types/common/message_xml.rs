@@ -23,6 +23,8 @@ use std::marker::PhantomData;
 // though quick_xml's impl_deserialize_for_internally_tagged_enum may work for
 // this one day.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 #[non_exhaustive]
 pub enum MessageXml {
@@ -34,6 +36,8 @@ pub enum MessageXml {
 
 /// One of the two observed kinds of MessageXml elements: a Value named via the @Name attribute.
 #[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MessageXmlValue {
     #[serde(rename = "@Name")]
     pub name: String,
@@ -43,17 +47,23 @@ pub struct MessageXmlValue {
 
 /// One of the two observed kinds of MessageXml elements: a tag with a single text value.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MessageXmlTagged {
     pub name: String,
     pub value: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct MessageXmlElements {
     pub elements: Vec<MessageXmlElement>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum MessageXmlElement {
     MessageXmlTagged(MessageXmlTagged),
     MessageXmlValue(MessageXmlValue),
@@ -61,6 +71,8 @@ pub enum MessageXmlElement {
 
 /// Data associated with a [`ResponseCode::ErrorServerBusy`](crate::response::ResponseCode::ErrorServerBusy).
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ServerBusy {
     /// The duration in milliseconds to wait before making additional requests.
     pub back_off_milliseconds: u32,
@@ -0,0 +1,307 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::fmt;
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use mail_parser::{MessageParser, MimeHeaders as _};
+
+use crate::{
+    create_item::CreateItem, Error, Message, MessageDisposition, MimeContent, RealItem, Redacted,
+};
+
+impl MimeContent {
+    /// Encodes a complete [RFC 5322] message, e.g. as formatted by an email
+    /// builder such as `lettre`, as MIME content to be sent to the server.
+    ///
+    /// The character set is taken from the message's top-level `Content-Type`
+    /// header, if it declares one.
+    ///
+    /// [RFC 5322]: https://datatracker.ietf.org/doc/html/rfc5322
+    pub fn encode(raw: impl AsRef<[u8]>) -> Self {
+        let raw = raw.as_ref();
+        let character_set = MessageParser::default().parse(raw).and_then(|message| {
+            message
+                .content_type()
+                .and_then(|content_type| content_type.attribute("charset"))
+                .map(str::to_string)
+        });
+
+        Self {
+            character_set,
+            content: BASE64_STANDARD.encode(raw),
+        }
+    }
+
+    /// Decodes and parses the content into its headers, bodies and
+    /// attachments.
+    pub fn parse(&self) -> Result<ParsedMimeMessage, Error> {
+        let raw = self.decoded_bytes()?;
+        let message = MessageParser::default()
+            .parse(&raw)
+            .ok_or(Error::InvalidMime)?;
+
+        let headers = message
+            .headers_raw()
+            .map(|(name, value)| MimeHeader {
+                name: name.to_string(),
+                value: unfold(value),
+            })
+            .collect();
+
+        let attachments = message
+            .attachments()
+            .map(|part| MimeAttachment {
+                name: part.attachment_name().map(str::to_string),
+                content_type: part.content_type().map(|content_type| {
+                    match content_type.subtype() {
+                        Some(subtype) => format!("{}/{subtype}", content_type.ctype()),
+                        None => content_type.ctype().to_string(),
+                    }
+                }),
+                content_id: part.content_id().map(str::to_string),
+                content: part.contents().to_vec(),
+            })
+            .collect();
+
+        Ok(ParsedMimeMessage {
+            headers,
+            subject: message.subject().map(str::to_string),
+            text_body: message.body_text(0).map(|body| body.into_owned()),
+            html_body: message.body_html(0).map(|body| body.into_owned()),
+            attachments,
+        })
+    }
+}
+
+impl Message {
+    /// Creates a message whose content is entirely described by a complete
+    /// [RFC 5322] message.
+    ///
+    /// The server derives the message's properties from its MIME content, so
+    /// no other properties are set.
+    ///
+    /// [RFC 5322]: https://datatracker.ietf.org/doc/html/rfc5322
+    pub fn from_mime(raw: impl AsRef<[u8]>) -> Self {
+        Self {
+            mime_content: Some(Box::new(MimeContent::encode(raw))),
+            ..Default::default()
+        }
+    }
+}
+
+impl CreateItem {
+    /// Creates a request to create a single message from a complete
+    /// [RFC 5322] message, e.g. to send that exact message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ews::{create_item::CreateItem, MessageDisposition};
+    ///
+    /// let raw = "From: alice@example.com\r\nTo: bob@example.com\r\nSubject: Hi\r\n\r\nHello\r\n";
+    /// let request = CreateItem::from_mime(raw, MessageDisposition::SendOnly);
+    /// ```
+    ///
+    /// [RFC 5322]: https://datatracker.ietf.org/doc/html/rfc5322
+    pub fn from_mime(raw: impl AsRef<[u8]>, message_disposition: MessageDisposition) -> Self {
+        Self {
+            message_disposition: Some(message_disposition),
+            items: vec![RealItem::Message(Message::from_mime(raw))],
+            ..Default::default()
+        }
+    }
+}
+
+/// Joins the lines of a folded header value and trims surrounding whitespace.
+fn unfold(value: &str) -> String {
+    value
+        .split("\r\n")
+        .flat_map(|line| line.split('\n'))
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The [RFC 5322] message carried by an item's [`MimeContent`], parsed into
+/// its parts.
+///
+/// [RFC 5322]: https://datatracker.ietf.org/doc/html/rfc5322
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParsedMimeMessage {
+    /// The top-level headers of the message, in the order they appear.
+    ///
+    /// Values are unfolded but otherwise left as they appear in the message,
+    /// i.e. encoded words are not decoded.
+    pub headers: Vec<MimeHeader>,
+
+    /// The decoded subject of the message.
+    pub subject: Option<String>,
+
+    /// The first plain text body of the message, converted from the HTML
+    /// body if the message has no plain text body.
+    pub text_body: Option<String>,
+
+    /// The first HTML body of the message, converted from the plain text body
+    /// if the message has no HTML body.
+    pub html_body: Option<String>,
+
+    /// The attachments of the message, including inline ones.
+    pub attachments: Vec<MimeAttachment>,
+}
+
+impl ParsedMimeMessage {
+    /// Gets the value of the first header with the given name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+}
+
+// The bodies are redacted so that logging a message doesn't leak them.
+impl fmt::Debug for ParsedMimeMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParsedMimeMessage")
+            .field("headers", &self.headers)
+            .field("subject", &self.subject)
+            .field("text_body", &Redacted::text(&self.text_body))
+            .field("html_body", &Redacted::text(&self.html_body))
+            .field("attachments", &self.attachments)
+            .finish()
+    }
+}
+
+/// A header of a parsed MIME message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MimeHeader {
+    /// The name of the header.
+    pub name: String,
+
+    /// The raw value of the header.
+    pub value: String,
+}
+
+/// An attachment of a parsed MIME message.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MimeAttachment {
+    /// The file name of the attachment, if any.
+    pub name: Option<String>,
+
+    /// The media type of the attachment, e.g. `image/png`.
+    pub content_type: Option<String>,
+
+    /// The content identifier of the attachment, by which it can be
+    /// referenced from an HTML body.
+    pub content_id: Option<String>,
+
+    /// The decoded content of the attachment.
+    pub content: Vec<u8>,
+}
+
+// The content is redacted so that logging a message doesn't leak it.
+impl fmt::Debug for MimeAttachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MimeAttachment")
+            .field("name", &self.name)
+            .field("content_type", &self.content_type)
+            .field("content_id", &self.content_id)
+            .field("content", &Redacted::from(self.content.as_slice()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::prelude::{Engine as _, BASE64_STANDARD};
+
+    use crate::{create_item::CreateItem, Error, MessageDisposition, MimeContent, RealItem};
+
+    #[test]
+    fn parse_mime_content() {
+        let raw = concat!(
+            "From: Alice <alice@example.com>\r\n",
+            "To: bob@example.com\r\n",
+            "Subject: =?UTF-8?Q?Caf=C3=A9?=\r\n",
+            "X-Custom: folded\r\n",
+            " value\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"b1\"\r\n",
+            "\r\n",
+            "--b1\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "\r\n",
+            "Hello Bob\r\n",
+            "--b1\r\n",
+            "Content-Type: application/octet-stream; name=\"data.bin\"\r\n",
+            "Content-Disposition: attachment; filename=\"data.bin\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "AAEC\r\n",
+            "--b1--\r\n",
+        );
+
+        // Wrap the encoded content the way some servers do.
+        let encoded = BASE64_STANDARD.encode(raw);
+        let (head, tail) = encoded.split_at(40);
+        let mime_content = MimeContent {
+            character_set: Some("UTF-8".to_string()),
+            content: format!("{head}\r\n{tail}"),
+        };
+
+        let parsed = mime_content.parse().expect("content should parse");
+
+        assert_eq!(parsed.subject.as_deref(), Some("Café"));
+        assert_eq!(parsed.header("x-custom"), Some("folded value"));
+        assert_eq!(parsed.header("To"), Some("bob@example.com"));
+        assert_eq!(parsed.text_body.as_deref(), Some("Hello Bob"));
+        assert_eq!(parsed.attachments.len(), 1);
+
+        let attachment = &parsed.attachments[0];
+        assert_eq!(attachment.name.as_deref(), Some("data.bin"));
+        assert_eq!(
+            attachment.content_type.as_deref(),
+            Some("application/octet-stream")
+        );
+        assert_eq!(attachment.content, vec![0, 1, 2]);
+
+        let invalid = MimeContent {
+            character_set: None,
+            content: "not base64!".to_string(),
+        };
+        assert!(matches!(invalid.parse(), Err(Error::MimeDecode(_))));
+    }
+
+    #[test]
+    fn create_item_from_mime() {
+        let raw = concat!(
+            "From: alice@example.com\r\n",
+            "To: bob@example.com\r\n",
+            "Subject: Hi\r\n",
+            "Content-Type: text/plain; charset=\"iso-8859-1\"\r\n",
+            "\r\n",
+            "Hello\r\n",
+        );
+
+        let request = CreateItem::from_mime(raw, MessageDisposition::SendAndSaveCopy);
+        assert!(matches!(
+            request.message_disposition,
+            Some(MessageDisposition::SendAndSaveCopy)
+        ));
+
+        let [RealItem::Message(message)] = request.items.as_slice() else {
+            panic!("request should contain a single message");
+        };
+        let mime_content = message
+            .mime_content
+            .as_ref()
+            .expect("message should have MIME content");
+
+        assert_eq!(mime_content.character_set.as_deref(), Some("iso-8859-1"));
+        assert_eq!(mime_content.decoded_bytes().unwrap(), raw.as_bytes());
+        assert_eq!(message.subject, None);
+    }
+}
@@ -0,0 +1,312 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use serde::Deserialize;
+
+use crate::{DateTime, FolderId, ItemId, SubscriptionId, Watermark};
+
+/// A batch of events for a subscription, as returned by pull, push and
+/// streaming notifications alike.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/notification-ex15websvcsotherref>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct Notification {
+    /// The identifier of the subscription the events belong to.
+    pub subscription_id: SubscriptionId,
+
+    /// The watermark of the last event before this batch.
+    ///
+    /// Streaming notifications omit this.
+    pub previous_watermark: Option<Watermark>,
+
+    /// Whether the server has more events for the subscription than it
+    /// returned in this batch.
+    pub more_events: Option<bool>,
+
+    /// The events, in the order in which they occurred.
+    #[serde(rename = "$value", default)]
+    pub events: Vec<NotificationEvent>,
+}
+
+/// An event reported for a subscription.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/notification-ex15websvcsotherref#child-elements>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub enum NotificationEvent {
+    /// The subscription is active, but no other event occurred.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/statusevent>
+    StatusEvent(StatusEvent),
+
+    /// A message was delivered to a mailbox.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/newmailevent>
+    NewMailEvent(ObjectChangedEvent),
+
+    /// An item or folder was created.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createdevent>
+    CreatedEvent(ObjectChangedEvent),
+
+    /// An item or folder was deleted.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deletedevent>
+    DeletedEvent(ObjectChangedEvent),
+
+    /// An item or folder was modified.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/modifiedevent>
+    ModifiedEvent(ModifiedEvent),
+
+    /// An item or folder was moved to another folder.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/movedevent>
+    MovedEvent(MovedCopiedEvent),
+
+    /// An item or folder was copied to another folder.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/copiedevent>
+    CopiedEvent(MovedCopiedEvent),
+
+    /// The free/busy status of a calendar item changed.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/freebusychangedevent>
+    FreeBusyChangedEvent(ObjectChangedEvent),
+}
+
+impl NotificationEvent {
+    /// Gets the watermark of the event, from which a subscription can be
+    /// resumed.
+    pub fn watermark(&self) -> Option<&Watermark> {
+        match self {
+            Self::StatusEvent(event) => event.watermark.as_ref(),
+            Self::NewMailEvent(event)
+            | Self::CreatedEvent(event)
+            | Self::DeletedEvent(event)
+            | Self::FreeBusyChangedEvent(event) => event.watermark.as_ref(),
+            Self::ModifiedEvent(event) => event.watermark.as_ref(),
+            Self::MovedEvent(event) | Self::CopiedEvent(event) => event.watermark.as_ref(),
+        }
+    }
+
+    /// Gets the time at which the event occurred, or `None` for status
+    /// events.
+    pub fn time_stamp(&self) -> Option<&DateTime> {
+        match self {
+            Self::StatusEvent(_) => None,
+            Self::NewMailEvent(event)
+            | Self::CreatedEvent(event)
+            | Self::DeletedEvent(event)
+            | Self::FreeBusyChangedEvent(event) => Some(&event.time_stamp),
+            Self::ModifiedEvent(event) => Some(&event.time_stamp),
+            Self::MovedEvent(event) | Self::CopiedEvent(event) => Some(&event.time_stamp),
+        }
+    }
+}
+
+/// An event indicating that a subscription is still active.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/statusevent>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct StatusEvent {
+    pub watermark: Option<Watermark>,
+}
+
+/// An event concerning a single item or folder.
+///
+/// Exactly one of `item_id` and `folder_id` is expected to be set.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createdevent>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectChangedEvent {
+    /// The watermark of the event.
+    ///
+    /// Streaming notifications may omit this.
+    pub watermark: Option<Watermark>,
+
+    /// The time at which the event occurred.
+    pub time_stamp: DateTime,
+
+    /// The folder concerned by the event, if any.
+    pub folder_id: Option<FolderId>,
+
+    /// The item concerned by the event, if any.
+    pub item_id: Option<ItemId>,
+
+    /// The folder containing the item or folder concerned by the event.
+    pub parent_folder_id: Option<FolderId>,
+}
+
+/// An event indicating that an item or folder was modified.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/modifiedevent>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct ModifiedEvent {
+    pub watermark: Option<Watermark>,
+    pub time_stamp: DateTime,
+    pub folder_id: Option<FolderId>,
+    pub item_id: Option<ItemId>,
+    pub parent_folder_id: Option<FolderId>,
+
+    /// The number of unread items in the modified folder.
+    ///
+    /// Only set for events concerning folders.
+    pub unread_count: Option<u32>,
+}
+
+/// An event indicating that an item or folder was moved or copied.
+///
+/// The identifiers of the item or folder and of its parent folder are those
+/// after the move or copy; the previous identifiers are given separately.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/movedevent>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct MovedCopiedEvent {
+    pub watermark: Option<Watermark>,
+    pub time_stamp: DateTime,
+    pub folder_id: Option<FolderId>,
+    pub item_id: Option<ItemId>,
+    pub parent_folder_id: Option<FolderId>,
+
+    /// The identifier of the folder before the move or copy, if the event
+    /// concerns a folder.
+    pub old_folder_id: Option<FolderId>,
+
+    /// The identifier of the item before the move or copy, if the event
+    /// concerns an item.
+    pub old_item_id: Option<ItemId>,
+
+    /// The parent folder before the move or copy.
+    pub old_parent_folder_id: Option<FolderId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_utils::assert_deserialized_content, Error, FolderId, ItemId, Watermark};
+
+    use super::{Notification, NotificationEvent};
+
+    #[test]
+    fn deserialize_notification() -> Result<(), Error> {
+        let content = r#"<Notification>
+            <SubscriptionId>FwBzdWJzY3JpcHRpb24=</SubscriptionId>
+            <PreviousWatermark>AQAAAA==</PreviousWatermark>
+            <MoreEvents>false</MoreEvents>
+            <StatusEvent><Watermark>AQAAAB==</Watermark></StatusEvent>
+            <NewMailEvent>
+                <Watermark>AQAAAC==</Watermark>
+                <TimeStamp>2024-05-01T08:30:00Z</TimeStamp>
+                <ItemId Id="AAMkAd" ChangeKey="CQAAAB"/>
+                <ParentFolderId Id="AAMkAi" ChangeKey="AQAAAA"/>
+            </NewMailEvent>
+            <ModifiedEvent>
+                <Watermark>AQAAAD==</Watermark>
+                <TimeStamp>2024-05-01T08:30:01Z</TimeStamp>
+                <FolderId Id="AAMkAi" ChangeKey="AQAAAB"/>
+                <ParentFolderId Id="AAMkAr"/>
+                <UnreadCount>3</UnreadCount>
+            </ModifiedEvent>
+            <MovedEvent>
+                <Watermark>AQAAAE==</Watermark>
+                <TimeStamp>2024-05-01T08:30:02Z</TimeStamp>
+                <ItemId Id="AAMkAe"/>
+                <ParentFolderId Id="AAMkAj"/>
+                <OldItemId Id="AAMkAd"/>
+                <OldParentFolderId Id="AAMkAi"/>
+            </MovedEvent>
+        </Notification>"#;
+
+        let folder_id = |id: &str| FolderId {
+            id: id.to_string(),
+            change_key: None,
+        };
+
+        let notification: Notification = {
+            let mut de = quick_xml::de::Deserializer::from_reader(content.as_bytes());
+            serde_path_to_error::deserialize(&mut de)?
+        };
+
+        assert_eq!(
+            notification.subscription_id.as_str(),
+            "FwBzdWJzY3JpcHRpb24="
+        );
+        assert_eq!(notification.more_events, Some(false));
+
+        let watermarks: Vec<_> = notification
+            .events
+            .iter()
+            .map(|event| event.watermark().map(Watermark::as_str))
+            .collect();
+        assert_eq!(
+            watermarks,
+            vec![
+                Some("AQAAAB=="),
+                Some("AQAAAC=="),
+                Some("AQAAAD=="),
+                Some("AQAAAE==")
+            ]
+        );
+        assert!(notification.events[0].time_stamp().is_none());
+
+        match &notification.events[1] {
+            NotificationEvent::NewMailEvent(event) => {
+                assert_eq!(
+                    event.item_id,
+                    Some(ItemId {
+                        id: "AAMkAd".to_string(),
+                        change_key: Some("CQAAAB".to_string()),
+                    })
+                );
+                assert!(event.folder_id.is_none());
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+
+        match &notification.events[2] {
+            NotificationEvent::ModifiedEvent(event) => {
+                assert_eq!(event.unread_count, Some(3));
+                assert_eq!(event.parent_folder_id, Some(folder_id("AAMkAr")));
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+
+        match &notification.events[3] {
+            NotificationEvent::MovedEvent(event) => {
+                assert_eq!(event.old_parent_folder_id, Some(folder_id("AAMkAi")));
+                assert_eq!(event.parent_folder_id, Some(folder_id("AAMkAj")));
+                assert_eq!(
+                    event.old_item_id.as_ref().map(|id| id.id.as_str()),
+                    Some("AAMkAd")
+                );
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_empty_notification() {
+        assert_deserialized_content(
+            "<Notification><SubscriptionId>FwBz</SubscriptionId></Notification>",
+            Notification {
+                subscription_id: "FwBz".into(),
+                previous_watermark: None,
+                more_events: None,
+                events: vec![],
+            },
+        );
+    }
+}
@@ -0,0 +1,197 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use html2text::render::text_renderer::{TaggedLine, TextDecorator};
+
+use crate::{Body, BodyType, Error};
+
+/// The width at which lines are wrapped, chosen so that paragraphs are kept
+/// on a single line.
+const UNWRAPPED_WIDTH: usize = 1 << 20;
+
+impl Body {
+    /// Converts the body to plain text, e.g. for notification previews or
+    /// search indexing.
+    ///
+    /// Text bodies are returned as they are. HTML bodies are stripped of
+    /// their markup, with paragraphs and line breaks kept as line breaks,
+    /// list items prefixed with `- ` or their number, web links followed by
+    /// their URL in angle brackets and images replaced by their alternative
+    /// text. Runs of blank lines are collapsed into one, and whitespace at
+    /// the end of lines is removed.
+    pub fn as_plain_text(&self) -> Result<String, Error> {
+        match self.body_type {
+            BodyType::Text => Ok(self.content_str().to_string()),
+            BodyType::HTML => {
+                let text = html2text::config::with_decorator(PlainTextDecorator::default())
+                    .no_table_borders()
+                    .string_from_read(self.content_str().as_bytes(), UNWRAPPED_WIDTH)
+                    .map_err(|err| Error::InvalidHtml(err.to_string()))?;
+
+                Ok(normalize_lines(&text))
+            }
+        }
+    }
+}
+
+/// Removes trailing whitespace from lines and collapses runs of blank lines.
+fn normalize_lines(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut blank_lines = 0;
+
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+
+        if !normalized.is_empty() {
+            normalized.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        normalized.push_str(line);
+        blank_lines = 0;
+    }
+
+    normalized
+}
+
+/// Renders HTML as text without any markup characters, apart from list
+/// prefixes and the URLs of links.
+#[derive(Clone, Debug, Default)]
+struct PlainTextDecorator {
+    /// The URL of the link being rendered, if it points to the web.
+    link: Option<String>,
+}
+
+impl TextDecorator for PlainTextDecorator {
+    type Annotation = ();
+
+    fn decorate_link_start(&mut self, url: &str) -> (String, Self::Annotation) {
+        let is_web_link = ["http://", "https://"].iter().any(|scheme| {
+            url.get(..scheme.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        });
+        self.link = is_web_link.then(|| url.to_string());
+
+        (String::new(), ())
+    }
+
+    fn decorate_link_end(&mut self) -> String {
+        self.link
+            .take()
+            .map(|url| format!(" <{url}>"))
+            .unwrap_or_default()
+    }
+
+    fn decorate_em_start(&self) -> (String, Self::Annotation) {
+        (String::new(), ())
+    }
+
+    fn decorate_em_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_strong_start(&self) -> (String, Self::Annotation) {
+        (String::new(), ())
+    }
+
+    fn decorate_strong_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_strikeout_start(&self) -> (String, Self::Annotation) {
+        (String::new(), ())
+    }
+
+    fn decorate_strikeout_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_code_start(&self) -> (String, Self::Annotation) {
+        (String::new(), ())
+    }
+
+    fn decorate_code_end(&self) -> String {
+        String::new()
+    }
+
+    fn decorate_preformat_first(&self) -> Self::Annotation {}
+
+    fn decorate_preformat_cont(&self) -> Self::Annotation {}
+
+    fn decorate_image(&mut self, _src: &str, title: &str) -> (String, Self::Annotation) {
+        (title.to_string(), ())
+    }
+
+    fn header_prefix(&self, _level: usize) -> String {
+        String::new()
+    }
+
+    fn quote_prefix(&self) -> String {
+        "> ".to_string()
+    }
+
+    fn unordered_item_prefix(&self) -> String {
+        "- ".to_string()
+    }
+
+    fn ordered_item_prefix(&self, i: i64) -> String {
+        format!("{i}. ")
+    }
+
+    fn make_subblock_decorator(&self) -> Self {
+        Self::default()
+    }
+
+    fn finalise(&mut self, _links: Vec<String>) -> Vec<TaggedLine<Self::Annotation>> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Body, Error};
+
+    #[test]
+    fn convert_html_to_plain_text() -> Result<(), Error> {
+        let body = Body::html(concat!(
+            "<html><head><style>p { color: red; }</style></head><body>",
+            "<h1>Quarterly report</h1>",
+            "<p>Figures &amp; <b>charts</b> are <a href=\"https://example.com/q2\">online</a>.</p>",
+            "<p>Line one<br>Line two</p><p>&nbsp;</p>",
+            "<ul><li>Revenue</li><li>Costs</li></ul>",
+            "<ol><li>Review</li><li>Sign off</li></ol>",
+            "<p><img src=\"cid:image001.png\" alt=\"Chart\"> <a href=\"mailto:a@example.com\">Alice</a></p>",
+            "</body></html>",
+        ));
+
+        assert_eq!(
+            body.as_plain_text()?,
+            concat!(
+                "Quarterly report\n\n",
+                "Figures & charts are online <https://example.com/q2>.\n\n",
+                "Line one\nLine two\n\n",
+                "- Revenue\n- Costs\n\n",
+                "1. Review\n2. Sign off\n\n",
+                "Chart Alice",
+            )
+        );
+
+        // Escaped markup is text, not markup.
+        let escaped =
+            Body::html("<p>Use &lt;b&gt; for bold and &lt;script&gt;x&lt;/script&gt; here</p>");
+        assert_eq!(
+            escaped.as_plain_text()?,
+            "Use <b> for bold and <script>x</script> here"
+        );
+
+        let text = Body::text("Plain <b>text</b> is left alone\n\n\n");
+        assert_eq!(
+            text.as_plain_text()?,
+            "Plain <b>text</b> is left alone\n\n\n"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use serde::Deserialize;
+use xml_struct::XmlSerialize;
+
+/// The recurrence of a calendar item or meeting request, made up of a
+/// pattern and the range over which it repeats.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/recurrence-recurrencetype>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(try_from = "RecurrenceElements")]
+pub struct Recurrence {
+    /// How often the item repeats.
+    #[xml_struct(flatten, ns_prefix = "t")]
+    pub pattern: RecurrencePattern,
+
+    /// When the repetition starts and ends.
+    #[xml_struct(flatten, ns_prefix = "t")]
+    pub range: RecurrenceRange,
+}
+
+/// The pattern of a recurrence.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/recurrence-recurrencetype>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(variant_ns_prefix = "t")]
+pub enum RecurrencePattern {
+    RelativeYearlyRecurrence(RelativeYearlyRecurrence),
+    AbsoluteYearlyRecurrence(AbsoluteYearlyRecurrence),
+    RelativeMonthlyRecurrence(RelativeMonthlyRecurrence),
+    AbsoluteMonthlyRecurrence(AbsoluteMonthlyRecurrence),
+    WeeklyRecurrence(WeeklyRecurrence),
+    DailyRecurrence(DailyRecurrence),
+
+    /// A task which is regenerated a number of days after it is completed.
+    ///
+    /// Regeneration patterns are only valid for tasks.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dailyregeneration>
+    DailyRegeneration(RegenerationRecurrence),
+
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/weeklyregeneration>
+    WeeklyRegeneration(RegenerationRecurrence),
+
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/monthlyregeneration>
+    MonthlyRegeneration(RegenerationRecurrence),
+
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/yearlyregeneration>
+    YearlyRegeneration(RegenerationRecurrence),
+}
+
+/// A recurrence on a given day of a given week of a month every year, e.g.
+/// the last Friday of June.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/relativeyearlyrecurrence>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct RelativeYearlyRecurrence {
+    /// The days of the week on which the item occurs, as a space-separated
+    /// list.
+    #[xml_struct(ns_prefix = "t")]
+    pub days_of_week: String,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub day_of_week_index: DayOfWeekIndex,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub month: Month,
+}
+
+/// A recurrence on a given day of a given month every year.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/absoluteyearlyrecurrence>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct AbsoluteYearlyRecurrence {
+    #[xml_struct(ns_prefix = "t")]
+    pub day_of_month: u8,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub month: Month,
+}
+
+/// A recurrence on a given day of a given week every few months, e.g. the
+/// second Tuesday of every month.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/relativemonthlyrecurrence>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct RelativeMonthlyRecurrence {
+    /// The number of months between occurrences.
+    #[xml_struct(ns_prefix = "t")]
+    pub interval: u32,
+
+    /// The days of the week on which the item occurs, as a space-separated
+    /// list.
+    #[xml_struct(ns_prefix = "t")]
+    pub days_of_week: String,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub day_of_week_index: DayOfWeekIndex,
+}
+
+/// A recurrence on a given day of the month every few months.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/absolutemonthlyrecurrence>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct AbsoluteMonthlyRecurrence {
+    /// The number of months between occurrences.
+    #[xml_struct(ns_prefix = "t")]
+    pub interval: u32,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub day_of_month: u8,
+}
+
+/// A recurrence on given days of the week every few weeks.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/weeklyrecurrence>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct WeeklyRecurrence {
+    /// The number of weeks between occurrences.
+    #[xml_struct(ns_prefix = "t")]
+    pub interval: u32,
+
+    /// The days of the week on which the item occurs, as a space-separated
+    /// list.
+    #[xml_struct(ns_prefix = "t")]
+    pub days_of_week: String,
+
+    /// The first day of the week, requiring Exchange Server 2010 or later.
+    #[xml_struct(ns_prefix = "t")]
+    pub first_day_of_week: Option<String>,
+}
+
+/// A recurrence every few days.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dailyrecurrence>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct DailyRecurrence {
+    /// The number of days between occurrences.
+    #[xml_struct(ns_prefix = "t")]
+    pub interval: u32,
+}
+
+/// A recurrence of a task, regenerated a number of days, weeks, months or
+/// years after the previous occurrence is completed.
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct RegenerationRecurrence {
+    /// The number of units between completion and the next occurrence.
+    #[xml_struct(ns_prefix = "t")]
+    pub interval: u32,
+}
+
+/// The range over which a recurrence repeats.
+///
+/// Dates are in the `xs:date` format, which may include an offset from UTC.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/recurrence-recurrencetype>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(variant_ns_prefix = "t")]
+pub enum RecurrenceRange {
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/noendrecurrence>
+    NoEndRecurrence(NoEndRecurrence),
+
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/enddaterecurrence>
+    EndDateRecurrence(EndDateRecurrence),
+
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/numberedrecurrence>
+    NumberedRecurrence(NumberedRecurrence),
+}
+
+/// A recurrence range without an end.
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct NoEndRecurrence {
+    #[xml_struct(ns_prefix = "t")]
+    pub start_date: String,
+}
+
+/// A recurrence range ending on a given date.
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct EndDateRecurrence {
+    #[xml_struct(ns_prefix = "t")]
+    pub start_date: String,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub end_date: String,
+}
+
+/// A recurrence range ending after a given number of occurrences.
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct NumberedRecurrence {
+    #[xml_struct(ns_prefix = "t")]
+    pub start_date: String,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub number_of_occurrences: u32,
+}
+
+/// The week of the month in which a relative recurrence occurs.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dayofweekindex>
+#[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum DayOfWeekIndex {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Last,
+}
+
+/// A month of the year.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/month>
+#[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum Month {
+    January,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+/// The elements of a recurrence as they appear in XML, where the pattern
+/// and the range are each one of several possible elements.
+#[derive(Deserialize)]
+struct RecurrenceElements {
+    #[serde(rename = "$value", default)]
+    elements: Vec<RecurrenceElement>,
+}
+
+#[derive(Deserialize)]
+enum RecurrenceElement {
+    RelativeYearlyRecurrence(RelativeYearlyRecurrence),
+    AbsoluteYearlyRecurrence(AbsoluteYearlyRecurrence),
+    RelativeMonthlyRecurrence(RelativeMonthlyRecurrence),
+    AbsoluteMonthlyRecurrence(AbsoluteMonthlyRecurrence),
+    WeeklyRecurrence(WeeklyRecurrence),
+    DailyRecurrence(DailyRecurrence),
+    DailyRegeneration(RegenerationRecurrence),
+    WeeklyRegeneration(RegenerationRecurrence),
+    MonthlyRegeneration(RegenerationRecurrence),
+    YearlyRegeneration(RegenerationRecurrence),
+    NoEndRecurrence(NoEndRecurrence),
+    EndDateRecurrence(EndDateRecurrence),
+    NumberedRecurrence(NumberedRecurrence),
+}
+
+impl TryFrom<RecurrenceElements> for Recurrence {
+    type Error = String;
+
+    fn try_from(value: RecurrenceElements) -> Result<Self, Self::Error> {
+        let mut pattern = None;
+        let mut range = None;
+        for element in value.elements {
+            match element {
+                RecurrenceElement::RelativeYearlyRecurrence(value) => {
+                    pattern = Some(RecurrencePattern::RelativeYearlyRecurrence(value))
+                }
+                RecurrenceElement::AbsoluteYearlyRecurrence(value) => {
+                    pattern = Some(RecurrencePattern::AbsoluteYearlyRecurrence(value))
+                }
+                RecurrenceElement::RelativeMonthlyRecurrence(value) => {
+                    pattern = Some(RecurrencePattern::RelativeMonthlyRecurrence(value))
+                }
+                RecurrenceElement::AbsoluteMonthlyRecurrence(value) => {
+                    pattern = Some(RecurrencePattern::AbsoluteMonthlyRecurrence(value))
+                }
+                RecurrenceElement::WeeklyRecurrence(value) => {
+                    pattern = Some(RecurrencePattern::WeeklyRecurrence(value))
+                }
+                RecurrenceElement::DailyRecurrence(value) => {
+                    pattern = Some(RecurrencePattern::DailyRecurrence(value))
+                }
+                RecurrenceElement::DailyRegeneration(value) => {
+                    pattern = Some(RecurrencePattern::DailyRegeneration(value))
+                }
+                RecurrenceElement::WeeklyRegeneration(value) => {
+                    pattern = Some(RecurrencePattern::WeeklyRegeneration(value))
+                }
+                RecurrenceElement::MonthlyRegeneration(value) => {
+                    pattern = Some(RecurrencePattern::MonthlyRegeneration(value))
+                }
+                RecurrenceElement::YearlyRegeneration(value) => {
+                    pattern = Some(RecurrencePattern::YearlyRegeneration(value))
+                }
+                RecurrenceElement::NoEndRecurrence(value) => {
+                    range = Some(RecurrenceRange::NoEndRecurrence(value))
+                }
+                RecurrenceElement::EndDateRecurrence(value) => {
+                    range = Some(RecurrenceRange::EndDateRecurrence(value))
+                }
+                RecurrenceElement::NumberedRecurrence(value) => {
+                    range = Some(RecurrenceRange::NumberedRecurrence(value))
+                }
+            }
+        }
+
+        Ok(Self {
+            pattern: pattern.ok_or("recurrence is missing a pattern")?,
+            range: range.ok_or("recurrence is missing a range")?,
+        })
+    }
+}
@@ -0,0 +1,90 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::fmt;
+
+/// Content which is shown only by its length when formatted with `Debug`.
+///
+/// The `Debug` implementations of types holding the bodies of messages, their
+/// MIME content or the content of attachments use this, so that logging a
+/// response or an item doesn't leak the content of the mailbox. The content
+/// remains accessible through the fields of those types.
+pub(crate) struct Redacted<'a>(&'a [u8]);
+
+impl<'a> Redacted<'a> {
+    /// Redacts optional text content.
+    pub(crate) fn text(content: &'a Option<String>) -> Option<Self> {
+        content.as_deref().map(|content| Self(content.as_bytes()))
+    }
+}
+
+impl<'a> From<&'a str> for Redacted<'a> {
+    fn from(content: &'a str) -> Self {
+        Self(content.as_bytes())
+    }
+}
+
+impl<'a> From<&'a [u8]> for Redacted<'a> {
+    fn from(content: &'a [u8]) -> Self {
+        Self(content)
+    }
+}
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted {} bytes>", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attachment, AttachmentId, Body, Message, MimeContent};
+
+    #[test]
+    fn debug_output_redacts_content() {
+        let message = Message {
+            subject: Some("Quarterly report".to_string()),
+            body: Some(Body::html("<p>Revenue is up</p>")),
+            mime_content: Some(Box::new(MimeContent {
+                character_set: Some("UTF-8".to_string()),
+                content: "U3ViamVjdDogSGkNCg0K".to_string(),
+            })),
+            ..Default::default()
+        };
+
+        let debug = format!("{message:?}");
+        assert!(debug.contains("Quarterly report"));
+        assert!(debug.contains(
+            "Body { body_type: HTML, is_truncated: None, content: Some(<redacted 20 bytes>) }"
+        ));
+        assert!(debug.contains(
+            r#"MimeContent { character_set: Some("UTF-8"), content: <redacted 20 bytes> }"#
+        ));
+        assert!(!debug.contains("Revenue"));
+        assert!(!debug.contains("U3ViamVj"));
+
+        let attachment = Attachment::FileAttachment {
+            attachment_id: AttachmentId {
+                id: "AAMkAD".to_string(),
+                root_item_id: None,
+                root_item_change_key: None,
+            },
+            name: "report.txt".to_string(),
+            content_type: Some("text/plain".to_string()),
+            content_id: None,
+            content_location: None,
+            size: Some(11),
+            last_modified_time: None,
+            is_inline: Some(false),
+            is_contact_photo: None,
+            content: Some("c2VjcmV0IG5vdGVz".to_string()),
+        };
+
+        let debug = format!("{attachment:?}");
+        assert!(debug.starts_with("FileAttachment {"));
+        assert!(debug.contains(r#"name: "report.txt""#));
+        assert!(debug.contains("content: Some(<redacted 16 bytes>)"));
+        assert!(!debug.contains("c2VjcmV0"));
+    }
+}
@@ -6,11 +6,14 @@ use serde::de::{self, Error, MapAccess, Visitor};
 use serde::{Deserialize, Deserializer};
 use std::fmt;
 use std::marker::PhantomData;
+use std::ops::Index;
 
 /// A generic representation of a response message from a request.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/responsemessages>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(default)]
 pub struct ResponseMessages<T> {
     #[serde(rename = "$value")]
@@ -25,12 +28,57 @@ impl<T> Default for ResponseMessages<T> {
     }
 }
 
+impl<T> ResponseMessages<T> {
+    /// Returns an iterator over the response messages.
+    pub fn iter(&self) -> std::slice::Iter<'_, ResponseClass<T>> {
+        self.response_messages.iter()
+    }
+
+    /// Returns the number of response messages.
+    pub fn len(&self) -> usize {
+        self.response_messages.len()
+    }
+
+    /// Returns `true` if there are no response messages.
+    pub fn is_empty(&self) -> bool {
+        self.response_messages.is_empty()
+    }
+}
+
+impl<T> Index<usize> for ResponseMessages<T> {
+    type Output = ResponseClass<T>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.response_messages[index]
+    }
+}
+
+impl<T> IntoIterator for ResponseMessages<T> {
+    type Item = ResponseClass<T>;
+    type IntoIter = std::vec::IntoIter<ResponseClass<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.response_messages.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ResponseMessages<T> {
+    type Item = &'a ResponseClass<T>;
+    type IntoIter = std::slice::Iter<'a, ResponseClass<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.response_messages.iter()
+    }
+}
+
 /// A fallible response to an operation.
 ///
 /// For a list of upstream types that use this sort of structuring, you can see [`ResponseCode`]'s
 /// [list of parent
 /// types](https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/responsecode#parent-elements).
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ResponseClass<T> {
     /// The operation was successful and returned the requested object.
     Success(T),
@@ -43,6 +91,17 @@ pub enum ResponseClass<T> {
     Warning(T),
 }
 
+impl<T> ResponseClass<T> {
+    /// Converts this response into a [`Result`], treating both successes and
+    /// warnings as [`Ok`].
+    pub fn into_result(self) -> Result<T, ResponseError> {
+        match self {
+            Self::Success(value) | Self::Warning(value) => Ok(value),
+            Self::Error(err) => Err(err),
+        }
+    }
+}
+
 // Manually implemented because quick_xml's impl_deserialize_for_internally_tagged_enum doesn't
 // currently support generics.
 impl<'de, T: Deserialize<'de>> Deserialize<'de> for ResponseClass<T> {
@@ -90,6 +149,8 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for ResponseClass<T> {
 
 /// Information available when an operation responded with an Error.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct ResponseError {
     pub message_text: String,
@@ -115,13 +176,41 @@ impl fmt::Display for ResponseError {
     }
 }
 
+impl ResponseError {
+    /// Determines whether the error was caused by a missing or outdated change
+    /// key, in which case the request may succeed once retried with the item's
+    /// current change key.
+    pub fn is_stale_change_key(&self) -> bool {
+        matches!(
+            self.response_code,
+            ResponseCode::ErrorStaleObject
+                | ResponseCode::ErrorChangeKeyRequired
+                | ResponseCode::ErrorChangeKeyRequiredForWriteOperations
+        )
+    }
+}
+
 impl std::error::Error for ResponseError {}
 
+impl ResponseCode {
+    /// Determines whether the code indicates that the server doesn't accept
+    /// the schema version targeted by the request, in which case the request
+    /// may succeed once retried targeting an older version.
+    pub fn is_server_version_rejected(self) -> bool {
+        matches!(
+            self,
+            ResponseCode::ErrorInvalidServerVersion | ResponseCode::ErrorIncorrectSchemaVersion
+        )
+    }
+}
+
 /// An error code describing the error encountered in processing a request, if
 /// any.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/responsecode>
 #[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum ResponseCode {
     #[default]
     NoError,
@@ -0,0 +1,239 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Deserialization of [`Restriction`]s.
+//!
+//! The operands of a comparison are written as sibling elements, with the
+//! compared property given by the name of its identifier element, e.g.
+//! `t:FieldURI`, rather than wrapped in an element of its own. This can't be
+//! expressed with the derive, so each restriction is read into an
+//! intermediate representation mirroring the XML, then checked and converted.
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+use crate::{
+    AndRestriction, Constant, ContainmentComparison, ContainmentMode, ContainsRestriction,
+    ExtendedFieldURI, FieldEqualTo, FieldURIOrConstant, NotRestriction, OrRestriction,
+    PathToElement, Restriction, RestrictionType,
+};
+
+/// A restriction as it appears in XML, named after its element.
+#[derive(Deserialize)]
+enum RestrictionElement {
+    And(Restrictions),
+    Or(Restrictions),
+    Not(Restriction),
+    IsEqualTo(Operands),
+    IsNotEqualTo(Operands),
+    IsGreaterThan(Operands),
+    IsGreaterThanOrEqualTo(Operands),
+    IsLessThan(Operands),
+    IsLessThanOrEqualTo(Operands),
+    Contains(Contains),
+    Exists(Operands),
+}
+
+/// The restrictions combined by an `And` or `Or` restriction.
+#[derive(Deserialize)]
+struct Restrictions {
+    #[serde(rename = "$value", default)]
+    restrictions: Vec<RestrictionType>,
+}
+
+/// The children of a restriction which compares a property to a value.
+#[derive(Deserialize)]
+struct Operands {
+    #[serde(rename = "$value", default)]
+    operands: Vec<Operand>,
+}
+
+/// The attributes and children of a `Contains` restriction.
+#[derive(Deserialize)]
+struct Contains {
+    #[serde(rename = "@ContainmentMode")]
+    containment_mode: Option<ContainmentMode>,
+
+    #[serde(rename = "@ContainmentComparison")]
+    containment_comparison: Option<ContainmentComparison>,
+
+    #[serde(rename = "$value", default)]
+    operands: Vec<Operand>,
+}
+
+/// A child of a comparison restriction.
+#[derive(Deserialize)]
+enum Operand {
+    ExtendedFieldURI(ExtendedFieldURI),
+    FieldURI {
+        #[serde(rename = "@FieldURI")]
+        field_uri: String,
+    },
+    IndexedFieldURI {
+        #[serde(rename = "@FieldURI")]
+        field_uri: String,
+
+        #[serde(rename = "@FieldIndex")]
+        field_index: String,
+    },
+    FieldURIOrConstant(FieldURIOrConstantElement),
+    Constant(Constant),
+}
+
+/// The single child of a `FieldURIOrConstant` element.
+#[derive(Deserialize)]
+struct FieldURIOrConstantElement {
+    #[serde(rename = "$value")]
+    operand: Box<Operand>,
+}
+
+impl Operand {
+    /// Gets the property identified by this operand, if any.
+    fn into_path(self) -> Option<PathToElement> {
+        match self {
+            Operand::ExtendedFieldURI(uri) => Some(PathToElement::from(uri)),
+            Operand::FieldURI { field_uri } => Some(PathToElement::FieldURI {
+                field_URI: field_uri,
+            }),
+            Operand::IndexedFieldURI {
+                field_uri,
+                field_index,
+            } => Some(PathToElement::IndexedFieldURI {
+                field_URI: field_uri,
+                field_index,
+            }),
+            Operand::FieldURIOrConstant(_) | Operand::Constant(_) => None,
+        }
+    }
+}
+
+/// Takes the property and the value it is compared against from the
+/// operands of a comparison.
+fn comparison_operands(
+    operands: Vec<Operand>,
+    restriction: &str,
+) -> Result<(PathToElement, Operand), String> {
+    let mut operands = operands.into_iter();
+    let (Some(path), Some(value), None) = (operands.next(), operands.next(), operands.next())
+    else {
+        return Err(format!("{restriction} must have exactly two operands"));
+    };
+
+    let path = path
+        .into_path()
+        .ok_or_else(|| format!("{restriction} must start with a property identifier"))?;
+
+    Ok((path, value))
+}
+
+impl Operands {
+    /// Converts the operands into a comparison between a property and a
+    /// value or another property.
+    fn into_field_equal_to(self, restriction: &str) -> Result<FieldEqualTo, String> {
+        let (path, value) = comparison_operands(self.operands, restriction)?;
+
+        let Operand::FieldURIOrConstant(FieldURIOrConstantElement { operand }) = value else {
+            return Err(format!(
+                "{restriction} must compare against FieldURIOrConstant"
+            ));
+        };
+
+        let value = match *operand {
+            Operand::Constant(constant) => FieldURIOrConstant::Constant(constant),
+            operand => FieldURIOrConstant::Path(operand.into_path().ok_or_else(|| {
+                format!("FieldURIOrConstant in {restriction} must have a single operand")
+            })?),
+        };
+
+        Ok(FieldEqualTo {
+            path,
+            FieldURIOrConstant: value,
+        })
+    }
+
+    /// Converts the operands into the property whose existence is checked.
+    fn into_path(self) -> Result<PathToElement, String> {
+        let mut operands = self.operands.into_iter();
+        match (operands.next(), operands.next()) {
+            (Some(operand), None) => operand
+                .into_path()
+                .ok_or_else(|| "Exists must contain a property identifier".to_string()),
+            _ => Err("Exists must have exactly one operand".to_string()),
+        }
+    }
+}
+
+impl TryFrom<RestrictionElement> for RestrictionType {
+    type Error = String;
+
+    fn try_from(element: RestrictionElement) -> Result<Self, Self::Error> {
+        let restriction_type = match element {
+            RestrictionElement::And(Restrictions { restrictions }) => {
+                RestrictionType::And(AndRestriction(wrap(restrictions)))
+            }
+            RestrictionElement::Or(Restrictions { restrictions }) => {
+                RestrictionType::Or(OrRestriction(wrap(restrictions)))
+            }
+            RestrictionElement::Not(restriction) => {
+                RestrictionType::Not(NotRestriction(Box::new(restriction)))
+            }
+            RestrictionElement::IsEqualTo(operands) => {
+                RestrictionType::IsEqualTo(operands.into_field_equal_to("IsEqualTo")?)
+            }
+            RestrictionElement::IsNotEqualTo(operands) => {
+                RestrictionType::IsNotEqualTo(operands.into_field_equal_to("IsNotEqualTo")?)
+            }
+            RestrictionElement::IsGreaterThan(operands) => {
+                RestrictionType::IsGreaterThan(operands.into_field_equal_to("IsGreaterThan")?)
+            }
+            RestrictionElement::IsGreaterThanOrEqualTo(operands) => {
+                RestrictionType::IsGreaterThanOrEqualTo(
+                    operands.into_field_equal_to("IsGreaterThanOrEqualTo")?,
+                )
+            }
+            RestrictionElement::IsLessThan(operands) => {
+                RestrictionType::IsLessThan(operands.into_field_equal_to("IsLessThan")?)
+            }
+            RestrictionElement::IsLessThanOrEqualTo(operands) => {
+                RestrictionType::IsLessThanOrEqualTo(
+                    operands.into_field_equal_to("IsLessThanOrEqualTo")?,
+                )
+            }
+            RestrictionElement::Contains(contains) => {
+                let (path, constant) = comparison_operands(contains.operands, "Contains")?;
+                let Operand::Constant(constant) = constant else {
+                    return Err("Contains must compare against a Constant".to_string());
+                };
+
+                RestrictionType::Contains(ContainsRestriction {
+                    containment_mode: contains.containment_mode,
+                    containment_comparison: contains.containment_comparison,
+                    path,
+                    constant,
+                })
+            }
+            RestrictionElement::Exists(operands) => RestrictionType::Exists(operands.into_path()?),
+        };
+
+        Ok(restriction_type)
+    }
+}
+
+/// Wraps each of the restrictions combined by an `And` or `Or` restriction.
+fn wrap(restrictions: Vec<RestrictionType>) -> Vec<Restriction> {
+    restrictions
+        .into_iter()
+        .map(|restriction_type| Restriction { restriction_type })
+        .collect()
+}
+
+impl<'de> Deserialize<'de> for RestrictionType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        RestrictionElement::deserialize(deserializer)?
+            .try_into()
+            .map_err(D::Error::custom)
+    }
+}
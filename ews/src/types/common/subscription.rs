@@ -0,0 +1,294 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use xml_struct::XmlSerialize;
+
+use crate::{response::ResponseCode, Notification, ResponseError};
+
+/// The identifier of a subscription to notifications.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/subscriptionid>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(transparent)]
+pub struct SubscriptionId(String);
+
+impl SubscriptionId {
+    /// Creates a subscription identifier from one returned by the server.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Gets the identifier, e.g. for persisting it.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the subscription identifier, returning the string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for SubscriptionId {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SubscriptionId {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl XmlSerialize for SubscriptionId {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        self.0.serialize_child_nodes(writer)
+    }
+}
+
+/// An opaque bookmark in the stream of events of a subscription.
+///
+/// A pull or push subscription can be recreated from the watermark of the
+/// last event processed, so that no events are missed.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/watermark>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(transparent)]
+pub struct Watermark(String);
+
+impl Watermark {
+    /// Creates a watermark from one returned by the server.
+    pub fn new(watermark: impl Into<String>) -> Self {
+        Self(watermark.into())
+    }
+
+    /// Gets the watermark, e.g. for persisting it.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes the watermark, returning the string.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl From<String> for Watermark {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Watermark {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl XmlSerialize for Watermark {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        self.0.serialize_child_nodes(writer)
+    }
+}
+
+/// Tracks the latest watermark of each active subscription, and detects
+/// subscriptions which must be recreated.
+///
+/// The tracker does not send requests itself: callers record new
+/// subscriptions with [`insert`], feed it each [`Notification`] received with
+/// [`process_notification`], and pass it errors reported for a subscription
+/// with [`process_error`], which indicates when to subscribe again.
+///
+/// [`insert`]: SubscriptionTracker::insert
+/// [`process_notification`]: SubscriptionTracker::process_notification
+/// [`process_error`]: SubscriptionTracker::process_error
+#[derive(Clone, Debug, Default)]
+pub struct SubscriptionTracker {
+    watermarks: HashMap<SubscriptionId, Option<Watermark>>,
+}
+
+/// An indication that a subscription was lost and must be recreated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Resubscribe {
+    /// The watermark from which the new subscription should resume, or `None`
+    /// if it should start from the current state of the mailbox.
+    pub watermark: Option<Watermark>,
+}
+
+impl SubscriptionTracker {
+    /// Creates a tracker with no subscriptions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a subscription, with the watermark returned when creating it
+    /// if any.
+    pub fn insert(&mut self, subscription_id: SubscriptionId, watermark: Option<Watermark>) {
+        self.watermarks.insert(subscription_id, watermark);
+    }
+
+    /// Stops tracking a subscription, e.g. after unsubscribing.
+    pub fn remove(&mut self, subscription_id: &SubscriptionId) -> Option<Watermark> {
+        self.watermarks.remove(subscription_id).flatten()
+    }
+
+    /// Determines whether a subscription is being tracked.
+    pub fn contains(&self, subscription_id: &SubscriptionId) -> bool {
+        self.watermarks.contains_key(subscription_id)
+    }
+
+    /// Gets the latest watermark of a subscription.
+    pub fn watermark(&self, subscription_id: &SubscriptionId) -> Option<&Watermark> {
+        self.watermarks.get(subscription_id)?.as_ref()
+    }
+
+    /// Records the watermark of the last event in a notification.
+    ///
+    /// Notifications for subscriptions which aren't tracked are ignored.
+    pub fn process_notification(&mut self, notification: &Notification) {
+        let Some(watermark) = self.watermarks.get_mut(&notification.subscription_id) else {
+            return;
+        };
+
+        if let Some(latest) = notification
+            .events
+            .iter()
+            .rev()
+            .find_map(|event| event.watermark())
+        {
+            *watermark = Some(latest.clone());
+        }
+    }
+
+    /// Processes an error reported for a subscription, determining whether
+    /// the subscription must be recreated.
+    ///
+    /// If the subscription is no longer usable, e.g. because it expired, it
+    /// stops being tracked and the watermark from which to resume is
+    /// returned. Returns `None` for other errors, which the caller should
+    /// handle as usual.
+    pub fn process_error(
+        &mut self,
+        subscription_id: &SubscriptionId,
+        error: &ResponseError,
+    ) -> Option<Resubscribe> {
+        if !error.is_subscription_lost() {
+            return None;
+        }
+
+        let watermark = self.remove(subscription_id);
+
+        // A rejected watermark can't be resumed from either.
+        let watermark = match error.response_code {
+            ResponseCode::ErrorInvalidWatermark => None,
+            _ => watermark,
+        };
+
+        Some(Resubscribe { watermark })
+    }
+}
+
+impl ResponseError {
+    /// Determines whether the error was caused by a subscription which no
+    /// longer exists or can no longer be used, in which case a new
+    /// subscription must be created.
+    pub fn is_subscription_lost(&self) -> bool {
+        matches!(
+            self.response_code,
+            ResponseCode::ErrorSubscriptionNotFound
+                | ResponseCode::ErrorExpiredSubscription
+                | ResponseCode::ErrorInvalidSubscription
+                | ResponseCode::ErrorSubscriptionUnsubscribed
+                | ResponseCode::ErrorInvalidWatermark
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        response::ResponseCode, Notification, NotificationEvent, ResponseError, StatusEvent,
+        SubscriptionId, Watermark,
+    };
+
+    use super::{Resubscribe, SubscriptionTracker};
+
+    fn error(response_code: ResponseCode) -> ResponseError {
+        ResponseError {
+            message_text: "The subscription is no longer valid.".to_string(),
+            response_code,
+            message_xml: None,
+        }
+    }
+
+    fn status(watermark: Option<&str>) -> NotificationEvent {
+        NotificationEvent::StatusEvent(StatusEvent {
+            watermark: watermark.map(Watermark::from),
+        })
+    }
+
+    #[test]
+    fn track_subscription_lifecycle() {
+        let id = SubscriptionId::from("FwBzdWJz");
+        let mut tracker = SubscriptionTracker::new();
+        tracker.insert(id.clone(), Some(Watermark::from("AQAAAA==")));
+
+        tracker.process_notification(&Notification {
+            subscription_id: id.clone(),
+            previous_watermark: Some(Watermark::from("AQAAAA==")),
+            more_events: Some(false),
+            events: vec![
+                status(Some("AQAAAB==")),
+                status(Some("AQAAAC==")),
+                status(None),
+            ],
+        });
+        assert_eq!(tracker.watermark(&id), Some(&Watermark::from("AQAAAC==")));
+
+        // Errors unrelated to the subscription are left to the caller.
+        assert_eq!(
+            tracker.process_error(&id, &error(ResponseCode::ErrorServerBusy)),
+            None
+        );
+        assert!(tracker.contains(&id));
+
+        assert_eq!(
+            tracker.process_error(&id, &error(ResponseCode::ErrorExpiredSubscription)),
+            Some(Resubscribe {
+                watermark: Some(Watermark::from("AQAAAC=="))
+            })
+        );
+        assert!(!tracker.contains(&id));
+    }
+
+    #[test]
+    fn invalid_watermark_resubscribes_without_watermark() {
+        let id = SubscriptionId::from("FwBzdWJz");
+        let mut tracker = SubscriptionTracker::new();
+        tracker.insert(id.clone(), Some(Watermark::from("AQAAAA==")));
+
+        assert_eq!(
+            tracker.process_error(&id, &error(ResponseCode::ErrorInvalidWatermark)),
+            Some(Resubscribe { watermark: None })
+        );
+    }
+}
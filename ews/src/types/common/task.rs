@@ -0,0 +1,123 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use serde::Deserialize;
+use xml_struct::XmlSerialize;
+
+use crate::{ArrayOfStrings, Body, DateTime, ExtendedProperty, Importance, ItemId, Recurrence};
+
+/// A task in a mailbox's to-do list.
+///
+/// Fields are declared in schema order, which Exchange requires when
+/// creating tasks.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/task>
+#[derive(Clone, Debug, Default, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct Task {
+    /// The task's Exchange identifier.
+    #[xml_struct(ns_prefix = "t")]
+    pub item_id: Option<ItemId>,
+
+    /// The Exchange class value of the item, e.g. `IPM.Task`.
+    #[xml_struct(ns_prefix = "t")]
+    pub item_class: Option<String>,
+
+    /// The subject of the task.
+    #[xml_struct(ns_prefix = "t")]
+    pub subject: Option<String>,
+
+    /// The body of the task.
+    #[xml_struct(ns_prefix = "t")]
+    pub body: Option<Body>,
+
+    /// A list of categories describing the task.
+    #[xml_struct(ns_prefix = "t")]
+    pub categories: Option<ArrayOfStrings>,
+
+    #[xml_struct(ns_prefix = "t")]
+    pub importance: Option<Importance>,
+
+    /// When the reminder for the task is due.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/reminderdueby>
+    #[xml_struct(ns_prefix = "t")]
+    pub reminder_due_by: Option<DateTime>,
+
+    /// Whether a reminder is set for the task.
+    #[xml_struct(ns_prefix = "t")]
+    pub reminder_is_set: Option<bool>,
+
+    /// Extended MAPI properties of the task.
+    #[xml_struct(ns_prefix = "t")]
+    pub extended_property: Option<Vec<ExtendedProperty>>,
+
+    /// When the task was completed.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/completedate>
+    #[xml_struct(ns_prefix = "t")]
+    pub complete_date: Option<DateTime>,
+
+    /// When the task is due.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/duedate>
+    #[xml_struct(ns_prefix = "t")]
+    pub due_date: Option<DateTime>,
+
+    /// Whether the task has been completed.
+    ///
+    /// This is computed by the server from the task's status.
+    #[xml_struct(ns_prefix = "t")]
+    pub is_complete: Option<bool>,
+
+    /// Whether the task recurs.
+    ///
+    /// This is computed by the server from the task's recurrence.
+    #[xml_struct(ns_prefix = "t")]
+    pub is_recurring: Option<bool>,
+
+    /// The recurrence of the task, which may use regeneration patterns.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/recurrence-taskrecurrencetype>
+    #[xml_struct(ns_prefix = "t")]
+    pub recurrence: Option<Recurrence>,
+
+    /// When work on the task starts.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/startdate>
+    #[xml_struct(ns_prefix = "t")]
+    pub start_date: Option<DateTime>,
+
+    /// The status of the task.
+    #[xml_struct(ns_prefix = "t")]
+    pub status: Option<TaskStatus>,
+}
+
+impl Task {
+    /// Creates a task with the given subject, leaving every other field
+    /// unset.
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: Some(subject.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// The status of a task.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/status>
+#[derive(Clone, Copy, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
+pub enum TaskStatus {
+    NotStarted,
+    InProgress,
+    Completed,
+    WaitingOnOthers,
+    Deferred,
+}
@@ -0,0 +1,252 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::io::{self, BufRead, Read};
+
+use quick_xml::{
+    events::{BytesText, Event},
+    Reader, Writer,
+};
+use serde::Deserialize;
+use time::{format_description::well_known::Iso8601, OffsetDateTime, PrimitiveDateTime};
+use xml_struct::XmlSerialize;
+
+use crate::{
+    get_server_time_zones::TimeZoneDefinition, get_user_availability::SerializableTimeZone,
+    soap::Header,
+};
+
+/// A reference to a time zone known to the server, such as the time zone in
+/// which a calendar item starts or ends.
+///
+/// Only the identifier is needed when creating items; the server resolves
+/// it to the full definition, as returned by [`GetServerTimeZones`].
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/starttimezone>
+///
+/// [`GetServerTimeZones`]: crate::get_server_time_zones::GetServerTimeZones
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TimeZoneReference {
+    /// The identifier of the time zone, e.g. `Eastern Standard Time`.
+    #[serde(rename = "@Id")]
+    #[xml_struct(attribute)]
+    pub id: String,
+
+    /// The display name of the time zone.
+    #[serde(rename = "@Name")]
+    #[xml_struct(attribute)]
+    pub name: Option<String>,
+}
+
+impl TimeZoneReference {
+    /// Creates a reference to the time zone with the given identifier.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: None,
+        }
+    }
+}
+
+impl From<&TimeZoneDefinition> for TimeZoneReference {
+    fn from(value: &TimeZoneDefinition) -> Self {
+        Self {
+            id: value.id.clone(),
+            name: Some(value.name.clone()),
+        }
+    }
+}
+
+/// The time zone of a calendar item, as understood by Exchange Server 2007.
+///
+/// Later versions use [`TimeZoneReference`] for the start and end time zones
+/// instead, but still accept this element.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/meetingtimezone>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "PascalCase")]
+pub struct MeetingTimeZone {
+    /// The identifier of the time zone, e.g. `Eastern Standard Time`.
+    #[serde(rename = "@TimeZoneName")]
+    #[xml_struct(attribute)]
+    pub time_zone_name: Option<String>,
+
+    /// The offset from UTC of standard time in the time zone, as an
+    /// `xs:duration`, e.g. `PT8H` for Pacific Standard Time.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/baseoffset>
+    #[xml_struct(ns_prefix = "t")]
+    pub base_offset: Option<String>,
+}
+
+impl MeetingTimeZone {
+    /// Creates a time zone given by its identifier, leaving the server to
+    /// look up its offsets.
+    pub fn new(time_zone_name: impl Into<String>) -> Self {
+        Self {
+            time_zone_name: Some(time_zone_name.into()),
+            base_offset: None,
+        }
+    }
+}
+
+/// The time zone in which the server interprets times which have no offset
+/// from UTC, and in which it may return them.
+///
+/// When deserializing a time without an offset, this crate otherwise assumes
+/// it is in UTC, which shifts it whenever the time zone of the mailbox
+/// differs. The server does not include the time zone in its responses, so
+/// the same context should be used both to send the `TimeZoneContext`
+/// header with a request and to interpret its response, by passing it to
+/// e.g. [`Envelope::from_xml_document`]; the clients in [`client`] do this
+/// for each request once configured with one.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/timezonecontext>
+///
+/// [`client`]: crate::client
+/// [`Envelope::from_xml_document`]: crate::soap::Envelope::from_xml_document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeZoneContext {
+    /// The time zone sent to the server.
+    pub time_zone: TimeZoneReference,
+
+    /// The rules of the time zone, used to interpret times without an
+    /// offset.
+    ///
+    /// The offset of each such time is looked up separately, so that times
+    /// on either side of a daylight saving time transition are interpreted
+    /// with the offset in effect at that time.
+    pub rules: SerializableTimeZone,
+}
+
+impl TimeZoneContext {
+    /// Creates a context for the given time zone and its rules, as created
+    /// with e.g. [`SerializableTimeZone::from_fixed_offset`].
+    pub fn new(time_zone: TimeZoneReference, rules: SerializableTimeZone) -> Self {
+        Self { time_zone, rules }
+    }
+
+    /// Gets the SOAP header telling the server to use this time zone.
+    pub fn header(&self) -> Header {
+        Header::TimeZoneContext {
+            time_zone_definition: self.time_zone.clone(),
+        }
+    }
+
+    /// Gives each time without an offset in the given document the offset
+    /// in effect in this time zone at that time, so that it is deserialized
+    /// in this time zone.
+    ///
+    /// Times are recognized by their text alone, so text which consists of
+    /// nothing but a time without an offset, such as the value of a string
+    /// extended property, is given an offset too. Documents which aren't
+    /// well-formed are returned unchanged, leaving deserialization to report
+    /// the error.
+    pub(crate) fn resolve_naive_times(&self, document: &[u8]) -> Vec<u8> {
+        let mut resolved = Vec::with_capacity(document.len());
+        match NaiveTimes::new(document, &self.rules).read_to_end(&mut resolved) {
+            Ok(_) => resolved,
+            Err(_) => document.to_vec(),
+        }
+    }
+}
+
+/// Reads a document from the inner reader, giving each time without an
+/// offset the offset in effect in a time zone at that time.
+///
+/// See [`TimeZoneContext::resolve_naive_times`].
+pub(crate) struct NaiveTimes<'a, R> {
+    reader: Reader<R>,
+    rules: &'a SerializableTimeZone,
+    buf: Vec<u8>,
+    pending: Vec<u8>,
+    position: usize,
+    done: bool,
+}
+
+impl<'a, R: BufRead> NaiveTimes<'a, R> {
+    pub(crate) fn new(reader: R, rules: &'a SerializableTimeZone) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            rules,
+            buf: Vec::new(),
+            pending: Vec::new(),
+            position: 0,
+            done: false,
+        }
+    }
+
+    /// Reads the next event into the pending output, with any time without
+    /// an offset resolved.
+    fn fill(&mut self) -> Result<(), quick_xml::Error> {
+        self.buf.clear();
+        self.pending.clear();
+        self.position = 0;
+
+        let event = match self.reader.read_event_into(&mut self.buf)? {
+            Event::Eof => {
+                self.done = true;
+                return Ok(());
+            }
+            Event::Text(text) => match resolve_naive_time(&text, self.rules) {
+                Some(resolved) => Event::Text(BytesText::from_escaped(resolved)),
+                None => Event::Text(text),
+            },
+            event => event,
+        };
+        Writer::new(&mut self.pending).write_event(event)?;
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for NaiveTimes<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = out.len().min(available.len());
+        out[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for NaiveTimes<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.position == self.pending.len() && !self.done {
+            self.fill()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        }
+
+        Ok(&self.pending[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.pending.len());
+    }
+}
+
+/// Gives the given text the offset in effect in the given time zone if it
+/// is a time without an offset.
+fn resolve_naive_time(text: &[u8], rules: &SerializableTimeZone) -> Option<String> {
+    let text = std::str::from_utf8(text).ok()?;
+    let local = PrimitiveDateTime::parse(text, &Iso8601::DEFAULT).ok()?;
+    if OffsetDateTime::parse(text, &Iso8601::DEFAULT).is_ok() {
+        return None;
+    }
+
+    let offset = rules.utc_offset_at_local(local);
+    let (hours, minutes, _) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+
+    Some(format!(
+        "{text}{sign}{:02}:{:02}",
+        hours.unsigned_abs(),
+        minutes.unsigned_abs()
+    ))
+}
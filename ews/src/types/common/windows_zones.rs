@@ -0,0 +1,283 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use time::UtcOffset;
+
+use crate::{get_server_time_zones::TimeZoneDefinition, TimeZoneReference};
+
+/// A Windows time zone, as identified by Exchange, and its equivalent in the
+/// IANA time zone database.
+///
+/// Exchange identifies time zones by their Windows identifier, e.g. `Eastern
+/// Standard Time`, in [`GetServerTimeZones`] responses and calendar items,
+/// whereas most other software uses IANA names such as `America/New_York`.
+/// The mapping follows the default (territory `001`) mapping of the
+/// [CLDR `windowsZones` data](https://github.com/unicode-org/cldr/blob/main/common/supplemental/windowsZones.xml),
+/// so the IANA names are CLDR's canonical identifiers, some of which are
+/// links in the IANA database, e.g. `Asia/Calcutta` for `Asia/Kolkata`.
+///
+/// [`GetServerTimeZones`]: crate::get_server_time_zones::GetServerTimeZones
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowsZone {
+    /// The Windows identifier of the time zone, e.g. `Eastern Standard Time`.
+    pub windows_id: &'static str,
+
+    /// The IANA name of the time zone, e.g. `America/New_York`.
+    pub iana_name: &'static str,
+
+    /// The offset from UTC of standard time in the time zone, i.e. outside
+    /// of daylight saving time.
+    pub standard_offset: UtcOffset,
+}
+
+impl WindowsZone {
+    /// Looks up the time zone with the given Windows identifier.
+    ///
+    /// Returns `None` for identifiers without a CLDR mapping, e.g. custom
+    /// time zones defined by a client.
+    pub fn from_windows_id(windows_id: &str) -> Option<Self> {
+        WINDOWS_ZONES
+            .binary_search_by(|(id, _, _)| (*id).cmp(windows_id))
+            .ok()
+            .map(|index| Self::from_entry(WINDOWS_ZONES[index]))
+    }
+
+    /// Looks up the Windows time zone which maps to the given IANA name.
+    ///
+    /// Only CLDR's canonical identifiers are recognized, e.g.
+    /// `Asia/Calcutta` but not `Asia/Kolkata`.
+    pub fn from_iana_name(iana_name: &str) -> Option<Self> {
+        WINDOWS_ZONES
+            .iter()
+            .find(|(_, name, _)| *name == iana_name)
+            .map(|entry| Self::from_entry(*entry))
+    }
+
+    fn from_entry(
+        (windows_id, iana_name, offset_minutes): (&'static str, &'static str, i16),
+    ) -> Self {
+        Self {
+            windows_id,
+            iana_name,
+            standard_offset: UtcOffset::from_whole_seconds(i32::from(offset_minutes) * 60)
+                .expect("time zone offsets in the table should be valid"),
+        }
+    }
+}
+
+impl TimeZoneDefinition {
+    /// Maps the time zone to its IANA equivalent.
+    ///
+    /// Returns `None` if the identifier of the time zone has no CLDR
+    /// mapping.
+    pub fn windows_zone(&self) -> Option<WindowsZone> {
+        WindowsZone::from_windows_id(&self.id)
+    }
+}
+
+impl TimeZoneReference {
+    /// Maps the referenced time zone to its IANA equivalent.
+    ///
+    /// Returns `None` if the identifier of the time zone has no CLDR
+    /// mapping.
+    pub fn windows_zone(&self) -> Option<WindowsZone> {
+        WindowsZone::from_windows_id(&self.id)
+    }
+}
+
+/// The Windows identifier, IANA name and standard offset from UTC in minutes
+/// of each time zone, sorted by Windows identifier.
+const WINDOWS_ZONES: &[(&str, &str, i16)] = &[
+    ("AUS Central Standard Time", "Australia/Darwin", 570),
+    ("AUS Eastern Standard Time", "Australia/Sydney", 600),
+    ("Afghanistan Standard Time", "Asia/Kabul", 270),
+    ("Alaskan Standard Time", "America/Anchorage", -540),
+    ("Aleutian Standard Time", "America/Adak", -600),
+    ("Altai Standard Time", "Asia/Barnaul", 420),
+    ("Arab Standard Time", "Asia/Riyadh", 180),
+    ("Arabian Standard Time", "Asia/Dubai", 240),
+    ("Arabic Standard Time", "Asia/Baghdad", 180),
+    ("Argentina Standard Time", "America/Buenos_Aires", -180),
+    ("Astrakhan Standard Time", "Europe/Astrakhan", 240),
+    ("Atlantic Standard Time", "America/Halifax", -240),
+    ("Aus Central W. Standard Time", "Australia/Eucla", 525),
+    ("Azerbaijan Standard Time", "Asia/Baku", 240),
+    ("Azores Standard Time", "Atlantic/Azores", -60),
+    ("Bahia Standard Time", "America/Bahia", -180),
+    ("Bangladesh Standard Time", "Asia/Dhaka", 360),
+    ("Belarus Standard Time", "Europe/Minsk", 180),
+    ("Bougainville Standard Time", "Pacific/Bougainville", 660),
+    ("Canada Central Standard Time", "America/Regina", -360),
+    ("Cape Verde Standard Time", "Atlantic/Cape_Verde", -60),
+    ("Caucasus Standard Time", "Asia/Yerevan", 240),
+    ("Cen. Australia Standard Time", "Australia/Adelaide", 570),
+    ("Central America Standard Time", "America/Guatemala", -360),
+    ("Central Asia Standard Time", "Asia/Bishkek", 360),
+    ("Central Brazilian Standard Time", "America/Cuiaba", -240),
+    ("Central Europe Standard Time", "Europe/Budapest", 60),
+    ("Central European Standard Time", "Europe/Warsaw", 60),
+    ("Central Pacific Standard Time", "Pacific/Guadalcanal", 660),
+    ("Central Standard Time", "America/Chicago", -360),
+    (
+        "Central Standard Time (Mexico)",
+        "America/Mexico_City",
+        -360,
+    ),
+    ("Chatham Islands Standard Time", "Pacific/Chatham", 765),
+    ("China Standard Time", "Asia/Shanghai", 480),
+    ("Cuba Standard Time", "America/Havana", -300),
+    ("Dateline Standard Time", "Etc/GMT+12", -720),
+    ("E. Africa Standard Time", "Africa/Nairobi", 180),
+    ("E. Australia Standard Time", "Australia/Brisbane", 600),
+    ("E. Europe Standard Time", "Europe/Chisinau", 120),
+    ("E. South America Standard Time", "America/Sao_Paulo", -180),
+    ("Easter Island Standard Time", "Pacific/Easter", -360),
+    ("Eastern Standard Time", "America/New_York", -300),
+    ("Eastern Standard Time (Mexico)", "America/Cancun", -300),
+    ("Egypt Standard Time", "Africa/Cairo", 120),
+    ("Ekaterinburg Standard Time", "Asia/Yekaterinburg", 300),
+    ("FLE Standard Time", "Europe/Kiev", 120),
+    ("Fiji Standard Time", "Pacific/Fiji", 720),
+    ("GMT Standard Time", "Europe/London", 0),
+    ("GTB Standard Time", "Europe/Bucharest", 120),
+    ("Georgian Standard Time", "Asia/Tbilisi", 240),
+    ("Greenland Standard Time", "America/Godthab", -120),
+    ("Greenwich Standard Time", "Atlantic/Reykjavik", 0),
+    ("Haiti Standard Time", "America/Port-au-Prince", -300),
+    ("Hawaiian Standard Time", "Pacific/Honolulu", -600),
+    ("India Standard Time", "Asia/Calcutta", 330),
+    ("Iran Standard Time", "Asia/Tehran", 210),
+    ("Israel Standard Time", "Asia/Jerusalem", 120),
+    ("Jordan Standard Time", "Asia/Amman", 180),
+    ("Kaliningrad Standard Time", "Europe/Kaliningrad", 120),
+    ("Korea Standard Time", "Asia/Seoul", 540),
+    ("Libya Standard Time", "Africa/Tripoli", 120),
+    ("Line Islands Standard Time", "Pacific/Kiritimati", 840),
+    ("Lord Howe Standard Time", "Australia/Lord_Howe", 630),
+    ("Magadan Standard Time", "Asia/Magadan", 660),
+    ("Magallanes Standard Time", "America/Punta_Arenas", -180),
+    ("Marquesas Standard Time", "Pacific/Marquesas", -570),
+    ("Mauritius Standard Time", "Indian/Mauritius", 240),
+    ("Middle East Standard Time", "Asia/Beirut", 120),
+    ("Montevideo Standard Time", "America/Montevideo", -180),
+    ("Morocco Standard Time", "Africa/Casablanca", 60),
+    ("Mountain Standard Time", "America/Denver", -420),
+    ("Mountain Standard Time (Mexico)", "America/Mazatlan", -420),
+    ("Myanmar Standard Time", "Asia/Rangoon", 390),
+    ("N. Central Asia Standard Time", "Asia/Novosibirsk", 420),
+    ("Namibia Standard Time", "Africa/Windhoek", 120),
+    ("Nepal Standard Time", "Asia/Katmandu", 345),
+    ("New Zealand Standard Time", "Pacific/Auckland", 720),
+    ("Newfoundland Standard Time", "America/St_Johns", -210),
+    ("Norfolk Standard Time", "Pacific/Norfolk", 660),
+    ("North Asia East Standard Time", "Asia/Irkutsk", 480),
+    ("North Asia Standard Time", "Asia/Krasnoyarsk", 420),
+    ("North Korea Standard Time", "Asia/Pyongyang", 540),
+    ("Omsk Standard Time", "Asia/Omsk", 360),
+    ("Pacific SA Standard Time", "America/Santiago", -240),
+    ("Pacific Standard Time", "America/Los_Angeles", -480),
+    ("Pacific Standard Time (Mexico)", "America/Tijuana", -480),
+    ("Pakistan Standard Time", "Asia/Karachi", 300),
+    ("Paraguay Standard Time", "America/Asuncion", -240),
+    ("Qyzylorda Standard Time", "Asia/Qyzylorda", 300),
+    ("Romance Standard Time", "Europe/Paris", 60),
+    ("Russia Time Zone 10", "Asia/Srednekolymsk", 660),
+    ("Russia Time Zone 11", "Asia/Kamchatka", 720),
+    ("Russia Time Zone 3", "Europe/Samara", 240),
+    ("Russian Standard Time", "Europe/Moscow", 180),
+    ("SA Eastern Standard Time", "America/Cayenne", -180),
+    ("SA Pacific Standard Time", "America/Bogota", -300),
+    ("SA Western Standard Time", "America/La_Paz", -240),
+    ("SE Asia Standard Time", "Asia/Bangkok", 420),
+    ("Saint Pierre Standard Time", "America/Miquelon", -180),
+    ("Sakhalin Standard Time", "Asia/Sakhalin", 660),
+    ("Samoa Standard Time", "Pacific/Apia", 780),
+    ("Sao Tome Standard Time", "Africa/Sao_Tome", 0),
+    ("Saratov Standard Time", "Europe/Saratov", 240),
+    ("Singapore Standard Time", "Asia/Singapore", 480),
+    ("South Africa Standard Time", "Africa/Johannesburg", 120),
+    ("South Sudan Standard Time", "Africa/Juba", 120),
+    ("Sri Lanka Standard Time", "Asia/Colombo", 330),
+    ("Sudan Standard Time", "Africa/Khartoum", 120),
+    ("Syria Standard Time", "Asia/Damascus", 180),
+    ("Taipei Standard Time", "Asia/Taipei", 480),
+    ("Tasmania Standard Time", "Australia/Hobart", 600),
+    ("Tocantins Standard Time", "America/Araguaina", -180),
+    ("Tokyo Standard Time", "Asia/Tokyo", 540),
+    ("Tomsk Standard Time", "Asia/Tomsk", 420),
+    ("Tonga Standard Time", "Pacific/Tongatapu", 780),
+    ("Transbaikal Standard Time", "Asia/Chita", 540),
+    ("Turkey Standard Time", "Europe/Istanbul", 180),
+    ("Turks And Caicos Standard Time", "America/Grand_Turk", -300),
+    ("US Eastern Standard Time", "America/Indianapolis", -300),
+    ("US Mountain Standard Time", "America/Phoenix", -420),
+    ("UTC", "Etc/UTC", 0),
+    ("UTC+12", "Etc/GMT-12", 720),
+    ("UTC+13", "Etc/GMT-13", 780),
+    ("UTC-02", "Etc/GMT+2", -120),
+    ("UTC-08", "Etc/GMT+8", -480),
+    ("UTC-09", "Etc/GMT+9", -540),
+    ("UTC-11", "Etc/GMT+11", -660),
+    ("Ulaanbaatar Standard Time", "Asia/Ulaanbaatar", 480),
+    ("Venezuela Standard Time", "America/Caracas", -240),
+    ("Vladivostok Standard Time", "Asia/Vladivostok", 600),
+    ("Volgograd Standard Time", "Europe/Volgograd", 180),
+    ("W. Australia Standard Time", "Australia/Perth", 480),
+    ("W. Central Africa Standard Time", "Africa/Lagos", 60),
+    ("W. Europe Standard Time", "Europe/Berlin", 60),
+    ("W. Mongolia Standard Time", "Asia/Hovd", 420),
+    ("West Asia Standard Time", "Asia/Tashkent", 300),
+    ("West Bank Standard Time", "Asia/Hebron", 120),
+    ("West Pacific Standard Time", "Pacific/Port_Moresby", 600),
+    ("Yakutsk Standard Time", "Asia/Yakutsk", 540),
+    ("Yukon Standard Time", "America/Whitehorse", -420),
+];
+
+#[cfg(test)]
+mod tests {
+    use time::UtcOffset;
+
+    use crate::{get_server_time_zones::TimeZoneDefinition, TimeZoneReference};
+
+    use super::{WindowsZone, WINDOWS_ZONES};
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(WINDOWS_ZONES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn map_windows_zones() {
+        let definition = TimeZoneDefinition {
+            id: "Eastern Standard Time".to_string(),
+            name: "(UTC-05:00) Eastern Time (US & Canada)".to_string(),
+            periods: None,
+            transitions_groups: None,
+            transitions: None,
+        };
+        assert_eq!(
+            definition.windows_zone(),
+            Some(WindowsZone {
+                windows_id: "Eastern Standard Time",
+                iana_name: "America/New_York",
+                standard_offset: UtcOffset::from_hms(-5, 0, 0).unwrap(),
+            })
+        );
+
+        let india = TimeZoneReference::new("India Standard Time")
+            .windows_zone()
+            .expect("time zone should be mapped");
+        assert_eq!(india.iana_name, "Asia/Calcutta");
+        assert_eq!(
+            india.standard_offset,
+            UtcOffset::from_hms(5, 30, 0).unwrap()
+        );
+
+        assert_eq!(
+            WindowsZone::from_iana_name("Europe/Berlin").map(|zone| zone.windows_id),
+            Some("W. Europe Standard Time")
+        );
+        assert_eq!(WindowsZone::from_windows_id("Customized Time Zone"), None);
+    }
+}
@@ -2,22 +2,42 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use xml_struct::XmlSerialize;
 
-use crate::{CopyMoveItemData, ItemResponseMessage, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, BaseItemId, BatchOperation, CopyMoveItemData, Error, ItemResponseMessage,
+    MESSAGES_NS_URI,
+};
 
 /// A request to copy one or more Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/copyitem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(ItemResponseMessage)]
+#[operation_response(ItemResponseMessage, validate = validate)]
 pub struct CopyItem {
     #[xml_struct(flatten)]
     pub inner: CopyMoveItemData,
 }
 
+impl BatchOperation for CopyItem {
+    type Input = BaseItemId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.inner.item_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.inner.item_ids
+    }
+}
+
+/// Checks a [`CopyItem`] operation before it is sent.
+fn validate(operation: &CopyItem) -> Result<(), Error> {
+    require_non_empty("CopyItem", "item_ids", &operation.inner.item_ids)
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
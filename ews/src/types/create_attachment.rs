@@ -2,23 +2,33 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use std::fmt;
+
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{AttachmentId, BaseItemId, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, AttachmentId, AttachmentItem, BatchOperation, Error, ItemId, Redacted,
+    MESSAGES_NS_URI,
+};
 
 /// A request to create one or more attachments on an Exchange item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createattachment>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(CreateAttachmentResponseMessage)]
+#[operation_response(CreateAttachmentResponseMessage, validate = validate)]
 pub struct CreateAttachment {
     /// The identifier of the parent Exchange store item to which the attachments will be added.
     ///
+    /// Unlike the `ItemIds` of other operations, `ParentItemId` carries the
+    /// `Id` and `ChangeKey` attributes itself rather than wrapping an
+    /// `ItemId` element, so this is an [`ItemId`] rather than a
+    /// [`BaseItemId`](crate::BaseItemId).
+    ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/parentitemid>
-    pub parent_item_id: BaseItemId,
+    pub parent_item_id: ItemId,
 
     /// The attachments to create.
     ///
@@ -26,8 +36,23 @@ pub struct CreateAttachment {
     pub attachments: Vec<NewAttachment>,
 }
 
+impl BatchOperation for CreateAttachment {
+    type Input = NewAttachment;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.attachments
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.attachments
+    }
+}
+
 /// An attachment to be created, without an existing attachment ID.
-#[derive(Clone, Debug, XmlSerialize)]
+// Attachments are created rarely and in small numbers, so the size of item
+// attachments isn't worth an allocation.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, XmlSerialize)]
 #[xml_struct(variant_ns_prefix = "t")]
 pub enum NewAttachment {
     /// An attachment containing an Exchange item.
@@ -35,23 +60,28 @@ pub enum NewAttachment {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/itemattachment>
     ItemAttachment {
         /// The name of the attachment.
+        #[xml_struct(ns_prefix = "t")]
         name: String,
 
         /// The MIME content type of the attachment.
+        #[xml_struct(ns_prefix = "t")]
         content_type: Option<String>,
 
         /// The content ID value.
+        #[xml_struct(ns_prefix = "t")]
         content_id: Option<String>,
 
         /// The content location.
+        #[xml_struct(ns_prefix = "t")]
         content_location: Option<String>,
 
         /// Whether the attachment appears inline within the parent item.
+        #[xml_struct(ns_prefix = "t")]
         is_inline: Option<bool>,
 
         /// The attached item content.
         #[xml_struct(flatten)]
-        item: AttachmentItemContent,
+        item: AttachmentItem,
     },
 
     /// An attachment containing a file.
@@ -59,95 +89,100 @@ pub enum NewAttachment {
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/fileattachment>
     FileAttachment {
         /// The name of the attachment.
+        #[xml_struct(ns_prefix = "t")]
         name: String,
 
         /// The MIME content type of the attachment.
+        #[xml_struct(ns_prefix = "t")]
         content_type: Option<String>,
 
         /// The content ID value.
+        #[xml_struct(ns_prefix = "t")]
         content_id: Option<String>,
 
         /// The content location.
+        #[xml_struct(ns_prefix = "t")]
         content_location: Option<String>,
 
         /// Whether the attachment appears inline within the parent item.
+        #[xml_struct(ns_prefix = "t")]
         is_inline: Option<bool>,
 
         /// Whether this is a contact photo.
+        #[xml_struct(ns_prefix = "t")]
         is_contact_photo: Option<bool>,
 
         /// The binary content of the file (base64 encoded).
+        #[xml_struct(ns_prefix = "t")]
         content: String,
     },
 }
 
-/// Content for item attachments in create requests.
-#[derive(Clone, Debug, XmlSerialize)]
-#[xml_struct(variant_ns_prefix = "t")]
-pub enum AttachmentItemContent {
-    /// A message item to attach.
-    Message {
-        /// The subject of the message.
-        subject: Option<String>,
-
-        /// The body of the message.
-        body: Option<MessageBody>,
-
-        /// The sender of the message.
-        from: Option<SingleRecipient>,
-
-        /// The primary recipients.
-        to_recipients: Option<Vec<EmailAddressType>>,
-
-        /// The carbon copy recipients.
-        cc_recipients: Option<Vec<EmailAddressType>>,
-
-        /// The blind carbon copy recipients.
-        bcc_recipients: Option<Vec<EmailAddressType>>,
-    },
+// The content of file attachments is redacted so that logging a request
+// doesn't leak it.
+impl fmt::Debug for NewAttachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NewAttachment::ItemAttachment {
+                name,
+                content_type,
+                content_id,
+                content_location,
+                is_inline,
+                item,
+            } => f
+                .debug_struct("ItemAttachment")
+                .field("name", name)
+                .field("content_type", content_type)
+                .field("content_id", content_id)
+                .field("content_location", content_location)
+                .field("is_inline", is_inline)
+                .field("item", item)
+                .finish(),
+            NewAttachment::FileAttachment {
+                name,
+                content_type,
+                content_id,
+                content_location,
+                is_inline,
+                is_contact_photo,
+                content,
+            } => f
+                .debug_struct("FileAttachment")
+                .field("name", name)
+                .field("content_type", content_type)
+                .field("content_id", content_id)
+                .field("content_location", content_location)
+                .field("is_inline", is_inline)
+                .field("is_contact_photo", is_contact_photo)
+                .field("content", &Redacted::from(content.as_str()))
+                .finish(),
+        }
+    }
 }
 
+/// Content for item attachments in create requests.
+#[deprecated(note = "use `AttachmentItem` instead")]
+pub type AttachmentItemContent = AttachmentItem;
+
 /// A message body for attachment items.
-#[derive(Clone, Debug, XmlSerialize)]
-pub struct MessageBody {
-    /// The type of the body content.
-    #[xml_struct(attribute)]
-    pub body_type: BodyTypeValue,
-
-    /// The body content.
-    #[xml_struct(flatten)]
-    pub content: Option<String>,
-}
+#[deprecated(note = "use `Body` instead")]
+pub type MessageBody = crate::Body;
 
 /// The type of body content.
-#[derive(Clone, Debug, XmlSerialize)]
-#[xml_struct(text)]
-pub enum BodyTypeValue {
-    HTML,
-    Text,
-}
+#[deprecated(note = "use `BodyType` instead")]
+pub type BodyTypeValue = crate::BodyType;
 
 /// A single recipient for message items.
-#[derive(Clone, Debug, XmlSerialize)]
-pub struct SingleRecipient {
-    /// The mailbox information.
-    pub mailbox: EmailAddressType,
-}
+#[deprecated(note = "use `Recipient` instead")]
+pub type SingleRecipient = crate::Recipient;
 
 /// An email address.
-#[derive(Clone, Debug, XmlSerialize)]
-pub struct EmailAddressType {
-    /// The display name.
-    pub name: Option<String>,
-
-    /// The email address.
-    pub email_address: String,
-
-    /// The routing type (typically "SMTP").
-    pub routing_type: Option<String>,
-}
+#[deprecated(note = "use `Mailbox` instead")]
+pub type EmailAddressType = crate::Mailbox;
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct CreateAttachmentResponseMessage {
     /// The created attachments.
@@ -156,6 +191,7 @@ pub struct CreateAttachmentResponseMessage {
 
 /// A collection of attachments created by CreateAttachment.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct CreatedAttachments {
     #[serde(rename = "$value", default)]
@@ -164,6 +200,7 @@ pub struct CreatedAttachments {
 
 /// An attachment that was created, containing only its ID.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub enum CreatedAttachment {
     /// A created item attachment.
     #[serde(rename_all = "PascalCase")]
@@ -180,16 +217,57 @@ pub enum CreatedAttachment {
     },
 }
 
+/// Checks a [`CreateAttachment`] operation before it is sent.
+fn validate(operation: &CreateAttachment) -> Result<(), Error> {
+    require_non_empty("CreateAttachment", "attachments", &operation.attachments)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{test_utils::assert_deserialized_content, AttachmentId, ResponseClass};
+    use crate::{
+        test_utils::{assert_deserialized_content, assert_serialized_content},
+        ArrayOfRecipients, AttachmentId, AttachmentItem, Body, ItemId, Message, Recipient,
+        ResponseClass,
+    };
 
     use super::{
-        CreateAttachmentResponse, CreateAttachmentResponseMessage, CreatedAttachment,
-        CreatedAttachments,
+        CreateAttachment, CreateAttachmentResponse, CreateAttachmentResponseMessage,
+        CreatedAttachment, CreatedAttachments, NewAttachment,
     };
     use crate::ResponseMessages;
 
+    #[test]
+    fn test_serialize_item_attachment() {
+        let create_attachment = CreateAttachment::new(
+            ItemId::new("AAMkADk="),
+            vec![NewAttachment::ItemAttachment {
+                name: "Forwarded".to_string(),
+                content_type: None,
+                content_id: None,
+                content_location: None,
+                is_inline: None,
+                item: AttachmentItem::Message(Message {
+                    subject: Some("Hello".to_string()),
+                    body: Some(Body::text("Hi there")),
+                    to_recipients: Some(ArrayOfRecipients(vec![Recipient::new(
+                        "jane@example.com",
+                    )])),
+                    ..Default::default()
+                }),
+            }],
+        );
+
+        let expected = concat!(
+            r#"<CreateAttachment xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+            r#"<ParentItemId Id="AAMkADk="/><Attachments><t:ItemAttachment><t:Name>Forwarded</t:Name>"#,
+            r#"<t:Message><t:Subject>Hello</t:Subject><t:Body BodyType="Text">Hi there</t:Body>"#,
+            r#"<t:ToRecipients><t:Mailbox><t:EmailAddress>jane@example.com</t:EmailAddress></t:Mailbox></t:ToRecipients>"#,
+            r#"</t:Message></t:ItemAttachment></Attachments></CreateAttachment>"#,
+        );
+
+        assert_serialized_content(&create_attachment, "CreateAttachment", expected);
+    }
+
     #[test]
     fn test_deserialize_create_attachment_response() {
         let content = r#"<CreateAttachmentResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages"
@@ -2,18 +2,134 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, Folder, FolderResponseMessage, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, BaseFolderId, BatchOperation, DistinguishedFolderName, Error, Folder,
+    FolderResponseMessage, SearchParameters, MESSAGES_NS_URI,
+};
 
 /// A request to create a new folder.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createfolder>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(FolderResponseMessage)]
+#[operation_response(FolderResponseMessage, validate = validate)]
 pub struct CreateFolder {
     pub parent_folder_id: BaseFolderId,
     pub folders: Vec<Folder>,
 }
+
+impl CreateFolder {
+    /// Creates a request to create a search folder, which finds the items
+    /// matching the given parameters, in the mailbox's search folders.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/how-to-work-with-search-folders-by-using-ews-in-exchange>
+    pub fn search_folder(
+        display_name: impl Into<String>,
+        search_parameters: SearchParameters,
+    ) -> Self {
+        Self {
+            parent_folder_id: DistinguishedFolderName::SearchFolders.into(),
+            folders: vec![Folder::new_search_folder_with_parameters(
+                display_name,
+                search_parameters,
+            )],
+        }
+    }
+}
+
+impl BatchOperation for CreateFolder {
+    type Input = Folder;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.folders
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.folders
+    }
+}
+
+/// Checks a [`CreateFolder`] operation before it is sent.
+fn validate(operation: &CreateFolder) -> Result<(), Error> {
+    require_non_empty("CreateFolder", "folders", &operation.folders)?;
+
+    operation
+        .folders
+        .iter()
+        .try_for_each(|folder| match folder {
+            // The server can't create a search folder without knowing what to
+            // search for.
+            Folder::SearchFolder {
+                search_parameters, ..
+            } => search_parameters
+                .as_ref()
+                .ok_or_else(|| Error::InvalidRequest {
+                    operation: "CreateFolder",
+                    reason: "search folders require `search_parameters`".to_string(),
+                })?
+                .validate("CreateFolder"),
+            _ => Ok(()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        restriction::field, test_utils::assert_serialized_content, BaseFolderId, Error, Folder,
+        FolderProperties, Operation, SearchFolderTraversal, SearchParameters,
+    };
+
+    use super::CreateFolder;
+
+    #[test]
+    fn serialize_create_search_folder() {
+        let search_parameters = SearchParameters::new(
+            field("message:IsRead").eq(false),
+            vec![BaseFolderId::msg_folder_root()],
+        )
+        .with_traversal(SearchFolderTraversal::Deep);
+        let create_folder = CreateFolder::search_folder("Unread mail", search_parameters);
+
+        let expected = concat!(
+            r#"<CreateFolder xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+            r#"<ParentFolderId><t:DistinguishedFolderId Id="searchfolders"/></ParentFolderId>"#,
+            r#"<Folders><t:SearchFolder><t:DisplayName>Unread mail</t:DisplayName>"#,
+            r#"<t:SearchParameters Traversal="Deep"><t:Restriction><t:IsEqualTo><t:FieldURI FieldURI="message:IsRead"/>"#,
+            r#"<t:FieldURIOrConstant><t:Constant Value="false"/></t:FieldURIOrConstant></t:IsEqualTo></t:Restriction>"#,
+            r#"<t:BaseFolderIds><t:DistinguishedFolderId Id="msgfolderroot"/></t:BaseFolderIds></t:SearchParameters>"#,
+            r#"</t:SearchFolder></Folders></CreateFolder>"#,
+        );
+
+        assert_serialized_content(&create_folder, "CreateFolder", expected);
+        create_folder
+            .validate()
+            .expect("search folder with parameters should be valid");
+    }
+
+    #[test]
+    fn validate_search_folder_parameters() {
+        let without_parameters = CreateFolder {
+            parent_folder_id: BaseFolderId::msg_folder_root(),
+            folders: vec![Folder::SearchFolder {
+                properties: FolderProperties::new("Unread mail"),
+                search_parameters: None,
+            }],
+        };
+        assert!(matches!(
+            without_parameters.validate(),
+            Err(Error::InvalidRequest { .. })
+        ));
+
+        let without_base_folders = CreateFolder::search_folder(
+            "Unread mail",
+            SearchParameters::new(field("message:IsRead").eq(false), vec![]),
+        );
+        assert!(matches!(
+            without_base_folders.validate(),
+            Err(Error::InvalidRequest { .. })
+        ));
+    }
+}
@@ -2,17 +2,42 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, ItemResponseMessage, MessageDisposition, RealItem, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, server_version::ExchangeServerVersion, BaseFolderId, BatchOperation, Error,
+    ItemLike, ItemResponseMessage, MeetingTimeZone, Message, MessageDisposition, RealItem,
+    MESSAGES_NS_URI,
+};
+
+/// Whether to send meeting invitations when creating a calendar item.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createitem#sendmeetinginvitations-attribute>
+#[derive(Clone, Copy, Debug, XmlSerialize)]
+#[xml_struct(text)]
+pub enum SendMeetingInvitations {
+    /// No invitations are sent.
+    SendToNone,
+
+    /// Invitations are sent to all attendees, without saving a copy.
+    SendOnlyToAll,
+
+    /// Invitations are sent to all attendees, and a copy is saved in the Sent
+    /// Items folder.
+    SendToAllAndSaveCopy,
+}
 
 /// A request to create (and optionally send) one or more Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createitem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Default, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(ItemResponseMessage)]
+#[operation_response(
+    ItemResponseMessage,
+    validate = validate,
+    for_server_version = for_server_version
+)]
 pub struct CreateItem {
     /// The action the Exchange server will take upon creating this item.
     ///
@@ -24,6 +49,15 @@ pub struct CreateItem {
     #[xml_struct(attribute)]
     pub message_disposition: Option<MessageDisposition>,
 
+    /// The action the Exchange server will take upon creating a calendar
+    /// item.
+    ///
+    /// Required when creating calendar items, otherwise it has no effect.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/createitem#sendmeetinginvitations-attribute>
+    #[xml_struct(attribute)]
+    pub send_meeting_invitations: Option<SendMeetingInvitations>,
+
     /// The folder in which to store an item once it has been created.
     ///
     /// This is ignored if `message_disposition` is [`SendOnly`].
@@ -37,14 +71,300 @@ pub struct CreateItem {
     pub items: Vec<RealItem>,
 }
 
+impl CreateItem {
+    /// Creates a request saving a message as a folder associated item, i.e. a
+    /// hidden item in the given folder, such as those used by clients to
+    /// store per-folder settings.
+    ///
+    /// Folder associated items can be found with [`ItemTraversal::Associated`].
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/isassociated>
+    ///
+    /// [`ItemTraversal::Associated`]: crate::ItemTraversal::Associated
+    pub fn associated(folder_id: BaseFolderId, message: Message) -> Self {
+        Self {
+            message_disposition: Some(MessageDisposition::SaveOnly),
+            send_meeting_invitations: None,
+            saved_item_folder_id: Some(folder_id),
+            items: vec![RealItem::Message(Message {
+                is_associated: Some(true),
+                ..message
+            })],
+        }
+    }
+}
+
+impl BatchOperation for CreateItem {
+    type Input = RealItem;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.items
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.items
+    }
+}
+
+/// Checks a [`CreateItem`] operation before it is sent.
+fn validate(operation: &CreateItem) -> Result<(), Error> {
+    require_non_empty("CreateItem", "items", &operation.items)?;
+
+    let has_calendar_item = operation
+        .items
+        .iter()
+        .any(|item| matches!(item, RealItem::CalendarItem(_)));
+    if has_calendar_item && operation.send_meeting_invitations.is_none() {
+        return Err(Error::InvalidRequest {
+            operation: "CreateItem",
+            reason: "`send_meeting_invitations` is required when creating calendar items"
+                .to_string(),
+        });
+    }
+
+    operation
+        .items
+        .iter()
+        .flat_map(ItemLike::extended_properties)
+        .try_for_each(|property| property.extended_field_URI.validate())
+}
+
+/// Describes the time zones of calendar items with `MeetingTimeZone` in
+/// requests targeting Exchange Server 2007, which predates `StartTimeZone`
+/// and `EndTimeZone`.
+fn for_server_version(
+    operation: &CreateItem,
+    version: ExchangeServerVersion,
+) -> Result<Option<CreateItem>, Error> {
+    let has_time_zones = |item: &RealItem| {
+        matches!(
+            item,
+            RealItem::CalendarItem(message)
+                if message.start_time_zone.is_some() || message.end_time_zone.is_some()
+        )
+    };
+    if version >= ExchangeServerVersion::Exchange2010 || !operation.items.iter().any(has_time_zones)
+    {
+        return Ok(None);
+    }
+
+    let items = operation
+        .items
+        .iter()
+        .map(|item| match item {
+            RealItem::CalendarItem(message) => {
+                with_meeting_time_zone(message).map(RealItem::CalendarItem)
+            }
+            item => Ok(item.clone()),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Some(CreateItem {
+        message_disposition: operation.message_disposition,
+        send_meeting_invitations: operation.send_meeting_invitations,
+        saved_item_folder_id: operation.saved_item_folder_id.clone(),
+        items,
+    }))
+}
+
+/// Replaces the start and end time zones of a calendar item with the single
+/// `MeetingTimeZone` understood by Exchange Server 2007.
+fn with_meeting_time_zone(message: &Message) -> Result<Message, Error> {
+    let mut message = message.clone();
+    let time_zone = match (message.start_time_zone.take(), message.end_time_zone.take()) {
+        (Some(start), Some(end)) if start.id != end.id => {
+            return Err(Error::InvalidRequest {
+                operation: "CreateItem",
+                reason: format!(
+                    "calendar items starting and ending in different time zones require {:?} \
+                    or later",
+                    ExchangeServerVersion::Exchange2010
+                ),
+            });
+        }
+        (start, end) => start.or(end),
+    };
+
+    if message.meeting_time_zone.is_none() {
+        message.meeting_time_zone =
+            time_zone.map(|time_zone| Box::new(MeetingTimeZone::new(time_zone.id)));
+    }
+
+    Ok(message)
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
-        test_utils::assert_deserialized_content, types::common::ItemResponseMessage, Items,
-        ResponseClass, ResponseMessages,
+        server_version::ExchangeServerVersion,
+        soap::Envelope,
+        test_utils::{assert_deserialized_content, assert_serialized_content},
+        types::common::ItemResponseMessage,
+        BaseFolderId, Contact, DateTime, EmailAddressEntry, EmailAddresses, Error, Items, Message,
+        MessageDisposition, NoEndRecurrence, Operation, PhoneNumberEntry, PhoneNumbers,
+        PhysicalAddressEntry, PhysicalAddresses, RealItem, Recurrence, RecurrencePattern,
+        RecurrenceRange, RegenerationRecurrence, ResponseClass, ResponseMessages, Task, TaskStatus,
+        TimeZoneReference,
     };
 
-    use super::CreateItemResponse;
+    use super::{CreateItem, CreateItemResponse, SendMeetingInvitations};
+
+    #[test]
+    fn test_serialize_send_meeting_invitations() {
+        let create_item = CreateItem::builder(vec![RealItem::CalendarItem(Message {
+            subject: Some("Standup".to_string()),
+            ..Default::default()
+        })])
+        .message_disposition(MessageDisposition::SaveOnly)
+        .send_meeting_invitations(SendMeetingInvitations::SendToAllAndSaveCopy)
+        .build();
+
+        let expected = r#"<CreateItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" MessageDisposition="SaveOnly" SendMeetingInvitations="SendToAllAndSaveCopy"><Items><t:CalendarItem><t:Subject>Standup</t:Subject></t:CalendarItem></Items></CreateItem>"#;
+
+        assert_serialized_content(&create_item, "CreateItem", expected);
+    }
+
+    #[test]
+    fn test_validate_calendar_item_requires_invitations() {
+        let create_item = CreateItem::new(vec![RealItem::CalendarItem(Message {
+            subject: Some("Standup".to_string()),
+            ..Default::default()
+        })]);
+
+        let err = create_item
+            .validate()
+            .expect_err("calendar items should require SendMeetingInvitations");
+        assert_eq!(
+            err.to_string(),
+            "invalid CreateItem request: `send_meeting_invitations` is required when creating calendar items"
+        );
+
+        CreateItem {
+            send_meeting_invitations: Some(SendMeetingInvitations::SendToNone),
+            ..create_item
+        }
+        .validate()
+        .expect("calendar item with invitations configured should be valid");
+
+        assert!(matches!(
+            CreateItem::new(Vec::new()).validate(),
+            Err(Error::InvalidRequest { .. })
+        ));
+    }
+
+    #[test]
+    fn test_time_zones_for_exchange_2007() {
+        let event = Message::builder()
+            .subject("Standup")
+            .time_zone(TimeZoneReference::new("Eastern Standard Time"))
+            .build();
+        let create_item = CreateItem::builder(vec![RealItem::CalendarItem(event.clone())])
+            .send_meeting_invitations(SendMeetingInvitations::SendToNone)
+            .build();
+
+        let document = |version| {
+            let document = Envelope::new_with_version(create_item.clone(), version)
+                .as_xml_document()
+                .expect("serialization should succeed");
+
+            String::from_utf8(document).expect("document should be UTF-8")
+        };
+
+        let current = document(ExchangeServerVersion::Exchange2010);
+        assert!(current.contains(r#"<t:StartTimeZone Id="Eastern Standard Time"/>"#));
+        assert!(!current.contains("MeetingTimeZone"));
+
+        // Exchange Server 2007 predates the start and end time zones.
+        let legacy = document(ExchangeServerVersion::Exchange2007_SP1);
+        assert!(legacy.contains(concat!(
+            r#"<t:CalendarItem><t:Subject>Standup</t:Subject>"#,
+            r#"<t:MeetingTimeZone TimeZoneName="Eastern Standard Time"></t:MeetingTimeZone>"#,
+            "</t:CalendarItem>",
+        )));
+        assert!(!legacy.contains("StartTimeZone"));
+
+        let mut event = event;
+        event.end_time_zone = Some(Box::new(TimeZoneReference::new("Pacific Standard Time")));
+        let err = CreateItem::builder(vec![RealItem::CalendarItem(event)])
+            .send_meeting_invitations(SendMeetingInvitations::SendToNone)
+            .build()
+            .for_server_version(ExchangeServerVersion::Exchange2007_SP1)
+            .expect_err("differing time zones should be rejected");
+        assert!(matches!(
+            err,
+            Error::InvalidRequest {
+                operation: "CreateItem",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_serialize_associated_item() {
+        let create_item = CreateItem::associated(
+            BaseFolderId::inbox(),
+            Message {
+                item_class: Some("IPM.Configuration.Example".to_string()),
+                subject: Some("Settings".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let expected = r#"<CreateItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" MessageDisposition="SaveOnly"><SavedItemFolderId><t:DistinguishedFolderId Id="inbox"/></SavedItemFolderId><Items><t:Message><t:ItemClass>IPM.Configuration.Example</t:ItemClass><t:Subject>Settings</t:Subject><t:IsAssociated>true</t:IsAssociated></t:Message></Items></CreateItem>"#;
+
+        assert_serialized_content(&create_item, "CreateItem", expected);
+    }
+
+    #[test]
+    fn test_serialize_contact() {
+        let mut address = PhysicalAddressEntry::new("Business");
+        address.city = Some("Springfield".to_string());
+
+        let create_item = CreateItem::builder(vec![RealItem::Contact(Contact {
+            given_name: Some("Jane".to_string()),
+            email_addresses: Some(EmailAddresses {
+                entry: vec![EmailAddressEntry::new("EmailAddress1", "jane@example.com")],
+            }),
+            phone_numbers: Some(PhoneNumbers {
+                entry: vec![PhoneNumberEntry::new("MobilePhone", "555-0100")],
+            }),
+            physical_addresses: Some(PhysicalAddresses {
+                entry: vec![address],
+            }),
+            surname: Some("Doe".to_string()),
+            ..Default::default()
+        })])
+        .build();
+
+        let expected = r#"<CreateItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><Items><t:Contact><t:GivenName>Jane</t:GivenName><t:EmailAddresses><t:Entry Key="EmailAddress1">jane@example.com</t:Entry></t:EmailAddresses><t:PhysicalAddresses><t:Entry Key="Business"><t:City>Springfield</t:City></t:Entry></t:PhysicalAddresses><t:PhoneNumbers><t:Entry Key="MobilePhone">555-0100</t:Entry></t:PhoneNumbers><t:Surname>Doe</t:Surname></t:Contact></Items></CreateItem>"#;
+
+        assert_serialized_content(&create_item, "CreateItem", expected);
+    }
+
+    #[test]
+    fn test_serialize_task() {
+        let due = time::OffsetDateTime::from_unix_timestamp(1_717_200_000).unwrap();
+
+        let create_item = CreateItem::builder(vec![RealItem::Task(Task {
+            reminder_is_set: Some(true),
+            due_date: Some(DateTime(due)),
+            recurrence: Some(Recurrence {
+                pattern: RecurrencePattern::WeeklyRegeneration(RegenerationRecurrence {
+                    interval: 2,
+                }),
+                range: RecurrenceRange::NoEndRecurrence(NoEndRecurrence {
+                    start_date: "2024-06-01".to_string(),
+                }),
+            }),
+            status: Some(TaskStatus::InProgress),
+            ..Task::new("Water the plants")
+        })])
+        .build();
+
+        let expected = r#"<CreateItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><Items><t:Task><t:Subject>Water the plants</t:Subject><t:ReminderIsSet>true</t:ReminderIsSet><t:DueDate>2024-06-01T00:00:00.000000000Z</t:DueDate><t:Recurrence><t:WeeklyRegeneration><t:Interval>2</t:Interval></t:WeeklyRegeneration><t:NoEndRecurrence><t:StartDate>2024-06-01</t:StartDate></t:NoEndRecurrence></t:Recurrence><t:Status>InProgress</t:Status></t:Task></Items></CreateItem>"#;
+
+        assert_serialized_content(&create_item, "CreateItem", expected);
+    }
 
     #[test]
     fn test_deserialize_create_item_response() {
@@ -0,0 +1,134 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use ews_proc_macros::{operation_response, OperationBuilder};
+use serde::Deserialize;
+use xml_struct::XmlSerialize;
+
+use crate::{
+    require_non_empty, AttachmentId, AttachmentIds, BaseItemId, BatchOperation, Error,
+    MESSAGES_NS_URI,
+};
+
+/// A request to delete one or more attachments from Exchange items.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deleteattachment>
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
+#[xml_struct(default_ns = MESSAGES_NS_URI)]
+#[operation_response(DeleteAttachmentResponseMessage, validate = validate)]
+pub struct DeleteAttachment {
+    /// The identifiers of the attachments to delete.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachmentids>
+    pub attachment_ids: AttachmentIds,
+}
+
+impl BatchOperation for DeleteAttachment {
+    type Input = AttachmentId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.attachment_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.attachment_ids.0
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteAttachmentResponseMessage {
+    /// The item from which the attachment was deleted, with its updated
+    /// change key.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/rootitemid>
+    pub root_item_id: Option<RootItemId>,
+}
+
+/// The identifier of the item from which an attachment was deleted.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/rootitemid>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct RootItemId {
+    #[serde(rename = "@RootItemId")]
+    pub root_item_id: String,
+
+    #[serde(rename = "@RootItemChangeKey")]
+    pub root_item_change_key: Option<String>,
+}
+
+impl From<RootItemId> for BaseItemId {
+    fn from(value: RootItemId) -> Self {
+        BaseItemId::ItemId {
+            id: value.root_item_id,
+            change_key: value.root_item_change_key,
+        }
+    }
+}
+
+/// Checks a [`DeleteAttachment`] operation before it is sent.
+fn validate(operation: &DeleteAttachment) -> Result<(), Error> {
+    require_non_empty(
+        "DeleteAttachment",
+        "attachment_ids",
+        &operation.attachment_ids,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        test_utils::{assert_deserialized_content, assert_serialized_content},
+        AttachmentId, ResponseClass, ResponseMessages,
+    };
+
+    use super::{
+        DeleteAttachment, DeleteAttachmentResponse, DeleteAttachmentResponseMessage, RootItemId,
+    };
+
+    #[test]
+    fn test_serialize_delete_attachment() {
+        let delete_attachment = DeleteAttachment::new(
+            vec![AttachmentId {
+                id: "AAMkAGI=".to_string(),
+                root_item_id: None,
+                root_item_change_key: None,
+            }]
+            .into(),
+        );
+
+        let expected = r#"<DeleteAttachment xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><AttachmentIds><t:AttachmentId Id="AAMkAGI="/></AttachmentIds></DeleteAttachment>"#;
+
+        assert_serialized_content(&delete_attachment, "DeleteAttachment", expected);
+    }
+
+    #[test]
+    fn test_deserialize_delete_attachment_response() {
+        let content = r#"<DeleteAttachmentResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages"
+                        xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"
+                        xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">
+                    <m:ResponseMessages>
+                        <m:DeleteAttachmentResponseMessage ResponseClass="Success">
+                            <m:ResponseCode>NoError</m:ResponseCode>
+                            <m:RootItemId RootItemId="AAMkAGE=" RootItemChangeKey="CQAAABYA"/>
+                        </m:DeleteAttachmentResponseMessage>
+                    </m:ResponseMessages>
+                </DeleteAttachmentResponse>"#;
+
+        let expected = DeleteAttachmentResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![ResponseClass::Success(DeleteAttachmentResponseMessage {
+                    root_item_id: Some(RootItemId {
+                        root_item_id: "AAMkAGE=".to_string(),
+                        root_item_change_key: Some("CQAAABYA".to_string()),
+                    }),
+                })],
+            },
+        };
+
+        assert_deserialized_content(content, expected);
+    }
+}
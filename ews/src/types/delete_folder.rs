@@ -2,18 +2,18 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, DeleteType, MESSAGES_NS_URI};
+use crate::{require_non_empty, BaseFolderId, BatchOperation, DeleteType, Error, MESSAGES_NS_URI};
 
 /// A request to delete one or more folders.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deletefolder>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(DeleteFolderResponseMessage)]
+#[operation_response(DeleteFolderResponseMessage, validate = validate)]
 pub struct DeleteFolder {
     /// The method the EWS server will use to perform the deletion.
     ///
@@ -27,8 +27,26 @@ pub struct DeleteFolder {
     pub folder_ids: Vec<BaseFolderId>,
 }
 
+impl BatchOperation for DeleteFolder {
+    type Input = BaseFolderId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.folder_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.folder_ids
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteFolderResponseMessage {
     pub message_text: Option<String>,
 }
+
+/// Checks a [`DeleteFolder`] operation before it is sent.
+fn validate(operation: &DeleteFolder) -> Result<(), Error> {
+    require_non_empty("DeleteFolder", "folder_ids", &operation.folder_ids)
+}
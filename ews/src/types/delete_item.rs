@@ -2,16 +2,19 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseItemId, DeleteType, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, server_version::ExchangeServerVersion, BaseItemId, BatchOperation,
+    DeleteType, Error, MESSAGES_NS_URI,
+};
 
 /// Whether to send meeting cancellations when deleting a calendar item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deleteitem#sendmeetingcancellations-attribute>
-#[derive(Debug, XmlSerialize)]
+#[derive(Clone, Debug, XmlSerialize)]
 #[xml_struct(text)]
 pub enum SendMeetingCancellations {
     SendToNone,
@@ -22,7 +25,7 @@ pub enum SendMeetingCancellations {
 /// Which tasks should be impacted when deleting a task item.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deleteitem#affectedtaskoccurrences-attribute>
-#[derive(Debug, XmlSerialize)]
+#[derive(Clone, Debug, XmlSerialize)]
 #[xml_struct(text)]
 pub enum AffectedTaskOccurrences {
     AllOccurrences,
@@ -32,9 +35,13 @@ pub enum AffectedTaskOccurrences {
 /// A request to delete one or more Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deleteitem>
-#[derive(Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(DeleteItemResponseMessage)]
+#[operation_response(
+    DeleteItemResponseMessage,
+    validate = validate,
+    for_server_version = for_server_version
+)]
 pub struct DeleteItem {
     /// The method the EWS server will use to perform the deletion.
     ///
@@ -60,6 +67,9 @@ pub struct DeleteItem {
 
     /// Whether to suppress read receipts for the deleted item(s).
     ///
+    /// Servers older than Exchange Server 2013 SP1 don't accept this
+    /// attribute, so it is left out of requests targeting them.
+    ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deleteitem#attributes>
     #[xml_struct(attribute)]
     pub suppress_read_receipts: Option<bool>,
@@ -70,8 +80,58 @@ pub struct DeleteItem {
     pub item_ids: Vec<BaseItemId>,
 }
 
+impl DeleteItem {
+    /// Creates a request to purge items, such as those found by
+    /// [`FindItem::recoverable_items`], so that they can no longer be
+    /// recovered by the user.
+    ///
+    /// Purged items are kept in the Recoverable Items Purges folder while the
+    /// mailbox is on hold, and removed otherwise.
+    ///
+    /// [`FindItem::recoverable_items`]: crate::find_item::FindItem::recoverable_items
+    pub fn purge(item_ids: Vec<BaseItemId>) -> Self {
+        Self::new(DeleteType::HardDelete, item_ids)
+    }
+}
+
+impl BatchOperation for DeleteItem {
+    type Input = BaseItemId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.item_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.item_ids
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteItemResponseMessage {
     pub message_text: Option<String>,
 }
+
+/// Checks a [`DeleteItem`] operation before it is sent.
+fn validate(operation: &DeleteItem) -> Result<(), Error> {
+    require_non_empty("DeleteItem", "item_ids", &operation.item_ids)
+}
+
+/// Leaves `SuppressReadReceipts` out of requests targeting servers older than
+/// Exchange Server 2013 SP1, which reject it.
+fn for_server_version(
+    operation: &DeleteItem,
+    version: ExchangeServerVersion,
+) -> Result<Option<DeleteItem>, Error> {
+    if version >= ExchangeServerVersion::Exchange2013_SP1
+        || operation.suppress_read_receipts.is_none()
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(DeleteItem {
+        suppress_read_receipts: None,
+        ..operation.clone()
+    }))
+}
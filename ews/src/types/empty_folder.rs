@@ -2,18 +2,18 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, DeleteType, MESSAGES_NS_URI};
+use crate::{require_non_empty, BaseFolderId, BatchOperation, DeleteType, Error, MESSAGES_NS_URI};
 
 /// A request to delete all items from one or more folders.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/emptyfolder>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(EmptyFolderResponseMessage)]
+#[operation_response(EmptyFolderResponseMessage, min_version = Exchange2010, validate = validate)]
 pub struct EmptyFolder {
     /// The method the EWS server will use to perform the deletion of items.
     ///
@@ -33,10 +33,28 @@ pub struct EmptyFolder {
     pub folder_ids: Vec<BaseFolderId>,
 }
 
+impl BatchOperation for EmptyFolder {
+    type Input = BaseFolderId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.folder_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.folder_ids
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct EmptyFolderResponseMessage {}
 
+/// Checks a [`EmptyFolder`] operation before it is sent.
+fn validate(operation: &EmptyFolder) -> Result<(), Error> {
+    require_non_empty("EmptyFolder", "folder_ids", &operation.folder_ids)
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -2,22 +2,25 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, FolderShape, Folders, Paging, Restriction, Traversal, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, BaseFolderId, Error, Folder, FolderShape, FolderTraversal, Folders,
+    IndexedPaging, Page, PagedOperation, Paging, Restriction, MESSAGES_NS_URI,
+};
 
 /// A request to find folders matching certain criteria.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/findfolder>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(FindFolderResponseMessage)]
+#[operation_response(FindFolderResponseMessage, validate = validate)]
 pub struct FindFolder {
     /// The traversal method for the find operation.
     #[xml_struct(attribute)]
-    pub traversal: Traversal,
+    pub traversal: FolderTraversal,
 
     /// A description of the information to be included in the response for each found folder.
     pub folder_shape: FolderShape,
@@ -33,10 +36,49 @@ pub struct FindFolder {
     pub parent_folder_ids: Vec<BaseFolderId>,
 }
 
+impl PagedOperation for FindFolder {
+    type Output = Folder;
+
+    fn offset(&self) -> u32 {
+        match &self.paging {
+            Some(Paging::IndexedPageItemView(paging)) => paging.offset,
+            None => 0,
+        }
+    }
+
+    fn with_offset(&self, offset: u32) -> Self {
+        let paging = match &self.paging {
+            Some(Paging::IndexedPageItemView(paging)) => paging.clone(),
+            None => IndexedPaging::default(),
+        };
+
+        Self {
+            paging: Some(Paging::IndexedPageItemView(IndexedPaging {
+                offset,
+                ..paging
+            })),
+            ..self.clone()
+        }
+    }
+
+    fn into_page(message: FindFolderResponseMessage) -> Page<Folder> {
+        let root_folder = message.root_folder;
+
+        Page {
+            results: root_folder.folders.inner,
+            next_offset: root_folder
+                .indexed_paging_offset
+                .and_then(|offset| u32::try_from(offset).ok()),
+            includes_last_item_in_range: root_folder.includes_last_item_in_range,
+        }
+    }
+}
+
 /// A response to a request for finding folders.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/findfolderresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct FindFolderResponseMessage {
     /// The root folder containing the search results.
@@ -47,6 +89,7 @@ pub struct FindFolderResponseMessage {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/rootfolder-findfolderresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct RootFolder {
     #[serde(rename = "@IndexedPagingOffset")]
@@ -60,3 +103,60 @@ pub struct RootFolder {
 
     pub folders: Folders,
 }
+
+/// Checks a [`FindFolder`] operation before it is sent.
+fn validate(operation: &FindFolder) -> Result<(), Error> {
+    require_non_empty(
+        "FindFolder",
+        "parent_folder_ids",
+        &operation.parent_folder_ids,
+    )?;
+    operation.folder_shape.validate("FindFolder")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FindFolder;
+    use crate::{
+        properties, test_utils::assert_serialized_content, BaseFolderId, BaseShape, FolderShape,
+        FolderTraversal, PathToElement,
+    };
+
+    #[test]
+    fn serialize_deep_find_folder() {
+        let find_folder = FindFolder::new(
+            FolderTraversal::Deep,
+            FolderShape {
+                base_shape: BaseShape::IdOnly,
+                additional_properties: None,
+            },
+            vec![BaseFolderId::DistinguishedFolderId {
+                id: "msgfolderroot".to_string(),
+                change_key: None,
+            }],
+        );
+
+        let expected = r#"<FindFolder xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" Traversal="Deep"><FolderShape><t:BaseShape>IdOnly</t:BaseShape></FolderShape><ParentFolderIds><t:DistinguishedFolderId Id="msgfolderroot"/></ParentFolderIds></FindFolder>"#;
+
+        assert_serialized_content(&find_folder, "FindFolder", expected);
+    }
+
+    #[test]
+    fn serialize_folder_shape_additional_properties() {
+        let find_folder = FindFolder::new(
+            FolderTraversal::Shallow,
+            FolderShape {
+                base_shape: BaseShape::IdOnly,
+                additional_properties: Some(vec![
+                    PathToElement::field("folder:UnreadCount"),
+                    properties::PR_ATTR_HIDDEN.field_uri().into(),
+                ]),
+            },
+            vec![BaseFolderId::inbox()],
+        );
+
+        let expected = r#"<FindFolder xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" Traversal="Shallow"><FolderShape><t:BaseShape>IdOnly</t:BaseShape><t:AdditionalProperties><t:FieldURI FieldURI="folder:UnreadCount"/><t:ExtendedFieldURI PropertyTag="0x10F4" PropertyType="Boolean"/></t:AdditionalProperties></FolderShape><ParentFolderIds><t:DistinguishedFolderId Id="inbox"/></ParentFolderIds></FindFolder>"#;
+
+        assert_serialized_content(&find_folder, "FindFolder", expected);
+    }
+}
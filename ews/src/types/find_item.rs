@@ -7,7 +7,9 @@ use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
 use crate::{
-    BaseFolderId, FieldOrder, ItemShape, Items, Paging, Restriction, Traversal, MESSAGES_NS_URI,
+    require_non_empty, restriction::field, BaseFolderId, DateTime, Error, FieldOrder,
+    IndexedPaging, ItemShape, ItemTraversal, Items, Page, PagedOperation, Paging, PathToElement,
+    RealItem, Restriction, SortDirection, SortOrder, MESSAGES_NS_URI,
 };
 
 /// A request to find items matching certain criteria.
@@ -15,11 +17,11 @@ use crate::{
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/finditem>
 #[derive(Clone, Debug, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(FindItemResponseMessage)]
+#[operation_response(FindItemResponseMessage, validate = validate)]
 pub struct FindItem {
     /// The traversal method for the find operation.
     #[xml_struct(attribute)]
-    pub traversal: Traversal,
+    pub traversal: ItemTraversal,
 
     /// A description of the information to be included in the response for each found item.
     pub item_shape: ItemShape,
@@ -28,30 +30,416 @@ pub struct FindItem {
     #[xml_struct(flatten, ns_prefix = "m")]
     pub paging: Option<Paging>,
 
+    /// How to group the results, if at all.
+    ///
+    /// Grouped results are returned in [`RootFolder::groups`] rather than
+    /// [`RootFolder::items`].
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/groupby>
+    pub group_by: Option<GroupBy>,
+
     /// Restriction to apply to the search.
     pub restriction: Option<Restriction>,
 
     /// Sort order for the results.
-    pub sort_order: Option<Vec<FieldOrder>>,
+    pub sort_order: Option<SortOrder>,
 
     /// The parent folder IDs to search in.
     pub parent_folder_ids: Vec<BaseFolderId>,
+
+    /// A search of the content of the items, in addition to any
+    /// restriction.
+    pub query_string: Option<QueryString>,
+}
+
+impl FindItem {
+    /// Creates a request from its required fields, leaving optional fields
+    /// unset.
+    pub fn new(
+        traversal: ItemTraversal,
+        item_shape: ItemShape,
+        parent_folder_ids: Vec<BaseFolderId>,
+    ) -> Self {
+        Self {
+            traversal,
+            item_shape,
+            paging: None,
+            group_by: None,
+            restriction: None,
+            sort_order: None,
+            parent_folder_ids,
+            query_string: None,
+        }
+    }
+
+    /// Creates a builder for a shallow search of the given folder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ews::{find_item::FindItem, BaseFolderId};
+    ///
+    /// let find_item = FindItem::in_folder(BaseFolderId::inbox())
+    ///     .unread()
+    ///     .newest_first()
+    ///     .page(50)
+    ///     .build();
+    /// ```
+    pub fn in_folder(folder_id: BaseFolderId) -> FindItemBuilder {
+        FindItemBuilder {
+            traversal: ItemTraversal::Shallow,
+            item_shape: ItemShape::default(),
+            paging: None,
+            group_by: None,
+            restrictions: Vec::new(),
+            sort_order: Vec::new(),
+            parent_folder_ids: vec![folder_id],
+            query_string: None,
+        }
+    }
+
+    /// Creates a builder for a shallow search of the Recoverable Items
+    /// Deletions folder, also known as the dumpster.
+    ///
+    /// The folder holds items deleted from the Deleted Items folder or soft
+    /// deleted from any folder until the retention period of the mailbox
+    /// ends. Items found in it can be recovered with [`MoveItem::recover`]
+    /// or purged with [`DeleteItem::purge`].
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/exchange-web-services/deleting-items-by-using-ews-in-exchange>
+    ///
+    /// [`MoveItem::recover`]: crate::move_item::MoveItem::recover
+    /// [`DeleteItem::purge`]: crate::delete_item::DeleteItem::purge
+    pub fn recoverable_items() -> FindItemBuilder {
+        Self::in_folder(BaseFolderId::recoverable_items_deletions())
+    }
+}
+
+/// A builder for a [`FindItem`] request.
+///
+/// Restrictions added to the builder are combined, so that only items matching
+/// all of them are found.
+#[derive(Clone, Debug)]
+pub struct FindItemBuilder {
+    traversal: ItemTraversal,
+    item_shape: ItemShape,
+    paging: Option<IndexedPaging>,
+    group_by: Option<GroupBy>,
+    restrictions: Vec<Restriction>,
+    sort_order: Vec<FieldOrder>,
+    parent_folder_ids: Vec<BaseFolderId>,
+    query_string: Option<QueryString>,
+}
+
+impl FindItemBuilder {
+    /// Adds another folder to search.
+    pub fn in_folder(mut self, folder_id: BaseFolderId) -> Self {
+        self.parent_folder_ids.push(folder_id);
+        self
+    }
+
+    /// Sets the traversal method for the search.
+    pub fn traversal(mut self, traversal: ItemTraversal) -> Self {
+        self.traversal = traversal;
+        self
+    }
+
+    /// Finds only the folder associated items of the folders, i.e. hidden
+    /// items such as those created with [`CreateItem::associated`].
+    ///
+    /// [`CreateItem::associated`]: crate::create_item::CreateItem::associated
+    pub fn associated(self) -> Self {
+        self.traversal(ItemTraversal::Associated)
+    }
+
+    /// Sets the properties to include for each item found.
+    pub fn shape(mut self, item_shape: ItemShape) -> Self {
+        self.item_shape = item_shape;
+        self
+    }
+
+    /// Only finds items matching the given restriction.
+    pub fn restrict(mut self, restriction: impl Into<Restriction>) -> Self {
+        self.restrictions.push(restriction.into());
+        self
+    }
+
+    /// Only finds items which haven't been read.
+    pub fn unread(self) -> Self {
+        self.restrict(field("message:IsRead").eq(false))
+    }
+
+    /// Only finds items received after the given time.
+    pub fn received_after(self, time: DateTime) -> Self {
+        self.restrict(field("item:DateTimeReceived").gt(time))
+    }
+
+    /// Only finds items received before the given time.
+    pub fn received_before(self, time: DateTime) -> Self {
+        self.restrict(field("item:DateTimeReceived").lt(time))
+    }
+
+    /// Sorts the items found by the given property, after any properties
+    /// previously sorted by.
+    pub fn sort_by(mut self, path: PathToElement, order: SortDirection) -> Self {
+        self.sort_order.push(FieldOrder { path, order });
+        self
+    }
+
+    /// Sorts the items found from most to least recently received.
+    pub fn newest_first(self) -> Self {
+        self.sort_by(
+            PathToElement::field("item:DateTimeReceived"),
+            SortDirection::Descending,
+        )
+    }
+
+    /// Returns at most `max_entries` items from the start of the results.
+    pub fn page(mut self, max_entries: u32) -> Self {
+        self.paging
+            .get_or_insert_with(Default::default)
+            .max_entries_returned = Some(max_entries);
+        self
+    }
+
+    /// Skips the first `offset` items of the results, e.g. to fetch a page
+    /// after the first.
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.paging.get_or_insert_with(Default::default).offset = offset;
+        self
+    }
+
+    /// Groups the items found as described.
+    pub fn group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by = Some(group_by);
+        self
+    }
+
+    /// Only finds items whose content matches the given search, e.g.
+    /// `subject:lunch`.
+    pub fn query(mut self, query_string: impl Into<QueryString>) -> Self {
+        self.query_string = Some(query_string.into());
+        self
+    }
+
+    /// Builds the request.
+    pub fn build(self) -> FindItem {
+        let mut restrictions = self.restrictions;
+        let restriction = match restrictions.len() {
+            0 => None,
+            1 => restrictions.pop(),
+            _ => Some(Restriction::and(restrictions)),
+        };
+
+        FindItem {
+            traversal: self.traversal,
+            item_shape: self.item_shape,
+            paging: self.paging.map(Paging::IndexedPageItemView),
+            group_by: self.group_by,
+            restriction,
+            sort_order: (!self.sort_order.is_empty()).then_some(SortOrder(self.sort_order)),
+            parent_folder_ids: self.parent_folder_ids,
+            query_string: self.query_string,
+        }
+    }
+}
+
+/// A search of the content of items using Advanced Query Syntax.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/querystring-querystringtype>
+#[derive(Clone, Debug, XmlSerialize)]
+pub struct QueryString {
+    /// Whether to discard the cached results of previous searches.
+    #[xml_struct(attribute)]
+    pub reset_cache: Option<bool>,
+
+    /// Whether to include deleted items in the results.
+    #[xml_struct(attribute)]
+    pub return_deleted_items: Option<bool>,
+
+    /// Whether to return the terms matched by the search, e.g. for
+    /// highlighting them.
+    ///
+    /// The terms are returned in [`FindItemResponseMessage::highlight_terms`].
+    #[xml_struct(attribute)]
+    pub return_highlight_terms: Option<bool>,
+
+    /// The search, e.g. `subject:lunch`.
+    #[xml_struct(flatten)]
+    pub query: String,
+}
+
+impl QueryString {
+    /// Creates a search for the given query.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            reset_cache: None,
+            return_deleted_items: None,
+            return_highlight_terms: None,
+            query: query.into(),
+        }
+    }
+
+    /// Requests the terms matched by the search.
+    pub fn with_highlight_terms(mut self) -> Self {
+        self.return_highlight_terms = Some(true);
+        self
+    }
+}
+
+impl From<&str> for QueryString {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for QueryString {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+/// A description of how to group the results of a search.
+///
+/// Groups are ordered by the aggregate value of their items, e.g. the most
+/// recent time at which any item in the group was received.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/groupby>
+#[derive(Clone, Debug, XmlSerialize)]
+pub struct GroupBy {
+    /// The order of the groups.
+    #[xml_struct(attribute)]
+    pub order: SortDirection,
+
+    /// The property whose value identifies the group of each item.
+    #[xml_struct(flatten)]
+    pub path: PathToElement,
+
+    /// The value by which groups are ordered.
+    #[xml_struct(ns_prefix = "t")]
+    pub aggregate_on: AggregateOn,
+}
+
+/// The property of the items in each group by which groups are ordered.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/aggregateon>
+#[derive(Clone, Debug, XmlSerialize)]
+pub struct AggregateOn {
+    /// Whether groups are ordered by the smallest or the largest value of the
+    /// property among their items.
+    #[xml_struct(attribute)]
+    pub aggregate: Aggregate,
+
+    /// The property to aggregate.
+    #[xml_struct(flatten)]
+    pub path: PathToElement,
+}
+
+/// The aggregate of a property over the items in a group.
+#[derive(Clone, Copy, Debug, XmlSerialize)]
+#[xml_struct(text)]
+pub enum Aggregate {
+    Minimum,
+    Maximum,
+}
+
+impl From<FindItemBuilder> for FindItem {
+    fn from(builder: FindItemBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl PagedOperation for FindItem {
+    type Output = RealItem;
+
+    fn offset(&self) -> u32 {
+        match &self.paging {
+            Some(Paging::IndexedPageItemView(paging)) => paging.offset,
+            None => 0,
+        }
+    }
+
+    fn with_offset(&self, offset: u32) -> Self {
+        let paging = match &self.paging {
+            Some(Paging::IndexedPageItemView(paging)) => paging.clone(),
+            None => IndexedPaging::default(),
+        };
+
+        Self {
+            paging: Some(Paging::IndexedPageItemView(IndexedPaging {
+                offset,
+                ..paging
+            })),
+            ..self.clone()
+        }
+    }
+
+    fn into_page(message: FindItemResponseMessage) -> Page<RealItem> {
+        let root_folder = message.root_folder;
+
+        // Items are returned in groups when the request groups them, but
+        // paging applies to the items regardless.
+        let mut results = root_folder.items.inner;
+        if let Some(groups) = root_folder.groups {
+            results.extend(groups.inner.into_iter().flat_map(|group| group.items.inner));
+        }
+
+        Page {
+            results,
+            next_offset: root_folder
+                .indexed_paging_offset
+                .and_then(|offset| u32::try_from(offset).ok()),
+            includes_last_item_in_range: root_folder.includes_last_item_in_range,
+        }
+    }
 }
 
 /// A response to a request for finding items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/finditemresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct FindItemResponseMessage {
     /// The root folder containing the search results.
     pub root_folder: RootFolder,
+
+    /// The terms matched by the search, if requested with
+    /// [`QueryString::return_highlight_terms`].
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/highlightterms>
+    pub highlight_terms: Option<HighlightTerms>,
+}
+
+/// A list of terms matched by a search.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/highlightterms>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct HighlightTerms {
+    #[serde(rename = "$value", default)]
+    pub inner: Vec<HighlightTerm>,
+}
+
+/// A term matched by a search, and the property in which it was matched.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/term>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct HighlightTerm {
+    /// The property in which the term was matched, e.g. `subject`.
+    pub scope: String,
+
+    /// The matched term.
+    pub value: String,
 }
 
 /// The root folder element in find responses.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/rootfolder-finditemresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct RootFolder {
     #[serde(rename = "@IndexedPagingOffset")]
@@ -63,5 +451,336 @@ pub struct RootFolder {
     #[serde(rename = "@IncludesLastItemInRange")]
     pub includes_last_item_in_range: bool,
 
+    /// The items found, if the request does not group them.
+    #[serde(default)]
     pub items: Items,
+
+    /// The groups of items found, if the request groups them.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/groups>
+    pub groups: Option<Groups>,
+}
+
+/// A list of groups of items.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/groups>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct Groups {
+    #[serde(rename = "$value", default)]
+    pub inner: Vec<GroupedItems>,
+}
+
+/// A group of items sharing the value of the property they are grouped by.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/groupeditems>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct GroupedItems {
+    /// The value of the grouping property shared by the items.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/groupindex>
+    pub group_index: String,
+
+    /// The items in the group.
+    #[serde(default)]
+    pub items: Items,
+}
+
+/// Checks a [`FindItem`] operation before it is sent.
+fn validate(operation: &FindItem) -> Result<(), Error> {
+    require_non_empty(
+        "FindItem",
+        "parent_folder_ids",
+        &operation.parent_folder_ids,
+    )?;
+    operation.item_shape.validate("FindItem")
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{OffsetDateTime, UtcOffset};
+
+    use super::{
+        Aggregate, AggregateOn, FindItem, FindItemResponse, FindItemResponseMessage, GroupBy,
+        HighlightTerm, QueryString, RootFolder,
+    };
+    use crate::{
+        test_utils::assert_serialized_content, BaseFolderId, DateTime, Error, FieldOrder,
+        IndexedPaging, ItemId, Items, Message, PagedOperation, Pager, Paging, PathToElement,
+        RealItem, ResponseClass, ResponseMessages, SortDirection,
+    };
+
+    #[test]
+    fn build_find_item() {
+        // 2024-05-01T10:30:00+02:00
+        let received_after = OffsetDateTime::from_unix_timestamp(1_714_552_200)
+            .unwrap()
+            .to_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+
+        let find_item = FindItem::in_folder(BaseFolderId::DistinguishedFolderId {
+            id: "inbox".to_string(),
+            change_key: None,
+        })
+        .unread()
+        .received_after(DateTime(received_after))
+        .newest_first()
+        .page(50)
+        .build();
+
+        assert!(matches!(
+            find_item.paging,
+            Some(Paging::IndexedPageItemView(IndexedPaging {
+                max_entries_returned: Some(50),
+                offset: 0,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            find_item.sort_order.as_deref().map(Vec::as_slice),
+            Some([FieldOrder {
+                order: SortDirection::Descending,
+                ..
+            }])
+        ));
+
+        let expected = r#"<Restriction><t:And><t:IsEqualTo><t:FieldURI FieldURI="message:IsRead"/><t:FieldURIOrConstant><t:Constant Value="false"/></t:FieldURIOrConstant></t:IsEqualTo><t:IsGreaterThan><t:FieldURI FieldURI="item:DateTimeReceived"/><t:FieldURIOrConstant><t:Constant Value="2024-05-01T08:30:00Z"/></t:FieldURIOrConstant></t:IsGreaterThan></t:And></Restriction>"#;
+
+        assert_serialized_content(&find_item.restriction, "Restriction", expected);
+    }
+
+    #[test]
+    fn page_through_find_item_results() {
+        fn response(ids: &[&str], next_offset: i32, last: bool) -> FindItemResponse {
+            FindItemResponse {
+                response_messages: ResponseMessages {
+                    response_messages: vec![ResponseClass::Success(FindItemResponseMessage {
+                        root_folder: RootFolder {
+                            indexed_paging_offset: Some(next_offset),
+                            total_items_in_view: 3,
+                            includes_last_item_in_range: last,
+                            items: Items {
+                                inner: ids
+                                    .iter()
+                                    .map(|id| {
+                                        RealItem::Message(Message {
+                                            item_id: Some(ItemId::new(*id)),
+                                            ..Default::default()
+                                        })
+                                    })
+                                    .collect(),
+                            },
+                            groups: None,
+                        },
+                        highlight_terms: None,
+                    })],
+                },
+            }
+        }
+
+        let mut pager = Pager::new(FindItem::in_folder(BaseFolderId::inbox()).page(2).build());
+
+        let first = pager
+            .next_operation()
+            .expect("first page should be requested");
+        assert!(matches!(
+            first.paging,
+            Some(Paging::IndexedPageItemView(IndexedPaging {
+                max_entries_returned: Some(2),
+                offset: 0,
+                ..
+            }))
+        ));
+        let items = pager.process(response(&["a", "b"], 2, false)).unwrap();
+        assert_eq!(items.len(), 2);
+
+        let second = pager
+            .next_operation()
+            .expect("second page should be requested");
+        assert!(matches!(
+            second.paging,
+            Some(Paging::IndexedPageItemView(IndexedPaging {
+                max_entries_returned: Some(2),
+                offset: 2,
+                ..
+            }))
+        ));
+        let items = pager.process(response(&["c"], 3, true)).unwrap();
+        assert_eq!(items.len(), 1);
+
+        assert!(pager.is_done());
+        assert!(pager.next_operation().is_none());
+    }
+
+    #[test]
+    fn stop_paging_when_offset_does_not_advance() {
+        let response = |next_offset: i32| FindItemResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![ResponseClass::Success(FindItemResponseMessage {
+                    root_folder: RootFolder {
+                        indexed_paging_offset: Some(next_offset),
+                        total_items_in_view: 10,
+                        includes_last_item_in_range: false,
+                        items: Items {
+                            inner: vec![RealItem::Message(Message::default())],
+                        },
+                        groups: None,
+                    },
+                    highlight_terms: None,
+                })],
+            },
+        };
+
+        let mut pager = Pager::new(FindItem::in_folder(BaseFolderId::inbox()).page(1).build());
+        pager.process(response(1)).unwrap();
+
+        // A server returning the same offset, or going back, would otherwise
+        // be asked for the same pages forever.
+        for next_offset in [1, 0] {
+            let mut stalled = pager.clone();
+            assert!(matches!(
+                stalled.process(response(next_offset)),
+                Err(Error::PagingStalled {
+                    offset: 1,
+                    next_offset: stalled_offset,
+                }) if stalled_offset == next_offset as u32
+            ));
+            assert!(stalled.is_done());
+        }
+    }
+
+    #[test]
+    fn serialize_sort_order() {
+        let find_item = FindItem::in_folder(BaseFolderId::inbox())
+            .sort_by(
+                PathToElement::field("item:Importance"),
+                SortDirection::Descending,
+            )
+            .newest_first()
+            .build();
+
+        let expected = concat!(
+            r#"<FindItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" Traversal="Shallow">"#,
+            r#"<ItemShape><t:BaseShape>IdOnly</t:BaseShape></ItemShape><SortOrder>"#,
+            r#"<t:FieldOrder Order="Descending"><t:FieldURI FieldURI="item:Importance"/></t:FieldOrder>"#,
+            r#"<t:FieldOrder Order="Descending"><t:FieldURI FieldURI="item:DateTimeReceived"/></t:FieldOrder>"#,
+            r#"</SortOrder><ParentFolderIds><t:DistinguishedFolderId Id="inbox"/></ParentFolderIds></FindItem>"#,
+        );
+        assert_serialized_content(&find_item, "FindItem", expected);
+    }
+
+    #[test]
+    fn find_associated_items() {
+        let find_item = FindItem::in_folder(BaseFolderId::inbox())
+            .associated()
+            .build();
+
+        let expected = r#"<FindItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" Traversal="Associated"><ItemShape><t:BaseShape>IdOnly</t:BaseShape></ItemShape><ParentFolderIds><t:DistinguishedFolderId Id="inbox"/></ParentFolderIds></FindItem>"#;
+        assert_serialized_content(&find_item, "FindItem", expected);
+
+        let response: FindItemResponse = quick_xml::de::from_str(concat!(
+            r#"<FindItemResponse><ResponseMessages>"#,
+            r#"<FindItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<RootFolder TotalItemsInView="1" IncludesLastItemInRange="true"><Items>"#,
+            r#"<Message><ItemId Id="a"/><ItemClass>IPM.Configuration.Example</ItemClass><IsAssociated>true</IsAssociated></Message>"#,
+            r#"</Items></RootFolder>"#,
+            r#"</FindItemResponseMessage></ResponseMessages></FindItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        let mut messages = response.response_messages.response_messages;
+        let ResponseClass::Success(message) = messages.remove(0) else {
+            panic!("response should be successful");
+        };
+        let page = FindItem::into_page(message);
+        assert!(matches!(
+            &page.results[..],
+            [RealItem::Message(Message {
+                is_associated: Some(true),
+                ..
+            })]
+        ));
+    }
+
+    #[test]
+    fn grouped_find_item() {
+        let find_item = FindItem::in_folder(BaseFolderId::inbox())
+            .group_by(GroupBy {
+                order: SortDirection::Descending,
+                path: PathToElement::field("message:ConversationTopic"),
+                aggregate_on: AggregateOn {
+                    aggregate: Aggregate::Maximum,
+                    path: PathToElement::field("item:DateTimeReceived"),
+                },
+            })
+            .build();
+
+        let expected = r#"<GroupBy Order="Descending"><t:FieldURI FieldURI="message:ConversationTopic"/><t:AggregateOn Aggregate="Maximum"><t:FieldURI FieldURI="item:DateTimeReceived"/></t:AggregateOn></GroupBy>"#;
+        assert_serialized_content(&find_item.group_by, "GroupBy", expected);
+
+        let response: FindItemResponse = quick_xml::de::from_str(concat!(
+            r#"<FindItemResponse><ResponseMessages>"#,
+            r#"<FindItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<RootFolder IndexedPagingOffset="3" TotalItemsInView="3" IncludesLastItemInRange="true"><Groups>"#,
+            r#"<GroupedItems><GroupIndex>Lunch</GroupIndex><Items>"#,
+            r#"<Message><ItemId Id="a"/></Message><Message><ItemId Id="b"/></Message>"#,
+            r#"</Items></GroupedItems>"#,
+            r#"<GroupedItems><GroupIndex>Status report</GroupIndex><Items>"#,
+            r#"<Message><ItemId Id="c"/></Message>"#,
+            r#"</Items></GroupedItems>"#,
+            r#"</Groups></RootFolder>"#,
+            r#"</FindItemResponseMessage></ResponseMessages></FindItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        let mut messages = response.response_messages.response_messages;
+        let ResponseClass::Success(message) = messages.remove(0) else {
+            panic!("response should be successful");
+        };
+        let groups = message
+            .root_folder
+            .groups
+            .as_ref()
+            .expect("groups should be present");
+        assert_eq!(groups.inner.len(), 2);
+        assert_eq!(groups.inner[0].group_index, "Lunch");
+        assert_eq!(groups.inner[1].items.inner.len(), 1);
+
+        let page = FindItem::into_page(message);
+        assert_eq!(page.results.len(), 3);
+    }
+
+    #[test]
+    fn find_item_with_highlight_terms() {
+        let find_item = FindItem::in_folder(BaseFolderId::inbox())
+            .query(QueryString::new("subject:lunch").with_highlight_terms())
+            .build();
+
+        let expected = r#"<FindItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" Traversal="Shallow"><ItemShape><t:BaseShape>IdOnly</t:BaseShape></ItemShape><ParentFolderIds><t:DistinguishedFolderId Id="inbox"/></ParentFolderIds><QueryString ReturnHighlightTerms="true">subject:lunch</QueryString></FindItem>"#;
+        assert_serialized_content(&find_item, "FindItem", expected);
+
+        let response: FindItemResponse = quick_xml::de::from_str(concat!(
+            r#"<FindItemResponse><ResponseMessages>"#,
+            r#"<FindItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<RootFolder TotalItemsInView="1" IncludesLastItemInRange="true"><Items>"#,
+            r#"<Message><ItemId Id="a"/><Subject>Lunch on Friday?</Subject></Message>"#,
+            r#"</Items></RootFolder>"#,
+            r#"<HighlightTerms><Term><Scope>subject</Scope><Value>lunch</Value></Term></HighlightTerms>"#,
+            r#"</FindItemResponseMessage></ResponseMessages></FindItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        let message = response.single().expect("response should be successful");
+        assert_eq!(
+            message
+                .highlight_terms
+                .expect("highlight terms should be present")
+                .inner,
+            vec![HighlightTerm {
+                scope: "subject".to_string(),
+                value: "lunch".to_string(),
+            }]
+        );
+    }
 }
@@ -2,18 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{Attachment, AttachmentId, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, AttachmentId, AttachmentIds, BatchOperation, Error, PathToElement,
+    MESSAGES_NS_URI,
+};
 
 /// A request to retrieve one or more attachments from Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getattachment>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Default, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(GetAttachmentResponseMessage)]
+#[operation_response(GetAttachmentResponseMessage, validate = validate)]
 pub struct GetAttachment {
     /// Optional shape information for the attachment. Typically used to specify
     /// whether to include the attachment content in the response.
@@ -24,62 +27,156 @@ pub struct GetAttachment {
     /// The identifiers of the attachments to retrieve.
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachmentids>
-    pub attachment_ids: Vec<AttachmentId>,
+    pub attachment_ids: AttachmentIds,
+}
+
+impl BatchOperation for GetAttachment {
+    type Input = AttachmentId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.attachment_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.attachment_ids.0
+    }
 }
 
 /// Describes what information to include in attachment responses.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attachmentshape>
-#[derive(Clone, Debug, XmlSerialize)]
-#[xml_struct(default_ns = MESSAGES_NS_URI)]
+#[derive(Clone, Debug, Default, XmlSerialize)]
 pub struct AttachmentShape {
-    /// Whether to include the attachment content in the response.
-    #[xml_struct(attribute)]
+    /// Whether to include the MIME content of attached items.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/includemimecontent>
+    #[xml_struct(ns_prefix = "t")]
     pub include_mime_content: Option<bool>,
 
-    /// The type of body content to include for item attachments.
+    /// The format in which to return the bodies of attached items.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/bodytype>
+    #[xml_struct(ns_prefix = "t")]
     pub body_type: Option<BodyType>,
 
-    /// Whether to filter HTML content when the body type is HTML.
+    /// Whether to remove potentially unsafe content, such as scripts, from
+    /// the HTML bodies of attached items.
+    ///
+    /// This only has an effect on bodies returned as HTML, i.e. when
+    /// `body_type` is [`BodyType::HTML`], or [`BodyType::Best`] and the body
+    /// is stored as HTML.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/filterhtmlcontent>
+    #[xml_struct(ns_prefix = "t")]
     pub filter_html_content: Option<bool>,
 
-    /// Additional properties to include in the response.
-    pub additional_properties: Option<Vec<String>>,
+    /// Properties of attached items to include in addition to those
+    /// implied by other fields.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/additionalproperties>
+    #[xml_struct(ns_prefix = "t")]
+    pub additional_properties: Option<Vec<PathToElement>>,
 }
 
-/// The type of body content to include for item attachments.
-#[derive(Clone, Debug, XmlSerialize, Deserialize)]
+/// The format in which to return the bodies of attached items.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/bodytype>
+#[derive(Clone, Copy, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[xml_struct(text)]
 pub enum BodyType {
-    #[serde(rename = "HTML")]
-    Html,
-    #[serde(rename = "Text")]
+    /// The body as HTML.
+    HTML,
+
+    /// The body as plain text.
     Text,
-    #[serde(rename = "Best")]
+
+    /// The body in the format in which it is stored.
     Best,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct GetAttachmentResponseMessage {
     /// The retrieved attachments.
-    pub attachments: Option<Attachments>,
+    pub attachments: Option<crate::Attachments>,
 }
 
 /// A collection of attachments returned by GetAttachment.
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
-#[serde(rename_all = "PascalCase")]
-pub struct Attachments {
-    #[serde(rename = "$value", default)]
-    pub inner: Vec<Attachment>,
+#[deprecated(note = "use `crate::Attachments` instead")]
+pub type Attachments = crate::Attachments;
+
+/// Checks a [`GetAttachment`] operation before it is sent.
+fn validate(operation: &GetAttachment) -> Result<(), Error> {
+    require_non_empty("GetAttachment", "attachment_ids", &operation.attachment_ids)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{test_utils::assert_deserialized_content, Attachment, AttachmentId, ResponseClass};
+    use crate::{
+        test_utils::{assert_deserialized_content, assert_serialized_content},
+        Attachment, AttachmentId, Attachments, PathToElement, ResponseClass,
+    };
 
-    use super::{Attachments, GetAttachmentResponse, GetAttachmentResponseMessage};
+    use super::{
+        AttachmentShape, BodyType, GetAttachment, GetAttachmentResponse,
+        GetAttachmentResponseMessage,
+    };
     use crate::ResponseMessages;
 
+    #[test]
+    fn test_serialize_get_attachment_with_additional_properties() {
+        let get_attachment = GetAttachment {
+            attachment_shape: Some(AttachmentShape {
+                additional_properties: Some(vec![
+                    PathToElement::field("item:Subject"),
+                    PathToElement::indexed("message:InternetMessageHeader", "X-Mailer"),
+                ]),
+                ..Default::default()
+            }),
+            attachment_ids: vec![AttachmentId {
+                id: "AAMkAGI=".to_string(),
+                root_item_id: None,
+                root_item_change_key: None,
+            }]
+            .into(),
+        };
+
+        let expected = r#"<GetAttachment xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><AttachmentShape><t:AdditionalProperties><t:FieldURI FieldURI="item:Subject"/><t:IndexedFieldURI FieldURI="message:InternetMessageHeader" FieldIndex="X-Mailer"/></t:AdditionalProperties></AttachmentShape><AttachmentIds><t:AttachmentId Id="AAMkAGI="/></AttachmentIds></GetAttachment>"#;
+
+        assert_serialized_content(&get_attachment, "GetAttachment", expected);
+    }
+
+    #[test]
+    fn test_serialize_get_attachment_with_filtered_html() {
+        // Based on the request documented at
+        // https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getattachment-operation
+        let get_attachment = GetAttachment {
+            attachment_shape: Some(AttachmentShape {
+                include_mime_content: Some(true),
+                body_type: Some(BodyType::HTML),
+                filter_html_content: Some(true),
+                additional_properties: None,
+            }),
+            attachment_ids: vec![AttachmentId {
+                id: "AAAtAEFkbWluaX".to_string(),
+                root_item_id: None,
+                root_item_change_key: None,
+            }]
+            .into(),
+        };
+
+        let expected = concat!(
+            r#"<GetAttachment xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+            r#"<AttachmentShape><t:IncludeMimeContent>true</t:IncludeMimeContent>"#,
+            r#"<t:BodyType>HTML</t:BodyType><t:FilterHtmlContent>true</t:FilterHtmlContent></AttachmentShape>"#,
+            r#"<AttachmentIds><t:AttachmentId Id="AAAtAEFkbWluaX"/></AttachmentIds></GetAttachment>"#,
+        );
+
+        assert_serialized_content(&get_attachment, "GetAttachment", expected);
+    }
+
     #[test]
     fn test_deserialize_get_attachment_response() {
         let content = r#"<GetAttachmentResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages"
@@ -112,7 +209,7 @@ mod test {
                                 root_item_change_key: None,
                             },
                             name: "test.txt".to_string(),
-                            content_type: "text/plain".to_string(),
+                            content_type: Some("text/plain".to_string()),
                             content_id: None,
                             content_location: None,
                             size: Some(5),
@@ -128,4 +225,32 @@ mod test {
 
         assert_deserialized_content(content, expected);
     }
+
+    #[test]
+    fn test_deserialize_file_attachment_without_content_type() -> Result<(), crate::Error> {
+        use crate::soap::Envelope;
+
+        let document =
+            include_bytes!("../../fixtures/get_attachment_file_without_content_type.xml");
+        let envelope = Envelope::<GetAttachmentResponse>::from_xml_document(document, None)?;
+        let message = envelope.body.into_result()?.remove(0);
+        let attachments = message
+            .attachments
+            .expect("response should have attachments");
+
+        let Attachment::FileAttachment {
+            name,
+            content_type,
+            content,
+            ..
+        } = &attachments.inner[0]
+        else {
+            panic!("expected a file attachment, got {:?}", attachments.inner[0]);
+        };
+        assert_eq!(name, "export-2024-05.csv");
+        assert_eq!(content_type, &None);
+        assert!(content.is_some());
+
+        Ok(())
+    }
 }
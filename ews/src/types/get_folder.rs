@@ -2,18 +2,20 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, FolderShape, Folders, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, BaseFolderId, BatchOperation, Error, FolderShape, Folders, MESSAGES_NS_URI,
+};
 
 /// A request to get information on one or more folders.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getfolder>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Default, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(GetFolderResponseMessage)]
+#[operation_response(GetFolderResponseMessage, validate = validate)]
 pub struct GetFolder {
     /// A description of the information to be included in the response for each
     /// retrieved folder.
@@ -23,12 +25,32 @@ pub struct GetFolder {
     pub folder_ids: Vec<BaseFolderId>,
 }
 
+impl BatchOperation for GetFolder {
+    type Input = BaseFolderId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.folder_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.folder_ids
+    }
+}
+
 /// A response to a request for an individual folder within a [`GetFolder`] operation.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getfolderresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct GetFolderResponseMessage {
     /// A collection of the retrieved folders.
     pub folders: Folders,
 }
+
+/// Checks a [`GetFolder`] operation before it is sent.
+fn validate(operation: &GetFolder) -> Result<(), Error> {
+    require_non_empty("GetFolder", "folder_ids", &operation.folder_ids)?;
+    operation.folder_shape.validate("GetFolder")
+}
@@ -2,19 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseItemId, ItemShape, Items, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, BaseItemId, BatchOperation, Error, ItemShape, Items, MESSAGES_NS_URI,
+};
 
 /// A request for the properties of one or more Exchange items, e.g. messages,
 /// calendar events, or contacts.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getitem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Default, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(GetItemResponseMessage)]
+#[operation_response(GetItemResponseMessage, validate = validate)]
 pub struct GetItem {
     /// A description of the information to be included in the response for each
     /// item.
@@ -28,8 +30,28 @@ pub struct GetItem {
     pub item_ids: Vec<BaseItemId>,
 }
 
+impl BatchOperation for GetItem {
+    type Input = BaseItemId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.item_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.item_ids
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "PascalCase")]
 pub struct GetItemResponseMessage {
     pub items: Items,
 }
+
+/// Checks a [`GetItem`] operation before it is sent.
+fn validate(operation: &GetItem) -> Result<(), Error> {
+    require_non_empty("GetItem", "item_ids", &operation.item_ids)?;
+    operation.item_shape.validate("GetItem")
+}
@@ -2,18 +2,18 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{Mailbox, MESSAGES_NS_URI};
+use crate::{require_non_empty, BatchOperation, Error, Mailbox, MESSAGES_NS_URI};
 
 /// A request to get mail tips for specified recipients.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getmailtips>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(GetMailTipsResponseMessage)]
+#[operation_response(GetMailTipsResponseMessage, min_version = Exchange2010, validate = validate)]
 pub struct GetMailTips {
     /// The email address sending the message.
     pub sending_as: Mailbox,
@@ -25,10 +25,23 @@ pub struct GetMailTips {
     pub mail_tips_requested: MailTipsRequested,
 }
 
+impl BatchOperation for GetMailTips {
+    type Input = Mailbox;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.recipients
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.recipients
+    }
+}
+
 /// Types of mail tips that can be requested.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mailtipsrequested>
 #[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(text)]
 pub enum MailTipsRequested {
     All,
@@ -47,6 +60,7 @@ pub enum MailTipsRequested {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getmailtipsresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct GetMailTipsResponseMessage {
     /// Mail tips for the recipients.
@@ -57,6 +71,7 @@ pub struct GetMailTipsResponseMessage {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mailtips>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct MailTips {
     /// The recipient for whom the mail tips apply.
@@ -97,6 +112,7 @@ pub struct MailTips {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/outofoffice>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct OutOfOffice {
     /// The out of office reply message.
@@ -107,6 +123,7 @@ pub struct OutOfOffice {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/replymessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct OutOfOfficeReplyMessage {
     /// The message content.
@@ -118,6 +135,11 @@ pub struct OutOfOfficeReplyMessage {
     pub culture: Option<String>,
 }
 
+/// Checks a [`GetMailTips`] operation before it is sent.
+fn validate(operation: &GetMailTips) -> Result<(), Error> {
+    require_non_empty("GetMailTips", "recipients", &operation.recipients)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,14 +150,14 @@ mod tests {
         let get_mail_tips = GetMailTips {
             sending_as: Mailbox {
                 name: Some("Test User".into()),
-                email_address: "test@example.com".into(),
+                email_address: Some("test@example.com".into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
             },
             recipients: vec![Mailbox {
                 name: Some("Recipient".into()),
-                email_address: "recipient@example.com".into(),
+                email_address: Some("recipient@example.com".into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
@@ -151,14 +173,14 @@ mod tests {
         let get_mail_tips = GetMailTips {
             sending_as: Mailbox {
                 name: Some("Test User".into()),
-                email_address: "test@example.com".into(),
+                email_address: Some("test@example.com".into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
             },
             recipients: vec![Mailbox {
                 name: Some("Recipient".into()),
-                email_address: "recipient@example.com".into(),
+                email_address: Some("recipient@example.com".into()),
                 routing_type: None,
                 mailbox_type: None,
                 item_id: None,
@@ -172,4 +194,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_split_get_mail_tips_batch() {
+        let get_mail_tips = GetMailTips {
+            sending_as: Mailbox::new("sender@example.com"),
+            recipients: (0..3)
+                .map(|i| Mailbox::new(format!("recipient-{i}@example.com")))
+                .collect(),
+            mail_tips_requested: MailTipsRequested::All,
+        };
+
+        let batches = get_mail_tips.split_batch(2);
+        let recipients: Vec<Vec<_>> = batches
+            .iter()
+            .map(|batch| {
+                assert_eq!(
+                    batch.sending_as.email_address.as_deref(),
+                    Some("sender@example.com")
+                );
+
+                batch
+                    .recipients
+                    .iter()
+                    .filter_map(|recipient| recipient.email_address.as_deref())
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            recipients,
+            vec![
+                vec!["recipient-0@example.com", "recipient-1@example.com"],
+                vec!["recipient-2@example.com"],
+            ]
+        );
+    }
 }
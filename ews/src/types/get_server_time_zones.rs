@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
@@ -11,9 +11,9 @@ use crate::MESSAGES_NS_URI;
 /// A request to retrieve time zone definitions from the Exchange server.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getservertimezones>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, Default, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(GetServerTimeZonesResponseMessage)]
+#[operation_response(GetServerTimeZonesResponseMessage, min_version = Exchange2010)]
 pub struct GetServerTimeZones {
     /// Whether to return the complete definitions for each time zone.
     ///
@@ -41,6 +41,7 @@ pub struct TimeZoneIds {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getservertimezonesresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct GetServerTimeZonesResponseMessage {
     /// The time zone definitions returned by the server.
@@ -49,6 +50,7 @@ pub struct GetServerTimeZonesResponseMessage {
 
 /// Container for time zone definitions.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneDefinitions {
     /// Array of time zone definitions.
@@ -60,6 +62,7 @@ pub struct TimeZoneDefinitions {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/timezonedefinition>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneDefinition {
     /// The unique identifier for the time zone.
@@ -82,6 +85,7 @@ pub struct TimeZoneDefinition {
 
 /// Container for time zone periods.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZonePeriods {
     /// Array of time zone periods.
@@ -91,6 +95,7 @@ pub struct TimeZonePeriods {
 
 /// Represents a period within a time zone definition.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZonePeriod {
     /// The bias (in minutes) for this period.
@@ -108,6 +113,7 @@ pub struct TimeZonePeriod {
 
 /// Container for time zone transition groups.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneTransitionsGroups {
     /// Array of transition groups.
@@ -117,6 +123,7 @@ pub struct TimeZoneTransitionsGroups {
 
 /// Represents a group of transitions for a time zone.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneTransitionsGroup {
     /// The identifier for this transitions group.
@@ -129,6 +136,7 @@ pub struct TimeZoneTransitionsGroup {
 
 /// Container for time zone transitions.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneTransitions {
     /// Array of transitions.
@@ -138,6 +146,7 @@ pub struct TimeZoneTransitions {
 
 /// Represents a transition between time zone periods.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneTransition {
     /// Points to a transition group.
@@ -146,6 +155,7 @@ pub struct TimeZoneTransition {
 
 /// Represents the target of a time zone transition.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct TimeZoneTransitionTo {
     /// The kind of transition target.
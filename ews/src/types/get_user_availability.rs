@@ -2,16 +2,20 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
-use serde::Deserialize;
+use ews_proc_macros::{operation_response, OperationBuilder};
+use serde::{de::IntoDeserializer as _, Deserialize, Deserializer};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
 use xml_struct::XmlSerialize;
 
-use crate::{DateTime, ResponseClass, MESSAGES_NS_URI, TYPES_NS_URI};
+use crate::{DateTime, Error, ResponseClass, MESSAGES_NS_URI, TYPES_NS_URI};
+
+#[cfg(feature = "iana-zones")]
+mod iana_zone;
 
 /// A request to get user availability information.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getuseravailability>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
 #[operation_response(GetUserAvailabilityResponseMessage)]
 pub struct GetUserAvailability {
@@ -26,10 +30,19 @@ pub struct GetUserAvailability {
     /// The time window for which to retrieve availability information.
     #[xml_struct(ns_prefix = "t")]
     pub free_busy_view_options: FreeBusyViewOptions,
+
+    /// The options for suggesting meeting times.
+    ///
+    /// Suggestions are only returned when these options are set.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/suggestionsviewoptions>
+    #[xml_struct(ns_prefix = "t")]
+    pub suggestions_view_options: Option<SuggestionsViewOptions>,
 }
 
 /// Time zone information for the request.
 #[derive(Clone, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(default_ns = TYPES_NS_URI)]
 #[serde(rename_all = "PascalCase")]
 pub struct SerializableTimeZone {
@@ -46,6 +59,7 @@ pub struct SerializableTimeZone {
 
 /// Time zone time information.
 #[derive(Clone, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(default_ns = TYPES_NS_URI)]
 #[serde(rename_all = "PascalCase")]
 pub struct SerializableTimeZoneTime {
@@ -65,8 +79,133 @@ pub struct SerializableTimeZoneTime {
     pub day_order: i32,
 }
 
+impl SerializableTimeZone {
+    /// Creates the UTC time zone.
+    pub fn utc() -> Self {
+        Self::from_fixed_offset(UtcOffset::UTC)
+    }
+
+    /// Creates a time zone with the given offset from UTC all year round.
+    ///
+    /// Offsets are truncated to whole minutes.
+    pub fn from_fixed_offset(offset: UtcOffset) -> Self {
+        Self {
+            bias: -i32::from(offset.whole_minutes()),
+            standard_time: Some(SerializableTimeZoneTime::never()),
+            daylight_time: Some(SerializableTimeZoneTime::never()),
+        }
+    }
+
+    /// Gets the offset from UTC of standard time in the time zone.
+    pub fn standard_offset(&self) -> UtcOffset {
+        let standard_bias = self.standard_time.as_ref().map_or(0, |time| time.bias);
+
+        offset_from_bias(self.bias + standard_bias)
+    }
+
+    /// Gets the offset from UTC in effect in the time zone at the given
+    /// instant, taking daylight saving time into account.
+    pub fn utc_offset_at(&self, at: OffsetDateTime) -> UtcOffset {
+        let standard_offset = self.standard_offset();
+        let (Some(standard), Some(daylight)) = (&self.standard_time, &self.daylight_time) else {
+            return standard_offset;
+        };
+        let daylight_offset = offset_from_bias(self.bias + daylight.bias);
+
+        let year = at.to_offset(standard_offset).year();
+        let (Some(daylight_start), Some(daylight_end)) =
+            (daylight.transition_in(year), standard.transition_in(year))
+        else {
+            return standard_offset;
+        };
+
+        // Each transition happens in the local time in effect before it.
+        let daylight_start = daylight_start.assume_offset(standard_offset);
+        let daylight_end = daylight_end.assume_offset(daylight_offset);
+
+        let is_daylight = if daylight_start < daylight_end {
+            daylight_start <= at && at < daylight_end
+        } else {
+            // Daylight saving time spans the turn of the year, as in the
+            // southern hemisphere.
+            at >= daylight_start || at < daylight_end
+        };
+
+        if is_daylight {
+            daylight_offset
+        } else {
+            standard_offset
+        }
+    }
+
+    /// Gets the offset from UTC in effect in the time zone at the given local
+    /// time.
+    ///
+    /// Local times which occur twice when daylight saving time ends are taken
+    /// to be in standard time, and those skipped when it starts to be in
+    /// daylight saving time.
+    pub fn utc_offset_at_local(&self, local: PrimitiveDateTime) -> UtcOffset {
+        // Both transitions are compared in standard time, so reading the
+        // local time as standard time places it on the right side of them.
+        self.utc_offset_at(local.assume_offset(self.standard_offset()))
+    }
+}
+
+impl SerializableTimeZoneTime {
+    /// Creates the transition of a time zone without daylight saving time,
+    /// which never happens.
+    ///
+    /// Servers expect both transitions of a time zone to be given, so time
+    /// zones without daylight saving time have two transitions in month 0.
+    fn never() -> Self {
+        Self {
+            bias: 0,
+            time: "00:00:00".to_string(),
+            day_of_week: DayOfWeek::Sunday,
+            month: 0,
+            day_order: 0,
+        }
+    }
+
+    /// Gets the local time of this transition in the given year.
+    ///
+    /// Returns `None` if the transition is not valid, e.g. if the time zone
+    /// does not observe daylight saving time.
+    fn transition_in(&self, year: i32) -> Option<PrimitiveDateTime> {
+        let month = Month::try_from(u8::try_from(self.month).ok()?).ok()?;
+        let weekday = self.day_of_week.weekday();
+
+        // A day order of 5 stands for the last such day of the month.
+        let first = Date::from_calendar_date(year, month, 1).ok()?;
+        let first_match = (0..7)
+            .map(|days| first + time::Duration::days(days))
+            .find(|date| date.weekday() == weekday)?;
+        let mut date = first_match + time::Duration::weeks(i64::from(self.day_order.max(1) - 1));
+        while date.month() != month {
+            date -= time::Duration::weeks(1);
+        }
+
+        let mut parts = self.time.split(':').map(str::parse::<u8>);
+        let hour = parts.next()?.ok()?;
+        let minute = parts.next().unwrap_or(Ok(0)).ok()?;
+        let second = parts.next().unwrap_or(Ok(0)).ok()?;
+
+        Some(PrimitiveDateTime::new(
+            date,
+            Time::from_hms(hour, minute, second).ok()?,
+        ))
+    }
+}
+
+/// Converts a Windows time zone bias, the number of minutes to add to local
+/// time to get UTC, into an offset from UTC.
+fn offset_from_bias(bias: i32) -> UtcOffset {
+    UtcOffset::from_whole_seconds(-bias * 60).unwrap_or(UtcOffset::UTC)
+}
+
 /// Days of the week.
 #[derive(Clone, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(text)]
 pub enum DayOfWeek {
     Sunday,
@@ -78,6 +217,35 @@ pub enum DayOfWeek {
     Saturday,
 }
 
+impl DayOfWeek {
+    /// Gets the corresponding day of the week.
+    pub fn weekday(&self) -> Weekday {
+        match self {
+            DayOfWeek::Sunday => Weekday::Sunday,
+            DayOfWeek::Monday => Weekday::Monday,
+            DayOfWeek::Tuesday => Weekday::Tuesday,
+            DayOfWeek::Wednesday => Weekday::Wednesday,
+            DayOfWeek::Thursday => Weekday::Thursday,
+            DayOfWeek::Friday => Weekday::Friday,
+            DayOfWeek::Saturday => Weekday::Saturday,
+        }
+    }
+}
+
+impl From<Weekday> for DayOfWeek {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Sunday => DayOfWeek::Sunday,
+            Weekday::Monday => DayOfWeek::Monday,
+            Weekday::Tuesday => DayOfWeek::Tuesday,
+            Weekday::Wednesday => DayOfWeek::Wednesday,
+            Weekday::Thursday => DayOfWeek::Thursday,
+            Weekday::Friday => DayOfWeek::Friday,
+            Weekday::Saturday => DayOfWeek::Saturday,
+        }
+    }
+}
+
 /// Array of mailbox data for availability queries.
 #[derive(Clone, Debug, XmlSerialize)]
 #[xml_struct(default_ns = TYPES_NS_URI)]
@@ -93,10 +261,36 @@ pub struct MailboxData {
     /// The email address of the mailbox.
     pub email: EmailAddress,
 
+    /// The role of the mailbox's owner in the meeting, which determines how
+    /// their availability is weighed in suggestions.
+    pub attendee_type: AttendeeType,
+
     /// Whether to exclude conflicts.
     pub exclude_conflicts: Option<bool>,
 }
 
+/// The role of an attendee whose availability is queried.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/attendeetype>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, XmlSerialize)]
+#[xml_struct(text)]
+pub enum AttendeeType {
+    /// The organizer of the meeting.
+    Organizer,
+
+    /// An attendee whose attendance is required.
+    Required,
+
+    /// An attendee whose attendance is optional.
+    Optional,
+
+    /// A room to book for the meeting.
+    Room,
+
+    /// A resource, e.g. equipment, to book for the meeting.
+    Resource,
+}
+
 /// Email address information.
 #[derive(Clone, Debug, XmlSerialize)]
 #[xml_struct(default_ns = TYPES_NS_URI)]
@@ -125,6 +319,61 @@ pub struct FreeBusyViewOptions {
     pub merged_free_busy_interval_in_minutes: Option<i32>,
 }
 
+/// Options for suggesting meeting times.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/suggestionsviewoptions>
+#[derive(Clone, Debug, XmlSerialize)]
+#[xml_struct(default_ns = TYPES_NS_URI)]
+pub struct SuggestionsViewOptions {
+    /// The percentage of attendees which must be available for a time to be
+    /// considered a good suggestion, from 1 to 49.
+    ///
+    /// The server defaults to 25.
+    pub good_threshold: Option<i32>,
+
+    /// The maximum number of suggestions returned for each day, from 0 to
+    /// 48.
+    pub maximum_results_by_day: Option<i32>,
+
+    /// The maximum number of suggestions outside of working hours returned
+    /// for each day, from 0 to 48.
+    pub maximum_non_work_hour_results_by_day: Option<i32>,
+
+    /// The duration of the meeting to suggest times for, from 30 to 1440
+    /// minutes.
+    pub meeting_duration_in_minutes: Option<i32>,
+
+    /// The minimum quality of the suggestions returned.
+    pub minimum_suggestion_quality: Option<SuggestionQuality>,
+
+    /// The time window in which to suggest times.
+    pub detailed_suggestions_window: Duration,
+
+    /// The time of the meeting being rescheduled, if any, so that it isn't
+    /// treated as a conflict.
+    pub current_meeting_time: Option<DateTime>,
+
+    /// The identifier of the meeting being rescheduled, if any.
+    pub global_object_id: Option<String>,
+}
+
+impl SuggestionsViewOptions {
+    /// Creates options for suggesting times within the given window, leaving
+    /// every other option to the server's default.
+    pub fn new(detailed_suggestions_window: Duration) -> Self {
+        Self {
+            good_threshold: None,
+            maximum_results_by_day: None,
+            maximum_non_work_hour_results_by_day: None,
+            meeting_duration_in_minutes: None,
+            minimum_suggestion_quality: None,
+            detailed_suggestions_window,
+            current_meeting_time: None,
+            global_object_id: None,
+        }
+    }
+}
+
 /// Time window for availability requests.
 #[derive(Clone, Debug, XmlSerialize)]
 #[xml_struct(default_ns = TYPES_NS_URI)]
@@ -138,6 +387,7 @@ pub struct Duration {
 
 /// The type of free/busy view to return.
 #[derive(Clone, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(text)]
 pub enum FreeBusyViewType {
     None,
@@ -149,6 +399,7 @@ pub enum FreeBusyViewType {
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct GetUserAvailabilityResponseMessage {
     pub free_busy_response_array: Option<FreeBusyResponseArray>,
@@ -157,6 +408,7 @@ pub struct GetUserAvailabilityResponseMessage {
 
 /// Array of free/busy responses.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct FreeBusyResponseArray {
     pub free_busy_response: Vec<FreeBusyResponse>,
@@ -167,6 +419,7 @@ pub type FreeBusyResponse = Option<ResponseClass<FreeBusyResponseData>>;
 
 /// Free/busy response data.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct FreeBusyResponseData {
     /// The free/busy view data.
@@ -175,6 +428,7 @@ pub struct FreeBusyResponseData {
 
 /// Free/busy view data for a mailbox.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct FreeBusyView {
     /// The type of free/busy view.
@@ -190,8 +444,95 @@ pub struct FreeBusyView {
     pub working_hours: Option<WorkingHours>,
 }
 
+impl FreeBusyView {
+    /// Decodes the merged free/busy data into the status of each interval.
+    ///
+    /// Returns an empty list if no merged data was returned.
+    pub fn merged_free_busy_statuses(&self) -> Result<Vec<LegacyFreeBusyStatus>, Error> {
+        self.merged_free_busy
+            .as_deref()
+            .map_or(Ok(Vec::new()), decode_merged_free_busy)
+    }
+
+    /// Decodes the merged free/busy data into time slots, given the options
+    /// with which the data was requested.
+    ///
+    /// The first slot starts at the start of the requested time window, and
+    /// each slot lasts for the requested interval, except that the last slot
+    /// ends no later than the end of the window. Any data beyond the end of
+    /// the window is ignored.
+    pub fn merged_free_busy_slots(
+        &self,
+        options: &FreeBusyViewOptions,
+    ) -> Result<Vec<FreeBusySlot>, Error> {
+        let interval = time::Duration::minutes(
+            options
+                .merged_free_busy_interval_in_minutes
+                .unwrap_or(DEFAULT_MERGED_FREE_BUSY_INTERVAL_IN_MINUTES)
+                .into(),
+        );
+        let window_end = options.time_window.end_time.0;
+
+        let mut start = options.time_window.start_time.0;
+        let mut slots = Vec::new();
+        for status in self.merged_free_busy_statuses()? {
+            if start >= window_end {
+                break;
+            }
+
+            let end = (start + interval).min(window_end);
+            slots.push(FreeBusySlot {
+                start: DateTime(start),
+                end: DateTime(end),
+                status,
+            });
+            start = end;
+        }
+
+        Ok(slots)
+    }
+}
+
+/// The interval used by the server for merged free/busy data if none is
+/// requested.
+const DEFAULT_MERGED_FREE_BUSY_INTERVAL_IN_MINUTES: i32 = 30;
+
+/// Decodes merged free/busy data, in which each digit represents the status
+/// of a mailbox for one interval.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mergedfreebusy>
+pub fn decode_merged_free_busy(merged: &str) -> Result<Vec<LegacyFreeBusyStatus>, Error> {
+    merged
+        .trim()
+        .chars()
+        .map(|digit| match digit {
+            '0' => Ok(LegacyFreeBusyStatus::Free),
+            '1' => Ok(LegacyFreeBusyStatus::Tentative),
+            '2' => Ok(LegacyFreeBusyStatus::Busy),
+            '3' => Ok(LegacyFreeBusyStatus::OOF),
+            '4' => Ok(LegacyFreeBusyStatus::NoData),
+            other => Err(Error::InvalidMergedFreeBusy(other)),
+        })
+        .collect()
+}
+
+/// The status of a mailbox during a single interval of merged free/busy
+/// data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FreeBusySlot {
+    /// The start of the interval.
+    pub start: DateTime,
+
+    /// The end of the interval.
+    pub end: DateTime,
+
+    /// The status of the mailbox during the interval.
+    pub status: LegacyFreeBusyStatus,
+}
+
 /// Array of calendar events.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct CalendarEventArray {
     pub calendar_event: Vec<CalendarEvent>,
@@ -199,6 +540,7 @@ pub struct CalendarEventArray {
 
 /// A calendar event in the free/busy response.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct CalendarEvent {
     /// The start time of the event.
@@ -216,6 +558,7 @@ pub struct CalendarEvent {
 
 /// Details about a calendar event.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct CalendarEventDetails {
     /// The unique identifier of the event.
@@ -245,6 +588,7 @@ pub struct CalendarEventDetails {
 
 /// Working hours information.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct WorkingHours {
     /// The time zone for working hours.
@@ -256,6 +600,7 @@ pub struct WorkingHours {
 
 /// Array of working periods.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct WorkingPeriodArray {
     pub working_period: Vec<WorkingPeriod>,
@@ -263,10 +608,14 @@ pub struct WorkingPeriodArray {
 
 /// A working period definition.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct WorkingPeriod {
     /// The days of the week for this working period.
-    pub day_of_week: DaysOfWeek,
+    ///
+    /// Exchange returns these as a space-separated list.
+    #[serde(deserialize_with = "deserialize_days_of_week")]
+    pub day_of_week: Vec<DaysOfWeek>,
 
     /// The start time of the working period.
     pub start_time_in_minutes: i32,
@@ -277,6 +626,7 @@ pub struct WorkingPeriod {
 
 /// Days of the week as a bitmask.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub enum DaysOfWeek {
     Sunday,
     Monday,
@@ -285,13 +635,166 @@ pub enum DaysOfWeek {
     Thursday,
     Friday,
     Saturday,
+    #[serde(alias = "Weekday")]
     Weekdays,
+    #[serde(alias = "WeekendDay")]
     WeekendDays,
     None,
 }
 
+/// Deserializes a space-separated list of days of the week.
+fn deserialize_days_of_week<'de, D>(deserializer: D) -> Result<Vec<DaysOfWeek>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let days = String::deserialize(deserializer)?;
+
+    days.split_whitespace()
+        .map(|day| DaysOfWeek::deserialize(day.into_deserializer()))
+        .collect()
+}
+
+impl DaysOfWeek {
+    /// Gets the individual days represented by this value.
+    pub fn weekdays(&self) -> &'static [Weekday] {
+        use Weekday::*;
+
+        match self {
+            DaysOfWeek::Sunday => &[Sunday],
+            DaysOfWeek::Monday => &[Monday],
+            DaysOfWeek::Tuesday => &[Tuesday],
+            DaysOfWeek::Wednesday => &[Wednesday],
+            DaysOfWeek::Thursday => &[Thursday],
+            DaysOfWeek::Friday => &[Friday],
+            DaysOfWeek::Saturday => &[Saturday],
+            DaysOfWeek::Weekdays => &[Monday, Tuesday, Wednesday, Thursday, Friday],
+            DaysOfWeek::WeekendDays => &[Saturday, Sunday],
+            DaysOfWeek::None => &[],
+        }
+    }
+}
+
+impl WorkingHours {
+    /// Gets the working hours on each day of the week, converted to the given
+    /// offset from UTC.
+    ///
+    /// Periods which cross midnight once converted are split in two. The
+    /// conversion uses the standard time of the working hours' time zone;
+    /// use [`is_working_time`] to account for daylight saving time at a
+    /// given instant.
+    ///
+    /// [`is_working_time`]: WorkingHours::is_working_time
+    pub fn ranges_in_offset(&self, offset: UtcOffset) -> Vec<WorkingTimeRange> {
+        let source_offset = self
+            .time_zone
+            .as_ref()
+            .map_or(UtcOffset::UTC, SerializableTimeZone::standard_offset);
+        let shift = i32::from(offset.whole_minutes()) - i32::from(source_offset.whole_minutes());
+
+        let mut ranges = Vec::new();
+        for period in self.working_periods() {
+            let start = period.start_time_in_minutes + shift;
+            let end = period.end_time_in_minutes + shift;
+            if start >= end {
+                continue;
+            }
+
+            for weekday in period.day_of_week.iter().flat_map(DaysOfWeek::weekdays) {
+                // Split the period at each midnight it crosses once shifted.
+                let mut day = start.div_euclid(MINUTES_PER_DAY);
+                while day * MINUTES_PER_DAY < end {
+                    let day_start = day * MINUTES_PER_DAY;
+                    let piece_start = start.max(day_start) - day_start;
+                    let piece_end = end.min(day_start + MINUTES_PER_DAY) - day_start;
+
+                    ranges.push(WorkingTimeRange {
+                        weekday: shift_weekday(*weekday, day),
+                        start: time_from_minutes(piece_start),
+                        end: time_from_minutes(piece_end),
+                    });
+
+                    day += 1;
+                }
+            }
+        }
+
+        ranges
+    }
+
+    /// Determines whether the given instant is within working hours.
+    pub fn is_working_time(&self, at: OffsetDateTime) -> bool {
+        let offset = self
+            .time_zone
+            .as_ref()
+            .map_or(UtcOffset::UTC, |time_zone| time_zone.utc_offset_at(at));
+        let local = at.to_offset(offset);
+        let minutes = i32::from(local.hour()) * 60 + i32::from(local.minute());
+
+        self.working_periods().any(|period| {
+            period
+                .day_of_week
+                .iter()
+                .flat_map(DaysOfWeek::weekdays)
+                .any(|weekday| *weekday == local.weekday())
+                && (period.start_time_in_minutes..period.end_time_in_minutes).contains(&minutes)
+        })
+    }
+
+    fn working_periods(&self) -> impl Iterator<Item = &WorkingPeriod> {
+        self.working_period_array
+            .iter()
+            .flat_map(|array| array.working_period.iter())
+    }
+}
+
+/// A range of working time on a single day of the week.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorkingTimeRange {
+    /// The day of the week.
+    pub weekday: Weekday,
+
+    /// The start of the range.
+    pub start: Time,
+
+    /// The end of the range, exclusive.
+    ///
+    /// An end of midnight means that the range lasts until the end of the
+    /// day.
+    pub end: Time,
+}
+
+impl WorkingTimeRange {
+    /// Determines whether the given time on the range's day is within the
+    /// range.
+    pub fn contains(&self, time: Time) -> bool {
+        self.start <= time && (time < self.end || self.end == Time::MIDNIGHT)
+    }
+}
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+fn shift_weekday(weekday: Weekday, days: i32) -> Weekday {
+    let index = (i32::from(weekday.number_days_from_monday()) + days).rem_euclid(7);
+
+    (0..index).fold(Weekday::Monday, |weekday, _| weekday.next())
+}
+
+/// Converts minutes from midnight into a time, treating the end of the day
+/// as midnight.
+fn time_from_minutes(minutes: i32) -> Time {
+    let minutes = minutes.rem_euclid(MINUTES_PER_DAY);
+
+    Time::from_hms((minutes / 60) as u8, (minutes % 60) as u8, 0)
+        .expect("minutes within a day should form a valid time")
+}
+
 /// Free/busy status values.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/legacyfreebusystatus>
+#[derive(Clone, Copy, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[xml_struct(text)]
 pub enum LegacyFreeBusyStatus {
     Free,
     Tentative,
@@ -305,6 +808,7 @@ pub type SuggestionsResponse = Option<ResponseClass<SuggestionsResponseData>>;
 
 /// Suggestions response data.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SuggestionsResponseData {
     /// Array of meeting time suggestions.
@@ -313,6 +817,7 @@ pub struct SuggestionsResponseData {
 
 /// Array of suggestion day results.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SuggestionDayResultArray {
     pub suggestion_day_result: Vec<SuggestionDayResult>,
@@ -320,6 +825,7 @@ pub struct SuggestionDayResultArray {
 
 /// Suggestion results for a single day.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SuggestionDayResult {
     /// The date for these suggestions.
@@ -334,6 +840,7 @@ pub struct SuggestionDayResult {
 
 /// Array of meeting time suggestions.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SuggestionArray {
     pub suggestion: Vec<Suggestion>,
@@ -341,6 +848,7 @@ pub struct SuggestionArray {
 
 /// A meeting time suggestion.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct Suggestion {
     /// The suggested meeting time.
@@ -358,6 +866,7 @@ pub struct Suggestion {
 
 /// Array of attendee conflict data.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct AttendeeConflictDataArray {
     pub unknown_attendee_conflict_data: Option<Vec<UnknownAttendeeConflictData>>,
@@ -367,6 +876,7 @@ pub struct AttendeeConflictDataArray {
 
 /// Conflict data for unknown attendees.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct UnknownAttendeeConflictData {
     // Add fields as needed based on EWS schema
@@ -374,6 +884,7 @@ pub struct UnknownAttendeeConflictData {
 
 /// Conflict data for individual attendees.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct IndividualAttendeeConflictData {
     pub busy_type: LegacyFreeBusyStatus,
@@ -381,6 +892,7 @@ pub struct IndividualAttendeeConflictData {
 
 /// Conflict data for group attendees.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct GroupAttendeeConflictData {
     pub number_of_members: Option<i32>,
@@ -390,7 +902,9 @@ pub struct GroupAttendeeConflictData {
 }
 
 /// Quality rating for suggestions.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, XmlSerialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[xml_struct(text)]
 pub enum SuggestionQuality {
     Excellent,
     Good,
@@ -412,6 +926,7 @@ mod tests {
 
         let mailbox_data = MailboxData {
             email,
+            attendee_type: AttendeeType::Required,
             exclude_conflicts: Some(false),
         };
 
@@ -432,6 +947,7 @@ mod tests {
                 mailbox_data: vec![mailbox_data],
             },
             free_busy_view_options: free_busy_options,
+            suggestions_view_options: None,
         };
 
         assert_eq!(operation.mailbox_data_array.mailbox_data.len(), 1);
@@ -482,6 +998,7 @@ mod tests {
             mailbox_data_array: MailboxDataArray {
                 mailbox_data: vec![MailboxData {
                     email,
+                    attendee_type: AttendeeType::Organizer,
                     exclude_conflicts: None,
                 }],
             },
@@ -493,6 +1010,7 @@ mod tests {
                 requested_view: FreeBusyViewType::Detailed,
                 merged_free_busy_interval_in_minutes: None,
             },
+            suggestions_view_options: None,
         };
 
         assert!(operation.time_zone.is_some());
@@ -503,6 +1021,73 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fixed_offset_time_zone() {
+        let at = time::OffsetDateTime::from_unix_timestamp(1_719_847_800).unwrap();
+
+        let utc = SerializableTimeZone::utc();
+        assert_eq!(utc.bias, 0);
+        assert_eq!(utc.utc_offset_at(at), UtcOffset::UTC);
+
+        let offset = UtcOffset::from_hms(5, 45, 0).unwrap();
+        let kathmandu = SerializableTimeZone::from_fixed_offset(offset);
+        assert_eq!(kathmandu.bias, -345);
+        assert_eq!(kathmandu.standard_offset(), offset);
+        assert_eq!(kathmandu.utc_offset_at(at), offset);
+        assert_eq!(
+            kathmandu.daylight_time.as_ref().map(|time| time.month),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_utc_offset_at_local_time() {
+        let pacific = SerializableTimeZone {
+            bias: 480,
+            standard_time: Some(SerializableTimeZoneTime {
+                bias: 0,
+                time: "02:00:00".to_string(),
+                day_of_week: DayOfWeek::Sunday,
+                month: 11,
+                day_order: 1,
+            }),
+            daylight_time: Some(SerializableTimeZoneTime {
+                bias: -60,
+                time: "02:00:00".to_string(),
+                day_of_week: DayOfWeek::Sunday,
+                month: 3,
+                day_order: 2,
+            }),
+        };
+        let standard = UtcOffset::from_hms(-8, 0, 0).unwrap();
+        let daylight = UtcOffset::from_hms(-7, 0, 0).unwrap();
+        let local = |month, day, hour, minute| {
+            PrimitiveDateTime::new(
+                Date::from_calendar_date(2024, month, day).unwrap(),
+                Time::from_hms(hour, minute, 0).unwrap(),
+            )
+        };
+
+        // Daylight saving time started on 10 March 2024 and ended on
+        // 3 November 2024.
+        assert_eq!(
+            pacific.utc_offset_at_local(local(Month::March, 10, 1, 59)),
+            standard
+        );
+        assert_eq!(
+            pacific.utc_offset_at_local(local(Month::March, 10, 3, 0)),
+            daylight
+        );
+        assert_eq!(
+            pacific.utc_offset_at_local(local(Month::November, 3, 0, 59)),
+            daylight
+        );
+        assert_eq!(
+            pacific.utc_offset_at_local(local(Month::November, 3, 1, 30)),
+            standard
+        );
+    }
+
     #[test]
     fn test_free_busy_view_types() {
         let view_types = vec![
@@ -591,6 +1176,7 @@ mod tests {
             .into_iter()
             .map(|email| MailboxData {
                 email,
+                attendee_type: AttendeeType::Required,
                 exclude_conflicts: Some(true),
             })
             .collect();
@@ -606,6 +1192,7 @@ mod tests {
                 requested_view: FreeBusyViewType::FreeBusyMerged,
                 merged_free_busy_interval_in_minutes: Some(60),
             },
+            suggestions_view_options: None,
         };
 
         assert_eq!(operation.mailbox_data_array.mailbox_data.len(), 3);
@@ -615,4 +1202,159 @@ mod tests {
             assert_eq!(mailbox.exclude_conflicts, Some(true));
         }
     }
+
+    #[test]
+    fn test_serialize_mailbox_data() {
+        let mailbox_data = MailboxData {
+            email: EmailAddress {
+                name: None,
+                address: "boardroom@example.com".to_string(),
+                routing_type: Some("SMTP".to_string()),
+            },
+            attendee_type: AttendeeType::Room,
+            exclude_conflicts: Some(false),
+        };
+
+        let expected = concat!(
+            r#"<t:MailboxData xmlns="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+            r#"<Email xmlns="http://schemas.microsoft.com/exchange/services/2006/types"><Address>boardroom@example.com</Address><RoutingType>SMTP</RoutingType></Email>"#,
+            r#"<AttendeeType>Room</AttendeeType><ExcludeConflicts>false</ExcludeConflicts>"#,
+            r#"</t:MailboxData>"#,
+        );
+
+        crate::test_utils::assert_serialized_content(&mailbox_data, "t:MailboxData", expected);
+    }
+
+    #[test]
+    fn test_serialize_suggestions_view_options() {
+        let start = time::OffsetDateTime::from_unix_timestamp(1_714_550_400).unwrap();
+        let options = SuggestionsViewOptions {
+            good_threshold: Some(25),
+            meeting_duration_in_minutes: Some(60),
+            minimum_suggestion_quality: Some(SuggestionQuality::Good),
+            ..SuggestionsViewOptions::new(Duration {
+                start_time: DateTime(start),
+                end_time: DateTime(start + time::Duration::days(1)),
+            })
+        };
+
+        let expected = concat!(
+            r#"<t:SuggestionsViewOptions xmlns="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+            r#"<GoodThreshold>25</GoodThreshold><MeetingDurationInMinutes>60</MeetingDurationInMinutes>"#,
+            r#"<MinimumSuggestionQuality>Good</MinimumSuggestionQuality>"#,
+            r#"<DetailedSuggestionsWindow xmlns="http://schemas.microsoft.com/exchange/services/2006/types"><StartTime>2024-05-01T08:00:00.000000000Z</StartTime>"#,
+            r#"<EndTime>2024-05-02T08:00:00.000000000Z</EndTime></DetailedSuggestionsWindow>"#,
+            r#"</t:SuggestionsViewOptions>"#,
+        );
+
+        crate::test_utils::assert_serialized_content(
+            &options,
+            "t:SuggestionsViewOptions",
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_decode_merged_free_busy() {
+        let start = time::OffsetDateTime::from_unix_timestamp(1_714_550_400).unwrap();
+        let options = FreeBusyViewOptions {
+            time_window: Duration {
+                start_time: DateTime(start),
+                end_time: DateTime(start + time::Duration::minutes(100)),
+            },
+            requested_view: FreeBusyViewType::MergedOnly,
+            merged_free_busy_interval_in_minutes: Some(30),
+        };
+        let view = FreeBusyView {
+            free_busy_view_type: FreeBusyViewType::MergedOnly,
+            merged_free_busy: Some("0234".to_string()),
+            calendar_event_array: None,
+            working_hours: None,
+        };
+
+        let slots = view
+            .merged_free_busy_slots(&options)
+            .expect("merged free/busy data should decode");
+        let statuses: Vec<_> = slots.iter().map(|slot| slot.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                LegacyFreeBusyStatus::Free,
+                LegacyFreeBusyStatus::Busy,
+                LegacyFreeBusyStatus::OOF,
+                LegacyFreeBusyStatus::NoData,
+            ]
+        );
+        assert_eq!(slots[1].start.0, start + time::Duration::minutes(30));
+        assert_eq!(slots[3].end.0, start + time::Duration::minutes(100));
+
+        assert!(matches!(
+            decode_merged_free_busy("01x"),
+            Err(Error::InvalidMergedFreeBusy('x'))
+        ));
+    }
+
+    #[test]
+    fn test_working_hours_ranges() -> Result<(), serde_path_to_error::Error<quick_xml::DeError>> {
+        let xml = r#"<WorkingHours>
+            <TimeZone>
+                <Bias>480</Bias>
+                <StandardTime>
+                    <Bias>0</Bias>
+                    <Time>02:00:00</Time>
+                    <DayOrder>1</DayOrder>
+                    <Month>11</Month>
+                    <DayOfWeek>Sunday</DayOfWeek>
+                </StandardTime>
+                <DaylightTime>
+                    <Bias>-60</Bias>
+                    <Time>02:00:00</Time>
+                    <DayOrder>2</DayOrder>
+                    <Month>3</Month>
+                    <DayOfWeek>Sunday</DayOfWeek>
+                </DaylightTime>
+            </TimeZone>
+            <WorkingPeriodArray>
+                <WorkingPeriod>
+                    <DayOfWeek>Monday Tuesday Wednesday Thursday Friday</DayOfWeek>
+                    <StartTimeInMinutes>480</StartTimeInMinutes>
+                    <EndTimeInMinutes>1020</EndTimeInMinutes>
+                </WorkingPeriod>
+            </WorkingPeriodArray>
+        </WorkingHours>"#;
+
+        let mut de = quick_xml::de::Deserializer::from_reader(xml.as_bytes());
+        let working_hours: WorkingHours = serde_path_to_error::deserialize(&mut de)?;
+
+        let ranges = working_hours.ranges_in_offset(time::UtcOffset::UTC);
+        assert_eq!(ranges.len(), 10);
+        assert_eq!(
+            ranges[0],
+            WorkingTimeRange {
+                weekday: time::Weekday::Monday,
+                start: time::Time::from_hms(16, 0, 0).unwrap(),
+                end: time::Time::MIDNIGHT,
+            }
+        );
+        assert_eq!(
+            ranges[9],
+            WorkingTimeRange {
+                weekday: time::Weekday::Saturday,
+                start: time::Time::MIDNIGHT,
+                end: time::Time::from_hms(1, 0, 0).unwrap(),
+            }
+        );
+        assert!(ranges[0].contains(time::Time::from_hms(23, 59, 0).unwrap()));
+
+        let at = |timestamp| time::OffsetDateTime::from_unix_timestamp(timestamp).unwrap();
+
+        // 08:30 local time is within working hours in both standard and
+        // daylight saving time, 07:30 is not.
+        assert!(working_hours.is_working_time(at(1_704_731_400)));
+        assert!(working_hours.is_working_time(at(1_719_847_800)));
+        assert!(!working_hours.is_working_time(at(1_704_727_800)));
+        assert!(!working_hours.is_working_time(at(1_719_793_800)));
+
+        Ok(())
+    }
 }
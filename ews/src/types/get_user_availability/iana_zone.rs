@@ -0,0 +1,196 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use jiff::{
+    tz::{Offset, TimeZone, TimeZoneTransition},
+    Timestamp,
+};
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+use crate::Error;
+
+use super::{SerializableTimeZone, SerializableTimeZoneTime};
+
+impl SerializableTimeZone {
+    /// Creates the time zone with the given IANA name, e.g. `Europe/Berlin`,
+    /// following the rules it observes in the given year.
+    ///
+    /// Zones which don't observe daylight saving time in that year have a
+    /// fixed offset, as with [`SerializableTimeZone::from_fixed_offset`],
+    /// which is the offset in effect at the end of the year. Zones with more
+    /// than one daylight saving period in a year only keep the first.
+    pub fn from_iana(name: &str, year: i32) -> Result<Self, Error> {
+        let zone = TimeZone::get(name)
+            .map_err(|err| Error::InvalidTimeZone(format!("unknown zone {name}: {err}")))?;
+        let start = start_of_year(year)?;
+        let end = start_of_year(year + 1)?;
+
+        let transitions: Vec<_> = zone
+            .following(start)
+            .take_while(|transition| transition.timestamp() < end)
+            .collect();
+        let daylight = transitions
+            .iter()
+            .find(|transition| transition.dst().is_dst());
+        let standard = transitions
+            .iter()
+            .find(|transition| !transition.dst().is_dst());
+
+        let (Some(daylight), Some(standard)) = (daylight, standard) else {
+            let last_second = end
+                .checked_sub(jiff::SignedDuration::from_secs(1))
+                .map_err(|err| Error::InvalidTimeZone(err.to_string()))?;
+
+            return Ok(Self::from_fixed_offset(utc_offset(
+                zone.to_offset(last_second),
+            )?));
+        };
+
+        let standard_minutes = offset_minutes(standard.offset());
+        let daylight_minutes = offset_minutes(daylight.offset());
+
+        Ok(Self {
+            bias: -standard_minutes,
+            standard_time: Some(transition_time(&zone, standard, 0)?),
+            daylight_time: Some(transition_time(
+                &zone,
+                daylight,
+                standard_minutes - daylight_minutes,
+            )?),
+        })
+    }
+}
+
+/// Describes a transition as the time at which it happens, in the local time
+/// in effect before it, and the bias of the period it starts.
+fn transition_time(
+    zone: &TimeZone,
+    transition: &TimeZoneTransition,
+    bias: i32,
+) -> Result<SerializableTimeZoneTime, Error> {
+    let timestamp = transition.timestamp();
+    let previous = timestamp
+        .checked_sub(jiff::SignedDuration::from_secs(1))
+        .map_err(|err| Error::InvalidTimeZone(err.to_string()))?;
+
+    let local = OffsetDateTime::from_unix_timestamp(timestamp.as_second())
+        .map_err(|err| Error::InvalidTimeZone(err.to_string()))?
+        .to_offset(utc_offset(zone.to_offset(previous))?);
+
+    // A day order of 5 stands for the last such day of the month, which is
+    // how rules such as "last Sunday of March" are expressed.
+    let day = i32::from(local.day());
+    let day_order = if day + 7 > i32::from(local.month().length(local.year())) {
+        5
+    } else {
+        (day - 1) / 7 + 1
+    };
+
+    Ok(SerializableTimeZoneTime {
+        bias,
+        time: format!(
+            "{:02}:{:02}:{:02}",
+            local.hour(),
+            local.minute(),
+            local.second()
+        ),
+        day_of_week: local.weekday().into(),
+        month: i32::from(u8::from(local.month())),
+        day_order,
+    })
+}
+
+fn start_of_year(year: i32) -> Result<Timestamp, Error> {
+    let date = Date::from_calendar_date(year, Month::January, 1)
+        .map_err(|err| Error::InvalidTimeZone(format!("invalid year {year}: {err}")))?;
+
+    Timestamp::from_second(date.midnight().assume_utc().unix_timestamp())
+        .map_err(|err| Error::InvalidTimeZone(format!("invalid year {year}: {err}")))
+}
+
+fn offset_minutes(offset: Offset) -> i32 {
+    offset.seconds() / 60
+}
+
+fn utc_offset(offset: Offset) -> Result<UtcOffset, Error> {
+    UtcOffset::from_whole_seconds(offset.seconds())
+        .map_err(|err| Error::InvalidTimeZone(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{OffsetDateTime, UtcOffset};
+
+    use crate::{
+        get_user_availability::{DayOfWeek, SerializableTimeZone, SerializableTimeZoneTime},
+        Error,
+    };
+
+    fn transition(
+        bias: i32,
+        time: &str,
+        day_of_week: DayOfWeek,
+        month: i32,
+        day_order: i32,
+    ) -> Option<SerializableTimeZoneTime> {
+        Some(SerializableTimeZoneTime {
+            bias,
+            time: time.to_string(),
+            day_of_week,
+            month,
+            day_order,
+        })
+    }
+
+    #[test]
+    fn time_zone_from_iana_name() {
+        let berlin = SerializableTimeZone::from_iana("Europe/Berlin", 2024).unwrap();
+        assert_eq!(
+            berlin,
+            SerializableTimeZone {
+                bias: -60,
+                standard_time: transition(0, "03:00:00", DayOfWeek::Sunday, 10, 5),
+                daylight_time: transition(-60, "02:00:00", DayOfWeek::Sunday, 3, 5),
+            }
+        );
+
+        let new_york = SerializableTimeZone::from_iana("America/New_York", 2024).unwrap();
+        assert_eq!(
+            new_york,
+            SerializableTimeZone {
+                bias: 300,
+                standard_time: transition(0, "02:00:00", DayOfWeek::Sunday, 11, 1),
+                daylight_time: transition(-60, "02:00:00", DayOfWeek::Sunday, 3, 2),
+            }
+        );
+
+        // Daylight saving time spans the turn of the year in the southern
+        // hemisphere.
+        let sydney = SerializableTimeZone::from_iana("Australia/Sydney", 2024).unwrap();
+        assert_eq!(
+            sydney,
+            SerializableTimeZone {
+                bias: -600,
+                standard_time: transition(0, "03:00:00", DayOfWeek::Sunday, 4, 1),
+                daylight_time: transition(-60, "02:00:00", DayOfWeek::Sunday, 10, 1),
+            }
+        );
+        let january = OffsetDateTime::from_unix_timestamp(1_704_931_200).unwrap();
+        assert_eq!(
+            sydney.utc_offset_at(january),
+            UtcOffset::from_hms(11, 0, 0).unwrap()
+        );
+
+        let tokyo = SerializableTimeZone::from_iana("Asia/Tokyo", 2024).unwrap();
+        assert_eq!(
+            tokyo,
+            SerializableTimeZone::from_fixed_offset(UtcOffset::from_hms(9, 0, 0).unwrap())
+        );
+
+        assert!(matches!(
+            SerializableTimeZone::from_iana("Mars/Olympus_Mons", 2024),
+            Err(Error::InvalidTimeZone(_))
+        ));
+    }
+}
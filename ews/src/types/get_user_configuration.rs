@@ -0,0 +1,299 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use ews_proc_macros::{operation_response, OperationBuilder};
+use serde::Deserialize;
+use xml_struct::XmlSerialize;
+
+use crate::{BaseFolderId, CategoryList, DistinguishedFolderName, Error, ItemId, MESSAGES_NS_URI};
+
+/// The name of the configuration object in which Outlook stores the master
+/// category list.
+const CATEGORY_LIST: &str = "CategoryList";
+
+/// A request to get a user configuration object, i.e. settings stored by a
+/// client in a folder of the mailbox.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getuserconfiguration>
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
+#[xml_struct(default_ns = MESSAGES_NS_URI)]
+#[operation_response(GetUserConfigurationResponseMessage, min_version = Exchange2010)]
+pub struct GetUserConfiguration {
+    /// The name of the configuration object and the folder it is stored in.
+    pub user_configuration_name: UserConfigurationName,
+
+    /// The parts of the configuration object to return.
+    pub user_configuration_properties: UserConfigurationProperties,
+}
+
+impl GetUserConfiguration {
+    /// Creates a request for the master category list of the mailbox, which
+    /// can be read from the response with
+    /// [`UserConfiguration::category_list`].
+    pub fn category_list() -> Self {
+        Self::new(
+            UserConfigurationName {
+                name: CATEGORY_LIST.to_string(),
+                folder_id: DistinguishedFolderName::Calendar.into(),
+            },
+            UserConfigurationProperties::XmlData,
+        )
+    }
+}
+
+/// The name of a user configuration object and the folder it is stored in.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/userconfigurationname>
+#[derive(Clone, Debug, Deserialize, XmlSerialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct UserConfigurationName {
+    /// The name of the configuration object, e.g. `CategoryList`.
+    #[xml_struct(attribute)]
+    #[serde(rename = "@Name")]
+    pub name: String,
+
+    /// The folder in which the configuration object is stored.
+    #[xml_struct(flatten)]
+    #[serde(rename = "$value")]
+    pub folder_id: BaseFolderId,
+}
+
+/// The parts of a user configuration object to return.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/userconfigurationproperties>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, XmlSerialize)]
+#[xml_struct(text)]
+pub enum UserConfigurationProperties {
+    /// The identifier of the configuration object.
+    Id,
+
+    /// The dictionary of the configuration object.
+    Dictionary,
+
+    /// The XML data of the configuration object.
+    XmlData,
+
+    /// The binary data of the configuration object.
+    BinaryData,
+
+    /// Every part of the configuration object.
+    All,
+}
+
+/// A response to a [`GetUserConfiguration`] request.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/getuserconfigurationresponsemessage>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct GetUserConfigurationResponseMessage {
+    /// The requested configuration object.
+    pub user_configuration: UserConfiguration,
+}
+
+/// A user configuration object, i.e. settings stored by a client in a folder
+/// of the mailbox.
+///
+/// Only the parts requested with [`UserConfigurationProperties`] are
+/// returned.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/userconfiguration>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct UserConfiguration {
+    /// The name of the configuration object and the folder it is stored in.
+    pub user_configuration_name: UserConfigurationName,
+
+    /// The identifier of the item in which the configuration object is
+    /// stored.
+    pub item_id: Option<ItemId>,
+
+    /// The dictionary of the configuration object.
+    pub dictionary: Option<UserConfigurationDictionary>,
+
+    /// The base64-encoded XML data of the configuration object.
+    pub xml_data: Option<String>,
+
+    /// The base64-encoded binary data of the configuration object.
+    pub binary_data: Option<String>,
+}
+
+impl UserConfiguration {
+    /// Parses the master category list stored in the XML data of the
+    /// configuration object, as returned for the request given by
+    /// [`GetUserConfiguration::category_list`].
+    ///
+    /// Returns `None` if the configuration object has no XML data, e.g.
+    /// because it wasn't requested.
+    pub fn category_list(&self) -> Result<Option<CategoryList>, Error> {
+        let Some(xml_data) = &self.xml_data else {
+            return Ok(None);
+        };
+
+        let xml = BASE64_STANDARD
+            .decode(xml_data.trim())
+            .map_err(|err| Error::InvalidCategoryList(format!("XML data is not base64: {err}")))?;
+
+        CategoryList::parse(&xml).map(Some)
+    }
+}
+
+/// The dictionary of a user configuration object.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dictionary>
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct UserConfigurationDictionary {
+    #[serde(rename = "DictionaryEntry", default)]
+    pub entries: Vec<DictionaryEntry>,
+}
+
+/// An entry in the dictionary of a user configuration object.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dictionaryentry>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct DictionaryEntry {
+    /// The key of the entry.
+    pub dictionary_key: DictionaryObject,
+
+    /// The value of the entry, or `None` if it is null.
+    pub dictionary_value: Option<DictionaryObject>,
+}
+
+/// A key or value in the dictionary of a user configuration object.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dictionarykey>
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct DictionaryObject {
+    /// The type of the object.
+    #[serde(rename = "Type")]
+    pub object_type: DictionaryObjectType,
+
+    /// The object, as text. Arrays have one value per element.
+    #[serde(default)]
+    pub value: Vec<String>,
+}
+
+/// The type of a key or value in the dictionary of a user configuration
+/// object.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/dictionarykey>
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub enum DictionaryObjectType {
+    DateTime,
+    Boolean,
+    Byte,
+    String,
+    Integer32,
+    UnsignedInteger32,
+    Integer64,
+    UnsignedInteger64,
+    StringArray,
+    ByteArray,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        test_utils::{assert_deserialized_content, assert_serialized_content},
+        BaseFolderId, ItemId, ResponseClass, ResponseMessages,
+    };
+
+    use super::{
+        DictionaryEntry, DictionaryObject, DictionaryObjectType, GetUserConfiguration,
+        GetUserConfigurationResponse, GetUserConfigurationResponseMessage, UserConfiguration,
+        UserConfigurationDictionary, UserConfigurationName,
+    };
+
+    #[test]
+    fn test_serialize_get_category_list() {
+        let expected = concat!(
+            r#"<GetUserConfiguration xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+            r#"<UserConfigurationName Name="CategoryList"><t:DistinguishedFolderId Id="calendar"/></UserConfigurationName>"#,
+            r#"<UserConfigurationProperties>XmlData</UserConfigurationProperties>"#,
+            r#"</GetUserConfiguration>"#,
+        );
+
+        assert_serialized_content(
+            &GetUserConfiguration::category_list(),
+            "GetUserConfiguration",
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_deserialize_get_user_configuration_response() {
+        let content = r#"<GetUserConfigurationResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">
+            <m:ResponseMessages>
+                <m:GetUserConfigurationResponseMessage ResponseClass="Success">
+                    <m:ResponseCode>NoError</m:ResponseCode>
+                    <m:UserConfiguration>
+                        <t:UserConfigurationName Name="OWA.UserOptions">
+                            <t:FolderId Id="AAMkAD" ChangeKey="AQAAAB"/>
+                        </t:UserConfigurationName>
+                        <t:ItemId Id="AAMkAE" ChangeKey="CQAAAB"/>
+                        <t:Dictionary>
+                            <t:DictionaryEntry>
+                                <t:DictionaryKey><t:Type>String</t:Type><t:Value>timezone</t:Value></t:DictionaryKey>
+                                <t:DictionaryValue><t:Type>String</t:Type><t:Value>UTC</t:Value></t:DictionaryValue>
+                            </t:DictionaryEntry>
+                            <t:DictionaryEntry>
+                                <t:DictionaryKey><t:Type>String</t:Type><t:Value>signature</t:Value></t:DictionaryKey>
+                            </t:DictionaryEntry>
+                        </t:Dictionary>
+                    </m:UserConfiguration>
+                </m:GetUserConfigurationResponseMessage>
+            </m:ResponseMessages>
+        </GetUserConfigurationResponse>"#;
+
+        let string = |value: &str| DictionaryObject {
+            object_type: DictionaryObjectType::String,
+            value: vec![value.to_string()],
+        };
+        let expected = GetUserConfigurationResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![ResponseClass::Success(
+                    GetUserConfigurationResponseMessage {
+                        user_configuration: UserConfiguration {
+                            user_configuration_name: UserConfigurationName {
+                                name: "OWA.UserOptions".to_string(),
+                                folder_id: BaseFolderId::FolderId {
+                                    id: "AAMkAD".to_string(),
+                                    change_key: Some("AQAAAB".to_string()),
+                                },
+                            },
+                            item_id: Some(ItemId {
+                                id: "AAMkAE".to_string(),
+                                change_key: Some("CQAAAB".to_string()),
+                            }),
+                            dictionary: Some(UserConfigurationDictionary {
+                                entries: vec![
+                                    DictionaryEntry {
+                                        dictionary_key: string("timezone"),
+                                        dictionary_value: Some(string("UTC")),
+                                    },
+                                    DictionaryEntry {
+                                        dictionary_key: string("signature"),
+                                        dictionary_value: None,
+                                    },
+                                ],
+                            }),
+                            xml_data: None,
+                            binary_data: None,
+                        },
+                    },
+                )],
+            },
+        };
+
+        assert_deserialized_content(content, expected);
+    }
+}
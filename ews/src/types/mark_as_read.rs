@@ -2,24 +2,34 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseItemId, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, server_version::ExchangeServerVersion, BaseItemId, BatchOperation, Error,
+    MESSAGES_NS_URI,
+};
 
 /// A request to mark one or more items as read or unread.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/markasread>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(MarkAsReadResponseMessage)]
+#[operation_response(
+    MarkAsReadResponseMessage,
+    validate = validate,
+    for_server_version = for_server_version
+)]
 pub struct MarkAsRead {
     /// Whether to mark the items as read (true) or unread (false).
     #[xml_struct(attribute)]
     pub read_flag: bool,
 
     /// Whether to suppress read receipts for the items.
+    ///
+    /// Servers older than Exchange Server 2013 SP1 don't accept this
+    /// attribute, so it is left out of requests targeting them.
     #[xml_struct(attribute)]
     pub suppress_read_receipts: Option<bool>,
 
@@ -27,13 +37,49 @@ pub struct MarkAsRead {
     pub item_ids: Vec<BaseItemId>,
 }
 
+impl BatchOperation for MarkAsRead {
+    type Input = BaseItemId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.item_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.item_ids
+    }
+}
+
 /// A response to a request for marking an item as read/unread.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/markasreadresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct MarkAsReadResponseMessage {}
 
+/// Checks a [`MarkAsRead`] operation before it is sent.
+fn validate(operation: &MarkAsRead) -> Result<(), Error> {
+    require_non_empty("MarkAsRead", "item_ids", &operation.item_ids)
+}
+
+/// Leaves `SuppressReadReceipts` out of requests targeting servers older than
+/// Exchange Server 2013 SP1, which reject it.
+fn for_server_version(
+    operation: &MarkAsRead,
+    version: ExchangeServerVersion,
+) -> Result<Option<MarkAsRead>, Error> {
+    if version >= ExchangeServerVersion::Exchange2013_SP1
+        || operation.suppress_read_receipts.is_none()
+    {
+        return Ok(None);
+    }
+
+    Ok(Some(MarkAsRead {
+        suppress_read_receipts: None,
+        ..operation.clone()
+    }))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -65,6 +111,31 @@ mod test {
         assert_serialized_content(&request, "MarkAsRead", expected);
     }
 
+    #[test]
+    fn test_serialize_mark_as_read_for_older_server() {
+        let request = MarkAsRead {
+            read_flag: false,
+            suppress_read_receipts: Some(true),
+            item_ids: vec![BaseItemId::ItemId {
+                id: "test-item-id".to_string(),
+                change_key: None,
+            }],
+        };
+
+        let expected = r#"<MarkAsRead xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" ReadFlag="false"><ItemIds><t:ItemId Id="test-item-id"/></ItemIds></MarkAsRead>"#;
+
+        let adapted = request
+            .for_server_version(ExchangeServerVersion::Exchange2010_SP2)
+            .expect("request should be adapted")
+            .expect("request should need adapting");
+        assert_serialized_content(&adapted, "MarkAsRead", expected);
+
+        assert!(request
+            .for_server_version(ExchangeServerVersion::Exchange2013_SP1)
+            .expect("request should be adapted")
+            .is_none());
+    }
+
     #[test]
     fn test_deserialize_mark_as_read_response() {
         let xml = r#"<MarkAsReadResponse xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">
@@ -82,4 +153,60 @@ mod test {
             ResponseClass::Success(_)
         ));
     }
+
+    #[test]
+    fn test_split_and_merge_mark_as_read_batch() {
+        let request = MarkAsRead {
+            read_flag: true,
+            suppress_read_receipts: Some(true),
+            item_ids: (0..5)
+                .map(|i| BaseItemId::ItemId {
+                    id: format!("item-{i}"),
+                    change_key: None,
+                })
+                .collect(),
+        };
+
+        let chunks = request.split_batch(2);
+        let chunk_ids: Vec<Vec<_>> = chunks
+            .iter()
+            .map(|chunk| {
+                assert!(chunk.read_flag);
+                assert_eq!(chunk.suppress_read_receipts, Some(true));
+
+                chunk
+                    .item_ids
+                    .iter()
+                    .map(|id| match id {
+                        BaseItemId::ItemId { id, .. } => id.as_str(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            chunk_ids,
+            vec![
+                vec!["item-0", "item-1"],
+                vec!["item-2", "item-3"],
+                vec!["item-4"]
+            ]
+        );
+
+        let responses = chunks.iter().map(|chunk| MarkAsReadResponse {
+            response_messages: crate::ResponseMessages {
+                response_messages: chunk
+                    .item_ids
+                    .iter()
+                    .map(|_| ResponseClass::Success(MarkAsReadResponseMessage {}))
+                    .collect(),
+            },
+        });
+
+        let merged = MarkAsRead::merge_response_messages(responses);
+        assert_eq!(merged.len(), 5);
+        assert!(merged
+            .iter()
+            .all(|message| matches!(message, ResponseClass::Success(_))));
+    }
 }
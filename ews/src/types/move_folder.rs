@@ -2,29 +2,49 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use xml_struct::XmlSerialize;
 
-use crate::{CopyMoveFolderData, FolderResponseMessage, MESSAGES_NS_URI};
+use crate::{
+    require_non_empty, BaseFolderId, BatchOperation, CopyMoveFolderData, Error,
+    FolderResponseMessage, MESSAGES_NS_URI,
+};
 
 /// A request to move one or more Exchange folders.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/movefolder>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(FolderResponseMessage)]
+#[operation_response(FolderResponseMessage, validate = validate)]
 pub struct MoveFolder {
     #[xml_struct(flatten)]
     pub inner: CopyMoveFolderData,
 }
 
+impl BatchOperation for MoveFolder {
+    type Input = BaseFolderId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.inner.folder_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.inner.folder_ids
+    }
+}
+
+/// Checks a [`MoveFolder`] operation before it is sent.
+fn validate(operation: &MoveFolder) -> Result<(), Error> {
+    require_non_empty("MoveFolder", "folder_ids", &operation.inner.folder_ids)
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
         move_folder::{MoveFolder, MoveFolderResponse},
         test_utils::{assert_deserialized_content, assert_serialized_content},
-        BaseFolderId, CopyMoveFolderData, Folder, FolderId, FolderResponseMessage, Folders,
-        ResponseClass, ResponseMessages,
+        BaseFolderId, CopyMoveFolderData, Folder, FolderId, FolderProperties,
+        FolderResponseMessage, Folders, ResponseClass, ResponseMessages,
     };
 
     #[test]
@@ -69,16 +89,18 @@ mod test {
                 response_messages: vec![ResponseClass::Success(FolderResponseMessage {
                     folders: Folders {
                         inner: vec![Folder::Folder {
-                            folder_id: Some(FolderId {
-                                id: "AAAlAFV".to_string(),
-                                change_key: Some("AQAAAB".to_string()),
-                            }),
-                            parent_folder_id: None,
-                            folder_class: None,
-                            display_name: None,
-                            total_count: None,
-                            child_folder_count: None,
-                            extended_property: None,
+                            properties: FolderProperties {
+                                folder_id: Some(FolderId {
+                                    id: "AAAlAFV".to_string(),
+                                    change_key: Some("AQAAAB".to_string()),
+                                }),
+                                parent_folder_id: None,
+                                folder_class: None,
+                                display_name: None,
+                                total_count: None,
+                                child_folder_count: None,
+                                extended_property: None,
+                            },
                             unread_count: None,
                         }],
                     },
@@ -2,31 +2,68 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use xml_struct::XmlSerialize;
 
-use crate::CopyMoveItemData;
+use crate::{require_non_empty, BaseFolderId, BaseItemId, BatchOperation, CopyMoveItemData, Error};
 
 use super::{ItemResponseMessage, MESSAGES_NS_URI};
 
 /// A request to move one or more Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/moveitem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(ItemResponseMessage)]
+#[operation_response(ItemResponseMessage, validate = validate)]
 pub struct MoveItem {
     #[xml_struct(flatten)]
     pub inner: CopyMoveItemData,
 }
 
+impl MoveItem {
+    /// Creates a request to recover deleted items, such as those found by
+    /// [`FindItem::recoverable_items`], by moving them to the given folder.
+    ///
+    /// Items get new identifiers when they are moved, so the request asks
+    /// for the identifiers of the recovered items to be returned.
+    ///
+    /// [`FindItem::recoverable_items`]: crate::find_item::FindItem::recoverable_items
+    pub fn recover(item_ids: Vec<BaseItemId>, to_folder_id: BaseFolderId) -> Self {
+        Self {
+            inner: CopyMoveItemData {
+                to_folder_id,
+                item_ids,
+                return_new_item_ids: Some(true),
+            },
+        }
+    }
+}
+
+impl BatchOperation for MoveItem {
+    type Input = BaseItemId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.inner.item_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.inner.item_ids
+    }
+}
+
+/// Checks a [`MoveItem`] operation before it is sent.
+fn validate(operation: &MoveItem) -> Result<(), Error> {
+    require_non_empty("MoveItem", "item_ids", &operation.inner.item_ids)
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
+        response::ResponseCode,
         test_utils::{assert_deserialized_content, assert_serialized_content},
         types::common::ItemResponseMessage,
-        BaseFolderId, BaseItemId, CopyMoveItemData, ItemId, Items, Message, RealItem,
-        ResponseClass, ResponseMessages,
+        BaseFolderId, BaseItemId, BatchOperation, CopyMoveItemData, Error, ItemId, Items, Message,
+        RealItem, ResponseClass, ResponseError, ResponseMessages,
     };
 
     use super::{MoveItem, MoveItemResponse};
@@ -52,6 +89,21 @@ mod test {
         assert_serialized_content(&move_item, "MoveItem", expected);
     }
 
+    #[test]
+    fn test_serialize_recover_item() {
+        let recover = MoveItem::recover(
+            vec![BaseItemId::ItemId {
+                id: "AAMkADdumpster1=".to_string(),
+                change_key: None,
+            }],
+            BaseFolderId::inbox(),
+        );
+
+        let expected = r#"<MoveItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><ToFolderId><t:DistinguishedFolderId Id="inbox"/></ToFolderId><ItemIds><t:ItemId Id="AAMkADdumpster1="/></ItemIds><ReturnNewItemIds>true</ReturnNewItemIds></MoveItem>"#;
+
+        assert_serialized_content(&recover, "MoveItem", expected);
+    }
+
     #[test]
     fn test_deserialize_move_item_response() {
         let content = r#"<MoveItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages"
@@ -87,4 +139,137 @@ mod test {
 
         assert_deserialized_content(content, response);
     }
+
+    #[test]
+    fn test_zip_move_item_response_messages() {
+        let item_ids = vec![
+            BaseItemId::ItemId {
+                id: "AAMkAd".to_string(),
+                change_key: None,
+            },
+            BaseItemId::ItemId {
+                id: "AAMkAe".to_string(),
+                change_key: None,
+            },
+        ];
+
+        let move_item = MoveItem {
+            inner: CopyMoveItemData {
+                to_folder_id: BaseFolderId::DistinguishedFolderId {
+                    id: "inbox".to_string(),
+                    change_key: None,
+                },
+                item_ids,
+                return_new_item_ids: None,
+            },
+        };
+
+        let success = ItemResponseMessage {
+            items: Items {
+                inner: vec![RealItem::Message(Message {
+                    item_id: Some(ItemId {
+                        id: "AAMkAf".to_string(),
+                        change_key: None,
+                    }),
+                    ..Default::default()
+                })],
+            },
+        };
+        let error = ResponseError {
+            message_text: "The specified object was not found in the store.".to_string(),
+            response_code: ResponseCode::ErrorItemNotFound,
+            message_xml: None,
+        };
+
+        let response = MoveItemResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![
+                    ResponseClass::Success(success.clone()),
+                    ResponseClass::Error(error.clone()),
+                ],
+            },
+        };
+
+        let zipped = move_item
+            .zip_response_messages(response)
+            .expect("response message count should match the input count");
+
+        let (inputs, results): (Vec<_>, Vec<_>) = zipped.into_iter().unzip();
+        let input_ids: Vec<_> = inputs
+            .into_iter()
+            .map(|input| match input {
+                BaseItemId::ItemId { id, .. } => id.as_str(),
+            })
+            .collect();
+
+        assert_eq!(input_ids, vec!["AAMkAd", "AAMkAe"]);
+        assert_eq!(results, vec![Ok(success), Err(error)]);
+
+        let short_response = MoveItemResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![],
+            },
+        };
+
+        assert!(matches!(
+            move_item.zip_response_messages(short_response),
+            Err(Error::ResponseMessageCountMismatch {
+                expected: 2,
+                actual: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn test_move_item_response_helpers() {
+        let success = ItemResponseMessage {
+            items: Items { inner: vec![] },
+        };
+        let error = ResponseError {
+            message_text: "The specified object was not found in the store.".to_string(),
+            response_code: ResponseCode::ErrorItemNotFound,
+            message_xml: None,
+        };
+
+        let response = MoveItemResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![
+                    ResponseClass::Success(success.clone()),
+                    ResponseClass::Error(error.clone()),
+                    ResponseClass::Warning(success.clone()),
+                ],
+            },
+        };
+
+        assert_eq!(response.successes().count(), 2);
+        assert_eq!(response.errors().collect::<Vec<_>>(), vec![&error]);
+        assert_eq!(response.clone().into_result(), Err(error.clone()));
+        assert!(matches!(
+            response.single(),
+            Err(Error::ResponseMessageCountMismatch {
+                expected: 1,
+                actual: 3
+            })
+        ));
+
+        let single = MoveItemResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![ResponseClass::Success(success.clone())],
+            },
+        };
+
+        assert_eq!(single.clone().into_result(), Ok(vec![success.clone()]));
+        assert_eq!(
+            single.single().expect("message should be successful"),
+            success
+        );
+
+        let failed = MoveItemResponse {
+            response_messages: ResponseMessages {
+                response_messages: vec![ResponseClass::Error(error.clone())],
+            },
+        };
+
+        assert!(matches!(failed.single(), Err(Error::Response(err)) if err == error));
+    }
 }
@@ -5,6 +5,8 @@
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
+use crate::{server_version::ExchangeServerVersion, Error};
+
 /// A marker trait for EWS operations.
 ///
 /// Types implementing this trait may appear in requests to EWS as the operation
@@ -20,6 +22,21 @@ pub trait Operation: XmlSerialize + sealed::EnvelopeBodyContents + std::fmt::Deb
     /// operation.
     type Response: OperationResponse;
 
+    /// The earliest version of Exchange Server supporting this operation.
+    ///
+    /// Servers reject operations which are newer than the version requested
+    /// in the `RequestServerVersion` header.
+    const MIN_VERSION: ExchangeServerVersion = ExchangeServerVersion::Exchange2007;
+
+    /// Determines whether this operation can be sent to a server targeting
+    /// the given version.
+    fn is_supported_by(version: ExchangeServerVersion) -> bool
+    where
+        Self: Sized,
+    {
+        version >= Self::MIN_VERSION
+    }
+
     /// Gets the name of the operation.
     ///
     /// This is the same as the local part of the name of the XML element used
@@ -27,6 +44,49 @@ pub trait Operation: XmlSerialize + sealed::EnvelopeBodyContents + std::fmt::Deb
     fn name(&self) -> &'static str {
         <Self as sealed::EnvelopeBodyContents>::name()
     }
+
+    /// Checks the operation for mistakes which the server would reject, such
+    /// as an empty list of item IDs.
+    ///
+    /// Exchange reports most of these mistakes with an opaque
+    /// `ErrorSchemaValidation` fault, so clients check operations before
+    /// sending them in order to return a descriptive error instead. The
+    /// checks don't guarantee the server will accept the operation.
+    fn validate(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Adapts the operation to a server targeting the given version, by
+    /// leaving out or replacing the elements and attributes which that
+    /// version predates.
+    ///
+    /// Returns `None` if the operation can be sent as-is, and an error if it
+    /// relies on something the version can't express.
+    fn for_server_version(&self, version: ExchangeServerVersion) -> Result<Option<Self>, Error>
+    where
+        Self: Sized,
+    {
+        let _ = version;
+
+        Ok(None)
+    }
+}
+
+/// Checks that a list in an operation isn't empty, as the EWS schema requires
+/// at least one entry in most lists.
+pub(crate) fn require_non_empty<T>(
+    operation: &'static str,
+    field: &str,
+    values: &[T],
+) -> Result<(), Error> {
+    if values.is_empty() {
+        return Err(Error::InvalidRequest {
+            operation,
+            reason: format!("`{field}` must not be empty"),
+        });
+    }
+
+    Ok(())
 }
 
 /// A marker trait for EWS operation responses.
@@ -43,7 +103,7 @@ pub trait OperationResponse:
     for<'de> Deserialize<'de> + sealed::EnvelopeBodyContents + std::fmt::Debug
 {
     /// The type of the messages in the response.
-    type Message;
+    type Message: for<'de> Deserialize<'de> + Send;
 
     /// In practice, every operation response contains a single `response_messages` field. This
     /// returns that field.
@@ -51,9 +111,272 @@ pub trait OperationResponse:
 
     /// Convert the response into its internal type.
     fn into_response_messages(self) -> Vec<crate::ResponseClass<Self::Message>>;
+
+    /// Gets a mutable reference to the messages in the response.
+    fn response_messages_mut(&mut self) -> &mut Vec<crate::ResponseClass<Self::Message>>;
+}
+
+/// An EWS operation which acts on a batch of inputs.
+///
+/// EWS returns one response message per input in such a batch, in the same
+/// order as the inputs appeared in the request. This trait allows callers to
+/// match each response message, and any error it carries, with the input it
+/// corresponds to.
+///
+/// Exchange also limits the number of inputs it will process in a single
+/// request, and stops with `ErrorBatchProcessingStopped` once that limit is
+/// reached. [`split_batch`] and [`merge_response_messages`] allow callers to
+/// send a large batch as several requests while still handling the results as
+/// a single set.
+///
+/// [`split_batch`]: BatchOperation::split_batch
+/// [`merge_response_messages`]: BatchOperation::merge_response_messages
+pub trait BatchOperation: Operation {
+    /// The type of each input in the batch, e.g. an item ID for [`GetItem`].
+    ///
+    /// [`GetItem`]: crate::get_item::GetItem
+    type Input;
+
+    /// The largest number of inputs which should be sent in a single request
+    /// for this operation by default.
+    ///
+    /// This matches the default throttling policy of Exchange Online. Servers
+    /// with a custom policy may accept more or fewer inputs, in which case
+    /// the batch size can be set on the clients in [`client`].
+    ///
+    /// [`client`]: crate::client
+    const MAX_BATCH_SIZE: usize = 100;
+
+    /// Gets the inputs in this batch, in the order in which they are sent.
+    fn batch_inputs(&self) -> &[Self::Input];
+
+    /// Gets a mutable reference to the inputs in this batch.
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input>;
+
+    /// Creates a copy of this operation acting on the given inputs in place of
+    /// its own.
+    fn with_batch_inputs(&self, inputs: Vec<Self::Input>) -> Self
+    where
+        Self: Sized + Clone,
+    {
+        let mut operation = self.clone();
+        *operation.batch_inputs_mut() = inputs;
+
+        operation
+    }
+
+    /// Splits this operation into operations acting on at most
+    /// `max_batch_size` inputs each, preserving the order of the inputs.
+    ///
+    /// The inputs are moved into the new operations rather than copied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_batch_size` is zero.
+    fn split_batch(mut self, max_batch_size: usize) -> Vec<Self>
+    where
+        Self: Sized + Clone,
+    {
+        assert!(max_batch_size > 0, "batch size must not be zero");
+
+        // Take the inputs out first, so that copying the rest of the
+        // operation for each batch doesn't copy all of them.
+        let inputs = std::mem::take(self.batch_inputs_mut());
+        let mut inputs = inputs.into_iter().peekable();
+
+        let mut batches = Vec::new();
+        while inputs.peek().is_some() {
+            let mut batch = self.clone();
+            *batch.batch_inputs_mut() = inputs.by_ref().take(max_batch_size).collect();
+            batches.push(batch);
+        }
+
+        batches
+    }
+
+    /// Merges the responses to operations created by [`split_batch`] into a
+    /// single list of response messages, in the order of the original inputs.
+    ///
+    /// The responses must be given in the same order as the operations they
+    /// respond to.
+    ///
+    /// [`split_batch`]: BatchOperation::split_batch
+    fn merge_response_messages(
+        responses: impl IntoIterator<Item = Self::Response>,
+    ) -> Vec<crate::ResponseClass<<Self::Response as OperationResponse>::Message>>
+    where
+        Self: Sized,
+    {
+        responses
+            .into_iter()
+            .flat_map(OperationResponse::into_response_messages)
+            .collect()
+    }
+
+    /// Pairs each input in this batch with its response message.
+    ///
+    /// Returns an [`Error::ResponseMessageCountMismatch`] if the number of
+    /// messages in the response differs from the number of inputs.
+    ///
+    /// [`Error::ResponseMessageCountMismatch`]: crate::Error::ResponseMessageCountMismatch
+    #[allow(clippy::type_complexity)]
+    fn zip_response_messages(
+        &self,
+        response: Self::Response,
+    ) -> Result<
+        Vec<(
+            &Self::Input,
+            Result<<Self::Response as OperationResponse>::Message, crate::ResponseError>,
+        )>,
+        crate::Error,
+    > {
+        let inputs = self.batch_inputs();
+        let messages = response.into_response_messages();
+
+        if inputs.len() != messages.len() {
+            return Err(crate::Error::ResponseMessageCountMismatch {
+                expected: inputs.len(),
+                actual: messages.len(),
+            });
+        }
+
+        Ok(inputs
+            .iter()
+            .zip(messages.into_iter().map(crate::ResponseClass::into_result))
+            .collect())
+    }
+}
+
+/// An EWS operation whose results are returned in pages, such as
+/// [`FindItem`].
+///
+/// Exchange limits the number of results returned for a single request, so
+/// retrieving all of them takes several requests with advancing offsets. A
+/// [`Pager`] takes care of issuing those requests.
+///
+/// [`FindItem`]: crate::find_item::FindItem
+pub trait PagedOperation: Operation + Clone {
+    /// The type of each result, e.g. an item for [`FindItem`].
+    ///
+    /// [`FindItem`]: crate::find_item::FindItem
+    type Output;
+
+    /// Gets the offset of the first result requested by this operation.
+    fn offset(&self) -> u32;
+
+    /// Creates a copy of this operation requesting the page of results which
+    /// starts at the given offset.
+    fn with_offset(&self, offset: u32) -> Self;
+
+    /// Extracts the page of results from a response message.
+    fn into_page(message: <Self::Response as OperationResponse>::Message) -> Page<Self::Output>;
+}
+
+/// A page of the results of a [`PagedOperation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page<T> {
+    /// The results in the page.
+    pub results: Vec<T>,
+
+    /// The offset of the first result of the next page, as indicated by the
+    /// server.
+    pub next_offset: Option<u32>,
+
+    /// Whether the page includes the last result.
+    pub includes_last_item_in_range: bool,
+}
+
+/// Drives a [`PagedOperation`] through its pages of results.
+///
+/// The pager does not send requests itself, so that it can be used with any
+/// client: [`next_operation`] gives the request for the next page, and
+/// [`process`] extracts the page from the response to that request and
+/// advances the offset. The clients in [`client`] wrap this in an iterator.
+///
+/// Paging requires the operation to search a single parent folder, as each
+/// folder is paged separately.
+///
+/// [`next_operation`]: Pager::next_operation
+/// [`process`]: Pager::process
+/// [`client`]: crate::client
+#[derive(Clone, Debug)]
+pub struct Pager<O> {
+    next: Option<O>,
+}
+
+impl<O> Pager<O>
+where
+    O: PagedOperation,
+{
+    /// Creates a pager starting from the page requested by the operation.
+    pub fn new(operation: O) -> Self {
+        Self {
+            next: Some(operation),
+        }
+    }
+
+    /// Gets the operation requesting the next page, if any pages remain.
+    pub fn next_operation(&self) -> Option<O> {
+        self.next.clone()
+    }
+
+    /// Determines whether all pages have been retrieved.
+    pub fn is_done(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// Stops paging, e.g. after the request for a page failed.
+    pub fn stop(&mut self) {
+        self.next = None;
+    }
+
+    /// Extracts the page of results from the response to the operation last
+    /// returned by [`next_operation`], and advances to the following page.
+    ///
+    /// Paging stops after an error, after the last page, or after an empty
+    /// page, so that a misbehaving server cannot cause an endless loop. For
+    /// the same reason, a next offset which isn't past the current one fails
+    /// with [`Error::PagingStalled`].
+    ///
+    /// [`Error::PagingStalled`]: crate::Error::PagingStalled
+    ///
+    /// [`next_operation`]: Pager::next_operation
+    pub fn process(&mut self, response: O::Response) -> Result<Vec<O::Output>, crate::Error> {
+        let Some(current) = self.next.take() else {
+            return Ok(Vec::new());
+        };
+
+        let mut messages = response.into_response_messages();
+        if messages.len() != 1 {
+            return Err(crate::Error::ResponseMessageCountMismatch {
+                expected: 1,
+                actual: messages.len(),
+            });
+        }
+
+        let message = messages.remove(0).into_result()?;
+        let page = O::into_page(message);
+
+        if !page.includes_last_item_in_range && !page.results.is_empty() {
+            let offset = current.offset();
+            let next_offset = page
+                .next_offset
+                .unwrap_or(offset + page.results.len() as u32);
+            if next_offset <= offset {
+                return Err(crate::Error::PagingStalled {
+                    offset,
+                    next_offset,
+                });
+            }
+
+            self.next = Some(current.with_offset(next_offset));
+        }
+
+        Ok(page.results)
+    }
 }
 
-pub(super) mod sealed {
+pub(crate) mod sealed {
     /// A trait for structures which may appear in the body of a SOAP envelope.
     pub trait EnvelopeBodyContents {
         /// Gets the name of the element enclosing the contents of this
@@ -0,0 +1,301 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Operations which this crate doesn't model yet, sent and received as raw
+//! XML.
+//!
+//! A [`RawOperation`] is sent in the same envelope as any other operation,
+//! with the same headers, and its response is returned as the XML of the
+//! element in the body of the response envelope.
+//!
+//! # Example
+//!
+//! ```
+//! use ews::{raw::RawOperation, soap::Envelope};
+//!
+//! # fn main() -> Result<(), ews::Error> {
+//! let operation = RawOperation::new(
+//!     "GetInboxRules",
+//!     concat!(
+//!         r#"<GetInboxRules xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+//!         "<MailboxSmtpAddress>user@example.com</MailboxSmtpAddress>",
+//!         "</GetInboxRules>",
+//!     ),
+//! );
+//!
+//! let request = Envelope::new(operation).as_raw_xml_document()?;
+//! # let _ = request;
+//! # Ok(())
+//! # }
+//! ```
+
+use quick_xml::{
+    events::{BytesText, Event},
+    name::QName,
+    Reader,
+};
+
+use crate::{
+    soap::{deserialize_headers, transcode_to_utf8, Envelope},
+    ContentFormat, DateTimeFormat, Error,
+};
+
+/// An operation given as the raw XML of the element to send in the body of
+/// the request envelope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawOperation {
+    name: String,
+    body: String,
+    response_name: String,
+}
+
+impl RawOperation {
+    /// Creates an operation with the given name, e.g. `GetInboxRules`, and
+    /// the XML of the element enclosing it.
+    ///
+    /// The envelope declares the `t` prefix for the EWS types namespace, but
+    /// the element must declare the EWS messages namespace itself. The
+    /// response is expected to be enclosed in an element named after the
+    /// operation with a `Response` suffix.
+    pub fn new(name: impl Into<String>, body: impl Into<String>) -> Self {
+        let name = name.into();
+        let response_name = format!("{name}Response");
+
+        Self {
+            name,
+            body: body.into(),
+            response_name,
+        }
+    }
+
+    /// Sets the name of the element expected to enclose the response.
+    pub fn with_response_name(mut self, response_name: impl Into<String>) -> Self {
+        self.response_name = response_name.into();
+        self
+    }
+
+    /// Gets the name of the operation.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the XML of the element enclosing the operation.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Gets the name of the element expected to enclose the response.
+    pub fn response_name(&self) -> &str {
+        &self.response_name
+    }
+
+    /// Extracts the response to this operation from a complete response
+    /// document.
+    ///
+    /// SOAP faults are returned as [`Error::RequestFault`], and responses
+    /// enclosed in an element with another name as
+    /// [`Error::UnexpectedResponse`].
+    pub fn parse_response(&self, document: &[u8]) -> Result<Envelope<RawResponse>, Error> {
+        let document = transcode_to_utf8(document)?;
+        let document = document.as_ref();
+        let headers = deserialize_headers(document)?;
+
+        let mut reader = Reader::from_reader(document);
+        let mut in_body = false;
+        loop {
+            let start = reader.buffer_position();
+            match reader.read_event()? {
+                Event::Start(element) if !in_body => {
+                    in_body = element.local_name().as_ref() == b"Body";
+                }
+                event @ (Event::Start(_) | Event::Empty(_)) if in_body => {
+                    let (Event::Start(element) | Event::Empty(element)) = &event else {
+                        unreachable!();
+                    };
+                    if element.local_name().as_ref() != self.response_name.as_bytes() {
+                        return Err(Error::UnexpectedResponse(document.to_vec()));
+                    }
+
+                    if let Event::Start(element) = &event {
+                        let name = element.name().as_ref().to_vec();
+                        reader.read_to_end(QName(&name))?;
+                    }
+
+                    let xml = &document[start..reader.buffer_position()];
+                    return Ok(Envelope {
+                        headers,
+                        custom_headers: Vec::new(),
+                        content_format: ContentFormat::default(),
+                        date_time_format: DateTimeFormat::default(),
+                        body: RawResponse {
+                            xml: String::from_utf8_lossy(xml).into_owned(),
+                        },
+                    });
+                }
+                Event::Eof => return Err(Error::UnexpectedResponse(document.to_vec())),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl xml_struct::XmlSerialize for RawOperation {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        // The operation is already XML, so write it as-is.
+        writer.write_event(Event::Text(BytesText::from_escaped(self.body.as_str())))?;
+
+        Ok(())
+    }
+}
+
+impl Envelope<RawOperation> {
+    /// Serializes the SOAP envelope as a complete XML document, writing the
+    /// raw operation as-is.
+    pub fn as_raw_xml_document(&self) -> Result<Vec<u8>, Error> {
+        use xml_struct::XmlSerialize;
+
+        self.write_document(|body, writer| {
+            body.serialize_child_nodes(writer)?;
+
+            Ok(())
+        })
+    }
+}
+
+/// The response to a [`RawOperation`], as the raw XML of the element
+/// enclosing it.
+///
+/// Namespace prefixes used in the response must be declared by the element
+/// itself, as is the case in responses from Exchange.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawResponse {
+    xml: String,
+}
+
+impl RawResponse {
+    /// Gets the XML of the element enclosing the response.
+    pub fn as_str(&self) -> &str {
+        &self.xml
+    }
+
+    /// Consumes the response, returning its XML.
+    pub fn into_string(self) -> String {
+        self.xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{soap::Envelope, Error};
+
+    use super::RawOperation;
+
+    fn operation() -> RawOperation {
+        RawOperation::new(
+            "GetInboxRules",
+            r#"<GetInboxRules xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><MailboxSmtpAddress>user@example.com</MailboxSmtpAddress></GetInboxRules>"#,
+        )
+    }
+
+    #[test]
+    fn serialize_raw_operation() {
+        let document = Envelope::new(operation())
+            .as_raw_xml_document()
+            .expect("envelope should serialize");
+        let document = String::from_utf8(document).expect("envelope should be UTF-8");
+
+        assert_eq!(
+            document,
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?>"#,
+                r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+                r#"<soap:Header><t:RequestServerVersion Version="Exchange2013_SP1"/></soap:Header><soap:Body>"#,
+                r#"<GetInboxRules xmlns="http://schemas.microsoft.com/exchange/services/2006/messages"><MailboxSmtpAddress>user@example.com</MailboxSmtpAddress></GetInboxRules>"#,
+                "</soap:Body></soap:Envelope>",
+            )
+        );
+    }
+
+    #[test]
+    fn parse_raw_response() {
+        let response = concat!(
+            r#"<m:GetInboxRulesResponse ResponseClass="Success" xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+            "<m:ResponseCode>NoError</m:ResponseCode><m:OutlookRuleBlobExists>false</m:OutlookRuleBlobExists>",
+            "</m:GetInboxRulesResponse>",
+        );
+        let document = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">"#,
+                r#"<s:Header><h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/></s:Header>"#,
+                "<s:Body>\n{}\n</s:Body></s:Envelope>",
+            ),
+            response
+        );
+
+        let envelope = operation()
+            .parse_response(document.as_bytes())
+            .expect("response should be extracted");
+        assert_eq!(envelope.headers.len(), 1);
+        assert_eq!(envelope.body.as_str(), response);
+
+        let err = operation()
+            .with_response_name("GetUserOofSettingsResponse")
+            .parse_response(document.as_bytes())
+            .expect_err("response with another name should be rejected");
+        assert!(matches!(err, Error::UnexpectedResponse(_)));
+    }
+
+    #[test]
+    fn parse_raw_response_in_other_encodings() {
+        let response = concat!(
+            r#"<m:GetInboxRulesResponse ResponseClass="Success" xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+            "<m:ResponseCode>NoError</m:ResponseCode><m:InboxRules><t:Rule xmlns:t=\"http://schemas.microsoft.com/exchange/services/2006/types\">",
+            "<t:DisplayName>Règle</t:DisplayName></t:Rule></m:InboxRules>",
+            "</m:GetInboxRulesResponse>",
+        );
+        let document = |encoding: &str| {
+            format!(
+                concat!(
+                    r#"<?xml version="1.0" encoding="{}"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">"#,
+                    r#"<s:Header><h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/></s:Header>"#,
+                    "<s:Body>{}</s:Body></s:Envelope>",
+                ),
+                encoding, response
+            )
+        };
+
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(document("utf-16").encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let latin1: Vec<u8> = document("iso-8859-1")
+            .chars()
+            .map(|character| u8::try_from(character).expect("character should be Latin-1"))
+            .collect();
+
+        for document in [utf16, latin1] {
+            let envelope = operation()
+                .parse_response(&document)
+                .expect("response should be extracted");
+            assert_eq!(envelope.headers.len(), 1);
+            assert_eq!(envelope.body.as_str(), response);
+        }
+    }
+
+    #[test]
+    fn parse_raw_fault() {
+        let fault = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault><faultcode>a:ErrorSchemaValidation</faultcode><faultstring>The request failed schema validation.</faultstring></s:Fault></s:Body></s:Envelope>"#;
+
+        let err = operation()
+            .parse_response(fault.as_bytes())
+            .expect_err("fault should be an error");
+        assert!(matches!(err, Error::RequestFault(_)));
+    }
+}
@@ -0,0 +1,396 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A registry of the operations supported by this crate, for handling
+//! envelopes whose operation isn't known statically.
+//!
+//! Proxies and logging middleware see requests and responses for any
+//! operation. The registry maps the name of the element in the body of such
+//! an envelope to its operation, and deserializes responses into a
+//! type-erased [`AnyResponse`], which can be inspected generically or
+//! downcast to the concrete response type.
+//!
+//! # Example
+//!
+//! ```
+//! use ews::{get_folder::GetFolderResponse, registry};
+//!
+//! # fn main() -> Result<(), ews::Error> {
+//! let document = concat!(
+//!     r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header>"#,
+//!     r#"<h:ServerVersionInfo xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types" MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08"/>"#,
+//!     r#"</s:Header><s:Body><m:GetFolderResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages">"#,
+//!     r#"<m:ResponseMessages/></m:GetFolderResponse></s:Body></s:Envelope>"#,
+//! );
+//!
+//! let envelope = registry::deserialize_response(document.as_bytes())?;
+//! assert_eq!(envelope.body.name(), "GetFolderResponse");
+//! assert!(envelope.body.downcast_ref::<GetFolderResponse>().is_some());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{any::Any, fmt};
+
+use quick_xml::{events::Event, Reader};
+
+use crate::{
+    get_item::GetItemResponse, server_version::ExchangeServerVersion, soap::Envelope,
+    types::sealed::EnvelopeBodyContents, Error, Operation, OperationResponse, ResponseClass,
+    ResponseError,
+};
+
+/// The response to an operation whose type has been erased.
+///
+/// This is implemented for the response to every operation.
+pub trait AnyResponse: fmt::Debug + Send + Sync {
+    /// Gets the name of the element enclosing the response, e.g.
+    /// `GetItemResponse`.
+    fn name(&self) -> &'static str;
+
+    /// Gets the number of messages in the response.
+    fn message_count(&self) -> usize;
+
+    /// Gets the errors among the messages in the response.
+    fn errors(&self) -> Vec<&ResponseError>;
+
+    /// Gets the response as [`Any`], e.g. for downcasting it.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Converts the boxed response into a boxed [`Any`], e.g. for
+    /// downcasting it.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<R> AnyResponse for R
+where
+    R: OperationResponse + Send + Sync + 'static,
+{
+    fn name(&self) -> &'static str {
+        <R as EnvelopeBodyContents>::name()
+    }
+
+    fn message_count(&self) -> usize {
+        self.response_messages().len()
+    }
+
+    fn errors(&self) -> Vec<&ResponseError> {
+        self.response_messages()
+            .iter()
+            .filter_map(|message| match message {
+                ResponseClass::Error(err) => Some(err),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl dyn AnyResponse {
+    /// Gets a reference to the response as the given type, if it is of that
+    /// type.
+    pub fn downcast_ref<R: OperationResponse + 'static>(&self) -> Option<&R> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Converts the response into the given type, or returns it unchanged if
+    /// it is of another type.
+    pub fn downcast<R: OperationResponse + 'static>(
+        self: Box<Self>,
+    ) -> Result<Box<R>, Box<dyn AnyResponse>> {
+        if self.as_any().is::<R>() {
+            Ok(self
+                .into_any()
+                .downcast()
+                .expect("response should be of the checked type"))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// An envelope containing the response to an operation whose type has been
+/// erased.
+pub type AnyEnvelope = Envelope<Box<dyn AnyResponse>>;
+
+/// An operation known to the registry.
+#[derive(Clone, Copy)]
+pub struct OperationEntry {
+    operation: &'static str,
+    response: &'static str,
+    min_version: ExchangeServerVersion,
+    deserialize: fn(&[u8]) -> Result<AnyEnvelope, Error>,
+}
+
+impl OperationEntry {
+    /// Gets the name of the element enclosing the operation in requests,
+    /// e.g. `GetItem`.
+    pub fn operation_name(&self) -> &'static str {
+        self.operation
+    }
+
+    /// Gets the name of the element enclosing the response to the operation,
+    /// e.g. `GetItemResponse`.
+    pub fn response_name(&self) -> &'static str {
+        self.response
+    }
+
+    /// Gets the earliest version of Exchange Server supporting the
+    /// operation.
+    pub fn min_version(&self) -> ExchangeServerVersion {
+        self.min_version
+    }
+
+    /// Deserializes a complete response document as the response to this
+    /// operation.
+    pub fn deserialize_response(&self, document: &[u8]) -> Result<AnyEnvelope, Error> {
+        (self.deserialize)(document)
+    }
+}
+
+impl fmt::Debug for OperationEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OperationEntry")
+            .field("operation", &self.operation)
+            .field("response", &self.response)
+            .field("min_version", &self.min_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Deserializes a response document as the given response type, erasing the
+/// type.
+fn deserialize_as<R>(document: &[u8]) -> Result<AnyEnvelope, Error>
+where
+    R: OperationResponse + Send + Sync + 'static,
+{
+    let envelope = Envelope::<R>::from_xml_document(document, None)?;
+
+    Ok(Envelope {
+        headers: envelope.headers,
+        custom_headers: envelope.custom_headers,
+        content_format: envelope.content_format,
+        date_time_format: envelope.date_time_format,
+        body: Box::new(envelope.body),
+    })
+}
+
+macro_rules! operations {
+    ($($module:ident::$operation:ident),* $(,)?) => {
+        &[$(OperationEntry {
+            operation: stringify!($operation),
+            response: concat!(stringify!($operation), "Response"),
+            min_version: <crate::$module::$operation as Operation>::MIN_VERSION,
+            deserialize: deserialize_as::<<crate::$module::$operation as Operation>::Response>,
+        }),*]
+    };
+}
+
+/// Every operation supported by this crate, in alphabetical order.
+static OPERATIONS: &[OperationEntry] = operations!(
+    copy_folder::CopyFolder,
+    copy_item::CopyItem,
+    create_attachment::CreateAttachment,
+    create_folder::CreateFolder,
+    create_item::CreateItem,
+    delete_attachment::DeleteAttachment,
+    delete_folder::DeleteFolder,
+    delete_item::DeleteItem,
+    empty_folder::EmptyFolder,
+    find_folder::FindFolder,
+    find_item::FindItem,
+    get_attachment::GetAttachment,
+    get_folder::GetFolder,
+    get_item::GetItem,
+    get_mail_tips::GetMailTips,
+    get_server_time_zones::GetServerTimeZones,
+    get_user_availability::GetUserAvailability,
+    get_user_configuration::GetUserConfiguration,
+    mark_as_read::MarkAsRead,
+    move_folder::MoveFolder,
+    move_item::MoveItem,
+    reply_to_item::ReplyToItem,
+    resolve_names::ResolveNames,
+    send_item::SendItem,
+    sync_folder_hierarchy::SyncFolderHierarchy,
+    sync_folder_items::SyncFolderItems,
+    update_folder::UpdateFolder,
+    update_item::UpdateItem,
+);
+
+/// Gets every operation supported by this crate, in alphabetical order.
+pub fn operations() -> &'static [OperationEntry] {
+    OPERATIONS
+}
+
+/// Finds the operation enclosed in elements with the given name, e.g.
+/// `GetItem`.
+pub fn find_operation(name: &str) -> Option<&'static OperationEntry> {
+    OPERATIONS.iter().find(|entry| entry.operation == name)
+}
+
+/// Finds the operation whose response is enclosed in elements with the
+/// given name, e.g. `GetItemResponse`.
+pub fn find_response(name: &str) -> Option<&'static OperationEntry> {
+    OPERATIONS.iter().find(|entry| entry.response == name)
+}
+
+/// Deserializes a complete response document as the response named by the
+/// element in its SOAP body.
+///
+/// SOAP faults are returned as [`Error::RequestFault`], and responses to
+/// operations unknown to the registry as [`Error::UnexpectedResponse`].
+pub fn deserialize_response(document: &[u8]) -> Result<AnyEnvelope, Error> {
+    let name = body_element_name(document)?;
+
+    if name == "Fault" {
+        // The type of the response doesn't matter when deserializing a fault.
+        return Err(
+            Envelope::<GetItemResponse>::from_xml_document(document, None)
+                .err()
+                .unwrap_or_else(|| Error::UnexpectedResponse(document.to_vec())),
+        );
+    }
+
+    match find_response(&name) {
+        Some(entry) => entry.deserialize_response(document),
+        None => Err(Error::UnexpectedResponse(document.to_vec())),
+    }
+}
+
+/// Gets the local name of the first element in the body of a SOAP envelope,
+/// e.g. `GetItem` for a request or `GetItemResponse` for its response.
+pub fn body_element_name(document: &[u8]) -> Result<String, Error> {
+    let mut reader = Reader::from_reader(document);
+    let mut in_body = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) if !in_body => {
+                in_body = start.local_name().as_ref() == b"Body";
+            }
+            Event::Start(start) | Event::Empty(start) if in_body => {
+                return Ok(String::from_utf8_lossy(start.local_name().as_ref()).into_owned());
+            }
+            Event::Eof => return Err(Error::UnexpectedResponse(document.to_vec())),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        find_folder::FindFolderResponse, get_folder::GetFolder, get_folder::GetFolderResponse,
+        server_version::ExchangeServerVersion, soap::Envelope, BaseFolderId, Error, FolderShape,
+    };
+
+    use super::{
+        body_element_name, deserialize_response, find_operation, find_response, operations,
+    };
+
+    /// Builds a response document with the given body content.
+    fn response_document(body: &str) -> String {
+        format!(
+            concat!(
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">"#,
+                r#"<s:Header><h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/></s:Header>"#,
+                r#"<s:Body>{}</s:Body></s:Envelope>"#,
+            ),
+            body
+        )
+    }
+
+    #[test]
+    fn every_response_name_matches_its_type() {
+        for entry in operations() {
+            let document = response_document(&format!(
+                "<{0}><ResponseMessages/></{0}>",
+                entry.response_name()
+            ));
+
+            let envelope = entry
+                .deserialize_response(document.as_bytes())
+                .expect("empty response should deserialize");
+            assert_eq!(envelope.body.name(), entry.response_name());
+            assert_eq!(envelope.headers.len(), 1);
+        }
+    }
+
+    #[test]
+    fn find_operations_by_name() {
+        let entry = find_operation("GetServerTimeZones").expect("operation should be known");
+        assert_eq!(entry.response_name(), "GetServerTimeZonesResponse");
+        assert_eq!(entry.min_version(), ExchangeServerVersion::Exchange2010);
+
+        assert_eq!(
+            find_response("GetItemResponse").map(|entry| entry.operation_name()),
+            Some("GetItem")
+        );
+        assert!(find_operation("Subscribe").is_none());
+    }
+
+    #[test]
+    fn deserialize_any_response() {
+        let document = response_document(concat!(
+            r#"<m:GetFolderResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+            r#"<m:GetFolderResponseMessage ResponseClass="Error"><m:MessageText>The specified folder could not be found in the store.</m:MessageText><m:ResponseCode>ErrorFolderNotFound</m:ResponseCode><m:DescriptiveLinkKey>0</m:DescriptiveLinkKey></m:GetFolderResponseMessage>"#,
+            r#"</m:ResponseMessages></m:GetFolderResponse>"#,
+        ));
+
+        let envelope =
+            deserialize_response(document.as_bytes()).expect("response should deserialize");
+        assert_eq!(envelope.body.message_count(), 1);
+        assert_eq!(envelope.body.errors().len(), 1);
+        assert!(envelope.body.downcast_ref::<FindFolderResponse>().is_none());
+
+        let response = envelope
+            .body
+            .downcast::<GetFolderResponse>()
+            .expect("response should be a GetFolder response");
+        assert_eq!(response.response_messages.response_messages.len(), 1);
+    }
+
+    #[test]
+    fn deserialize_unknown_response() {
+        let document =
+            response_document("<m:SubscribeResponse><m:ResponseMessages/></m:SubscribeResponse>");
+        assert!(matches!(
+            deserialize_response(document.as_bytes()),
+            Err(Error::UnexpectedResponse(_))
+        ));
+
+        let fault = concat!(
+            r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>"#,
+            r#"<s:Fault><faultcode>a:ErrorSchemaValidation</faultcode><faultstring>The request failed schema validation.</faultstring></s:Fault>"#,
+            r#"</s:Body></s:Envelope>"#,
+        );
+        assert!(matches!(
+            deserialize_response(fault.as_bytes()),
+            Err(Error::RequestFault(_))
+        ));
+    }
+
+    #[test]
+    fn find_operation_of_request() {
+        let request = Envelope::new(GetFolder::new(
+            FolderShape::default(),
+            vec![BaseFolderId::inbox()],
+        ))
+        .as_xml_document()
+        .expect("request should serialize");
+
+        let name = body_element_name(&request).expect("request should have a body");
+        assert_eq!(
+            find_operation(&name).map(|entry| entry.response_name()),
+            Some("GetFolderResponse")
+        );
+    }
+}
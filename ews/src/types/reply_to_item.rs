@@ -2,7 +2,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use xml_struct::XmlSerialize;
 
 use crate::{
@@ -13,7 +13,7 @@ use crate::{
 /// A reply to the sender of an item in the Exchange store.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/replytoitem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
 #[operation_response(ReplyToItemResponseMessage)]
 pub struct ReplyToItem {
@@ -96,7 +96,7 @@ mod tests {
             to_recipients: Some(ArrayOfRecipients(vec![Recipient {
                 mailbox: Mailbox {
                     name: Some("John Doe".to_string()),
-                    email_address: "john.doe@example.com".to_string(),
+                    email_address: Some("john.doe@example.com".to_string()),
                     routing_type: None,
                     mailbox_type: None,
                     item_id: None,
@@ -125,6 +125,22 @@ mod tests {
         assert_serialized_content(&reply_to_item, "ReplyToItem", expected);
     }
 
+    #[test]
+    fn test_build_reply_to_item() {
+        let reply_to_item = ReplyToItem::builder(ItemId {
+            id: "AAAtAEF/swbAAA=".to_string(),
+            change_key: Some("EwAAABYA/s4b".to_string()),
+        })
+        .message_disposition(MessageDisposition::SendOnly)
+        .subject("Re: Test Subject".to_string())
+        .new_body_content(Body::text("This is my reply."))
+        .build();
+
+        let expected = r#"<ReplyToItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" MessageDisposition="SendOnly"><t:Subject>Re: Test Subject</t:Subject><t:ReferenceItemId Id="AAAtAEF/swbAAA=" ChangeKey="EwAAABYA/s4b"/><t:NewBodyContent BodyType="Text">This is my reply.</t:NewBodyContent></ReplyToItem>"#;
+
+        assert_serialized_content(&reply_to_item, "ReplyToItem", expected);
+    }
+
     #[test]
     fn test_deserialize_reply_to_item_response() {
         let content = r#"<ReplyToItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages"
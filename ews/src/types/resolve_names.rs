@@ -2,16 +2,20 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
 use crate::{BaseFolderId, MESSAGES_NS_URI};
+pub use crate::{
+    CompleteName, Contact, EmailAddressEntry, EmailAddresses, Mailbox, MailboxType,
+    PhoneNumberEntry, PhoneNumbers, PhysicalAddressEntry, PhysicalAddresses,
+};
 
 /// A request to resolve ambiguous email addresses and display names.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/resolvenames>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
 #[operation_response(ResolveNamesResponseMessage)]
 pub struct ResolveNames {
@@ -64,6 +68,7 @@ pub enum ContactDataShape {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/resolvenamesresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct ResolveNamesResponseMessage {
     /// Collection of resolved names.
@@ -74,6 +79,7 @@ pub struct ResolveNamesResponseMessage {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/resolutionset>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct ResolutionSet {
     /// Whether names were included that could not be resolved.
@@ -96,193 +102,19 @@ pub struct ResolutionSet {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/resolution>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct Resolution {
-    /// The mailbox information for the resolved name.
+    /// The mailbox information for the resolved name, which can be used as
+    /// a [`Recipient`] of an item.
+    ///
+    /// [`Recipient`]: crate::Recipient
     pub mailbox: Mailbox,
 
     /// Contact information if available.
     pub contact: Option<Contact>,
 }
 
-/// Mailbox information for a resolved name.
-///
-/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/mailbox>
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct Mailbox {
-    /// The display name of the mailbox.
-    pub name: Option<String>,
-
-    /// The email address of the mailbox.
-    pub email_address: Option<String>,
-
-    /// The routing type (usually SMTP).
-    pub routing_type: Option<String>,
-
-    /// The mailbox type.
-    pub mailbox_type: Option<MailboxType>,
-}
-
-/// The type of mailbox.
-#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
-pub enum MailboxType {
-    Mailbox,
-    PublicDL,
-    PrivateDL,
-    Contact,
-    PublicFolder,
-    Unknown,
-    OneOff,
-    GroupMailbox,
-}
-
-/// Contact information for a resolved name.
-///
-/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/contact>
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct Contact {
-    /// The display name of the contact.
-    pub display_name: Option<String>,
-
-    /// The given name of the contact.
-    pub given_name: Option<String>,
-
-    /// The initials of the contact.
-    pub initials: Option<String>,
-
-    /// The middle name of the contact.
-    pub middle_name: Option<String>,
-
-    /// The nickname of the contact.
-    pub nickname: Option<String>,
-
-    /// The complete name of the contact.
-    pub complete_name: Option<CompleteName>,
-
-    /// The company name of the contact.
-    pub company_name: Option<String>,
-
-    /// Email addresses for the contact.
-    pub email_addresses: Option<EmailAddresses>,
-
-    /// Phone numbers for the contact.
-    pub phone_numbers: Option<PhoneNumbers>,
-
-    /// Physical addresses for the contact.
-    pub physical_addresses: Option<PhysicalAddresses>,
-}
-
-/// Complete name information for a contact.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct CompleteName {
-    /// The title of the contact.
-    pub title: Option<String>,
-
-    /// The first name of the contact.
-    pub first_name: Option<String>,
-
-    /// The middle name of the contact.
-    pub middle_name: Option<String>,
-
-    /// The last name of the contact.
-    pub last_name: Option<String>,
-
-    /// The suffix of the contact.
-    pub suffix: Option<String>,
-
-    /// The initials of the contact.
-    pub initials: Option<String>,
-
-    /// The full name of the contact.
-    pub full_name: Option<String>,
-
-    /// The nickname of the contact.
-    pub nickname: Option<String>,
-
-    /// The Yomi first name.
-    pub yomi_first_name: Option<String>,
-
-    /// The Yomi last name.
-    pub yomi_last_name: Option<String>,
-}
-
-/// Email addresses for a contact.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct EmailAddresses {
-    /// Collection of email address entries.
-    pub entry: Vec<EmailAddressEntry>,
-}
-
-/// An email address entry.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct EmailAddressEntry {
-    /// The key identifying the email address type.
-    #[serde(rename = "@Key")]
-    pub key: String,
-
-    /// The email address value.
-    #[serde(rename = "$text")]
-    pub value: String,
-}
-
-/// Phone numbers for a contact.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct PhoneNumbers {
-    /// Collection of phone number entries.
-    pub entry: Vec<PhoneNumberEntry>,
-}
-
-/// A phone number entry.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct PhoneNumberEntry {
-    /// The key identifying the phone number type.
-    #[serde(rename = "@Key")]
-    pub key: String,
-
-    /// The phone number value.
-    #[serde(rename = "$text")]
-    pub value: String,
-}
-
-/// Physical addresses for a contact.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct PhysicalAddresses {
-    /// Collection of physical address entries.
-    pub entry: Vec<PhysicalAddressEntry>,
-}
-
-/// A physical address entry.
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "PascalCase")]
-pub struct PhysicalAddressEntry {
-    /// The key identifying the address type.
-    #[serde(rename = "@Key")]
-    pub key: String,
-
-    /// The street address.
-    pub street: Option<String>,
-
-    /// The city.
-    pub city: Option<String>,
-
-    /// The state or province.
-    pub state: Option<String>,
-
-    /// The country or region.
-    pub country_or_region: Option<String>,
-
-    /// The postal code.
-    pub postal_code: Option<String>,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,4 +141,71 @@ mod tests {
             Some(ContactDataShape::Default)
         ));
     }
+
+    #[test]
+    fn test_resolved_mailbox_as_recipient() {
+        let content = concat!(
+            r#"<Resolution xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+            r#"<t:Mailbox><t:Name>John Doe</t:Name><t:EmailAddress>john.doe@example.com</t:EmailAddress>"#,
+            r#"<t:RoutingType>SMTP</t:RoutingType><t:MailboxType>Mailbox</t:MailboxType></t:Mailbox>"#,
+            r#"</Resolution>"#,
+        );
+        let resolution: Resolution =
+            quick_xml::de::from_str(content).expect("resolution should deserialize");
+        assert_eq!(resolution.mailbox.mailbox_type, Some(MailboxType::Mailbox));
+
+        let recipient: crate::Recipient = resolution.mailbox.into();
+        crate::test_utils::assert_serialized_content(
+            &recipient,
+            "t:ToRecipients",
+            concat!(
+                "<t:ToRecipients><t:Mailbox><t:Name>John Doe</t:Name>",
+                "<t:EmailAddress>john.doe@example.com</t:EmailAddress>",
+                "<t:RoutingType>SMTP</t:RoutingType><t:MailboxType>Mailbox</t:MailboxType>",
+                "</t:Mailbox></t:ToRecipients>"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_resolve_private_distribution_list() {
+        use crate::{soap::Envelope, OperationResponse, RoutingType};
+
+        // Private distribution lists have no address and a routing type
+        // other than `SMTP` or `EX`.
+        let document = include_bytes!("../../fixtures/resolve_names_private_distribution_list.xml");
+        let envelope = Envelope::<ResolveNamesResponse>::from_xml_document(document, None)
+            .expect("response should deserialize");
+
+        let message = envelope
+            .body
+            .into_response_messages()
+            .remove(0)
+            .into_result()
+            .expect("response should be a warning");
+        let resolutions = message.resolution_set.unwrap().resolution;
+
+        let list = &resolutions[0].mailbox;
+        assert_eq!(list.email_address, None);
+        assert_eq!(
+            list.routing_type,
+            Some(RoutingType::Other("MAPIPDL".to_string()))
+        );
+        assert_eq!(list.mailbox_type, Some(MailboxType::PrivateDL));
+        assert_eq!(resolutions[1].mailbox.routing_type, Some(RoutingType::SMTP));
+
+        // The list can be addressed by its identifier, without an empty
+        // address.
+        let recipient: crate::Recipient = list.clone().into();
+        crate::test_utils::assert_serialized_content(
+            &recipient,
+            "t:ToRecipients",
+            concat!(
+                "<t:ToRecipients><t:Mailbox><t:Name>Project team</t:Name>",
+                "<t:RoutingType>MAPIPDL</t:RoutingType><t:MailboxType>PrivateDL</t:MailboxType>",
+                r#"<t:ItemId Id="AAMkADprivatedl=" ChangeKey="EgAAABYAAAD1"/>"#,
+                "</t:Mailbox></t:ToRecipients>"
+            ),
+        );
+    }
 }
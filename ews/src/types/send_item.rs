@@ -2,18 +2,18 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, BaseItemId, MESSAGES_NS_URI};
+use crate::{require_non_empty, BaseFolderId, BaseItemId, BatchOperation, Error, MESSAGES_NS_URI};
 
 /// A request to send one or more Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/senditem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(SendItemResponseMessage)]
+#[operation_response(SendItemResponseMessage, validate = validate)]
 pub struct SendItem {
     /// Whether to save a copy of the sent item.
     ///
@@ -35,15 +35,36 @@ pub struct SendItem {
     pub saved_item_folder_id: Option<BaseFolderId>,
 }
 
+impl BatchOperation for SendItem {
+    type Input = BaseItemId;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.item_ids
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.item_ids
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SendItemResponseMessage {}
 
+/// Checks a [`SendItem`] operation before it is sent.
+fn validate(operation: &SendItem) -> Result<(), Error> {
+    require_non_empty("SendItem", "item_ids", &operation.item_ids)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{test_utils::assert_deserialized_content, ResponseClass};
+    use crate::{
+        test_utils::{assert_deserialized_content, assert_serialized_content},
+        BaseItemId, ResponseClass,
+    };
 
-    use super::{SendItemResponse, SendItemResponseMessage};
+    use super::{SendItem, SendItemResponse, SendItemResponseMessage};
     use crate::ResponseMessages;
 
     #[test]
@@ -66,4 +87,21 @@ mod test {
 
         assert_deserialized_content(content, expected);
     }
+
+    #[test]
+    fn test_new_send_item() {
+        let send_item = SendItem::new(
+            false,
+            vec![BaseItemId::ItemId {
+                id: "AAAtAEF/swbAAA=".to_string(),
+                change_key: Some("EwAAABYA/s4b".to_string()),
+            }],
+        );
+
+        assert!(send_item.saved_item_folder_id.is_none());
+
+        let expected = r#"<SendItem xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" SaveItemToFolder="false"><ItemIds><t:ItemId Id="AAAtAEF/swbAAA=" ChangeKey="EwAAABYA/s4b"/></ItemIds></SendItem>"#;
+
+        assert_serialized_content(&send_item, "SendItem", expected);
+    }
 }
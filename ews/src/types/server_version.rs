@@ -15,6 +15,7 @@ use crate::Error;
 #[derive(
     Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, XmlSerialize,
 )]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(text)]
 pub enum ExchangeServerVersion {
     Exchange2007,
@@ -27,6 +28,24 @@ pub enum ExchangeServerVersion {
     Exchange2013_SP1,
 }
 
+impl ExchangeServerVersion {
+    /// Gets the version preceding this one, or `None` for the oldest known
+    /// version.
+    pub fn previous(self) -> Option<Self> {
+        match self {
+            ExchangeServerVersion::Exchange2007 => None,
+            ExchangeServerVersion::Exchange2007_SP1 => Some(ExchangeServerVersion::Exchange2007),
+            ExchangeServerVersion::Exchange2010 => Some(ExchangeServerVersion::Exchange2007_SP1),
+            ExchangeServerVersion::Exchange2010_SP1 => Some(ExchangeServerVersion::Exchange2010),
+            ExchangeServerVersion::Exchange2010_SP2 => {
+                Some(ExchangeServerVersion::Exchange2010_SP1)
+            }
+            ExchangeServerVersion::Exchange2013 => Some(ExchangeServerVersion::Exchange2010_SP2),
+            ExchangeServerVersion::Exchange2013_SP1 => Some(ExchangeServerVersion::Exchange2013),
+        }
+    }
+}
+
 /// Parses the provided string into a known version identifier.
 impl TryFrom<&str> for ExchangeServerVersion {
     /// If the provided string could not be turned into a known version
@@ -72,6 +91,7 @@ impl From<ExchangeServerVersion> for String {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/serverversioninfo>
 #[derive(Clone, Debug, Deserialize, XmlSerialize)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub struct ServerVersionInfo {
     #[xml_struct(attribute)]
     #[serde(rename = "@MajorVersion")]
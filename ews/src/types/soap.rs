@@ -2,21 +2,33 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+use std::{
+    borrow::Cow,
+    io::{BufRead, Cursor, Read},
+};
+
 use de::EnvelopeContent;
 use quick_xml::{
-    events::{BytesDecl, BytesEnd, BytesStart, Event},
-    Writer,
+    events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
+    Reader, Writer,
 };
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
 use crate::{
-    response::ResponseCode, types::sealed, types::server_version, Error, MessageXml, Operation,
-    OperationResponse, SOAP_NS_URI, TYPES_NS_URI,
+    response::ResponseCode, types::common::NaiveTimes, types::sealed, types::server_version,
+    ContentFormat, DateTimeFormat, Error, MessageXml, Operation, OperationResponse,
+    TimeZoneContext, TimeZoneReference, SOAP_NS_URI, TYPES_NS_URI,
 };
 
 mod de;
-use self::de::DeserializeEnvelope;
+use self::de::{DeserializeEnvelope, DeserializeHeaders};
+mod encoding;
+pub(crate) use self::encoding::transcode_to_utf8;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod stream;
+use self::stream::TailReader;
 
 use super::server_version::ExchangeServerVersion;
 
@@ -31,6 +43,7 @@ use super::server_version::ExchangeServerVersion;
 // remove their XML attributes due to
 // https://github.com/thunderbird/xml-struct-rs/issues/9
 #[derive(Clone, Debug, Deserialize, XmlSerialize)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[xml_struct(variant_ns_prefix = "t")]
 #[non_exhaustive]
 pub enum Header {
@@ -43,6 +56,16 @@ pub enum Header {
         version: ExchangeServerVersion,
     },
 
+    /// The time zone in which the server interprets times without an offset
+    /// from UTC.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/timezonecontext>
+    TimeZoneContext {
+        #[xml_struct(ns_prefix = "t")]
+        #[serde(rename = "TimeZoneDefinition")]
+        time_zone_definition: TimeZoneReference,
+    },
+
     /// The version information of the Exchange Server instance that generated
     /// the attached response.
     ///
@@ -50,24 +73,119 @@ pub enum Header {
     ServerVersionInfo(server_version::ServerVersionInfo),
 }
 
+/// A caller-provided element to send in the `soap:Header` section of a
+/// request, e.g. a proprietary header required by a gateway in front of the
+/// EWS endpoint.
+///
+/// The element is serialized when the header is created, so that headers of
+/// any type can be attached to the same envelope.
+///
+/// # Example
+///
+/// ```
+/// use ews::soap::CustomHeader;
+/// use xml_struct::XmlSerialize;
+///
+/// #[derive(XmlSerialize)]
+/// #[xml_struct(default_ns = "urn:example:gateway")]
+/// struct RoutingHint {
+///     tenant: String,
+/// }
+///
+/// # fn main() -> Result<(), ews::Error> {
+/// let header = CustomHeader::new(
+///     "RoutingHint",
+///     &RoutingHint {
+///         tenant: "contoso".to_string(),
+///     },
+/// )?;
+/// assert_eq!(
+///     header.as_str(),
+///     r#"<RoutingHint xmlns="urn:example:gateway"><Tenant>contoso</Tenant></RoutingHint>"#
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomHeader {
+    xml: String,
+}
+
+impl CustomHeader {
+    /// Serializes a header as an element with the given name.
+    ///
+    /// The name may include the `t` prefix of the EWS types namespace, which
+    /// is declared on the envelope. Other namespaces must be declared by the
+    /// header itself, e.g. with `#[xml_struct(default_ns = "...")]`.
+    pub fn new<T: XmlSerialize>(name: &str, header: &T) -> Result<Self, Error> {
+        let mut writer = Writer::new(Vec::new());
+        header.serialize_as_element(&mut writer, name)?;
+
+        Ok(Self {
+            xml: String::from_utf8(writer.into_inner())
+                .expect("serialized header should be valid UTF-8"),
+        })
+    }
+
+    /// Gets the serialized header.
+    pub fn as_str(&self) -> &str {
+        &self.xml
+    }
+}
+
+impl XmlSerialize for CustomHeader {
+    fn serialize_child_nodes<W>(
+        &self,
+        writer: &mut quick_xml::Writer<W>,
+    ) -> Result<(), xml_struct::Error>
+    where
+        W: std::io::Write,
+    {
+        // The header has already been serialized, so write it as-is.
+        writer.write_event(Event::Text(BytesText::from_escaped(self.xml.as_str())))?;
+
+        Ok(())
+    }
+}
+
 /// A SOAP envelope containing the body of an EWS operation or response.
 ///
 /// See <https://www.w3.org/TR/2000/NOTE-SOAP-20000508/#_Toc478383494>
 #[derive(Clone, Debug)]
 pub struct Envelope<B> {
     pub headers: Vec<Header>,
+
+    /// Caller-provided headers, written after the known EWS headers.
+    ///
+    /// These are only sent with requests, and are always empty in
+    /// responses.
+    pub custom_headers: Vec<CustomHeader>,
+
+    /// How the content of bodies and MIME content is written when the
+    /// envelope is serialized.
+    ///
+    /// This is only used for requests, and is always the default in
+    /// responses.
+    pub content_format: ContentFormat,
+
+    /// How times are written when the envelope is serialized.
+    ///
+    /// This is only used for requests, and is always the default in
+    /// responses.
+    pub date_time_format: DateTimeFormat,
+
     pub body: B,
 }
 
-impl<B> Envelope<B>
-where
-    B: Operation,
-{
+impl<B> Envelope<B> {
     pub fn new(body: B) -> Self {
         Self {
             headers: vec![Header::RequestServerVersion {
                 version: ExchangeServerVersion::default(),
             }],
+            custom_headers: Vec::new(),
+            content_format: ContentFormat::default(),
+            date_time_format: DateTimeFormat::default(),
             body,
         }
     }
@@ -75,12 +193,101 @@ where
     pub fn new_with_version(body: B, version: ExchangeServerVersion) -> Self {
         Self {
             headers: vec![Header::RequestServerVersion { version }],
+            custom_headers: Vec::new(),
+            content_format: ContentFormat::default(),
+            date_time_format: DateTimeFormat::default(),
             body,
         }
     }
+}
 
+impl<B> Envelope<B>
+where
+    B: Operation,
+{
     /// Serializes the SOAP envelope as a complete XML document.
+    ///
+    /// Times are written in the envelope's [`DateTimeFormat`], and the
+    /// content of bodies in its [`ContentFormat`].
+    ///
+    /// The body is adapted to the version given by the `RequestServerVersion`
+    /// header, as with [`Operation::for_server_version`].
     pub fn as_xml_document(&self) -> Result<Vec<u8>, Error> {
+        let adapted = self.body.for_server_version(self.target_version())?;
+        let body = adapted.as_ref().unwrap_or(&self.body);
+
+        self.write_document(|_, writer| {
+            body.serialize_as_element(writer, <B as sealed::EnvelopeBodyContents>::name())?;
+
+            Ok(())
+        })
+    }
+
+    /// Serializes the SOAP envelope as an XML document with each element on
+    /// its own indented line, e.g. for logging requests or comparing them
+    /// with the examples in Microsoft's documentation.
+    ///
+    /// The document only differs from [`Envelope::as_xml_document`] in the
+    /// whitespace between elements, which should be preferred for requests
+    /// sent to a server.
+    pub fn to_pretty_xml(&self) -> Result<String, Error> {
+        indent_document(&self.as_xml_document()?)
+    }
+}
+
+/// Rewrites a compact XML document with each element on its own line,
+/// indented by its depth.
+fn indent_document(document: &[u8]) -> Result<String, Error> {
+    let mut reader = Reader::from_reader(document);
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    loop {
+        match reader.read_event()? {
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
+
+impl Header {
+    /// Determines whether this header can be sent to a server targeting the
+    /// given version.
+    fn is_supported_by(&self, version: ExchangeServerVersion) -> bool {
+        match self {
+            // Time zone contexts were introduced with Exchange Server 2010.
+            Header::TimeZoneContext { .. } => version >= ExchangeServerVersion::Exchange2010,
+            _ => true,
+        }
+    }
+}
+
+impl<B> Envelope<B> {
+    /// Gets the version targeted by the envelope's `RequestServerVersion`
+    /// header, or the latest known version without one.
+    fn target_version(&self) -> ExchangeServerVersion {
+        self.headers
+            .iter()
+            .find_map(|header| match header {
+                Header::RequestServerVersion { version } => Some(*version),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Serializes the SOAP envelope as a complete XML document, writing the
+    /// body with the given function.
+    ///
+    /// Headers which the version given by the `RequestServerVersion` header
+    /// doesn't accept are left out; the body should already be adapted to
+    /// that version.
+    pub(crate) fn write_document<F>(&self, write_body: F) -> Result<Vec<u8>, Error>
+    where
+        F: FnOnce(&B, &mut Writer<Vec<u8>>) -> Result<(), Error>,
+    {
+        let version = self.target_version();
+
         const SOAP_ENVELOPE: &str = "soap:Envelope";
         const SOAP_HEADER: &str = "soap:Header";
         const SOAP_BODY: &str = "soap:Body";
@@ -100,20 +307,27 @@ where
                 .with_attributes([("xmlns:soap", SOAP_NS_URI), ("xmlns:t", TYPES_NS_URI)]),
         ))?;
 
-        // Write the SOAP headers.
-        self.headers
-            .serialize_as_element(&mut writer, SOAP_HEADER)?;
+        // Write the SOAP headers, followed by any custom ones.
+        writer.write_event(Event::Start(BytesStart::new(SOAP_HEADER)))?;
+        for header in &self.headers {
+            if header.is_supported_by(version) {
+                header.serialize_child_nodes(&mut writer)?;
+            }
+        }
+        self.custom_headers.serialize_child_nodes(&mut writer)?;
+        writer.write_event(Event::End(BytesEnd::new(SOAP_HEADER)))?;
 
         writer.write_event(Event::Start(BytesStart::new(SOAP_BODY)))?;
 
         // Write the operation itself.
-        self.body
-            .serialize_as_element(&mut writer, <B as sealed::EnvelopeBodyContents>::name())?;
+        write_body(&self.body, &mut writer)?;
 
         writer.write_event(Event::End(BytesEnd::new(SOAP_BODY)))?;
         writer.write_event(Event::End(BytesEnd::new(SOAP_ENVELOPE)))?;
 
-        Ok(writer.into_inner())
+        let document = self.date_time_format.apply(writer.into_inner())?;
+
+        self.content_format.apply(document)
     }
 }
 
@@ -122,21 +336,138 @@ where
     B: OperationResponse,
 {
     /// Populates an [`Envelope`] from raw XML.
-    pub fn from_xml_document(document: &[u8]) -> Result<Self, Error> {
-        let de = &mut quick_xml::de::Deserializer::from_reader(document);
+    ///
+    /// If deserialization fails, the returned [`Error::DeserializeEnvelope`]
+    /// includes the path to the element which could not be deserialized and
+    /// an excerpt of the document preceding the point of failure.
+    ///
+    /// Documents in encodings other than UTF-8 are transcoded first, with
+    /// the encoding detected from their byte order mark or XML declaration.
+    ///
+    /// Times without an offset are deserialized in the time zone of the given
+    /// context, which should be the one sent with the request, or in UTC
+    /// without one.
+    ///
+    /// With the `rayon` feature, the messages of large responses are
+    /// deserialized in parallel.
+    pub fn from_xml_document(
+        document: &[u8],
+        time_zone_context: Option<&TimeZoneContext>,
+    ) -> Result<Self, Error> {
+        let document = transcode_to_utf8(document)?;
+        let document = match time_zone_context {
+            Some(time_zone_context) => Cow::Owned(time_zone_context.resolve_naive_times(&document)),
+            None => document,
+        };
+        let document = document.as_ref();
+
+        #[cfg(feature = "rayon")]
+        if let Some(envelope) = parallel::from_xml_document(document) {
+            return Ok(envelope);
+        }
+
+        Self::deserialize_document(document)
+    }
+
+    /// Populates an [`Envelope`] from raw XML read from `reader`, e.g. the
+    /// body of an HTTP response, deserializing it as it is read rather than
+    /// buffering the whole document first.
+    ///
+    /// Errors and times without an offset are handled as with
+    /// [`Envelope::from_xml_document`], and read failures are reported as
+    /// [`Error::Io`]. Documents in encodings other than UTF-8 are read in
+    /// full so that they can be transcoded, and responses are never
+    /// deserialized in parallel.
+    pub fn from_reader<R: BufRead>(
+        mut reader: R,
+        time_zone_context: Option<&TimeZoneContext>,
+    ) -> Result<Self, Error> {
+        let start = stream::read_declaration(&mut reader)?;
+        let (encoding, bom_length) = encoding::detect_document_encoding(&start)?;
+
+        if encoding != encoding_rs::UTF_8 {
+            let mut document = start;
+            reader.read_to_end(&mut document)?;
+
+            return Self::from_xml_document(&document, time_zone_context);
+        }
+
+        let mut start = Cursor::new(start);
+        start.set_position(bom_length as u64);
+        let reader = start.chain(reader);
+
+        match time_zone_context {
+            Some(time_zone_context) => {
+                Self::deserialize_reader(NaiveTimes::new(reader, &time_zone_context.rules))
+            }
+            None => Self::deserialize_reader(reader),
+        }
+    }
+
+    /// Populates an [`Envelope`] from raw UTF-8 XML read from `reader`.
+    fn deserialize_reader<R: BufRead>(reader: R) -> Result<Self, Error> {
+        let mut reader = TailReader::new(reader, SNIPPET_MAX_LEN);
+
+        let de = &mut quick_xml::de::Deserializer::from_reader(&mut reader);
+        let result: Result<DeserializeEnvelope<B>, _> = serde_path_to_error::deserialize(de);
+        match result {
+            Ok(envelope) => envelope.into_envelope(),
+            Err(source) => {
+                let tail = reader.tail();
+
+                Err(Error::DeserializeEnvelope {
+                    source,
+                    snippet: snippet_before(&tail, tail.len()),
+                })
+            }
+        }
+    }
+
+    /// Populates an [`Envelope`] from raw XML, deserializing the whole
+    /// document at once.
+    fn deserialize_document(document: &[u8]) -> Result<Self, Error> {
+        // Keep a handle on the unread portion of the document so that we can
+        // tell how far the deserializer got if it fails.
+        let mut remaining = document;
+        let de = &mut quick_xml::de::Deserializer::from_reader(&mut remaining);
 
         // `serde_path_to_error` ensures that we get sufficient information to
         // debug errors in deserialization. serde's default errors only provide
         // the immediate error with no context; this gives us a description of
         // the context within the structure.
-        let envelope: DeserializeEnvelope<B> = serde_path_to_error::deserialize(de)?;
+        let result: Result<DeserializeEnvelope<B>, _> = serde_path_to_error::deserialize(de);
+        let envelope = match result {
+            Ok(envelope) => envelope,
+            Err(source) => {
+                let position = document.len() - remaining.len();
+
+                return Err(Error::DeserializeEnvelope {
+                    source,
+                    snippet: snippet_before(document, position),
+                });
+            }
+        };
+
+        envelope.into_envelope()
+    }
+}
 
-        match envelope.body {
+impl<B> DeserializeEnvelope<B>
+where
+    B: OperationResponse,
+{
+    /// Converts a deserialized document into an [`Envelope`], or the fault it
+    /// contains into an error.
+    fn into_envelope(self) -> Result<Envelope<B>, Error> {
+        match self.body {
             EnvelopeContent::Body(body) => Ok(Envelope {
-                headers: envelope
+                headers: self
                     .header
                     .expect("all non-fault responses should have headers")
                     .inner,
+                custom_headers: Vec::new(),
+                content_format: ContentFormat::default(),
+                date_time_format: DateTimeFormat::default(),
                 body,
             }),
             EnvelopeContent::Fault(fault) => Err(Error::RequestFault(Box::new(fault))),
@@ -144,11 +475,63 @@ where
     }
 }
 
+/// Deserializes the headers of a complete response document, regardless of
+/// the contents of its body.
+///
+/// The document must already be transcoded to UTF-8 with
+/// [`transcode_to_utf8`]; its XML declaration, which may still name its
+/// original encoding, is ignored.
+///
+/// Returns an [`Error::RequestFault`] if the body contains a SOAP fault.
+pub(crate) fn deserialize_headers(document: &[u8]) -> Result<Vec<Header>, Error> {
+    let mut remaining = document;
+    let de = &mut quick_xml::de::Deserializer::from_reader(&mut remaining);
+
+    let result: Result<DeserializeHeaders, _> = serde_path_to_error::deserialize(de);
+    let envelope = match result {
+        Ok(envelope) => envelope,
+        Err(source) => {
+            let position = document.len() - remaining.len();
+
+            return Err(Error::DeserializeEnvelope {
+                source,
+                snippet: snippet_before(document, position),
+            });
+        }
+    };
+
+    match envelope.body.fault {
+        Some(fault) => Err(Error::RequestFault(Box::new(fault))),
+        None => Ok(envelope
+            .header
+            .map(|headers| headers.inner)
+            .unwrap_or_default()),
+    }
+}
+
+/// The maximum length in bytes of the excerpt included in envelope
+/// deserialization errors.
+const SNIPPET_MAX_LEN: usize = 120;
+
+/// Gets a short excerpt of `document` ending at `position`, for use in error
+/// messages.
+fn snippet_before(document: &[u8], position: usize) -> String {
+    let end = position.min(document.len());
+    let start = end.saturating_sub(SNIPPET_MAX_LEN);
+
+    // The start of the excerpt may fall in the middle of a multibyte
+    // character, so decode lossily rather than failing outright.
+    String::from_utf8_lossy(&document[start..end])
+        .trim()
+        .to_string()
+}
+
 /// A structured representation of a SOAP fault, indicating an error in an EWS
 /// request.
 ///
 /// See <https://www.w3.org/TR/2000/NOTE-SOAP-20000508/#_Toc478383507>
 #[derive(Clone, Debug, Deserialize, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub struct Fault {
     /// An error code indicating the fault in the original request.
     // While `faultcode` is defined in the SOAP spec as a `QName`, we avoid
@@ -173,6 +556,7 @@ pub struct Fault {
 ///
 /// This element is not documented in the EWS reference.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 #[non_exhaustive]
 pub struct FaultDetail {
@@ -203,12 +587,15 @@ pub struct FaultDetail {
 
 #[cfg(test)]
 mod tests {
+    use std::io::BufReader;
+
     use ews_proc_macros::operation_response;
     use serde::Deserialize;
     use xml_struct::XmlSerialize;
 
     use crate::{
         get_folder::{GetFolderResponse, GetFolderResponseMessage},
+        get_server_time_zones::{GetServerTimeZones, TimeZoneIds},
         response::{ResponseClass, ResponseCode, ResponseError, ResponseMessages},
         sync_folder_items::SyncFolderItemsResponse,
         types::{
@@ -218,11 +605,47 @@ mod tests {
             },
             sealed::EnvelopeBodyContents,
         },
-        Error, Folder, FolderId, Folders, MessageXml, OperationResponse,
+        Error, Folder, FolderId, FolderProperties, Folders, MessageXml, OperationResponse,
     };
 
     use super::Envelope;
 
+    #[test]
+    fn serialize_envelope_with_indentation() {
+        let envelope = Envelope::new(GetServerTimeZones {
+            return_full_time_zone_data: Some(false),
+            ids: Some(TimeZoneIds {
+                id: vec!["UTC".to_string()],
+            }),
+        });
+
+        let expected = r#"<?xml version="1.0" encoding="utf-8"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">
+  <soap:Header>
+    <t:RequestServerVersion Version="Exchange2013_SP1"/>
+  </soap:Header>
+  <soap:Body>
+    <GetServerTimeZones xmlns="http://schemas.microsoft.com/exchange/services/2006/messages" ReturnFullTimeZoneData="false">
+      <Ids xmlns="http://schemas.microsoft.com/exchange/services/2006/messages">
+        <t:Id>UTC</t:Id>
+      </Ids>
+    </GetServerTimeZones>
+  </soap:Body>
+</soap:Envelope>"#;
+
+        assert_eq!(envelope.to_pretty_xml().unwrap(), expected);
+
+        let compact: String = expected
+            .lines()
+            .map(str::trim_start)
+            .collect::<Vec<_>>()
+            .concat();
+        assert_eq!(
+            String::from_utf8(envelope.as_xml_document().unwrap()).unwrap(),
+            compact
+        );
+    }
+
     #[test]
     fn deserialize_envelope_with_content() {
         #[derive(Clone, Debug, Deserialize)]
@@ -239,6 +662,9 @@ mod tests {
             fn into_response_messages(self) -> Vec<ResponseClass<Self::Message>> {
                 self.other_field.response_messages
             }
+            fn response_messages_mut(&mut self) -> &mut Vec<ResponseClass<Self::Message>> {
+                &mut self.other_field.response_messages
+            }
         }
 
         impl EnvelopeBodyContents for SomeStruct {
@@ -251,8 +677,8 @@ mod tests {
         // test the generic behavior of the interface.
         let xml = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header></s:Header><s:Body><foo:Foo><text>testing content</text><other_field/></foo:Foo></s:Body></s:Envelope>"#;
 
-        let actual: Envelope<SomeStruct> =
-            Envelope::from_xml_document(xml.as_bytes()).expect("deserialization should succeed");
+        let actual: Envelope<SomeStruct> = Envelope::from_xml_document(xml.as_bytes(), None)
+            .expect("deserialization should succeed");
 
         assert_eq!(
             actual.body.text,
@@ -269,6 +695,7 @@ mod tests {
 
     /// A meaningless struct.
     #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
     pub struct Bar {}
 
     #[test]
@@ -280,7 +707,7 @@ mod tests {
         // This XML is drawn from testing data for `evolution-ews`.
         let xml = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault><faultcode xmlns:a="http://schemas.microsoft.com/exchange/services/2006/types">a:ErrorSchemaValidation</faultcode><faultstring xml:lang="en-US">The request failed schema validation: The 'Id' attribute is invalid - The value 'invalidparentid' is invalid according to its datatype 'http://schemas.microsoft.com/exchange/services/2006/types:DistinguishedFolderIdNameType' - The Enumeration constraint failed.</faultstring><detail><e:ResponseCode xmlns:e="http://schemas.microsoft.com/exchange/services/2006/errors">ErrorSchemaValidation</e:ResponseCode><e:Message xmlns:e="http://schemas.microsoft.com/exchange/services/2006/errors">The request failed schema validation.</e:Message><t:MessageXml xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><t:LineNumber>2</t:LineNumber><t:LinePosition>630</t:LinePosition><t:Violation>The 'Id' attribute is invalid - The value 'invalidparentid' is invalid according to its datatype 'http://schemas.microsoft.com/exchange/services/2006/types:DistinguishedFolderIdNameType' - The Enumeration constraint failed.</t:Violation></t:MessageXml></detail></s:Fault></s:Body></s:Envelope>"#;
 
-        let err = <Envelope<FooResponse>>::from_xml_document(xml.as_bytes())
+        let err = <Envelope<FooResponse>>::from_xml_document(xml.as_bytes(), None)
             .expect_err("should return error when body contains fault");
 
         if let Error::RequestFault(fault) = err {
@@ -362,7 +789,7 @@ mod tests {
   </s:Body>
 </s:Envelope>"#;
 
-        let err = <Envelope<FooResponse>>::from_xml_document(xml.as_bytes())
+        let err = <Envelope<FooResponse>>::from_xml_document(xml.as_bytes(), None)
             .expect_err("should return error when body contains fault");
 
         // The testing here isn't as thorough as the invalid schema test due to
@@ -423,7 +850,7 @@ mod tests {
         // real-life examples.
         let xml = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body><s:Fault><faultcode xmlns:a="http://schemas.microsoft.com/exchange/services/2006/types">a:ErrorServerBusy</faultcode><faultstring xml:lang="en-US">I made this up because I don't have real testing data. 🙃</faultstring><detail><e:ResponseCode xmlns:e="http://schemas.microsoft.com/exchange/services/2006/errors">ErrorServerBusy</e:ResponseCode><e:Message xmlns:e="http://schemas.microsoft.com/exchange/services/2006/errors">Who really knows?</e:Message><t:MessageXml xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><t:Value Name="BackOffMilliseconds">25</t:Value></t:MessageXml></detail></s:Fault></s:Body></s:Envelope>"#;
 
-        let err = <Envelope<FooResponse>>::from_xml_document(xml.as_bytes())
+        let err = <Envelope<FooResponse>>::from_xml_document(xml.as_bytes(), None)
             .expect_err("should return error when body contains fault");
 
         // The testing here isn't as thorough as the invalid schema test due to
@@ -505,7 +932,8 @@ mod tests {
         };
 
         let envelope: Envelope<SyncFolderItemsResponse> =
-            Envelope::from_xml_document(xml.as_bytes()).expect("deserialization should succeed");
+            Envelope::from_xml_document(xml.as_bytes(), None)
+                .expect("deserialization should succeed");
         assert_eq!(envelope.body, expected_resp);
     }
 
@@ -551,16 +979,18 @@ mod tests {
                 response_messages: vec![ResponseClass::Success(GetFolderResponseMessage {
                     folders: Folders { inner: vec![
                         Folder::Folder {
-                            folder_id: Some(FolderId {
-                                id: "AQMkADRiZGNhMWIxLWIwOGMtNDQAZjktODk3OS0zZWIxODJjNmI4NWYALgAAA8ZmIFRjoG9PpiagjztHaIcBAFSUeaisgPtKo3c6hV+VzpcAAAIBCAAAAA==".to_owned(),
-                                change_key: Some("AQAAABYAAABUlHmorID7SqN3OoVflc6XAAAAAACW".to_owned())
-                            }),
-                            parent_folder_id: None,
-                            folder_class: None,
-                            display_name: None,
-                            total_count: None,
-                            child_folder_count: None,
-                            extended_property: None,
+                            properties: FolderProperties {
+                                folder_id: Some(FolderId {
+                                    id: "AQMkADRiZGNhMWIxLWIwOGMtNDQAZjktODk3OS0zZWIxODJjNmI4NWYALgAAA8ZmIFRjoG9PpiagjztHaIcBAFSUeaisgPtKo3c6hV+VzpcAAAIBCAAAAA==".to_owned(),
+                                    change_key: Some("AQAAABYAAABUlHmorID7SqN3OoVflc6XAAAAAACW".to_owned())
+                                }),
+                                parent_folder_id: None,
+                                folder_class: None,
+                                display_name: None,
+                                total_count: None,
+                                child_folder_count: None,
+                                extended_property: None
+                            },
                             unread_count: None
                         }
                     ]},
@@ -572,7 +1002,8 @@ mod tests {
         // with no error caused by the presence of attributes in the `s:Body`
         // element.
         let envelope: Envelope<GetFolderResponse> =
-            Envelope::from_xml_document(xml.as_bytes()).expect("deserialization should succeed");
+            Envelope::from_xml_document(xml.as_bytes(), None)
+                .expect("deserialization should succeed");
 
         // Check that the parsed body is in line with what we expect.
         assert_eq!(envelope.body, expected_resp);
@@ -622,16 +1053,18 @@ mod tests {
                 response_messages: vec![ResponseClass::Warning(GetFolderResponseMessage {
                     folders: Folders { inner: vec![
                         Folder::Folder {
-                            folder_id: Some(FolderId {
-                                id: "AQMkADRiZGNhMWIxLWIwOGMtNDQAZjktODk3OS0zZWIxODJjNmI4NWYALgAAA8ZmIFRjoG9PpiagjztHaIcBAFSUeaisgPtKo3c6hV+VzpcAAAIBCAAAAA==".to_owned(),
-                                change_key: Some("AQAAABYAAABUlHmorID7SqN3OoVflc6XAAAAAACW".to_owned())
-                            }),
-                            parent_folder_id: None,
-                            folder_class: None,
-                            display_name: None,
-                            total_count: None,
-                            child_folder_count: None,
-                            extended_property: None,
+                            properties: FolderProperties {
+                                folder_id: Some(FolderId {
+                                    id: "AQMkADRiZGNhMWIxLWIwOGMtNDQAZjktODk3OS0zZWIxODJjNmI4NWYALgAAA8ZmIFRjoG9PpiagjztHaIcBAFSUeaisgPtKo3c6hV+VzpcAAAIBCAAAAA==".to_owned(),
+                                    change_key: Some("AQAAABYAAABUlHmorID7SqN3OoVflc6XAAAAAACW".to_owned())
+                                }),
+                                parent_folder_id: None,
+                                folder_class: None,
+                                display_name: None,
+                                total_count: None,
+                                child_folder_count: None,
+                                extended_property: None
+                            },
                             unread_count: None
                         }
                     ]},
@@ -642,9 +1075,150 @@ mod tests {
         // Check that the XML is successfully deserialized in the first place,
         // with no error caused by use of the Warning variant.
         let envelope: Envelope<GetFolderResponse> =
-            Envelope::from_xml_document(xml.as_bytes()).expect("deserialization should succeed");
+            Envelope::from_xml_document(xml.as_bytes(), None)
+                .expect("deserialization should succeed");
 
         // Check that the parsed body is in line with what we expect.
         assert_eq!(envelope.body, expected_resp);
     }
+
+    #[test]
+    fn deserialize_envelope_error_includes_path_and_snippet() {
+        // `TotalCount` is a `u32`, so a non-numeric value
+        // should fail deserialization partway through the body.
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header></s:Header><s:Body><m:GetFolderResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages><m:GetFolderResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Folders><t:Folder><t:TotalCount>many</t:TotalCount></t:Folder></m:Folders></m:GetFolderResponseMessage></m:ResponseMessages></m:GetFolderResponse></s:Body></s:Envelope>"#;
+
+        let err = <Envelope<GetFolderResponse>>::from_xml_document(xml.as_bytes(), None)
+            .expect_err("should return error when a value is invalid");
+
+        let Error::DeserializeEnvelope { source, snippet } = &err else {
+            panic!("error should be envelope deserialization error, got: {err:?}");
+        };
+
+        assert!(
+            source.path().to_string().contains("Folders"),
+            "path should point into the folders list, got: {}",
+            source.path()
+        );
+        assert!(
+            snippet.contains("<t:TotalCount>many"),
+            "snippet should contain the offending element, got: {snippet}"
+        );
+    }
+
+    #[test]
+    fn deserialize_envelope_from_reader() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header><h:ServerVersionInfo MajorVersion="15" MinorVersion="1" MajorBuildNumber="2507" MinorBuildNumber="57" Version="V2017_07_11" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/></s:Header><s:Body><m:GetFolderResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages><m:GetFolderResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Folders><t:Folder><t:FolderId Id="AQMkADRiZGNhMWIx" ChangeKey="AQAAABYAAABU"/><t:DisplayName>Inbox</t:DisplayName></t:Folder></m:Folders></m:GetFolderResponseMessage></m:ResponseMessages></m:GetFolderResponse></s:Body></s:Envelope>"#;
+
+        let expected = <Envelope<GetFolderResponse>>::from_xml_document(xml.as_bytes(), None)
+            .expect("deserialization should succeed")
+            .body;
+
+        let envelope = <Envelope<GetFolderResponse>>::from_reader(xml.as_bytes(), None)
+            .expect("deserialization should succeed");
+        assert_eq!(envelope.body, expected);
+
+        // The document may arrive in chunks smaller than its declaration, and
+        // start with a byte order mark.
+        let with_bom = [b"\xEF\xBB\xBF", xml.as_bytes()].concat();
+        let reader = BufReader::with_capacity(7, with_bom.as_slice());
+        let envelope = <Envelope<GetFolderResponse>>::from_reader(reader, None)
+            .expect("deserialization should succeed");
+        assert_eq!(envelope.body, expected);
+
+        // Documents in other encodings are transcoded.
+        let utf16 = xml.replace("utf-8", "utf-16");
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(utf16.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        let envelope = <Envelope<GetFolderResponse>>::from_reader(utf16.as_slice(), None)
+            .expect("deserialization should succeed");
+        assert_eq!(envelope.body, expected);
+
+        // Errors point at the portion of the document read last.
+        let invalid = xml.replace(
+            "<t:DisplayName>Inbox</t:DisplayName>",
+            "<t:TotalCount>many</t:TotalCount>",
+        );
+        let reader = BufReader::with_capacity(16, invalid.as_bytes());
+        let err = <Envelope<GetFolderResponse>>::from_reader(reader, None)
+            .expect_err("should return error when a value is invalid");
+
+        let Error::DeserializeEnvelope { source, snippet } = &err else {
+            panic!("error should be envelope deserialization error, got: {err:?}");
+        };
+        assert!(
+            source.path().to_string().contains("Folders"),
+            "path should point into the folders list, got: {}",
+            source.path()
+        );
+        assert!(
+            snippet.contains("<t:TotalCount>many"),
+            "snippet should contain the offending element, got: {snippet}"
+        );
+    }
+
+    #[test]
+    fn deserialize_naive_times_in_time_zone_context() {
+        use crate::{
+            get_item::GetItemResponse, get_user_availability::SerializableTimeZone, DateTime,
+            RealItem, TimeZoneContext, TimeZoneReference,
+        };
+
+        let xml = concat!(
+            r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header/><s:Body>"#,
+            r#"<m:GetItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+            r#"<m:GetItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Items>"#,
+            r#"<t:Message><t:Subject>Fish &amp; chips</t:Subject><t:DateTimeReceived>2024-05-01T09:00:00</t:DateTimeReceived><t:DateTimeSent>2024-05-01T08:00:00Z</t:DateTimeSent></t:Message>"#,
+            r#"</m:Items></m:GetItemResponseMessage></m:ResponseMessages></m:GetItemResponse></s:Body></s:Envelope>"#,
+        );
+        let context = TimeZoneContext::new(
+            TimeZoneReference::new("Pacific Standard Time"),
+            SerializableTimeZone::from_fixed_offset(time::UtcOffset::from_hms(-7, 0, 0).unwrap()),
+        );
+
+        let received_and_sent = |envelope: Envelope<GetItemResponse>| {
+            let message = envelope
+                .body
+                .response_messages
+                .response_messages
+                .into_iter()
+                .next()
+                .expect("response should have a message")
+                .into_result()
+                .expect("message should be successful");
+            match message.items.inner.into_iter().next() {
+                Some(RealItem::Message(message)) => {
+                    assert_eq!(message.subject.as_deref(), Some("Fish & chips"));
+                    (message.date_time_received, message.date_time_sent)
+                }
+                other => panic!("unexpected item {other:?}"),
+            }
+        };
+
+        // 2024-05-01T16:00:00Z and 2024-05-01T08:00:00Z.
+        let received = time::OffsetDateTime::from_unix_timestamp(1_714_579_200).unwrap();
+        let sent = time::OffsetDateTime::from_unix_timestamp(1_714_550_400).unwrap();
+        let expected = (Some(DateTime(received)), Some(DateTime(sent)));
+
+        let envelope = Envelope::from_xml_document(xml.as_bytes(), Some(&context))
+            .expect("deserialization should succeed");
+        assert_eq!(received_and_sent(envelope), expected);
+
+        let reader = BufReader::with_capacity(16, xml.as_bytes());
+        let envelope =
+            Envelope::from_reader(reader, Some(&context)).expect("deserialization should succeed");
+        assert_eq!(received_and_sent(envelope), expected);
+
+        let envelope =
+            Envelope::from_reader(xml.as_bytes(), None).expect("deserialization should succeed");
+        assert_eq!(
+            received_and_sent(envelope),
+            (
+                Some(DateTime(received - time::Duration::hours(7))),
+                Some(DateTime(sent))
+            )
+        );
+    }
 }
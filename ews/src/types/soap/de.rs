@@ -28,6 +28,21 @@ where
     pub body: EnvelopeContent<T>,
 }
 
+/// A helper for deserializing the headers of SOAP envelopes and any fault,
+/// ignoring other contents of the body.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(super) struct DeserializeHeaders {
+    pub header: Option<SoapHeaders>,
+    pub body: FaultOnly,
+}
+
+#[derive(Deserialize)]
+pub struct FaultOnly {
+    #[serde(rename = "Fault")]
+    pub fault: Option<Fault>,
+}
+
 #[derive(Deserialize)]
 pub struct SoapHeaders {
     #[serde(rename = "$value", default)]
@@ -0,0 +1,164 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Detection of the encoding of response documents.
+//!
+//! Exchange itself always responds in UTF-8, but some proxies re-encode
+//! responses, e.g. as UTF-16 with a byte order mark, or as another charset
+//! declared in the XML declaration. Such documents are transcoded to UTF-8
+//! before being parsed.
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+use crate::Error;
+
+/// Transcodes a complete XML document to UTF-8, detecting its encoding from
+/// its byte order mark, or failing that, from its XML declaration.
+///
+/// Documents which are already in UTF-8 are returned unchanged, save for any
+/// byte order mark. Malformed sequences in other encodings are replaced with
+/// U+FFFD.
+pub(crate) fn transcode_to_utf8(document: &[u8]) -> Result<Cow<'_, [u8]>, Error> {
+    let (encoding, bom_length) = detect_document_encoding(document)?;
+    let content = &document[bom_length..];
+
+    if encoding == UTF_8 {
+        return Ok(Cow::Borrowed(content));
+    }
+
+    let (decoded, _) = encoding.decode_without_bom_handling(content);
+
+    Ok(Cow::Owned(decoded.into_owned().into_bytes()))
+}
+
+/// Detects the encoding of a document from its start, which must include its
+/// XML declaration if it has one, along with the length of its byte order
+/// mark.
+pub(crate) fn detect_document_encoding(start: &[u8]) -> Result<(&'static Encoding, usize), Error> {
+    match Encoding::for_bom(start) {
+        Some(detected) => Ok(detected),
+        None => Ok((detect_encoding(start)?, 0)),
+    }
+}
+
+/// Detects the encoding of a document without a byte order mark.
+fn detect_encoding(document: &[u8]) -> Result<&'static Encoding, Error> {
+    // The XML declaration is the only place the encoding can be declared, so
+    // a document in UTF-16 without a byte order mark must start with it.
+    match document {
+        [b'<', 0, b'?', 0, ..] => return Ok(UTF_16LE),
+        [0, b'<', 0, b'?', ..] => return Ok(UTF_16BE),
+        _ => {}
+    }
+
+    let Some(label) = declared_encoding(document) else {
+        return Ok(UTF_8);
+    };
+
+    Encoding::for_label(label)
+        .ok_or_else(|| Error::UnsupportedEncoding(String::from_utf8_lossy(label).into_owned()))
+}
+
+/// Gets the value of the `encoding` pseudo-attribute of the XML declaration
+/// of an ASCII-compatible document, if any.
+fn declared_encoding(document: &[u8]) -> Option<&[u8]> {
+    let declaration = document.strip_prefix(b"<?xml")?;
+    let end = declaration.windows(2).position(|window| window == b"?>")?;
+    let declaration = &declaration[..end];
+
+    let start = declaration
+        .windows(b"encoding".len())
+        .position(|window| window == b"encoding")?;
+    let value = declaration[start + b"encoding".len()..]
+        .trim_ascii_start()
+        .strip_prefix(b"=")?
+        .trim_ascii_start();
+
+    let (&quote, value) = value.split_first()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let end = value.iter().position(|&byte| byte == quote)?;
+
+    Some(&value[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{get_folder::GetFolderResponse, soap::Envelope, Error};
+
+    use super::transcode_to_utf8;
+
+    const DOCUMENT: &str = concat!(
+        r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header>"#,
+        r#"<h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/>"#,
+        r#"</s:Header><s:Body><m:GetFolderResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+        r#"<m:GetFolderResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Folders>"#,
+        r#"<t:Folder><t:FolderId Id="inbox-id"/><t:DisplayName>Boîte de réception</t:DisplayName></t:Folder>"#,
+        r#"</m:Folders></m:GetFolderResponseMessage></m:ResponseMessages></m:GetFolderResponse></s:Body></s:Envelope>"#,
+    );
+
+    fn utf16(text: &str, little_endian: bool, bom: bool) -> Vec<u8> {
+        let bom = bom.then_some('\u{feff}');
+        bom.into_iter()
+            .chain(text.chars())
+            .collect::<String>()
+            .encode_utf16()
+            .flat_map(|unit| match little_endian {
+                true => unit.to_le_bytes(),
+                false => unit.to_be_bytes(),
+            })
+            .collect()
+    }
+
+    fn display_name(document: &[u8]) -> Result<String, Error> {
+        let envelope = Envelope::<GetFolderResponse>::from_xml_document(document, None)?;
+        let message = envelope.body.single()?;
+        match &message.folders.inner[0] {
+            crate::Folder::Folder { properties, .. } => {
+                Ok(properties.display_name.clone().unwrap())
+            }
+            other => panic!("unexpected folder {other:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialize_documents_in_other_encodings() -> Result<(), Error> {
+        let utf16_declared = format!(r#"<?xml version="1.0" encoding="utf-16"?>{DOCUMENT}"#);
+        let latin1_declared = format!(r#"<?xml version="1.0" encoding='ISO-8859-1' ?>{DOCUMENT}"#);
+        let latin1: Vec<u8> = latin1_declared.chars().map(|c| c as u8).collect();
+
+        let documents = [
+            DOCUMENT.as_bytes().to_vec(),
+            [b"\xEF\xBB\xBF", DOCUMENT.as_bytes()].concat(),
+            utf16(DOCUMENT, true, true),
+            utf16(DOCUMENT, false, true),
+            utf16(&utf16_declared, true, false),
+            utf16(&utf16_declared, false, false),
+            latin1,
+        ];
+
+        for document in documents {
+            assert_eq!(display_name(&document)?, "Boîte de réception");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_unknown_encoding() {
+        let document = format!(r#"<?xml version="1.0" encoding="x-unknown"?>{DOCUMENT}"#);
+
+        assert!(matches!(
+            transcode_to_utf8(document.as_bytes()),
+            Err(Error::UnsupportedEncoding(label)) if label == "x-unknown"
+        ));
+        assert!(matches!(
+            transcode_to_utf8(DOCUMENT.as_bytes()),
+            Ok(std::borrow::Cow::Borrowed(_))
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Parallel deserialization of the messages of large responses.
+//!
+//! The messages of a response are independent of one another, so those of
+//! large responses, such as a `GetItem` response for a batch of hundreds of
+//! items, are located in the raw document and deserialized on the `rayon`
+//! thread pool before being merged back in order.
+
+use std::ops::Range;
+
+use quick_xml::{events::Event, Reader};
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::{OperationResponse, ResponseClass};
+
+use super::Envelope;
+
+/// The number of response messages below which deserializing them in
+/// parallel isn't worth the overhead.
+const MIN_PARALLEL_MESSAGES: usize = 32;
+
+/// The location of the response messages within a document.
+#[derive(Debug, PartialEq, Eq)]
+struct ResponseMessagesRanges {
+    /// The range of the content of the `ResponseMessages` element.
+    content: Range<usize>,
+
+    /// The range of each child element of the `ResponseMessages` element.
+    messages: Vec<Range<usize>>,
+}
+
+/// Deserializes the response in the given document, deserializing its
+/// messages in parallel.
+///
+/// Returns `None` if the response has too few messages to benefit, or if
+/// deserialization fails; the caller should then deserialize the document as
+/// a whole, which also produces detailed errors.
+pub(super) fn from_xml_document<B>(document: &[u8]) -> Option<Envelope<B>>
+where
+    B: OperationResponse,
+{
+    let ranges = locate_response_messages(document)?;
+    if ranges.messages.len() < MIN_PARALLEL_MESSAGES {
+        return None;
+    }
+
+    // Deserialize everything but the messages as usual.
+    let mut skeleton = Vec::with_capacity(document.len() - ranges.content.len());
+    skeleton.extend_from_slice(&document[..ranges.content.start]);
+    skeleton.extend_from_slice(&document[ranges.content.end..]);
+    let mut envelope = Envelope::<B>::deserialize_document(&skeleton).ok()?;
+
+    let messages = ranges
+        .messages
+        .into_par_iter()
+        .map(|range| {
+            let mut de = quick_xml::de::Deserializer::from_reader(&document[range]);
+            ResponseClass::<B::Message>::deserialize(&mut de)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+
+    *envelope.body.response_messages_mut() = messages;
+
+    Some(envelope)
+}
+
+/// Finds the `ResponseMessages` element in the given document and the range
+/// of each of its children.
+fn locate_response_messages(document: &[u8]) -> Option<ResponseMessagesRanges> {
+    let mut reader = Reader::from_reader(document);
+
+    // Skip to the content of the `ResponseMessages` element.
+    let content_start = loop {
+        match reader.read_event().ok()? {
+            Event::Start(start) if start.local_name().as_ref() == b"ResponseMessages" => {
+                break reader.buffer_position();
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+    };
+
+    let mut messages = Vec::new();
+    let mut message_start = content_start;
+    let mut depth = 0_usize;
+    loop {
+        let event_start = reader.buffer_position();
+        match reader.read_event().ok()? {
+            Event::Start(_) => {
+                if depth == 0 {
+                    message_start = event_start;
+                }
+                depth += 1;
+            }
+            Event::Empty(_) if depth == 0 => {
+                messages.push(event_start..reader.buffer_position());
+            }
+            Event::End(_) if depth == 0 => {
+                return Some(ResponseMessagesRanges {
+                    content: content_start..event_start,
+                    messages,
+                });
+            }
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    messages.push(message_start..reader.buffer_position());
+                }
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        get_item::GetItemResponse, get_user_availability::SerializableTimeZone, soap::Envelope,
+        TimeZoneContext, TimeZoneReference,
+    };
+
+    use super::{locate_response_messages, MIN_PARALLEL_MESSAGES};
+
+    /// Builds a `GetItem` response with the given number of messages, every
+    /// third of which is an error.
+    fn get_item_response(count: usize) -> String {
+        let messages: String = (0..count)
+            .map(|i| {
+                if i % 3 == 2 {
+                    r#"<m:GetItemResponseMessage ResponseClass="Error"><m:MessageText>The specified object was not found in the store.</m:MessageText><m:ResponseCode>ErrorItemNotFound</m:ResponseCode><m:DescriptiveLinkKey>0</m:DescriptiveLinkKey><m:Items/></m:GetItemResponseMessage>"#.to_string()
+                } else {
+                    format!(
+                        r#"<m:GetItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Items><t:Message><t:ItemId Id="item{i}" ChangeKey="ck"/><t:Subject>Message {i}</t:Subject><t:DateTimeReceived>2024-05-01T09:00:00</t:DateTimeReceived></t:Message></m:Items></m:GetItemResponseMessage>"#
+                    )
+                }
+            })
+            .collect();
+
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/">"#,
+                r#"<s:Header><h:ServerVersionInfo MajorVersion="15" MinorVersion="20" MajorBuildNumber="7452" MinorBuildNumber="50" Version="V2018_01_08" xmlns:h="http://schemas.microsoft.com/exchange/services/2006/types"/></s:Header><s:Body>"#,
+                r#"<m:GetItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types">"#,
+                "<m:ResponseMessages>\n{}\n</m:ResponseMessages></m:GetItemResponse></s:Body></s:Envelope>",
+            ),
+            messages
+        )
+    }
+
+    #[test]
+    fn locate_messages() {
+        let document = get_item_response(4);
+        let ranges =
+            locate_response_messages(document.as_bytes()).expect("messages should be found");
+
+        assert_eq!(ranges.messages.len(), 4);
+        for range in ranges.messages {
+            let message = &document[range];
+            assert!(message.starts_with("<m:GetItemResponseMessage "));
+            assert!(message.ends_with("</m:GetItemResponseMessage>"));
+        }
+        assert!(document[ranges.content]
+            .trim()
+            .ends_with("</m:GetItemResponseMessage>"));
+    }
+
+    #[test]
+    fn parallel_matches_serial() {
+        let document = get_item_response(MIN_PARALLEL_MESSAGES * 3);
+        let context = TimeZoneContext::new(
+            TimeZoneReference::new("Pacific Standard Time"),
+            SerializableTimeZone::from_fixed_offset(time::UtcOffset::from_hms(-7, 0, 0).unwrap()),
+        );
+
+        let document = context.resolve_naive_times(document.as_bytes());
+
+        let parallel = super::from_xml_document::<GetItemResponse>(&document)
+            .expect("parallel deserialization should succeed");
+        let serial = Envelope::<GetItemResponse>::deserialize_document(&document)
+            .expect("serial deserialization should succeed");
+
+        assert_eq!(parallel.body, serial.body);
+        assert_eq!(parallel.headers.len(), 1);
+    }
+
+    #[test]
+    fn small_responses_are_deserialized_serially() {
+        let document = get_item_response(MIN_PARALLEL_MESSAGES - 1);
+
+        assert!(super::from_xml_document::<GetItemResponse>(document.as_bytes()).is_none());
+    }
+}
@@ -0,0 +1,103 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Support for deserializing envelopes as they are read rather than from a
+//! complete document.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, Read},
+};
+
+/// The maximum number of bytes read to find the XML declaration of a
+/// document, which determines its encoding.
+const DECLARATION_MAX_LEN: usize = 1024;
+
+/// Reads the start of a document, up to and including its XML declaration if
+/// it has one, so that its encoding can be detected.
+pub(super) fn read_declaration<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut start = Vec::new();
+
+    while start.len() < DECLARATION_MAX_LEN {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+
+        let len = buf.len().min(DECLARATION_MAX_LEN - start.len());
+        start.extend_from_slice(&buf[..len]);
+        reader.consume(len);
+
+        if start.windows(2).any(|window| window == b"?>") {
+            break;
+        }
+    }
+
+    Ok(start)
+}
+
+/// A reader which remembers the last bytes consumed from it, so that errors
+/// can point at the portion of the document where deserialization failed.
+pub(super) struct TailReader<R> {
+    inner: R,
+    tail: Tail,
+}
+
+impl<R: BufRead> TailReader<R> {
+    pub(super) fn new(inner: R, capacity: usize) -> Self {
+        Self {
+            inner,
+            tail: Tail {
+                bytes: VecDeque::with_capacity(capacity),
+                capacity,
+            },
+        }
+    }
+
+    /// Gets the last bytes consumed from the reader.
+    pub(super) fn tail(&self) -> Vec<u8> {
+        self.tail.bytes.iter().copied().collect()
+    }
+}
+
+impl<R: BufRead> Read for TailReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.tail.record(&buf[..read]);
+
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for TailReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // `consume` can't fail, so take the bytes from the inner buffer
+        // without refilling it.
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.tail.record(&buf[..amt.min(buf.len())]);
+        }
+
+        self.inner.consume(amt);
+    }
+}
+
+/// The last bytes consumed from a [`TailReader`], up to its capacity.
+struct Tail {
+    bytes: VecDeque<u8>,
+    capacity: usize,
+}
+
+impl Tail {
+    fn record(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[bytes.len().saturating_sub(self.capacity)..];
+        let overflow = (self.bytes.len() + bytes.len()).saturating_sub(self.capacity);
+
+        self.bytes.drain(..overflow);
+        self.bytes.extend(bytes);
+    }
+}
@@ -0,0 +1,826 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Incremental parsing of the items in large responses.
+//!
+//! Deserializing a [`FindItemResponse`] or [`SyncFolderItemsResponse`]
+//! materializes every item it contains at once, which for the first
+//! synchronization of a large mailbox can take a lot of memory. The types in
+//! this module instead parse a response document from any [`BufRead`], e.g.
+//! the body of an HTTP response, and decode each item only as it is
+//! requested, so that only one item is held in memory at a time.
+//!
+//! Similarly, [`decode_attachment_content`] and [`AttachmentUpload`] transfer
+//! the content of file attachments of any size in constant memory.
+//!
+//! # Example
+//!
+//! ```
+//! use ews::{streaming::StreamedItems, RealItem};
+//!
+//! # fn main() -> Result<(), ews::Error> {
+//! let body = concat!(
+//!     r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body>"#,
+//!     r#"<m:FindItemResponse><m:ResponseMessages>"#,
+//!     r#"<m:FindItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode>"#,
+//!     r#"<m:RootFolder TotalItemsInView="1" IncludesLastItemInRange="true"><t:Items>"#,
+//!     r#"<t:Message><t:ItemId Id="item"/><t:Subject>Hello</t:Subject></t:Message>"#,
+//!     r#"</t:Items></m:RootFolder></m:FindItemResponseMessage>"#,
+//!     r#"</m:ResponseMessages></m:FindItemResponse></s:Body></s:Envelope>"#,
+//! );
+//!
+//! let mut items = StreamedItems::new(body.as_bytes(), None);
+//! while let Some(item) = items.next() {
+//!     if let RealItem::Message(message) = item? {
+//!         println!("{:?}", message.subject);
+//!     }
+//! }
+//! assert_eq!(items.includes_last_item_in_range(), Some(true));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`FindItemResponse`]: crate::find_item::FindItemResponse
+//! [`SyncFolderItemsResponse`]: crate::sync_folder_items::SyncFolderItemsResponse
+
+use std::{
+    io::{self, BufRead, Read, Write},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use base64::{engine::general_purpose::STANDARD, read::DecoderReader, Engine as _};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader, Writer,
+};
+use serde::Deserialize;
+
+use crate::{
+    create_attachment::{CreateAttachment, NewAttachment},
+    server_version::ExchangeServerVersion,
+    soap::{Envelope, Fault},
+    sync_folder_items::Change,
+    Error, ItemId, RealItem, ResponseError, SyncState, TimeZoneContext,
+};
+
+/// Incrementally parses the items found by a `FindItem` request.
+///
+/// Items in grouped responses are yielded in order, one group after the
+/// other. The properties of the search, such as
+/// [`includes_last_item_in_range`], are available once the first item has
+/// been yielded.
+///
+/// [`includes_last_item_in_range`]: StreamedItems::includes_last_item_in_range
+pub struct StreamedItems<R> {
+    stream: ElementStream<R, RealItem>,
+}
+
+impl<R: BufRead> StreamedItems<R> {
+    /// Creates a parser for the `FindItem` response document read from the
+    /// given reader.
+    ///
+    /// Times without an offset are deserialized in the time zone of the given
+    /// context, which should be the one sent with the request, or in UTC
+    /// without one.
+    pub fn new(reader: R, time_zone_context: Option<TimeZoneContext>) -> Self {
+        Self {
+            stream: ElementStream::new(reader, b"Items", time_zone_context),
+        }
+    }
+
+    /// Gets the offset of the next page of items, for indexed paging.
+    pub fn indexed_paging_offset(&self) -> Option<i32> {
+        self.stream.properties.indexed_paging_offset
+    }
+
+    /// Gets the total number of items matching the search.
+    pub fn total_items_in_view(&self) -> Option<u32> {
+        self.stream.properties.total_items_in_view
+    }
+
+    /// Determines whether the response includes the last matching item.
+    pub fn includes_last_item_in_range(&self) -> Option<bool> {
+        self.stream.properties.includes_last_item_in_range
+    }
+}
+
+impl<R: BufRead> Iterator for StreamedItems<R> {
+    type Item = Result<RealItem, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.next()
+    }
+}
+
+/// Incrementally parses the changes returned by a `SyncFolderItems`
+/// request.
+///
+/// The synchronization state and whether the response includes the last
+/// change are available once the first change has been yielded.
+pub struct StreamedChanges<R> {
+    stream: ElementStream<R, Change>,
+}
+
+impl<R: BufRead> StreamedChanges<R> {
+    /// Creates a parser for the `SyncFolderItems` response document read
+    /// from the given reader.
+    ///
+    /// Times without an offset are deserialized as described for
+    /// [`StreamedItems::new`].
+    pub fn new(reader: R, time_zone_context: Option<TimeZoneContext>) -> Self {
+        Self {
+            stream: ElementStream::new(reader, b"Changes", time_zone_context),
+        }
+    }
+
+    /// Gets the synchronization state following application of the changes.
+    pub fn sync_state(&self) -> Option<&SyncState> {
+        self.stream.properties.sync_state.as_ref()
+    }
+
+    /// Determines whether all changes have been synchronized following this
+    /// response.
+    pub fn includes_last_item_in_range(&self) -> Option<bool> {
+        self.stream.properties.includes_last_item_in_range
+    }
+}
+
+impl<R: BufRead> Iterator for StreamedChanges<R> {
+    type Item = Result<Change, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.next()
+    }
+}
+
+/// Decodes the content of the file attachment in a `GetAttachment` response
+/// document read from the given reader, writing it to the given writer.
+///
+/// The base64-encoded content is decoded as it is read, so that neither the
+/// encoded nor the decoded content is held in memory as a whole. Returns the
+/// number of bytes written.
+///
+/// Only the content of the first file attachment in the response is decoded,
+/// so this is intended for requests for a single attachment.
+pub fn decode_attachment_content<R, W>(reader: R, mut writer: W) -> Result<u64, Error>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut reader = Reader::from_reader(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let start = match reader.read_event_into(&mut buf)? {
+            Event::Start(start) => start.into_owned(),
+            Event::Empty(start) if start.local_name().as_ref() == b"Content" => return Ok(0),
+            Event::Eof => return Err(Error::MissingAttachmentContent),
+            _ => continue,
+        };
+
+        if start.local_name().as_ref() == b"Content" {
+            let content = EncodedContent {
+                reader: reader.get_mut(),
+                done: false,
+            };
+            let mut decoder = DecoderReader::new(content, &STANDARD);
+
+            return io::copy(&mut decoder, &mut writer).map_err(|err| {
+                let decode_err = err
+                    .get_ref()
+                    .and_then(|inner| inner.downcast_ref::<base64::DecodeError>());
+                match decode_err {
+                    Some(decode_err) => Error::AttachmentDecode(decode_err.clone()),
+                    None => err.into(),
+                }
+            });
+        }
+
+        check_for_error(&mut reader, &mut buf, start)?;
+    }
+}
+
+/// A `CreateAttachment` request document for a file attachment whose
+/// content is base64-encoded as the document is read.
+///
+/// Reading the document reads the content from the given reader in chunks,
+/// so that it can be sent as the body of an HTTP request without holding the
+/// content in memory as a whole.
+pub struct AttachmentUpload<R> {
+    inner: io::Chain<io::Chain<Markup, Base64Encoder<R>>, Markup>,
+}
+
+/// The markup of a document surrounding the attachment content.
+type Markup = io::Cursor<Vec<u8>>;
+
+impl<R: Read> AttachmentUpload<R> {
+    /// Creates a request document attaching a file with the given name,
+    /// MIME content type and content to the given item.
+    pub fn new(
+        server_version: ExchangeServerVersion,
+        parent_item_id: ItemId,
+        name: impl Into<String>,
+        content_type: impl Into<String>,
+        content: R,
+    ) -> Result<Self, Error> {
+        // Serialize the document around a placeholder, which the encoded
+        // content replaces. The content is the last field of the attachment,
+        // so the last occurrence of the placeholder is the right one.
+        const PLACEHOLDER: &[u8] = b"ATTACHMENT-CONTENT";
+
+        let operation = CreateAttachment::new(
+            parent_item_id,
+            vec![NewAttachment::FileAttachment {
+                name: name.into(),
+                content_type: Some(content_type.into()),
+                content_id: None,
+                content_location: None,
+                is_inline: None,
+                is_contact_photo: None,
+                content: String::from_utf8_lossy(PLACEHOLDER).into_owned(),
+            }],
+        );
+        let mut document =
+            Envelope::new_with_version(operation, server_version).as_xml_document()?;
+
+        let position = document
+            .windows(PLACEHOLDER.len())
+            .rposition(|window| window == PLACEHOLDER)
+            .expect("the serialized document should contain the placeholder");
+        let suffix = document.split_off(position + PLACEHOLDER.len());
+        document.truncate(position);
+
+        let encoder = Base64Encoder {
+            inner: content,
+            encoded: String::new(),
+            position: 0,
+            done: false,
+        };
+
+        Ok(Self {
+            inner: io::Cursor::new(document)
+                .chain(encoder)
+                .chain(io::Cursor::new(suffix)),
+        })
+    }
+}
+
+impl<R: Read> Read for AttachmentUpload<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// Reads the base64-encoded text of a `Content` element from the underlying
+/// reader of an XML parser, skipping whitespace and stopping at the end of
+/// the element.
+struct EncodedContent<'a, R> {
+    reader: &'a mut R,
+    done: bool,
+}
+
+impl<R: BufRead> Read for EncodedContent<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0;
+        while written < out.len() && !self.done {
+            let available = self.reader.fill_buf()?;
+            if available.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "attachment content ended unexpectedly",
+                ));
+            }
+
+            let mut consumed = 0;
+            for &byte in available {
+                if byte == b'<' {
+                    self.done = true;
+                    break;
+                }
+
+                consumed += 1;
+                if !byte.is_ascii_whitespace() {
+                    out[written] = byte;
+                    written += 1;
+                    if written == out.len() {
+                        break;
+                    }
+                }
+            }
+            self.reader.consume(consumed);
+        }
+
+        Ok(written)
+    }
+}
+
+/// The number of bytes of content encoded at a time. Only the final chunk of
+/// the content may be padded, so this must be a multiple of 3.
+const ENCODE_CHUNK_LEN: usize = 3 * 1024;
+
+/// Base64-encodes the content read from the inner reader.
+struct Base64Encoder<R> {
+    inner: R,
+    encoded: String,
+    position: usize,
+    done: bool,
+}
+
+impl<R: Read> Base64Encoder<R> {
+    /// Encodes the next chunk of content.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0; ENCODE_CHUNK_LEN];
+        let mut len = 0;
+        while len < chunk.len() {
+            match self.inner.read(&mut chunk[len..]) {
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(read) => len += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.encoded.clear();
+        STANDARD.encode_string(&chunk[..len], &mut self.encoded);
+        self.position = 0;
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Base64Encoder<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.encoded.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let remaining = &self.encoded.as_bytes()[self.position..];
+        let len = out.len().min(remaining.len());
+        out[..len].copy_from_slice(&remaining[..len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+/// Properties of a response which precede its items.
+#[derive(Debug, Default)]
+struct ResponseProperties {
+    indexed_paging_offset: Option<i32>,
+    total_items_in_view: Option<u32>,
+    includes_last_item_in_range: Option<bool>,
+    sync_state: Option<SyncState>,
+}
+
+impl ResponseProperties {
+    /// Records the properties of a search from the attributes of its root
+    /// folder.
+    fn read_root_folder(&mut self, start: &BytesStart<'_>) -> Result<(), Error> {
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            let value = attribute.unescape_value()?;
+            match attribute.key.local_name().as_ref() {
+                b"IndexedPagingOffset" => self.indexed_paging_offset = parse(&value),
+                b"TotalItemsInView" => self.total_items_in_view = parse(&value),
+                b"IncludesLastItemInRange" => self.includes_last_item_in_range = parse(&value),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Yields each child of the container elements with the given name in a
+/// response document, deserialized as `T`.
+struct ElementStream<R, T> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    container: &'static [u8],
+    in_container: bool,
+    done: bool,
+    properties: ResponseProperties,
+    time_zone_context: Option<TimeZoneContext>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<R, T> ElementStream<R, T>
+where
+    R: BufRead,
+    T: for<'de> Deserialize<'de>,
+{
+    fn new(
+        reader: R,
+        container: &'static [u8],
+        time_zone_context: Option<TimeZoneContext>,
+    ) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            buf: Vec::new(),
+            container,
+            in_container: false,
+            done: false,
+            properties: ResponseProperties::default(),
+            time_zone_context,
+            marker: PhantomData,
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<T, Error>> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.advance().transpose();
+        if matches!(
+            result,
+            None | Some(Err(Error::RequestFault(_) | Error::InvalidXml(_)))
+        ) {
+            self.done = true;
+        }
+
+        result
+    }
+
+    /// Reads up to the next element in a container, handling the elements
+    /// preceding it.
+    fn advance(&mut self) -> Result<Option<T>, Error> {
+        loop {
+            self.buf.clear();
+            let start = match self.reader.read_event_into(&mut self.buf)? {
+                Event::Start(start) => start.into_owned(),
+                Event::Empty(start) if self.in_container => {
+                    let mut writer = Writer::new(Vec::new());
+                    writer.write_event(Event::Empty(start))?;
+                    return self.deserialize_element(&writer.into_inner()).map(Some);
+                }
+                Event::Empty(start) => {
+                    if start.local_name().as_ref() == b"RootFolder" {
+                        self.properties.read_root_folder(&start)?;
+                    }
+                    continue;
+                }
+                Event::End(end) => {
+                    if end.local_name().as_ref() == self.container {
+                        self.in_container = false;
+                    }
+                    continue;
+                }
+                Event::Eof => return Ok(None),
+                _ => continue,
+            };
+
+            if self.in_container {
+                let element = capture(&mut self.reader, &mut self.buf, start)?;
+                return self.deserialize_element(&element).map(Some);
+            }
+
+            match start.local_name().as_ref() {
+                name if name == self.container => self.in_container = true,
+                b"RootFolder" => self.properties.read_root_folder(&start)?,
+                b"SyncState" => {
+                    self.properties.sync_state = Some(SyncState::new(self.read_text()?));
+                }
+                b"IncludesLastItemInRange" => {
+                    self.properties.includes_last_item_in_range = parse(&self.read_text()?);
+                }
+                _ => check_for_error(&mut self.reader, &mut self.buf, start)?,
+            }
+        }
+    }
+
+    /// Deserializes an element in a container, in the time zone of the
+    /// stream's context.
+    fn deserialize_element(&self, element: &[u8]) -> Result<T, Error> {
+        match &self.time_zone_context {
+            Some(time_zone_context) => deserialize(&time_zone_context.resolve_naive_times(element)),
+            None => deserialize(element),
+        }
+    }
+
+    /// Reads the text content of the element which was just started.
+    fn read_text(&mut self) -> Result<String, Error> {
+        let mut text = String::new();
+        loop {
+            self.buf.clear();
+            match self.reader.read_event_into(&mut self.buf)? {
+                Event::Text(content) => text.push_str(&content.unescape()?),
+                Event::CData(content) => text.push_str(&String::from_utf8_lossy(&content)),
+                Event::End(_) => return Ok(text),
+                Event::Eof => {
+                    return Err(quick_xml::Error::UnexpectedEof("text".to_string()).into());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Copies the element which was just started, including its content,
+/// into a standalone document.
+fn capture<R: BufRead>(
+    reader: &mut Reader<R>,
+    buf: &mut Vec<u8>,
+    start: BytesStart<'static>,
+) -> Result<Vec<u8>, Error> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(Event::Start(start))?;
+
+    let mut depth = 0_usize;
+    loop {
+        buf.clear();
+        let event = reader.read_event_into(buf)?;
+        match &event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) if depth == 0 => {
+                writer.write_event(event)?;
+                return Ok(writer.into_inner());
+            }
+            Event::End(_) => depth -= 1,
+            Event::Eof => {
+                return Err(quick_xml::Error::UnexpectedEof("element".to_string()).into());
+            }
+            _ => {}
+        }
+        writer.write_event(event)?;
+    }
+}
+
+/// Fails with the error reported by the element which was just started if
+/// it is a SOAP fault or an unsuccessful response message.
+fn check_for_error<R: BufRead>(
+    reader: &mut Reader<R>,
+    buf: &mut Vec<u8>,
+    start: BytesStart<'static>,
+) -> Result<(), Error> {
+    match start.local_name().as_ref() {
+        b"Fault" => {
+            let fault: Fault = deserialize(&capture(reader, buf, start)?)?;
+            Err(Error::RequestFault(Box::new(fault)))
+        }
+        name if name.ends_with(b"ResponseMessage") => {
+            let class = start.try_get_attribute("ResponseClass")?;
+            if class.is_some_and(|class| class.value.as_ref() == b"Error") {
+                let error: ResponseError = deserialize(&capture(reader, buf, start)?)?;
+                return Err(error.into());
+            }
+
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Deserializes a standalone element.
+fn deserialize<T>(element: &[u8]) -> Result<T, Error>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let mut de = quick_xml::de::Deserializer::from_reader(element);
+
+    Ok(serde_path_to_error::deserialize(&mut de)?)
+}
+
+/// Parses the value of a property, ignoring invalid values.
+fn parse<V: FromStr>(value: &str) -> Option<V> {
+    value.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read};
+
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+
+    use crate::{
+        create_attachment::{CreateAttachment, NewAttachment},
+        get_user_availability::SerializableTimeZone,
+        response::ResponseCode,
+        server_version::ExchangeServerVersion,
+        soap::Envelope,
+        sync_folder_items::Change,
+        DateTime, Error, ItemId, RealItem, TimeZoneContext, TimeZoneReference,
+    };
+
+    use super::{decode_attachment_content, AttachmentUpload, StreamedChanges, StreamedItems};
+
+    fn envelope(body: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Header/><s:Body>{body}</s:Body></s:Envelope>"#
+        )
+    }
+
+    #[test]
+    fn stream_found_items() {
+        let document = envelope(concat!(
+            r#"<m:FindItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+            r#"<m:FindItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode>"#,
+            r#"<m:RootFolder IndexedPagingOffset="2" TotalItemsInView="5" IncludesLastItemInRange="false"><t:Items>"#,
+            r#"<t:Message><t:ItemId Id="first"/><t:Subject>Fish &amp; chips</t:Subject><t:Body BodyType="Text">Nested <![CDATA[<b>]]></t:Body></t:Message>"#,
+            r#"<t:Contact><t:ItemId Id="second"/><t:Subject>Jane</t:Subject></t:Contact>"#,
+            r#"</t:Items></m:RootFolder></m:FindItemResponseMessage>"#,
+            r#"<m:FindItemResponseMessage ResponseClass="Error"><m:MessageText>Id is malformed.</m:MessageText><m:ResponseCode>ErrorInvalidIdMalformed</m:ResponseCode><m:DescriptiveLinkKey>0</m:DescriptiveLinkKey></m:FindItemResponseMessage>"#,
+            r#"</m:ResponseMessages></m:FindItemResponse>"#,
+        ));
+
+        let mut items = StreamedItems::new(document.as_bytes(), None);
+        assert_eq!(items.includes_last_item_in_range(), None);
+
+        match items.next() {
+            Some(Ok(RealItem::Message(message))) => {
+                assert_eq!(message.subject.as_deref(), Some("Fish & chips"));
+            }
+            other => panic!("expected a message, got {other:?}"),
+        }
+        assert_eq!(items.indexed_paging_offset(), Some(2));
+        assert_eq!(items.total_items_in_view(), Some(5));
+        assert_eq!(items.includes_last_item_in_range(), Some(false));
+
+        assert!(matches!(items.next(), Some(Ok(RealItem::Contact(_)))));
+        match items.next() {
+            Some(Err(Error::Response(error))) => {
+                assert_eq!(error.response_code, ResponseCode::ErrorInvalidIdMalformed);
+            }
+            other => panic!("expected an error, got {other:?}"),
+        }
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn stream_changes() {
+        let document = envelope(concat!(
+            r#"<m:SyncFolderItemsResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+            r#"<m:SyncFolderItemsResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode>"#,
+            r#"<m:SyncState>state-1</m:SyncState><m:IncludesLastItemInRange>true</m:IncludesLastItemInRange>"#,
+            r#"<m:Changes>"#,
+            r#"<t:Create><t:Message><t:ItemId Id="created"/><t:Subject>New</t:Subject></t:Message></t:Create>"#,
+            r#"<t:Delete><t:ItemId Id="deleted"/></t:Delete>"#,
+            r#"<t:ReadFlagChange><t:ItemId Id="read"/><t:IsRead>true</t:IsRead></t:ReadFlagChange>"#,
+            r#"</m:Changes></m:SyncFolderItemsResponseMessage></m:ResponseMessages></m:SyncFolderItemsResponse>"#,
+        ));
+
+        let mut changes = StreamedChanges::new(document.as_bytes(), None);
+        let changes_read: Vec<Change> = changes
+            .by_ref()
+            .collect::<Result<_, _>>()
+            .expect("changes should parse");
+
+        assert!(matches!(
+            &changes_read[..],
+            [
+                Change::Create { item: RealItem::Message(_) },
+                Change::Delete { item_id },
+                Change::ReadFlagChange { is_read: true, .. },
+            ] if item_id.id == "deleted"
+        ));
+        assert_eq!(
+            changes.sync_state().map(|state| state.as_str()),
+            Some("state-1")
+        );
+        assert_eq!(changes.includes_last_item_in_range(), Some(true));
+    }
+
+    #[test]
+    fn stream_fault() {
+        let document = envelope(
+            "<s:Fault><faultcode>a:ErrorSchemaValidation</faultcode><faultstring>The request failed schema validation.</faultstring></s:Fault>",
+        );
+
+        let mut items = StreamedItems::new(document.as_bytes(), None);
+        assert!(matches!(items.next(), Some(Err(Error::RequestFault(_)))));
+        assert!(items.next().is_none());
+    }
+
+    #[test]
+    fn stream_items_in_time_zone_context() {
+        let document = envelope(concat!(
+            r#"<m:FindItemResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+            r#"<m:FindItemResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode>"#,
+            r#"<m:RootFolder TotalItemsInView="1" IncludesLastItemInRange="true"><t:Items>"#,
+            r#"<t:Message><t:ItemId Id="first"/><t:DateTimeReceived>2024-05-01T09:00:00</t:DateTimeReceived></t:Message>"#,
+            r#"</t:Items></m:RootFolder></m:FindItemResponseMessage>"#,
+            r#"</m:ResponseMessages></m:FindItemResponse>"#,
+        ));
+        let context = TimeZoneContext::new(
+            TimeZoneReference::new("Pacific Standard Time"),
+            SerializableTimeZone::from_fixed_offset(time::UtcOffset::from_hms(-7, 0, 0).unwrap()),
+        );
+
+        // Items are only decoded as they are requested, so the context must
+        // still apply then.
+        let mut items = StreamedItems::new(document.as_bytes(), Some(context));
+        match items.next() {
+            Some(Ok(RealItem::Message(message))) => {
+                // 2024-05-01T16:00:00Z.
+                let received = time::OffsetDateTime::from_unix_timestamp(1_714_579_200).unwrap();
+                assert_eq!(message.date_time_received, Some(DateTime(received)));
+            }
+            other => panic!("expected a message, got {other:?}"),
+        }
+        assert!(items.next().is_none());
+    }
+
+    fn get_attachment_response(content: &str) -> String {
+        envelope(&format!(
+            concat!(
+                r#"<m:GetAttachmentResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages" xmlns:t="http://schemas.microsoft.com/exchange/services/2006/types"><m:ResponseMessages>"#,
+                r#"<m:GetAttachmentResponseMessage ResponseClass="Success"><m:ResponseCode>NoError</m:ResponseCode><m:Attachments>"#,
+                r#"<t:FileAttachment><t:AttachmentId Id="attachment"/><t:Name>data.bin</t:Name><t:ContentType>application/octet-stream</t:ContentType>"#,
+                r#"<t:Content>{}</t:Content></t:FileAttachment>"#,
+                r#"</m:Attachments></m:GetAttachmentResponseMessage></m:ResponseMessages></m:GetAttachmentResponse>"#,
+            ),
+            content
+        ))
+    }
+
+    #[test]
+    fn decode_streamed_attachment_content() {
+        let content: Vec<u8> = (0..5000_u32).map(|i| (i % 251) as u8).collect();
+
+        // Wrap the encoded content across lines, as some servers do.
+        let encoded = STANDARD.encode(&content);
+        let wrapped = encoded
+            .as_bytes()
+            .chunks(76)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let document = get_attachment_response(&wrapped);
+
+        // Use a small buffer so that the content spans many reads.
+        let reader = BufReader::with_capacity(16, document.as_bytes());
+        let mut decoded = Vec::new();
+        let len = decode_attachment_content(reader, &mut decoded).expect("content should decode");
+
+        assert_eq!(len, content.len() as u64);
+        assert_eq!(decoded, content);
+    }
+
+    #[test]
+    fn decode_attachment_content_errors() {
+        let document = envelope(concat!(
+            r#"<m:GetAttachmentResponse xmlns:m="http://schemas.microsoft.com/exchange/services/2006/messages"><m:ResponseMessages>"#,
+            r#"<m:GetAttachmentResponseMessage ResponseClass="Error"><m:MessageText>The specified attachment was not found.</m:MessageText><m:ResponseCode>ErrorInvalidAttachmentId</m:ResponseCode><m:DescriptiveLinkKey>0</m:DescriptiveLinkKey></m:GetAttachmentResponseMessage>"#,
+            r#"</m:ResponseMessages></m:GetAttachmentResponse>"#,
+        ));
+        match decode_attachment_content(document.as_bytes(), Vec::new()) {
+            Err(Error::Response(error)) => {
+                assert_eq!(error.response_code, ResponseCode::ErrorInvalidAttachmentId);
+            }
+            other => panic!("expected an error response, got {other:?}"),
+        }
+
+        let document = get_attachment_response("not base64!");
+        assert!(matches!(
+            decode_attachment_content(document.as_bytes(), Vec::new()),
+            Err(Error::AttachmentDecode(_))
+        ));
+    }
+
+    #[test]
+    fn upload_matches_create_attachment() {
+        let content: Vec<u8> = (0..10_000_u32).map(|i| (i % 253) as u8).collect();
+
+        let mut upload = AttachmentUpload::new(
+            ExchangeServerVersion::Exchange2013,
+            ItemId::new("parent"),
+            "data.bin",
+            "application/octet-stream",
+            content.as_slice(),
+        )
+        .expect("document should serialize");
+        let mut streamed = Vec::new();
+        upload
+            .read_to_end(&mut streamed)
+            .expect("document should be read");
+
+        let operation = CreateAttachment::new(
+            ItemId::new("parent"),
+            vec![NewAttachment::FileAttachment {
+                name: "data.bin".to_string(),
+                content_type: Some("application/octet-stream".to_string()),
+                content_id: None,
+                content_location: None,
+                is_inline: None,
+                is_contact_photo: None,
+                content: STANDARD.encode(&content),
+            }],
+        );
+        let expected = Envelope::new_with_version(operation, ExchangeServerVersion::Exchange2013)
+            .as_xml_document()
+            .expect("document should serialize");
+
+        assert_eq!(
+            String::from_utf8(streamed).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+    }
+}
@@ -2,19 +2,19 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, Folder, FolderId, FolderShape, MESSAGES_NS_URI};
+use crate::{BaseFolderId, Error, Folder, FolderId, FolderShape, SyncState, MESSAGES_NS_URI};
 
 /// A request for a list of folders which have been created, updated, or deleted
 /// server-side.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/syncfolderhierarchy>
-#[derive(Clone, Debug, Default, XmlSerialize)]
+#[derive(Clone, Debug, Default, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(SyncFolderHierarchyResponseMessage)]
+#[operation_response(SyncFolderHierarchyResponseMessage, validate = validate)]
 pub struct SyncFolderHierarchy {
     /// A description of the information to be included in the response for each
     /// changed folder.
@@ -29,18 +29,19 @@ pub struct SyncFolderHierarchy {
     /// which is a descendant of the requested folder.
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/syncstate-ex15websvcsotherref>
-    pub sync_state: Option<String>,
+    pub sync_state: Option<SyncState>,
 }
 
 /// A response to a request for an individual folder within a [`SyncFolderHierarchy`] operation.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/syncfolderhierarchyresponsemessage>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SyncFolderHierarchyResponseMessage {
     /// An identifier for the synchronization state following application of the
     /// changes included in this response.
-    pub sync_state: String,
+    pub sync_state: SyncState,
 
     /// Whether all relevant folder changes have been synchronized following
     /// this response.
@@ -56,6 +57,7 @@ pub struct SyncFolderHierarchyResponseMessage {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/changes-hierarchy>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub struct Changes {
     #[serde(default, rename = "$value")]
     pub inner: Vec<Change>,
@@ -65,6 +67,7 @@ pub struct Changes {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/changes-hierarchy>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub enum Change {
     /// A creation of a folder.
     ///
@@ -93,3 +96,8 @@ pub enum Change {
         folder_id: FolderId,
     },
 }
+
+/// Checks a [`SyncFolderHierarchy`] operation before it is sent.
+fn validate(operation: &SyncFolderHierarchy) -> Result<(), Error> {
+    operation.folder_shape.validate("SyncFolderHierarchy")
+}
@@ -2,19 +2,21 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, BaseItemId, ItemId, ItemShape, RealItem, MESSAGES_NS_URI};
+use crate::{
+    BaseFolderId, BaseItemId, Error, ItemId, ItemShape, RealItem, SyncState, MESSAGES_NS_URI,
+};
 
 /// A request for a list of items which have been created, updated, or deleted
 /// server-side.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/syncfolderitems>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(SyncFolderItemsResponseMessage)]
+#[operation_response(SyncFolderItemsResponseMessage, validate = validate)]
 pub struct SyncFolderItems {
     /// A description of the information to be included in the response for each
     /// changed item.
@@ -29,7 +31,7 @@ pub struct SyncFolderItems {
     /// which is contained in the requested folder.
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/syncstate-ex15websvcsotherref>
-    pub sync_state: Option<String>,
+    pub sync_state: Option<SyncState>,
 
     /// A list of item IDs for which changes should not be returned.
     ///
@@ -47,11 +49,12 @@ pub struct SyncFolderItems {
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct SyncFolderItemsResponseMessage {
     /// An identifier for the synchronization state following application of the
     /// changes included in this response.
-    pub sync_state: String,
+    pub sync_state: SyncState,
 
     /// Whether all relevant item changes have been synchronized following this
     /// response.
@@ -76,6 +79,7 @@ pub enum SyncScope {
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub struct Changes {
     #[serde(default, rename = "$value")]
     pub inner: Vec<Change>,
@@ -85,6 +89,7 @@ pub struct Changes {
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/changes-items>
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub enum Change {
     /// A creation of an item.
     ///
@@ -116,3 +121,179 @@ pub enum Change {
     #[serde(rename_all = "PascalCase")]
     ReadFlagChange { item_id: ItemId, is_read: bool },
 }
+
+/// A batch of changes retrieved while synchronizing a folder.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChangeBatch {
+    /// The changes in the batch, in the order in which they should be
+    /// applied.
+    pub changes: Vec<Change>,
+
+    /// The synchronization state following application of the changes.
+    ///
+    /// Persisting this after applying each batch allows resuming an
+    /// interrupted synchronization.
+    pub sync_state: SyncState,
+}
+
+/// Drives a [`SyncFolderItems`] operation until all changes to the folder
+/// have been retrieved.
+///
+/// Exchange returns at most [`max_changes_returned`] changes for a single
+/// request, so catching up with a folder takes several requests, each
+/// starting from the synchronization state returned by the previous one.
+///
+/// Like [`Pager`], the driver does not send requests itself:
+/// [`next_operation`] gives the next request, and [`process`] extracts the
+/// batch of changes from the response to that request. The clients in
+/// [`client`] wrap this in a `sync_folder_items` method.
+///
+/// [`max_changes_returned`]: SyncFolderItems::max_changes_returned
+/// [`Pager`]: crate::Pager
+/// [`next_operation`]: ItemSync::next_operation
+/// [`process`]: ItemSync::process
+/// [`client`]: crate::client
+#[derive(Clone, Debug)]
+pub struct ItemSync {
+    next: Option<SyncFolderItems>,
+    sync_state: Option<SyncState>,
+}
+
+impl ItemSync {
+    /// Creates a driver starting from the synchronization state of the
+    /// operation, or from scratch if it has none.
+    pub fn new(operation: SyncFolderItems) -> Self {
+        Self {
+            sync_state: operation.sync_state.clone(),
+            next: Some(operation),
+        }
+    }
+
+    /// Gets the operation requesting the next batch of changes, if any
+    /// remain.
+    pub fn next_operation(&self) -> Option<SyncFolderItems> {
+        self.next.clone()
+    }
+
+    /// Determines whether the folder has been fully synchronized.
+    pub fn is_done(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// Stops synchronizing, e.g. after a request failed.
+    pub fn stop(&mut self) {
+        self.next = None;
+    }
+
+    /// Gets the latest synchronization state, which is final once
+    /// [`is_done`] returns `true` without an error having occurred.
+    ///
+    /// [`is_done`]: ItemSync::is_done
+    pub fn sync_state(&self) -> Option<&SyncState> {
+        self.sync_state.as_ref()
+    }
+
+    /// Extracts the batch of changes from the response to the operation last
+    /// returned by [`next_operation`], and advances to the returned
+    /// synchronization state.
+    ///
+    /// Synchronization stops after an error, once the server reports that
+    /// all changes have been returned, or after an empty batch, so that a
+    /// misbehaving server cannot cause an endless loop.
+    ///
+    /// [`next_operation`]: ItemSync::next_operation
+    pub fn process(&mut self, response: SyncFolderItemsResponse) -> Result<ChangeBatch, Error> {
+        let Some(mut current) = self.next.take() else {
+            return Ok(ChangeBatch::default());
+        };
+
+        let message = response.single()?;
+        let changes = message.changes.inner;
+        self.sync_state = Some(message.sync_state.clone());
+
+        if !message.includes_last_item_in_range && !changes.is_empty() {
+            current.sync_state = Some(message.sync_state.clone());
+            self.next = Some(current);
+        }
+
+        Ok(ChangeBatch {
+            changes,
+            sync_state: message.sync_state,
+        })
+    }
+}
+
+/// Checks a [`SyncFolderItems`] operation before it is sent.
+fn validate(operation: &SyncFolderItems) -> Result<(), Error> {
+    if !(1..=512).contains(&operation.max_changes_returned) {
+        return Err(Error::InvalidRequest {
+            operation: "SyncFolderItems",
+            reason: format!(
+                "`max_changes_returned` must be in the range 1..=512, got {}",
+                operation.max_changes_returned
+            ),
+        });
+    }
+
+    operation.item_shape.validate("SyncFolderItems")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, ItemSync, SyncFolderItems, SyncFolderItemsResponse};
+    use crate::{test_utils::assert_serialized_content, BaseFolderId, ItemShape, SyncState};
+
+    fn response(xml: &str) -> SyncFolderItemsResponse {
+        quick_xml::de::from_str(xml).expect("response should deserialize")
+    }
+
+    #[test]
+    fn sync_until_last_item_in_range() {
+        let operation = SyncFolderItems::builder(ItemShape::id_only(), BaseFolderId::inbox(), 1)
+            .sync_state(SyncState::new("state-0"))
+            .build();
+
+        assert_serialized_content(
+            &operation.sync_state,
+            "SyncState",
+            "<SyncState>state-0</SyncState>",
+        );
+
+        let mut sync = ItemSync::new(operation);
+        assert_eq!(sync.sync_state(), Some(&SyncState::new("state-0")));
+
+        let batch = sync
+            .process(response(concat!(
+                r#"<SyncFolderItemsResponse><ResponseMessages>"#,
+                r#"<SyncFolderItemsResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+                r#"<SyncState>state-1</SyncState><IncludesLastItemInRange>false</IncludesLastItemInRange>"#,
+                r#"<Changes><Delete><ItemId Id="deleted" ChangeKey="ck"/></Delete></Changes>"#,
+                r#"</SyncFolderItemsResponseMessage></ResponseMessages></SyncFolderItemsResponse>"#,
+            )))
+            .expect("first batch should succeed");
+
+        assert_eq!(batch.sync_state.as_str(), "state-1");
+        assert!(
+            matches!(&batch.changes[..], [Change::Delete { item_id }] if item_id.id == "deleted")
+        );
+
+        let next = sync
+            .next_operation()
+            .expect("sync should continue until the last item");
+        assert_eq!(next.sync_state, Some(SyncState::new("state-1")));
+
+        let batch = sync
+            .process(response(concat!(
+                r#"<SyncFolderItemsResponse><ResponseMessages>"#,
+                r#"<SyncFolderItemsResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+                r#"<SyncState>state-2</SyncState><IncludesLastItemInRange>true</IncludesLastItemInRange>"#,
+                r#"<Changes/>"#,
+                r#"</SyncFolderItemsResponseMessage></ResponseMessages></SyncFolderItemsResponse>"#,
+            )))
+            .expect("last batch should succeed");
+
+        assert!(batch.changes.is_empty());
+        assert!(sync.is_done());
+        assert_eq!(sync.sync_state().map(SyncState::as_str), Some("state-2"));
+    }
+}
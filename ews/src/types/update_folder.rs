@@ -2,11 +2,11 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
-use crate::{BaseFolderId, MESSAGES_NS_URI};
+use crate::{BaseFolderId, Error, MESSAGES_NS_URI};
 
 use super::{Folder, Folders, PathToElement};
 
@@ -64,25 +64,36 @@ pub struct FolderChange {
 /// An operation to update a given property of a specified folder.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/updatefolder>.
-#[derive(Debug, XmlSerialize)]
+#[derive(Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(UpdateFolderResponseMessage)]
+#[operation_response(UpdateFolderResponseMessage, validate = validate)]
 pub struct UpdateFolder {
     pub folder_changes: FolderChanges,
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct UpdateFolderResponseMessage {
     pub folders: Folders,
 }
 
+/// Checks an [`UpdateFolder`] operation before it is sent.
+fn validate(operation: &UpdateFolder) -> Result<(), Error> {
+    match &operation.folder_changes.folder_change.updates {
+        Updates::AppendToFolderField => Ok(()),
+        Updates::SetFolderField { field_URI, .. } | Updates::DeleteFolderField { field_URI } => {
+            field_URI.validate()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test_utils::assert_deserialized_content;
     use crate::test_utils::assert_serialized_content;
-    use crate::{BaseFolderId, FolderId, ResponseClass, ResponseMessages};
+    use crate::{BaseFolderId, FolderId, FolderProperties, ResponseClass, ResponseMessages};
 
     #[test]
     fn serialize_update_request() {
@@ -94,17 +105,17 @@ mod tests {
                         change_key: Some("GO3u/".to_string()),
                     },
                     updates: Updates::SetFolderField {
-                        field_URI: PathToElement::FieldURI {
-                            field_URI: "folder:DisplayName".to_string(),
-                        },
+                        field_URI: PathToElement::field("folder:DisplayName"),
                         folder: Folder::Folder {
-                            display_name: Some("NewFolderName".to_string()),
-                            folder_id: None,
-                            parent_folder_id: None,
-                            folder_class: None,
-                            total_count: None,
-                            child_folder_count: None,
-                            extended_property: None,
+                            properties: FolderProperties {
+                                display_name: Some("NewFolderName".to_string()),
+                                folder_id: None,
+                                parent_folder_id: None,
+                                folder_class: None,
+                                total_count: None,
+                                child_folder_count: None,
+                                extended_property: None,
+                            },
                             unread_count: None,
                         },
                     },
@@ -139,16 +150,18 @@ mod tests {
                 response_messages: vec![ResponseClass::Success(UpdateFolderResponseMessage {
                     folders: Folders {
                         inner: vec![Folder::Folder {
-                            folder_id: Some(FolderId {
-                                id: "AAAlAFVz".to_string(),
-                                change_key: Some("AQAAAB".to_string()),
-                            }),
-                            parent_folder_id: None,
-                            folder_class: None,
-                            display_name: None,
-                            total_count: None,
-                            child_folder_count: None,
-                            extended_property: None,
+                            properties: FolderProperties {
+                                folder_id: Some(FolderId {
+                                    id: "AAAlAFVz".to_string(),
+                                    change_key: Some("AQAAAB".to_string()),
+                                }),
+                                parent_folder_id: None,
+                                folder_class: None,
+                                display_name: None,
+                                total_count: None,
+                                child_folder_count: None,
+                                extended_property: None,
+                            },
                             unread_count: None,
                         }],
                     },
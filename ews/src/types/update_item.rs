@@ -2,19 +2,29 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use ews_proc_macros::operation_response;
+use ews_proc_macros::{operation_response, OperationBuilder};
 use serde::Deserialize;
 use xml_struct::XmlSerialize;
 
 use crate::types::common::{BaseItemId, Message, MessageDisposition, PathToElement};
-use crate::{Items, MESSAGES_NS_URI};
+use crate::{
+    get_item::{GetItem, GetItemResponse},
+    require_non_empty,
+    server_version::ExchangeServerVersion,
+    BatchOperation, Body, Error, ExtendedFieldURI, ExtendedProperty, Importance, ItemLike,
+    ItemShape, Items, OperationResponse, ResponseClass, MESSAGES_NS_URI,
+};
 
 /// A request to update properties of one or more Exchange items.
 ///
 /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/updateitem>
-#[derive(Clone, Debug, XmlSerialize)]
+#[derive(Clone, Debug, OperationBuilder, XmlSerialize)]
 #[xml_struct(default_ns = MESSAGES_NS_URI)]
-#[operation_response(UpdateItemResponseMessage)]
+#[operation_response(
+    UpdateItemResponseMessage,
+    validate = validate,
+    for_server_version = for_server_version
+)]
 pub struct UpdateItem {
     /// The action the Exchange server will take upon updating this item.
     ///
@@ -35,16 +45,66 @@ pub struct UpdateItem {
     #[xml_struct(attribute)]
     pub conflict_resolution: Option<ConflictResolution>,
 
+    /// The action the Exchange server will take upon updating a calendar
+    /// item.
+    ///
+    /// Required when updating calendar items, otherwise it has no effect.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/updateitem#sendmeetinginvitationsorcancellations-attribute>
+    #[xml_struct(attribute)]
+    pub send_meeting_invitations_or_cancellations: Option<SendMeetingInvitationsOrCancellations>,
+
     /// A list of items and their corresponding updates.
     ///
     /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/itemchanges>
     pub item_changes: Vec<ItemChange>,
 }
 
+impl BatchOperation for UpdateItem {
+    type Input = ItemChange;
+
+    fn batch_inputs(&self) -> &[Self::Input] {
+        &self.item_changes
+    }
+
+    fn batch_inputs_mut(&mut self) -> &mut Vec<Self::Input> {
+        &mut self.item_changes
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 #[serde(rename_all = "PascalCase")]
 pub struct UpdateItemResponseMessage {
     pub items: Items,
+
+    /// The conflicts the server encountered with concurrent changes to the
+    /// item while applying the update.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/conflictresults>
+    pub conflict_results: Option<ConflictResults>,
+}
+
+impl UpdateItemResponseMessage {
+    /// Whether the update conflicted with concurrent changes to the item,
+    /// which the server resolved according to the requested
+    /// [`ConflictResolution`] rather than applying the update as given.
+    pub fn has_conflicts(&self) -> bool {
+        self.conflict_results
+            .as_ref()
+            .is_some_and(|results| results.count > 0)
+    }
+}
+
+/// The conflicts encountered while updating an item.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/conflictresults>
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+#[serde(rename_all = "PascalCase")]
+pub struct ConflictResults {
+    /// The number of conflicts.
+    pub count: u32,
 }
 
 /// The method used by the Exchange server to resolve conflicts between item
@@ -65,6 +125,32 @@ pub enum ConflictResolution {
     AlwaysOverwrite,
 }
 
+/// Whether to send meeting invitations or cancellations when updating a
+/// calendar item.
+///
+/// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/updateitem#sendmeetinginvitationsorcancellations-attribute>
+#[derive(Clone, Copy, Debug, XmlSerialize)]
+#[xml_struct(text)]
+pub enum SendMeetingInvitationsOrCancellations {
+    /// No updates are sent.
+    SendToNone,
+
+    /// Updates are sent to all attendees, without saving a copy.
+    SendOnlyToAll,
+
+    /// Updates are sent only to attendees affected by the change, without
+    /// saving a copy.
+    SendOnlyToChanged,
+
+    /// Updates are sent to all attendees, and a copy is saved in the Sent
+    /// Items folder.
+    SendToAllAndSaveCopy,
+
+    /// Updates are sent only to attendees affected by the change, and a copy
+    /// is saved in the Sent Items folder.
+    SendToChangedAndSaveCopy,
+}
+
 #[derive(Clone, Debug, XmlSerialize)]
 pub struct ItemChange {
     #[xml_struct(ns_prefix = "t")]
@@ -111,4 +197,685 @@ pub enum ItemChangeDescription {
         #[xml_struct(ns_prefix = "t")]
         message: Message,
     },
+
+    /// An update appending to the value of a single field, such as a body.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/appendtoitemfield>
+    AppendToItemField {
+        /// The field to be appended to.
+        #[xml_struct(flatten, ns_prefix = "t")]
+        field_uri: PathToElement,
+
+        /// The value to append to the specified field.
+        #[xml_struct(ns_prefix = "t")]
+        message: Message,
+    },
+
+    /// An update removing the value of a single field.
+    ///
+    /// See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-reference/deleteitemfield>
+    DeleteItemField {
+        /// The field to be removed.
+        #[xml_struct(flatten, ns_prefix = "t")]
+        field_uri: PathToElement,
+    },
+}
+
+/// A builder for the changes to make to a single item.
+///
+/// Each change to a field must be paired with an item carrying the new value
+/// in the property corresponding to that field. This builder takes care of
+/// that pairing for common properties.
+///
+/// # Example
+///
+/// ```
+/// use ews::{
+///     properties,
+///     update_item::{Changes, UpdateItem},
+///     BaseItemId, Body, MessageDisposition,
+/// };
+///
+/// let change = Changes::new()
+///     .set_subject("Updated subject")
+///     .append_body(Body::text("\n-- Sent from my phone"))
+///     .clear_categories()
+///     .set_extended_property(properties::PR_FOLLOWUP_ICON, "6")
+///     .for_item(BaseItemId::ItemId {
+///         id: "AAMkAD...".to_string(),
+///         change_key: Some("CQAAAB...".to_string()),
+///     });
+///
+/// let update = UpdateItem::new(MessageDisposition::SaveOnly, vec![change]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Changes {
+    updates: Vec<ItemChangeDescription>,
+}
+
+impl Changes {
+    /// Creates an empty set of changes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the value of a field to its value in the given item.
+    pub fn set(mut self, field_uri: impl Into<PathToElement>, message: Message) -> Self {
+        self.updates.push(ItemChangeDescription::SetItemField {
+            field_uri: field_uri.into(),
+            message,
+        });
+        self
+    }
+
+    /// Appends the value of a field in the given item to its current value.
+    pub fn append(mut self, field_uri: impl Into<PathToElement>, message: Message) -> Self {
+        self.updates.push(ItemChangeDescription::AppendToItemField {
+            field_uri: field_uri.into(),
+            message,
+        });
+        self
+    }
+
+    /// Removes the value of a field.
+    pub fn delete(mut self, field_uri: impl Into<PathToElement>) -> Self {
+        self.updates.push(ItemChangeDescription::DeleteItemField {
+            field_uri: field_uri.into(),
+        });
+        self
+    }
+
+    /// Sets the subject of the item.
+    pub fn set_subject(self, subject: impl Into<String>) -> Self {
+        self.set(
+            PathToElement::field("item:Subject"),
+            Message {
+                subject: Some(subject.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Replaces the body of the item.
+    pub fn set_body(self, body: Body) -> Self {
+        self.set(
+            PathToElement::field("item:Body"),
+            Message {
+                body: Some(body),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Appends to the body of the item.
+    ///
+    /// The appended body should be of the same type as the item's body.
+    pub fn append_body(self, body: Body) -> Self {
+        self.append(
+            PathToElement::field("item:Body"),
+            Message {
+                body: Some(body),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Replaces the categories of the item.
+    pub fn set_categories<I>(self, categories: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let categories = categories.into_iter().map(Into::into).collect();
+
+        self.set(
+            PathToElement::field("item:Categories"),
+            Message {
+                categories: Some(categories),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Removes all categories from the item.
+    pub fn clear_categories(self) -> Self {
+        self.delete(PathToElement::field("item:Categories"))
+    }
+
+    /// Sets the importance of the item.
+    pub fn set_importance(self, importance: Importance) -> Self {
+        self.set(
+            PathToElement::field("item:Importance"),
+            Message {
+                importance: Some(importance),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Marks the message as read or unread.
+    pub fn set_is_read(self, is_read: bool) -> Self {
+        self.set(
+            PathToElement::field("message:IsRead"),
+            Message {
+                is_read: Some(is_read),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Sets the value of an extended MAPI property of the item.
+    pub fn set_extended_property(
+        self,
+        field_uri: impl Into<ExtendedFieldURI>,
+        value: impl Into<String>,
+    ) -> Self {
+        let field_uri = field_uri.into();
+
+        self.set(
+            PathToElement::extended(field_uri.clone()),
+            Message {
+                extended_property: Some(vec![ExtendedProperty {
+                    extended_field_URI: field_uri,
+                    value: value.into(),
+                }]),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Removes an extended MAPI property from the item.
+    pub fn delete_extended_property(self, field_uri: impl Into<ExtendedFieldURI>) -> Self {
+        self.delete(PathToElement::extended(field_uri.into()))
+    }
+
+    /// Determines whether no changes have been added.
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+
+    /// Applies the changes to the item with the given ID.
+    pub fn for_item(self, item_id: BaseItemId) -> ItemChange {
+        ItemChange {
+            item_change: ItemChangeInner {
+                item_id,
+                updates: Updates {
+                    inner: self.updates,
+                },
+            },
+        }
+    }
+}
+
+impl From<Changes> for Updates {
+    fn from(value: Changes) -> Self {
+        Self {
+            inner: value.updates,
+        }
+    }
+}
+
+/// Retries the changes of an [`UpdateItem`] operation which failed because
+/// of a missing or outdated change key.
+///
+/// Updates are rejected with `ErrorStaleObject` when the item was modified
+/// since its change key was retrieved, e.g. by another client. The refresh
+/// fetches the current change key of each affected item with a [`GetItem`]
+/// request, then retries only the affected changes. It does not send
+/// requests itself, so that it can be used with any client; the clients in
+/// [`client`] wrap this in an `update_item_refreshing_change_keys` method.
+///
+/// Retrying overwrites any changes made by the other client to the fields
+/// being updated, so this should only be used where that is acceptable.
+///
+/// [`client`]: crate::client
+#[derive(Clone, Debug)]
+pub struct ChangeKeyRefresh {
+    operation: UpdateItem,
+    stale: Vec<usize>,
+}
+
+impl ChangeKeyRefresh {
+    /// Inspects the response messages to an operation for errors caused by
+    /// stale change keys, returning `None` if there are none.
+    pub fn new(
+        operation: &UpdateItem,
+        messages: &[ResponseClass<UpdateItemResponseMessage>],
+    ) -> Option<Self> {
+        let stale: Vec<_> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| {
+                matches!(message, ResponseClass::Error(err) if err.is_stale_change_key())
+            })
+            .map(|(index, _)| index)
+            .filter(|&index| index < operation.item_changes.len())
+            .collect();
+
+        if stale.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            operation: operation.clone(),
+            stale,
+        })
+    }
+
+    /// Gets the request for the current change keys of the affected items.
+    pub fn get_item(&self) -> GetItem {
+        GetItem::new(
+            ItemShape::id_only(),
+            self.stale
+                .iter()
+                .map(|&index| {
+                    self.operation.item_changes[index]
+                        .item_change
+                        .item_id
+                        .clone()
+                })
+                .collect(),
+        )
+    }
+
+    /// Patches the affected changes with the change keys in the response to
+    /// the request given by [`get_item`], returning the operation to retry.
+    ///
+    /// Changes to items which could not be fetched, e.g. because they have
+    /// since been deleted, are not retried. Returns `None` if no changes are
+    /// left to retry.
+    ///
+    /// [`get_item`]: ChangeKeyRefresh::get_item
+    pub fn retry_operation(
+        &mut self,
+        response: GetItemResponse,
+    ) -> Result<Option<UpdateItem>, Error> {
+        let messages = response.into_response_messages();
+        if messages.len() != self.stale.len() {
+            return Err(Error::ResponseMessageCountMismatch {
+                expected: self.stale.len(),
+                actual: messages.len(),
+            });
+        }
+
+        let mut refreshed = Vec::new();
+        let mut item_changes = Vec::new();
+        for (&index, message) in self.stale.iter().zip(messages) {
+            let Ok(message) = message.into_result() else {
+                continue;
+            };
+            let Some(item_id) = message.items.inner.first().and_then(ItemLike::item_id) else {
+                continue;
+            };
+
+            let mut change = self.operation.item_changes[index].clone();
+            change.item_change.item_id = BaseItemId::ItemId {
+                id: item_id.id.clone(),
+                change_key: item_id.change_key.clone(),
+            };
+
+            refreshed.push(index);
+            item_changes.push(change);
+        }
+
+        self.stale = refreshed;
+        if item_changes.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.operation.with_batch_inputs(item_changes)))
+    }
+
+    /// Replaces the response messages for the retried changes with those in
+    /// the response to the operation given by [`retry_operation`].
+    ///
+    /// [`retry_operation`]: ChangeKeyRefresh::retry_operation
+    pub fn merge(
+        &self,
+        messages: &mut [ResponseClass<UpdateItemResponseMessage>],
+        response: UpdateItemResponse,
+    ) -> Result<(), Error> {
+        let retried = response.into_response_messages();
+        if retried.len() != self.stale.len() {
+            return Err(Error::ResponseMessageCountMismatch {
+                expected: self.stale.len(),
+                actual: retried.len(),
+            });
+        }
+
+        for (&index, message) in self.stale.iter().zip(retried) {
+            if let Some(slot) = messages.get_mut(index) {
+                *slot = message;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks an [`UpdateItem`] operation before it is sent.
+fn validate(operation: &UpdateItem) -> Result<(), Error> {
+    require_non_empty("UpdateItem", "item_changes", &operation.item_changes)?;
+
+    let mut updates_calendar_item = false;
+    for change in &operation.item_changes {
+        let updates = &change.item_change.updates.inner;
+        require_non_empty("UpdateItem", "updates", updates)?;
+
+        for update in updates {
+            let field_uri = match update {
+                ItemChangeDescription::SetItemField { field_uri, .. }
+                | ItemChangeDescription::AppendToItemField { field_uri, .. }
+                | ItemChangeDescription::DeleteItemField { field_uri } => field_uri,
+            };
+            field_uri.validate()?;
+
+            updates_calendar_item |= matches!(
+                field_uri,
+                PathToElement::FieldURI { field_URI } if field_URI.starts_with("calendar:")
+            );
+        }
+    }
+
+    if updates_calendar_item
+        && operation
+            .send_meeting_invitations_or_cancellations
+            .is_none()
+    {
+        return Err(Error::InvalidRequest {
+            operation: "UpdateItem",
+            reason: "`send_meeting_invitations_or_cancellations` is required when updating \
+                calendar properties"
+                .to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Rejects updates to the start and end time zones of calendar items in
+/// requests targeting Exchange Server 2007, which predates them.
+fn for_server_version(
+    operation: &UpdateItem,
+    version: ExchangeServerVersion,
+) -> Result<Option<UpdateItem>, Error> {
+    if version >= ExchangeServerVersion::Exchange2010 {
+        return Ok(None);
+    }
+
+    let updates_time_zones = operation
+        .item_changes
+        .iter()
+        .flat_map(|change| &change.item_change.updates.inner)
+        .any(|update| match update {
+            ItemChangeDescription::SetItemField { message, .. }
+            | ItemChangeDescription::AppendToItemField { message, .. } => {
+                message.start_time_zone.is_some() || message.end_time_zone.is_some()
+            }
+            ItemChangeDescription::DeleteItemField { .. } => false,
+        });
+    if updates_time_zones {
+        return Err(Error::InvalidRequest {
+            operation: "UpdateItem",
+            reason: format!(
+                "updating `start_time_zone` or `end_time_zone` requires {:?} or later; update \
+                `meeting_time_zone` instead",
+                ExchangeServerVersion::Exchange2010
+            ),
+        });
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ChangeKeyRefresh, Changes, ItemChangeDescription, SendMeetingInvitationsOrCancellations,
+        UpdateItem, UpdateItemResponse, UpdateItemResponseMessage, Updates,
+    };
+    use crate::{
+        properties, response::ResponseCode, server_version::ExchangeServerVersion,
+        test_utils::assert_serialized_content, BaseItemId, Body, Error, Items, Message,
+        MessageDisposition, Operation, PathToElement, PropertyType, ResponseClass, ResponseError,
+        TimeZoneReference,
+    };
+
+    #[test]
+    fn build_item_changes() {
+        let updates: Updates = Changes::new()
+            .set_subject("Hello")
+            .append_body(Body::text("more"))
+            .clear_categories()
+            .set_categories(["Work"])
+            .into();
+
+        assert_serialized_content(
+            &updates,
+            "Updates",
+            concat!(
+                r#"<Updates>"#,
+                r#"<t:SetItemField><t:FieldURI FieldURI="item:Subject"/><t:Message><t:Subject>Hello</t:Subject></t:Message></t:SetItemField>"#,
+                r#"<t:AppendToItemField><t:FieldURI FieldURI="item:Body"/><t:Message><t:Body BodyType="Text">more</t:Body></t:Message></t:AppendToItemField>"#,
+                r#"<t:DeleteItemField><t:FieldURI FieldURI="item:Categories"/></t:DeleteItemField>"#,
+                r#"<t:SetItemField><t:FieldURI FieldURI="item:Categories"/><t:Message><t:Categories><t:String>Work</t:String></t:Categories></t:Message></t:SetItemField>"#,
+                r#"</Updates>"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn extended_property_change_pairs_field_and_value() {
+        let updates: Updates = Changes::new()
+            .set_extended_property(properties::PR_FOLLOWUP_ICON, "6")
+            .into();
+
+        let [ItemChangeDescription::SetItemField { field_uri, message }] = &updates.inner[..]
+        else {
+            panic!("expected a single SetItemField change");
+        };
+
+        let expected_uri = properties::PR_FOLLOWUP_ICON.field_uri();
+        assert!(matches!(
+            field_uri,
+            PathToElement::ExtendedFieldURI { property_tag, property_type, .. }
+                if *property_tag == expected_uri.property_tag
+                    && *property_type == expected_uri.property_type
+        ));
+
+        let properties = message
+            .extended_property
+            .as_deref()
+            .expect("message should carry the property value");
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties[0].extended_field_URI, expected_uri);
+        assert_eq!(properties[0].value, "6");
+        assert_eq!(message.categories, None);
+    }
+
+    #[test]
+    fn validate_item_changes() {
+        let item_id = || BaseItemId::ItemId {
+            id: "AAMkAD...".to_string(),
+            change_key: None,
+        };
+
+        let calendar_change = Changes::new()
+            .set(
+                PathToElement::field("calendar:Location"),
+                Message::default(),
+            )
+            .for_item(item_id());
+        let err = UpdateItem::new(MessageDisposition::SaveOnly, vec![calendar_change.clone()])
+            .validate()
+            .expect_err("calendar updates should require meeting updates to be configured");
+        assert!(matches!(
+            err,
+            Error::InvalidRequest {
+                operation: "UpdateItem",
+                ..
+            }
+        ));
+        UpdateItem::builder(MessageDisposition::SaveOnly, vec![calendar_change])
+            .send_meeting_invitations_or_cancellations(
+                SendMeetingInvitationsOrCancellations::SendToNone,
+            )
+            .build()
+            .validate()
+            .expect("calendar update should be valid");
+
+        let empty_change = Changes::new().for_item(item_id());
+        let err = UpdateItem::new(MessageDisposition::SaveOnly, vec![empty_change])
+            .validate()
+            .expect_err("item changes should require updates");
+        assert_eq!(
+            err.to_string(),
+            "invalid UpdateItem request: `updates` must not be empty"
+        );
+
+        let conflicting_uri = PathToElement::ExtendedFieldURI {
+            distinguished_property_set_id: None,
+            property_set_id: None,
+            property_tag: Some("0x1090".to_string()),
+            property_name: Some("FlagStatus".to_string()),
+            property_id: None,
+            property_type: PropertyType::Integer,
+        };
+        let change = Changes::new().delete(conflicting_uri).for_item(item_id());
+        let err = UpdateItem::new(MessageDisposition::SaveOnly, vec![change])
+            .validate()
+            .expect_err("conflicting extended field URI attributes should be rejected");
+        assert!(matches!(err, Error::InvalidExtendedFieldUri(_)));
+    }
+
+    #[test]
+    fn time_zone_updates_require_exchange_2010() {
+        let change = Changes::new()
+            .set(
+                PathToElement::field("calendar:StartTimeZone"),
+                Message {
+                    start_time_zone: Some(Box::new(TimeZoneReference::new(
+                        "Eastern Standard Time",
+                    ))),
+                    ..Default::default()
+                },
+            )
+            .for_item(BaseItemId::ItemId {
+                id: "AAMkAD...".to_string(),
+                change_key: None,
+            });
+        let update = UpdateItem::builder(MessageDisposition::SaveOnly, vec![change])
+            .send_meeting_invitations_or_cancellations(
+                SendMeetingInvitationsOrCancellations::SendToNone,
+            )
+            .build();
+
+        assert!(update
+            .for_server_version(ExchangeServerVersion::Exchange2010)
+            .expect("update should be supported")
+            .is_none());
+        assert!(matches!(
+            update.for_server_version(ExchangeServerVersion::Exchange2007_SP1),
+            Err(Error::InvalidRequest {
+                operation: "UpdateItem",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn refresh_stale_change_keys() {
+        let item_id = |id: &str, change_key: &str| BaseItemId::ItemId {
+            id: id.to_string(),
+            change_key: Some(change_key.to_string()),
+        };
+        let operation = UpdateItem::new(
+            MessageDisposition::SaveOnly,
+            vec![
+                Changes::new()
+                    .set_is_read(true)
+                    .for_item(item_id("a", "a1")),
+                Changes::new()
+                    .set_is_read(true)
+                    .for_item(item_id("b", "b1")),
+            ],
+        );
+
+        let mut messages = vec![
+            ResponseClass::Success(UpdateItemResponseMessage {
+                items: Items::default(),
+                conflict_results: None,
+            }),
+            ResponseClass::Error(ResponseError {
+                message_text: "The change key passed in the request does not match the current change key for the item.".to_string(),
+                response_code: ResponseCode::ErrorStaleObject,
+                message_xml: None,
+            }),
+        ];
+
+        let mut refresh =
+            ChangeKeyRefresh::new(&operation, &messages).expect("stale change key should be found");
+        assert!(
+            matches!(&refresh.get_item().item_ids[..], [BaseItemId::ItemId { id, .. }] if id == "b")
+        );
+
+        let current = quick_xml::de::from_str(concat!(
+            r#"<GetItemResponse><ResponseMessages>"#,
+            r#"<GetItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<Items><Message><ItemId Id="b" ChangeKey="b2"/></Message></Items>"#,
+            r#"</GetItemResponseMessage></ResponseMessages></GetItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        let retry = refresh
+            .retry_operation(current)
+            .expect("refresh should succeed")
+            .expect("stale change should be retried");
+        assert_eq!(retry.item_changes.len(), 1);
+        assert!(matches!(
+            &retry.item_changes[0].item_change.item_id,
+            BaseItemId::ItemId { id, change_key: Some(change_key) } if id == "b" && change_key == "b2"
+        ));
+
+        let retried = quick_xml::de::from_str(concat!(
+            r#"<UpdateItemResponse><ResponseMessages>"#,
+            r#"<UpdateItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<Items/></UpdateItemResponseMessage></ResponseMessages></UpdateItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        refresh
+            .merge(&mut messages, retried)
+            .expect("merge should succeed");
+        assert!(messages
+            .iter()
+            .all(|message| matches!(message, ResponseClass::Success(_))));
+    }
+
+    #[test]
+    fn deserialize_conflict_results() {
+        let response: UpdateItemResponse = quick_xml::de::from_str(concat!(
+            r#"<UpdateItemResponse><ResponseMessages>"#,
+            r#"<UpdateItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<Items><Message><ItemId Id="a" ChangeKey="a2"/></Message></Items>"#,
+            r#"<ConflictResults><Count>1</Count></ConflictResults>"#,
+            r#"</UpdateItemResponseMessage>"#,
+            r#"<UpdateItemResponseMessage ResponseClass="Success"><ResponseCode>NoError</ResponseCode>"#,
+            r#"<Items><Message><ItemId Id="b" ChangeKey="b2"/></Message></Items>"#,
+            r#"<ConflictResults><Count>0</Count></ConflictResults>"#,
+            r#"</UpdateItemResponseMessage></ResponseMessages></UpdateItemResponse>"#,
+        ))
+        .expect("response should deserialize");
+
+        let conflicts: Vec<_> = response
+            .response_messages
+            .response_messages
+            .iter()
+            .map(|message| match message {
+                ResponseClass::Success(message) => message.has_conflicts(),
+                other => panic!("expected a successful message, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(conflicts, vec![true, false]);
+    }
 }
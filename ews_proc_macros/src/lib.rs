@@ -1,14 +1,88 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Ident, ItemStruct};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, Ident, ItemStruct, Path,
+    PathArguments, Token, Type,
+};
+
+/// The arguments of the `operation_response` attribute.
+struct OperationResponseArgs {
+    /// The type of the messages in the response.
+    response_type: Ident,
+
+    /// The earliest `ExchangeServerVersion` variant supporting the operation,
+    /// if later than the first.
+    min_version: Option<Ident>,
+
+    /// A function checking the operation before it is sent, if any.
+    validate: Option<Path>,
+
+    /// A function adapting the operation to older server versions, if any.
+    for_server_version: Option<Path>,
+}
+
+impl Parse for OperationResponseArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let response_type = input.parse()?;
+
+        let mut min_version = None;
+        let mut validate = None;
+        let mut for_server_version = None;
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            if key == "min_version" {
+                min_version = Some(input.parse()?);
+            } else if key == "validate" {
+                validate = Some(input.parse()?);
+            } else if key == "for_server_version" {
+                for_server_version = Some(input.parse()?);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "expected `min_version`, `validate` or `for_server_version`",
+                ));
+            }
+        }
+
+        Ok(Self {
+            response_type,
+            min_version,
+            validate,
+            for_server_version,
+        })
+    }
+}
 
 /// Annotate a struct as having its response include response messages of the given type,
 /// and generate a response struct for it with the expected attributes and methods.
 ///
 /// Response structs are named by appending "Response" to the end of the name of this struct.
+/// They are given methods for handling their messages without matching on each
+/// `ResponseClass`: `into_result()`, `successes()`, `errors()` and `single()`.
+///
+/// Operations introduced after Exchange 2007 give the earliest version
+/// supporting them as a second argument, e.g.
+/// `#[operation_response(GetMailTipsResponseMessage, min_version = Exchange2010)]`.
+///
+/// Operations which can be checked for mistakes before being sent give the
+/// function doing so, which takes a reference to the operation, e.g.
+/// `#[operation_response(GetItemResponseMessage, validate = validate)]`.
+///
+/// Operations with elements or attributes which older servers reject give
+/// the function adapting them to a server version, which takes a reference
+/// to the operation and the version, e.g.
+/// `#[operation_response(MarkAsReadResponseMessage, for_server_version = for_server_version)]`.
 #[proc_macro_attribute]
 pub fn operation_response(attr: TokenStream, annotated_item: TokenStream) -> TokenStream {
-    let response_type = parse_macro_input!(attr as Ident);
+    let OperationResponseArgs {
+        response_type,
+        min_version,
+        validate,
+        for_server_version,
+    } = parse_macro_input!(attr as OperationResponseArgs);
     let input_struct = parse_macro_input!(annotated_item as ItemStruct);
 
     let request_name = input_struct.ident.clone();
@@ -22,11 +96,40 @@ See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-ref
     );
     let response_doc_attr = quote! { #[doc = #response_doc] };
 
+    let min_version = min_version.map(|min_version| {
+        quote! {
+            const MIN_VERSION: crate::server_version::ExchangeServerVersion =
+                crate::server_version::ExchangeServerVersion::#min_version;
+        }
+    });
+
+    let validate = validate.map(|validate| {
+        quote! {
+            fn validate(&self) -> Result<(), crate::Error> {
+                #validate(self)
+            }
+        }
+    });
+
+    let for_server_version = for_server_version.map(|for_server_version| {
+        quote! {
+            fn for_server_version(
+                &self,
+                version: crate::server_version::ExchangeServerVersion,
+            ) -> Result<Option<Self>, crate::Error> {
+                #for_server_version(self, version)
+            }
+        }
+    });
+
     let expanded = quote! {
         #input_struct
 
         impl crate::Operation for #request_name {
             type Response = #response_name;
+            #min_version
+            #validate
+            #for_server_version
         }
 
         impl crate::types::sealed::EnvelopeBodyContents for #request_name {
@@ -37,11 +140,71 @@ See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-ref
 
         #response_doc_attr
         #[derive(Clone, Debug, serde::Deserialize, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
         #[serde(rename_all = "PascalCase")]
         pub struct #response_name {
             pub response_messages: crate::ResponseMessages<#response_type>,
         }
 
+        impl #response_name {
+            /// Converts the response into the messages it contains, or the
+            /// first error among them.
+            ///
+            /// Warnings are treated as successes.
+            pub fn into_result(self) -> Result<Vec<#response_type>, crate::ResponseError> {
+                self.response_messages
+                    .response_messages
+                    .into_iter()
+                    .map(crate::ResponseClass::into_result)
+                    .collect()
+            }
+
+            /// Iterates over the successful messages in the response,
+            /// including warnings.
+            pub fn successes(&self) -> impl Iterator<Item = &#response_type> {
+                self.response_messages
+                    .response_messages
+                    .iter()
+                    .filter_map(|message| match message {
+                        crate::ResponseClass::Success(value)
+                        | crate::ResponseClass::Warning(value) => Some(value),
+                        crate::ResponseClass::Error(_) => None,
+                    })
+            }
+
+            /// Iterates over the errors in the response.
+            pub fn errors(&self) -> impl Iterator<Item = &crate::ResponseError> {
+                self.response_messages
+                    .response_messages
+                    .iter()
+                    .filter_map(|message| match message {
+                        crate::ResponseClass::Error(err) => Some(err),
+                        _ => None,
+                    })
+            }
+
+            /// Converts a response expected to contain exactly one message
+            /// into that message.
+            ///
+            /// Returns an [`Error::ResponseMessageCountMismatch`] if the
+            /// response contains any other number of messages, or an
+            /// [`Error::Response`] if the message is an error.
+            ///
+            /// [`Error::ResponseMessageCountMismatch`]: crate::Error::ResponseMessageCountMismatch
+            /// [`Error::Response`]: crate::Error::Response
+            pub fn single(self) -> Result<#response_type, crate::Error> {
+                let mut messages = self.response_messages.response_messages;
+                if messages.len() != 1 {
+                    return Err(crate::Error::ResponseMessageCountMismatch {
+                        expected: 1,
+                        actual: messages.len(),
+                    });
+                }
+
+                Ok(messages.remove(0).into_result()?)
+            }
+        }
+
         impl crate::OperationResponse for #response_name {
             type Message = #response_type;
             fn response_messages(&self) -> &[crate::ResponseClass<Self::Message>] {
@@ -50,6 +213,9 @@ See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-ref
             fn into_response_messages(self) -> Vec<crate::ResponseClass<Self::Message>> {
                 self.response_messages.response_messages
             }
+            fn response_messages_mut(&mut self) -> &mut Vec<crate::ResponseClass<Self::Message>> {
+                &mut self.response_messages.response_messages
+            }
         }
 
         impl crate::types::sealed::EnvelopeBodyContents for #response_name {
@@ -61,3 +227,151 @@ See <https://learn.microsoft.com/en-us/exchange/client-developer/web-service-ref
 
     TokenStream::from(expanded)
 }
+
+/// Derive a constructor and a builder for an operation struct, distinguishing
+/// between required and optional fields.
+///
+/// Fields of type `Option<T>` are optional and can be set using a method of the
+/// same name on the builder, which takes a `T`. Every other field is required
+/// and is passed, in the order of declaration, to the generated `new()` and
+/// `builder()` functions. `new()` leaves every optional field unset, so that
+/// adding optional fields to an operation doesn't break its consumers.
+///
+/// Builders are named by appending "Builder" to the end of the name of the
+/// struct.
+#[proc_macro_derive(OperationBuilder)]
+pub fn derive_operation_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &input.ident,
+                    "OperationBuilder can only be derived for structs with named fields",
+                )
+                .to_compile_error()
+                .into()
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(
+                &input.ident,
+                "OperationBuilder can only be derived for structs",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
+    let request_name = &input.ident;
+    let builder_name = Ident::new(&format!("{request_name}Builder"), request_name.span());
+
+    let mut required_params = Vec::new();
+    let mut field_inits = Vec::new();
+    let mut setters = Vec::new();
+
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+
+        match option_inner_type(&field.ty) {
+            Some(inner_type) => {
+                let docs = field
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("doc"));
+
+                field_inits.push(quote! { #ident: None });
+                setters.push(quote! {
+                    #(#docs)*
+                    pub fn #ident(mut self, #ident: #inner_type) -> Self {
+                        self.inner.#ident = Some(#ident);
+                        self
+                    }
+                });
+            }
+            None => {
+                let ty = &field.ty;
+
+                required_params.push((ident, ty));
+                field_inits.push(quote! { #ident });
+            }
+        }
+    }
+
+    let builder_doc = format!("A builder for a [`{request_name}`] operation.");
+    let new_doc = format!(
+        "Creates a [`{request_name}`] operation from its required fields, leaving optional fields unset."
+    );
+    let constructor_doc =
+        format!("Creates a builder for a [`{request_name}`] operation from its required fields.");
+    let required_args: Vec<_> = required_params.iter().map(|(ident, _)| ident).collect();
+    let required_params: Vec<_> = required_params
+        .iter()
+        .map(|(ident, ty)| quote! { #ident: #ty })
+        .collect();
+
+    let expanded = quote! {
+        #[doc = #builder_doc]
+        #[derive(Debug)]
+        pub struct #builder_name {
+            inner: #request_name,
+        }
+
+        impl #request_name {
+            #[doc = #new_doc]
+            #[allow(clippy::too_many_arguments)]
+            pub fn new(#(#required_params),*) -> Self {
+                Self {
+                    #(#field_inits),*
+                }
+            }
+
+            #[doc = #constructor_doc]
+            #[allow(clippy::too_many_arguments)]
+            pub fn builder(#(#required_params),*) -> #builder_name {
+                #builder_name {
+                    inner: Self::new(#(#required_args),*),
+                }
+            }
+        }
+
+        impl #builder_name {
+            #(#setters)*
+
+            /// Builds the operation.
+            pub fn build(self) -> #request_name {
+                self.inner
+            }
+        }
+
+        impl From<#builder_name> for #request_name {
+            fn from(builder: #builder_name) -> Self {
+                builder.build()
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+/// Gets `T` if the given type is `Option<T>`.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(inner_type) => Some(inner_type),
+            _ => None,
+        },
+        _ => None,
+    }
+}